@@ -32,6 +32,8 @@ pub fn remote_loopback_deploy(
             num_cores: cores_per_host,
             ssh: Default::default(),
             perf_path: None,
+            labels: vec![],
+            weight: None,
         });
     }
 
@@ -39,6 +41,7 @@ pub fn remote_loopback_deploy(
         hosts,
         tracing_dir: None,
         cleanup_executable: false,
+        launcher: Default::default(),
     });
 
     let mut join_handles = vec![];
@@ -48,6 +51,10 @@ pub fn remote_loopback_deploy(
             runtime: runtime.clone(),
             host_id: Some(host_id),
             skip_single_remote_check: true,
+            checkpoint: None,
+            concurrency: Default::default(),
+            affinity: Default::default(),
+            metrics: None,
         };
         let body = body.clone();
         join_handles.push(
@@ -56,7 +63,7 @@ pub fn remote_loopback_deploy(
                 .spawn(move || {
                     let mut env = StreamEnvironment::new(config);
                     body(&mut env);
-                    env.execute_blocking();
+                    env.execute_blocking().unwrap();
                 })
                 .unwrap(),
         )
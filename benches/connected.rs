@@ -120,7 +120,7 @@ fn bench_main(c: &mut Criterion) {
                 });
 
                 connected(source);
-                env.execute_blocking();
+                env.execute_blocking().unwrap();
             })
         });
 
@@ -24,7 +24,7 @@ fn csv_bench(c: &mut Criterion) {
                     .for_each(|v| {
                         std::hint::black_box(v);
                     });
-                env.execute_blocking();
+                env.execute_blocking().unwrap();
             })
         },
     );
@@ -38,7 +38,7 @@ fn csv_bench(c: &mut Criterion) {
                 env.stream_csv_noirdata(path.to_path_buf()).for_each(|v| {
                     std::hint::black_box(v);
                 });
-                env.execute_blocking();
+                env.execute_blocking().unwrap();
             })
         },
     );
@@ -367,7 +367,7 @@ fn bench_main(c: &mut Criterion) {
                 b.iter(|| {
                     let mut env = StreamEnvironment::default();
                     run_query(&mut env, $q, *size);
-                    env.execute_blocking();
+                    env.execute_blocking().unwrap();
                 })
             });
             g.bench_with_input(
@@ -23,7 +23,7 @@ fn shuffle(dataset: &'static [u32]) {
         .shuffle()
         .map(|n| n.wrapping_sub(42));
     stream.for_each(std::mem::drop);
-    env.execute_blocking();
+    env.execute_blocking().unwrap();
 }
 
 fn shuffle_benchmark(c: &mut Criterion) {
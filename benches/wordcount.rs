@@ -60,7 +60,7 @@ fn wordcount_bench(c: &mut Criterion) {
                 b.iter(move || {
                     let mut env = StreamEnvironment::default();
                     wc_fold(&mut env, path);
-                    env.execute_blocking();
+                    env.execute_blocking().unwrap();
                 })
             },
         );
@@ -72,7 +72,7 @@ fn wordcount_bench(c: &mut Criterion) {
                 b.iter(move || {
                     let mut env = StreamEnvironment::default();
                     wc_fold_assoc(&mut env, path);
-                    env.execute_blocking();
+                    env.execute_blocking().unwrap();
                 })
             },
         );
@@ -84,7 +84,7 @@ fn wordcount_bench(c: &mut Criterion) {
                 b.iter(move || {
                     let mut env = StreamEnvironment::default();
                     wc_count_assoc(&mut env, path);
-                    env.execute_blocking();
+                    env.execute_blocking().unwrap();
                 })
             },
         );
@@ -96,7 +96,7 @@ fn wordcount_bench(c: &mut Criterion) {
                 b.iter(move || {
                     let mut env = StreamEnvironment::default();
                     wc_reduce(&mut env, path);
-                    env.execute_blocking();
+                    env.execute_blocking().unwrap();
                 })
             },
         );
@@ -108,7 +108,7 @@ fn wordcount_bench(c: &mut Criterion) {
                 b.iter(move || {
                     let mut env = StreamEnvironment::default();
                     wc_reduce_assoc(&mut env, path);
-                    env.execute_blocking();
+                    env.execute_blocking().unwrap();
                 })
             },
         );
@@ -120,7 +120,7 @@ fn wordcount_bench(c: &mut Criterion) {
                 b.iter(move || {
                     let mut env = StreamEnvironment::default();
                     wc_fast(&mut env, path);
-                    env.execute_blocking();
+                    env.execute_blocking().unwrap();
                 })
             },
         );
@@ -132,7 +132,7 @@ fn wordcount_bench(c: &mut Criterion) {
                 b.iter(move || {
                     let mut env = StreamEnvironment::default();
                     wc_fast_kstring(&mut env, path);
-                    env.execute_blocking();
+                    env.execute_blocking().unwrap();
                 })
             },
         );
@@ -336,7 +336,7 @@ fn main() {
     };
 
     let start = Instant::now();
-    env.execute_blocking();
+    env.execute_blocking().unwrap();
     let elapsed = start.elapsed();
     eprintln!("Elapsed: {elapsed:?}");
 
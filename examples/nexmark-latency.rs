@@ -215,7 +215,7 @@ fn main() {
     }
 
     let start = Instant::now();
-    env.execute_blocking();
+    env.execute_blocking().unwrap();
     println!("q{q}:elapsed:{:?}", start.elapsed());
 
     eprintln!("==================================================");
@@ -261,6 +261,7 @@ impl Q4Unary {
                 StreamElement::Watermark(w) => self.watermark = w as u64 + 1,
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
                 StreamElement::Terminate => return StreamElement::Terminate,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::FlushAndRestart => {
                     // Close all open auctions
                     if let Some(m) = self.open.iter().map(|e| e.0 .0).max() {
@@ -490,7 +491,7 @@ fn main() {
     }
 
     let start = Instant::now();
-    env.execute_blocking();
+    env.execute_blocking().unwrap();
     println!("q{q}:elapsed:{:?}", start.elapsed());
 
     // eprintln!("Query{i}: {:?}", q.get());
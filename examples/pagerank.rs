@@ -85,7 +85,7 @@ fn main() {
     dropme.for_each(|_| {});
 
     let start = Instant::now();
-    env.execute_blocking();
+    env.execute_blocking().unwrap();
     let elapsed = start.elapsed();
 
     if let Some(mut res) = result.get() {
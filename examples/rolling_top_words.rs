@@ -207,5 +207,5 @@ fn main() {
                 tester.add();
             }
         });
-    env.execute_blocking();
+    env.execute_blocking().unwrap();
 }
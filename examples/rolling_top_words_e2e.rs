@@ -153,6 +153,6 @@ fn main() {
         .fold(0, |acc, _| *acc += 1)
         .for_each(|c| println!("{c}"));
     let start = Instant::now();
-    env.execute_blocking();
+    env.execute_blocking().unwrap();
     println!("Elapsed: {:?}", start.elapsed());
 }
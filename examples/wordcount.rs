@@ -35,7 +35,7 @@ fn main() {
         .fold(0, |count, _word| *count += 1)
         .collect_vec();
     let start = Instant::now();
-    env.execute_blocking();
+    env.execute_blocking().unwrap();
     let elapsed = start.elapsed();
     if let Some(_res) = result.get() {
         eprintln!("Output: {:?}", _res.len());
@@ -67,7 +67,7 @@ async fn main() {
         .fold(0, |count, _word| *count += 1)
         .collect_vec();
     let start = Instant::now();
-    env.execute().await;
+    env.execute().await.unwrap();
     let elapsed = start.elapsed();
     if let Some(_res) = result.get() {
         eprintln!("Output: {:?}", _res.len());
@@ -30,7 +30,7 @@ fn main() {
         .group_by_count(|word: &String| word.clone())
         .collect_vec();
     let start = Instant::now();
-    env.execute_blocking();
+    env.execute_blocking().unwrap();
     let elapsed = start.elapsed();
 
     if let Some(_res) = result.get() {
@@ -70,7 +70,7 @@ async fn main() {
         .group_by_count(|word: &String| word.clone())
         .collect_vec();
     let start = Instant::now();
-    env.execute().await;
+    env.execute().await.unwrap();
     let elapsed = start.elapsed();
 
     if let Some(_r) = result.get() {
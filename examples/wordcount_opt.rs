@@ -45,7 +45,7 @@ fn main() {
         .collect_vec();
 
     let start = Instant::now();
-    env.execute_blocking();
+    env.execute_blocking().unwrap();
     let elapsed = start.elapsed();
 
     if let Some(_res) = result.get() {
@@ -0,0 +1,123 @@
+//! Pinning of the threads of the replicas to the cores of the host.
+//!
+//! See [`CoreAffinity`](crate::config::CoreAffinity) for the available policies.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::config::CoreAffinity;
+use crate::network::Coord;
+
+/// Assign the cores to the replicas of a host, following a [`CoreAffinity`] policy.
+#[derive(Debug)]
+pub(crate) struct CorePlacement {
+    policy: CoreAffinity,
+    /// The cores the process is allowed to run on.
+    cores: Vec<usize>,
+    /// The number of replicas placed so far, for [`CoreAffinity::RoundRobin`].
+    placed: AtomicUsize,
+}
+
+impl CorePlacement {
+    /// Build the placement for the cores available to this process.
+    ///
+    /// Returns `None` if the threads should not be pinned.
+    pub(crate) fn new(policy: CoreAffinity) -> Option<Self> {
+        if policy == CoreAffinity::None {
+            return None;
+        }
+        let cores = available_cores();
+        if cores.is_empty() {
+            log::warn!("core affinity {policy:?} is not supported on this host, ignoring it");
+            return None;
+        }
+        Some(Self::with_cores(policy, cores))
+    }
+
+    fn with_cores(policy: CoreAffinity, cores: Vec<usize>) -> Self {
+        Self {
+            policy,
+            cores,
+            placed: AtomicUsize::new(0),
+        }
+    }
+
+    /// The core the replica should be pinned to.
+    pub(crate) fn core_for(&self, coord: Coord) -> Option<usize> {
+        let index = match self.policy {
+            CoreAffinity::None => return None,
+            CoreAffinity::Replica => coord.replica_id as usize,
+            CoreAffinity::RoundRobin => self.placed.fetch_add(1, Ordering::Relaxed),
+        };
+        Some(self.cores[index % self.cores.len()])
+    }
+}
+
+/// The cores the current process is allowed to run on.
+#[cfg(target_os = "linux")]
+fn available_cores() -> Vec<usize> {
+    // SAFETY: `cpu_set_t` is a plain bitmask, and the size passed is the one of the set
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Vec::new();
+        }
+        (0..libc::CPU_SETSIZE as usize)
+            .filter(|&core| libc::CPU_ISSET(core, &set))
+            .collect()
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_cores() -> Vec<usize> {
+    Vec::new()
+}
+
+/// Pin the current thread to a core.
+#[cfg(target_os = "linux")]
+pub(crate) fn pin_current_thread(core: usize) {
+    // SAFETY: `cpu_set_t` is a plain bitmask, and the size passed is the one of the set
+    let res = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(core, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+    if res != 0 {
+        log::warn!(
+            "cannot pin the thread to core {core}: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn pin_current_thread(_core: usize) {}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::CoreAffinity;
+    use crate::network::Coord;
+
+    use super::CorePlacement;
+
+    #[test]
+    fn placement() {
+        let replica = CorePlacement::with_cores(CoreAffinity::Replica, vec![2, 3, 5]);
+        assert_eq!(replica.core_for(Coord::new(0, 0, 0)), Some(2));
+        assert_eq!(replica.core_for(Coord::new(1, 0, 0)), Some(2));
+        assert_eq!(replica.core_for(Coord::new(1, 0, 4)), Some(3));
+
+        let round_robin = CorePlacement::with_cores(CoreAffinity::RoundRobin, vec![2, 3, 5]);
+        let cores = (0..4)
+            .map(|r| round_robin.core_for(Coord::new(0, 0, r)))
+            .collect::<Vec<_>>();
+        assert_eq!(cores, vec![Some(2), Some(3), Some(5), Some(2)]);
+    }
+
+    #[test]
+    fn available_cores() {
+        let cores = super::available_cores();
+        if cfg!(target_os = "linux") {
+            assert!(!cores.is_empty());
+        }
+    }
+}
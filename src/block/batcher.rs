@@ -54,6 +54,8 @@ pub(crate) struct Batcher<Out: ExchangeData> {
     buffer: Vec<StreamElement<Out>>,
     /// Time of the last flush of the buffer.    
     last_send: Instant,
+    /// Time the first message of the buffer was enqueued.
+    first_enqueued: Instant,
     /// The coordinate of this block, used for marking the sender of the batch.
     coord: Coord,
 }
@@ -65,12 +67,16 @@ impl<Out: ExchangeData> Batcher<Out> {
             mode,
             buffer: Default::default(),
             last_send: Instant::now(),
+            first_enqueued: Instant::now(),
             coord,
         }
     }
 
     /// Put a message in the batch queue, it won't be sent immediately.
     pub(crate) fn enqueue(&mut self, message: StreamElement<Out>) {
+        if self.buffer.is_empty() {
+            self.first_enqueued = Instant::now();
+        }
         match self.mode {
             BatchMode::Adaptive(n, max_delay) => {
                 self.buffer.push(message);
@@ -88,6 +94,9 @@ impl<Out: ExchangeData> Batcher<Out> {
             BatchMode::Single => {
                 let message = NetworkMessage::new_single(message, self.coord);
                 self.remote_sender.send(message).unwrap();
+                if let Some(metrics) = self.remote_sender.metrics() {
+                    metrics.batch_sent(Duration::ZERO);
+                }
             }
         }
     }
@@ -105,6 +114,9 @@ impl<Out: ExchangeData> Batcher<Out> {
             std::mem::swap(&mut self.buffer, &mut batch);
             let message = NetworkMessage::new_batch(batch, self.coord);
             self.remote_sender.send(message).unwrap();
+            if let Some(metrics) = self.remote_sender.metrics() {
+                metrics.batch_sent(self.first_enqueued.elapsed().into());
+            }
             self.last_send = Instant::now();
         }
     }
@@ -115,6 +127,9 @@ impl<Out: ExchangeData> Batcher<Out> {
         if !self.buffer.is_empty() {
             let message = NetworkMessage::new_batch(self.buffer, self.coord);
             self.remote_sender.send(message).unwrap();
+            if let Some(metrics) = self.remote_sender.metrics() {
+                metrics.batch_sent(self.first_enqueued.elapsed().into());
+            }
         }
     }
 }
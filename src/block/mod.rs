@@ -78,16 +78,30 @@ pub(crate) struct SchedulerRequirements {
     ///
     /// The value specified is only an upper bound, the scheduler is allowed to spawn less blocks,
     pub(crate) replication: Replication,
+    /// The labels a remote host must have to run the replicas of this block. If empty the block
+    /// can run on all the hosts.
+    pub(crate) host_labels: Vec<String>,
 }
 
 /// Replication factor for a block
+///
+/// New ways to replicate a block may be added, so matching on this enum requires a wildcard arm.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[non_exhaustive]
 pub enum Replication {
     /// The number of replicas is unlimited and will be determined by the launch configuration.
     #[default]
     Unlimited,
     /// The number of replicas is limited to a fixed number.
     Limited(CoordUInt),
+    /// The number of replicas on each host is proportional to its number of cores: a host with
+    /// `num_cores` cores runs `ceil(num_cores * numerator / denominator)` replicas, at least one.
+    ///
+    /// Unlike the other variants, this may give a block more replicas than the cores of the host.
+    PerCore {
+        numerator: CoordUInt,
+        denominator: CoordUInt,
+    },
     /// The number of replicas is limited to one per host.
     Host,
     /// The number of replicas is limited to one across all the hosts.
@@ -104,6 +118,21 @@ impl Replication {
         Self::Limited(size)
     }
 
+    /// Replicate the block `numerator / denominator` times the number of cores of each host.
+    ///
+    /// For example `new_per_core(2, 1)` spawns two replicas per core, `new_per_core(1, 4)` one
+    /// replica every four cores.
+    pub fn new_per_core(numerator: CoordUInt, denominator: CoordUInt) -> Self {
+        assert!(
+            numerator > 0 && denominator > 0,
+            "Replication factor must be greater than zero!"
+        );
+        Self::PerCore {
+            numerator,
+            denominator,
+        }
+    }
+
     pub fn new_host() -> Self {
         Self::Host
     }
@@ -115,20 +144,50 @@ impl Replication {
     pub fn is_unlimited(&self) -> bool {
         matches!(self, Replication::Unlimited)
     }
+
+    /// The most restrictive of the two replications.
+    ///
+    /// Since the number of cores of the hosts is not known here, a fixed limit takes precedence
+    /// over a per-core replication.
     pub fn intersect(&self, rhs: Self) -> Self {
         match (*self, rhs) {
             (Replication::One, _) | (_, Replication::One) => Replication::One,
             (Replication::Host, _) | (_, Replication::Host) => Replication::Host,
             (Replication::Limited(n), Replication::Limited(m)) => Replication::Limited(n.min(m)),
             (Replication::Limited(n), _) | (_, Replication::Limited(n)) => Replication::Limited(n),
+            (
+                Replication::PerCore {
+                    numerator: n1,
+                    denominator: d1,
+                },
+                Replication::PerCore {
+                    numerator: n2,
+                    denominator: d2,
+                },
+            ) => {
+                if n1 as u128 * d2 as u128 <= n2 as u128 * d1 as u128 {
+                    *self
+                } else {
+                    rhs
+                }
+            }
+            (r @ Replication::PerCore { .. }, _) | (_, r @ Replication::PerCore { .. }) => r,
             (Replication::Unlimited, Replication::Unlimited) => Replication::Unlimited,
         }
     }
 
+    /// The number of replicas on a host with `n` cores.
     pub(crate) fn clamp(&self, n: CoordUInt) -> CoordUInt {
         match self {
             Replication::Unlimited => n,
             Replication::Limited(q) => n.min(*q),
+            Replication::PerCore {
+                numerator,
+                denominator,
+            } => {
+                let replicas = n.saturating_mul(*numerator).div_ceil(*denominator);
+                replicas.max(1)
+            }
             Replication::Host => 1,
             Replication::One => 1,
         }
@@ -182,6 +241,13 @@ impl SchedulerRequirements {
     pub(crate) fn replication(&mut self, replication: Replication) {
         self.replication = self.replication.intersect(replication);
     }
+
+    /// Run this block only on the hosts with the given label.
+    pub(crate) fn host_label(&mut self, label: String) {
+        if !self.host_labels.contains(&label) {
+            self.host_labels.push(label);
+        }
+    }
 }
 
 /// Hashing function for group by operations
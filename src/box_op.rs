@@ -84,12 +84,26 @@ impl<T: Data> Operator<T> for BoxedOperator<T> {
     }
 }
 
+impl<T, Op> Stream<T, Op>
+where
+    T: Data,
+    Op: Operator<T> + 'static,
+{
+    /// Hide the operators of the stream behind a [`BoxedOperator`].
+    ///
+    /// This allows to store streams built with different operators in the same collection, like
+    /// the ones passed to [`Stream::union_all`].
+    pub fn into_boxed(self) -> Stream<T, BoxedOperator<T>> {
+        self.add_operator(BoxedOperator::new)
+    }
+}
+
 impl<Op> Stream<NoirData, Op>
 where
     Op: Operator<NoirData> + 'static,
 {
     #[allow(dead_code)]
     pub fn into_box(self) -> Stream<NoirData, BoxedOperator<NoirData>> {
-        self.add_operator(|prev| BoxedOperator::new(prev))
+        self.add_operator(BoxedOperator::new)
     }
 }
@@ -9,15 +9,17 @@ use std::time::Duration;
 use crossbeam_channel::{
     bounded as bounded_ext, select, unbounded as unbounded_ext, Receiver as ReceiverExt,
     RecvError as ExtRecvError, RecvTimeoutError as ExtRecvTimeoutError, SendError as SendErrorExt,
-    Sender as SenderExt, TryRecvError as ExtTryRecvError,
+    Sender as SenderExt, TryRecvError as ExtTryRecvError, TrySendError as ExtTrySendError,
 };
 #[cfg(feature = "flume")]
 use flume::{
     bounded as bounded_ext, unbounded as unbounded_ext, Receiver as ReceiverExt,
     RecvError as ExtRecvError, RecvTimeoutError as ExtRecvTimeoutError, SendError as SendErrorExt,
-    Sender as SenderExt, TryRecvError as ExtTryRecvError,
+    Sender as SenderExt, TryRecvError as ExtTryRecvError, TrySendError as ExtTrySendError,
 };
 
+use crate::concurrency;
+
 pub trait ChannelItem: Send + 'static {}
 impl<T: Send + 'static> ChannelItem for T {}
 
@@ -106,6 +108,36 @@ mod select_impl {
             }
         };
     }
+
+    macro_rules! select_any_impl {
+        ($receivers:expr) => {{
+            let mut select = crossbeam_channel::Select::new();
+            for receiver in $receivers {
+                select.recv(&receiver.0);
+            }
+            let operation = select.select();
+            let index = operation.index();
+            let el = operation.recv(&$receivers[index].0);
+            (index, el.map_err(RecvError::from))
+        }};
+    }
+
+    macro_rules! select_any_timeout_impl {
+        ($receivers:expr, $timeout:expr) => {{
+            let mut select = crossbeam_channel::Select::new();
+            for receiver in $receivers {
+                select.recv(&receiver.0);
+            }
+            select
+                .select_timeout($timeout)
+                .map(|operation| {
+                    let index = operation.index();
+                    let el = operation.recv(&$receivers[index].0);
+                    (index, el.map_err(RecvError::from))
+                })
+                .map_err(|_| RecvTimeoutError::Timeout)
+        }};
+    }
 }
 
 #[cfg(feature = "flume")]
@@ -129,27 +161,71 @@ mod select_impl {
                 .map_err(|_| RecvTimeoutError::Timeout)
         };
     }
+
+    macro_rules! select_any_impl {
+        ($receivers:expr) => {{
+            let mut selector = flume::Selector::new();
+            for (index, receiver) in $receivers.iter().enumerate() {
+                selector =
+                    selector.recv(&receiver.0, move |el| (index, el.map_err(RecvError::from)));
+            }
+            selector.wait()
+        }};
+    }
+
+    macro_rules! select_any_timeout_impl {
+        ($receivers:expr, $timeout:expr) => {{
+            let mut selector = flume::Selector::new();
+            for (index, receiver) in $receivers.iter().enumerate() {
+                selector =
+                    selector.recv(&receiver.0, move |el| (index, el.map_err(RecvError::from)));
+            }
+            selector
+                .wait_timeout($timeout)
+                .map_err(|_| RecvTimeoutError::Timeout)
+        }};
+    }
 }
 
 /// A wrapper on a bounded channel sender.
 #[derive(Debug, Clone)]
 pub struct Sender<T: ChannelItem>(SenderExt<T>);
 /// A wrapper on a bounded channel receiver.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Receiver<T: ChannelItem>(ReceiverExt<T>);
 
 impl<T: ChannelItem> Sender<T> {
     /// Send a message in the channel, blocking if it's full.
+    ///
+    /// With a concurrency limit the slot of the replica is released while blocked.
     #[inline]
     pub fn send(&self, item: T) -> Result<(), SendError<T>> {
-        self.0.send(item)
+        if !concurrency::is_limited() {
+            return self.0.send(item);
+        }
+        match self.0.try_send(item) {
+            Ok(()) => Ok(()),
+            Err(ExtTrySendError::Full(item)) => concurrency::blocking(|| self.0.send(item)),
+            Err(ExtTrySendError::Disconnected(item)) => Err(SendErrorExt(item)),
+        }
     }
 }
 
 impl<T: ChannelItem> Receiver<T> {
     /// Block until a message is present in the channel and return it when ready.
+    ///
+    /// With a concurrency limit the slot of the replica is released while blocked.
     #[inline]
     pub fn recv(&self) -> Result<T, RecvError> {
+        if concurrency::is_limited() {
+            match self.0.try_recv() {
+                Ok(item) => return Ok(item),
+                Err(ExtTryRecvError::Disconnected) => return Err(RecvError::Disconnected),
+                Err(ExtTryRecvError::Empty) => {
+                    return concurrency::blocking(|| self.0.recv()).map_err(RecvError::from)
+                }
+            }
+        }
         self.0.recv().map_err(RecvError::from)
     }
 
@@ -159,6 +235,12 @@ impl<T: ChannelItem> Receiver<T> {
         self.0.try_recv().map_err(TryRecvError::from)
     }
 
+    /// The number of messages waiting in the channel.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
     #[inline]
     #[cfg(feature = "flume")]
     pub async fn recv_async(&self) -> Result<T, RecvError> {
@@ -170,6 +252,13 @@ impl<T: ChannelItem> Receiver<T> {
     /// If the timeout expires an error is returned.
     #[inline]
     pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        if concurrency::is_limited() {
+            if let Ok(item) = self.0.try_recv() {
+                return Ok(item);
+            }
+            return concurrency::blocking(|| self.0.recv_timeout(timeout))
+                .map_err(RecvTimeoutError::from);
+        }
         self.0.recv_timeout(timeout).map_err(RecvTimeoutError::from)
     }
 
@@ -180,7 +269,7 @@ impl<T: ChannelItem> Receiver<T> {
     /// fairness property.
     #[inline]
     pub fn select<T2: ChannelItem>(&self, other: &Receiver<T2>) -> SelectResult<T, T2> {
-        select_impl!(self, other)
+        concurrency::blocking(|| select_impl!(self, other))
     }
 
     /// Same as `select`, with a timeout.
@@ -190,7 +279,26 @@ impl<T: ChannelItem> Receiver<T> {
         other: &Receiver<T2>,
         timeout: Duration,
     ) -> Result<SelectResult<T, T2>, RecvTimeoutError> {
-        select_timeout_impl!(self, other, timeout)
+        concurrency::blocking(|| select_timeout_impl!(self, other, timeout))
+    }
+
+    /// Receive a message from any of the provided receivers, returning also the index of the
+    /// receiver in the list.
+    ///
+    /// If many receivers are ready one of them is chosen randomly (with an unspecified
+    /// probability). The list must not be empty.
+    #[inline]
+    pub fn select_any(receivers: &[&Receiver<T>]) -> (usize, Result<T, RecvError>) {
+        concurrency::blocking(|| select_any_impl!(receivers))
+    }
+
+    /// Same as `select_any`, with a timeout.
+    #[inline]
+    pub fn select_any_timeout(
+        receivers: &[&Receiver<T>],
+        timeout: Duration,
+    ) -> Result<(usize, Result<T, RecvError>), RecvTimeoutError> {
+        concurrency::blocking(|| select_any_timeout_impl!(receivers, timeout))
     }
 }
 
@@ -213,7 +321,7 @@ impl<T: ChannelItem> UnboundedReceiver<T> {
     /// Block until a message is present in the channel and return it when ready.
     #[inline]
     pub fn recv(&self) -> Result<T, RecvError> {
-        self.0.recv().map_err(RecvError::from)
+        concurrency::blocking(|| self.0.recv()).map_err(RecvError::from)
     }
 
     /// Block until a message is present in the channel and return it when ready.
@@ -221,6 +329,13 @@ impl<T: ChannelItem> UnboundedReceiver<T> {
     /// If the timeout expires an error is returned.
     #[inline]
     pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        if concurrency::is_limited() {
+            if let Ok(item) = self.0.try_recv() {
+                return Ok(item);
+            }
+            return concurrency::blocking(|| self.0.recv_timeout(timeout))
+                .map_err(RecvTimeoutError::from);
+        }
         self.0.recv_timeout(timeout).map_err(RecvTimeoutError::from)
     }
 
@@ -231,7 +346,7 @@ impl<T: ChannelItem> UnboundedReceiver<T> {
     /// fairness property.
     #[inline]
     pub fn select<T2: ChannelItem>(&self, other: &UnboundedReceiver<T2>) -> SelectResult<T, T2> {
-        select_impl!(self, other)
+        concurrency::blocking(|| select_impl!(self, other))
     }
 
     /// Same as `select`, with a timeout.
@@ -241,7 +356,7 @@ impl<T: ChannelItem> UnboundedReceiver<T> {
         other: &UnboundedReceiver<T2>,
         timeout: Duration,
     ) -> Result<SelectResult<T, T2>, RecvTimeoutError> {
-        select_timeout_impl!(self, other, timeout)
+        concurrency::blocking(|| select_timeout_impl!(self, other, timeout))
     }
 }
 
@@ -251,7 +366,7 @@ mod tests {
 
     use itertools::Itertools;
 
-    use crate::channel::{bounded, SelectResult};
+    use crate::channel::{bounded, Receiver, RecvTimeoutError, SelectResult};
 
     const TEST_CAPACITY: usize = 10;
 
@@ -306,6 +421,21 @@ mod tests {
         assert_eq!(elem2, SelectResult::B(Ok("test".to_string())));
     }
 
+    #[test]
+    fn test_select_any_local() {
+        let (senders, receivers): (Vec<_>, Vec<_>) = (0..3).map(|_| bounded(TEST_CAPACITY)).unzip();
+        let receivers = receivers.iter().collect_vec();
+
+        senders[2].send(123).unwrap();
+        assert_eq!(Receiver::select_any(&receivers), (2, Ok(123)));
+
+        senders[0].send(456).unwrap();
+        assert_eq!(Receiver::select_any(&receivers), (0, Ok(456)));
+
+        let timeout = Receiver::select_any_timeout(&receivers, Duration::from_millis(50));
+        assert_eq!(timeout, Err(RecvTimeoutError::Timeout));
+    }
+
     #[test]
     fn test_select_timeout_local() {
         let (sender1, receiver1) = bounded(TEST_CAPACITY);
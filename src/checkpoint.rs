@@ -0,0 +1,658 @@
+//! Checkpointing of the state of the operators.
+//!
+//! When [`EnvironmentConfig::checkpoint`](crate::EnvironmentConfig::checkpoint) is set, the
+//! sources periodically inject a [`StreamElement::Checkpoint`](crate::operator::StreamElement)
+//! barrier into the stream. When a block receives a barrier from one of its previous replicas it
+//! stops reading from it until the same barrier is received from all the others (the barriers
+//! are _aligned_), then it forwards the barrier. Each stateful operator saves a snapshot of its
+//! state when the barrier reaches it, and a checkpoint is complete when its barrier has reached
+//! the end of all the blocks of the job.
+//!
+//! A job started with the same configuration and the same job graph resumes from the last
+//! complete checkpoint found in the directory: the operators restore their state and the sources
+//! seek to the position they had when the checkpoint was taken. The files
+//! ([`FileSource`](crate::operator::source::FileSource),
+//! [`CsvSource`](crate::operator::source::CsvSource)) are read from the saved offset, while the
+//! [`IteratorSource`](crate::operator::source::IteratorSource), the
+//! [`ParallelIteratorSource`](crate::operator::source::ParallelIteratorSource) and the
+//! [`GeneratorSource`](crate::operator::source::GeneratorSource) skip the items they already
+//! emitted. The sources that cannot be read again (the sockets, the async streams and the channels
+//! that are not [shared](crate::operator::source::ChannelSource::share)) cannot be used with the
+//! checkpoints.
+//!
+//! The checkpoints are deleted when the job completes, unless it was cancelled with
+//! [`JobHandle::cancel`](crate::JobHandle::cancel) or it failed: only those jobs can be resumed.
+//!
+//! The state is saved by the operators whose state can be serialized:
+//!
+//! - the keyed aggregations performed with [`Stream::group_by_fold`](crate::Stream::group_by_fold)
+//!   (and the operators based on it, like `group_by_reduce`, `group_by_sum`, ...);
+//! - the global aggregations performed with [`Stream::fold_assoc`](crate::Stream::fold_assoc)
+//!   (and the operators based on it, like `reduce_assoc`, `sum`, ...);
+//! - [`Stream::rich_map_with_state`](crate::Stream::rich_map_with_state).
+//!
+//! Custom operators can save their state using [`ExecutionMetadata::operator_state`] during
+//! `setup`. The other stateful operators are not checkpointed: among others the windows, the
+//! joins, the iterations, [`rich_map`](crate::Stream::rich_map) (use `rich_map_with_state`
+//! instead), [`reorder`](crate::Stream::reorder), [`sort_by_key`](crate::Stream::sort_by_key) and
+//! [`zip`](crate::Stream::zip).
+//!
+//! When the checkpoints are enabled, a job with a source or an operator that does not support them
+//! fails before processing any element, with a [`JobError`](crate::JobError) of kind
+//! [`Checkpoint`](crate::JobErrorKind::Checkpoint).
+//!
+//! In a distributed execution each host saves the state of its own replicas, a checkpoint is
+//! restored only if it's complete on all the hosts: the directory should be shared between the
+//! hosts.
+//!
+//! ## Rescaling
+//!
+//! A job started with [`StreamEnvironment::execute_rescalable`](crate::StreamEnvironment::execute_rescalable)
+//! can change the number of its replicas with [`JobHandle::rescale`](crate::JobHandle::rescale).
+//! The sources pause after emitting the barrier of a last checkpoint, the job is torn down once
+//! the checkpoint is complete and a new job is started with the new number of replicas, resuming
+//! from that checkpoint.
+//!
+//! When the number of replicas of a block changed, the keyed aggregations merge the state saved by
+//! all the previous replicas and each replica keeps the keys it's responsible for, following the
+//! hash of the key used by [`group_by`](crate::Stream::group_by). The partial results of the
+//! global aggregations are assigned to the new replicas in a round-robin fashion. The state of
+//! [`Stream::rich_map_with_state`](crate::Stream::rich_map_with_state), of the custom operators
+//! and the position of the rewindable sources are restored only by the replicas that existed
+//! before rescaling.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::time::Instant;
+
+use once_cell::sync::OnceCell;
+use parking_lot::{Condvar, Mutex};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::config::{CheckpointConfig, EnvironmentConfig, ExecutionRuntime};
+use crate::job::{JobState, RescaleError};
+use crate::network::Coord;
+use crate::scheduler::{BlockId, ExecutionMetadata, HostId, ReplicaId};
+
+/// Identifier of a checkpoint, increasing during the execution of the job.
+pub type CheckpointId = u64;
+
+/// Handle to the state of an operator replica saved in the checkpoints.
+///
+/// Obtained with [`ExecutionMetadata::operator_state`].
+#[derive(Clone, Debug)]
+pub struct OperatorState {
+    job: Arc<JobState>,
+    block_id: BlockId,
+    replica_id: ReplicaId,
+    /// The index of the operator among the ones of the replica that registered their state.
+    index: usize,
+}
+
+impl OperatorState {
+    pub(crate) fn new(
+        job: Arc<JobState>,
+        block_id: BlockId,
+        replica_id: ReplicaId,
+        index: usize,
+    ) -> Self {
+        Self {
+            job,
+            block_id,
+            replica_id,
+            index,
+        }
+    }
+
+    fn name(&self, replica_id: ReplicaId) -> String {
+        format!(
+            "b{:02}-r{:02}-op{:02}",
+            self.block_id, replica_id, self.index
+        )
+    }
+
+    /// The state saved in the checkpoint the job resumed from, if any.
+    pub fn restore<T: DeserializeOwned>(&self) -> Option<T> {
+        self.restore_bytes()
+            .map(|bytes| bincode::deserialize(&bytes).expect("Corrupted checkpoint"))
+    }
+
+    /// Save the state of the operator for the checkpoint `id`.
+    ///
+    /// This should be called when the barrier of the checkpoint is received, before forwarding
+    /// it.
+    pub fn snapshot<T: Serialize>(&self, id: CheckpointId, state: &T) {
+        let bytes = bincode::serialize(state).expect("Cannot serialize the operator state");
+        self.snapshot_bytes(id, &bytes);
+    }
+
+    pub(crate) fn restore_bytes(&self) -> Option<Vec<u8>> {
+        let coordinator = self.job.checkpoints()?;
+        let path = coordinator
+            .host_dir(coordinator.restored?)
+            .join(self.name(self.replica_id));
+        match fs::read(&path) {
+            Ok(bytes) => Some(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => panic!("Cannot read checkpoint {}: {e}", path.display()),
+        }
+    }
+
+    /// The states saved by all the replicas of this operator on this host, sorted by replica.
+    pub(crate) fn restore_all_bytes(&self) -> Vec<(ReplicaId, Vec<u8>)> {
+        let Some(coordinator) = self.job.checkpoints() else {
+            return vec![];
+        };
+        let Some(restored) = coordinator.restored else {
+            return vec![];
+        };
+        let dir = coordinator.host_dir(restored);
+        let prefix = format!("b{:02}-r", self.block_id);
+        let suffix = format!("-op{:02}", self.index);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return vec![];
+        };
+        let mut states: Vec<_> = entries
+            .filter_map(|e| {
+                let path = e.ok()?.path();
+                let replica_id = path
+                    .file_name()?
+                    .to_str()?
+                    .strip_prefix(&prefix)?
+                    .strip_suffix(&suffix)?
+                    .parse()
+                    .ok()?;
+                let bytes = fs::read(&path)
+                    .unwrap_or_else(|e| panic!("Cannot read checkpoint {}: {e}", path.display()));
+                Some((replica_id, bytes))
+            })
+            .collect();
+        states.sort_by_key(|(replica_id, _)| *replica_id);
+        states
+    }
+
+    pub(crate) fn snapshot_bytes(&self, id: CheckpointId, bytes: &[u8]) {
+        let Some(coordinator) = self.job.checkpoints() else {
+            return;
+        };
+        let dir = coordinator.host_dir(id);
+        let path = dir.join(self.name(self.replica_id));
+        fs::create_dir_all(&dir)
+            .and_then(|_| fs::write(&path, bytes))
+            .unwrap_or_else(|e| panic!("Cannot write checkpoint {}: {e}", path.display()));
+    }
+}
+
+/// Serialization hook for the state of an operator.
+///
+/// The operators are generic over the type of their state, which is not always serializable: the
+/// methods that build them provide the codec only when the type allows it.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub(crate) struct StateCodec<T> {
+    encode: fn(&T) -> Vec<u8>,
+    decode: fn(&[u8]) -> T,
+}
+
+impl<T: Serialize + DeserializeOwned> StateCodec<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            encode: |state| bincode::serialize(state).expect("Cannot serialize the operator state"),
+            decode: |bytes| bincode::deserialize(bytes).expect("Corrupted checkpoint"),
+        }
+    }
+}
+
+/// The [`OperatorState`] of an operator together with the codec of its state.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub(crate) struct Checkpointed<T> {
+    codec: Option<StateCodec<T>>,
+    state: Option<OperatorState>,
+}
+
+impl<T> Default for Checkpointed<T> {
+    fn default() -> Self {
+        Self {
+            codec: None,
+            state: None,
+        }
+    }
+}
+
+impl<T> Checkpointed<T> {
+    pub(crate) fn new(codec: Option<StateCodec<T>>) -> Self {
+        Self { codec, state: None }
+    }
+
+    /// Register the operator, returning the state to restore if any.
+    pub(crate) fn setup(&mut self, metadata: &mut ExecutionMetadata) -> Option<T> {
+        let codec = self.codec.as_ref()?;
+        self.state = metadata.operator_state();
+        let bytes = self.state.as_ref()?.restore_bytes()?;
+        Some((codec.decode)(&bytes))
+    }
+
+    /// The states saved by the previous replicas of the operator, if the number of replicas of the
+    /// block on this host changed since the checkpoint the job resumed from.
+    ///
+    /// The states are tagged with the id of the replica that saved them, the operator is
+    /// responsible for redistributing them among the new replicas.
+    pub(crate) fn rescaled(&self, metadata: &ExecutionMetadata) -> Option<Vec<(ReplicaId, T)>> {
+        let codec = self.codec.as_ref()?;
+        let states = self.state.as_ref()?.restore_all_bytes();
+        let replicas = metadata
+            .replicas
+            .iter()
+            .filter(|coord| coord.host_id == metadata.coord.host_id)
+            .count();
+        if states.is_empty() || states.len() == replicas {
+            return None;
+        }
+        Some(
+            states
+                .into_iter()
+                .map(|(replica_id, bytes)| (replica_id, (codec.decode)(&bytes)))
+                .collect(),
+        )
+    }
+
+    /// Whether the state has to be saved.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.codec.is_some() && self.state.is_some()
+    }
+
+    pub(crate) fn snapshot(&self, id: CheckpointId, state: &T) {
+        if let (Some(codec), Some(op_state)) = (&self.codec, &self.state) {
+            op_state.snapshot_bytes(id, &(codec.encode)(state));
+        }
+    }
+}
+
+/// Keeps track of the checkpoints of the job on this host.
+#[derive(Debug)]
+pub(crate) struct CheckpointCoordinator {
+    dir: PathBuf,
+    interval: std::time::Duration,
+    host_id: HostId,
+    num_hosts: usize,
+    /// The last complete checkpoint found when the job was created.
+    restored: Option<CheckpointId>,
+    /// The last checkpoint the sources should emit.
+    triggered: AtomicU64,
+    /// The last checkpoint before the job is stopped, `0` if the job is not being stopped.
+    stop: AtomicU64,
+    /// Why the checkpoints are disabled, set when the job graph does not support them.
+    disabled: OnceCell<String>,
+    progress: Mutex<Progress>,
+    /// Notified when a checkpoint completes, a worker finishes, the checkpoints are disabled, the
+    /// job is aborted, or the state of the last checkpoint changes.
+    progress_changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct Progress {
+    workers: Vec<Coord>,
+    /// The last checkpoint whose barrier reached the end of each worker.
+    acked: HashMap<Coord, CheckpointId>,
+    finished: HashSet<Coord>,
+    completed: CheckpointId,
+    /// Whether all the workers have been set up.
+    ready: bool,
+}
+
+impl CheckpointCoordinator {
+    pub(crate) fn new(config: &EnvironmentConfig, checkpoint: &CheckpointConfig) -> Self {
+        let num_hosts = match &config.runtime {
+            ExecutionRuntime::Local(_) => 1,
+            ExecutionRuntime::Remote(remote) => remote.hosts.len(),
+        };
+        let restored = last_complete(&checkpoint.dir, num_hosts);
+        if let Some(id) = restored {
+            info!(
+                "resuming from checkpoint {id} in {}",
+                checkpoint.dir.display()
+            );
+        }
+        let coordinator = Self {
+            dir: checkpoint.dir.clone(),
+            interval: checkpoint.interval,
+            host_id: config.host_id.unwrap_or_default(),
+            num_hosts,
+            restored,
+            triggered: AtomicU64::new(restored.unwrap_or(0)),
+            stop: AtomicU64::new(0),
+            disabled: OnceCell::new(),
+            progress: Mutex::new(Progress {
+                completed: restored.unwrap_or(0),
+                ..Default::default()
+            }),
+            progress_changed: Condvar::new(),
+        };
+        // the incomplete checkpoints of a previous execution will be taken again
+        for id in checkpoint_ids(&coordinator.dir) {
+            if id > restored.unwrap_or(0) {
+                let _ = fs::remove_dir_all(coordinator.host_dir(id));
+                let _ = fs::remove_file(coordinator.marker(id, coordinator.host_id));
+            }
+        }
+        coordinator
+    }
+
+    /// The checkpoint the job resumed from.
+    pub(crate) fn restored(&self) -> Option<CheckpointId> {
+        self.restored
+    }
+
+    /// The last checkpoint the sources should emit.
+    #[inline]
+    pub(crate) fn triggered(&self) -> CheckpointId {
+        match self.stop() {
+            Some(stop) => stop,
+            None => self.triggered.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The last checkpoint the sources should emit before pausing, if the job is being stopped.
+    #[inline]
+    pub(crate) fn stop(&self) -> Option<CheckpointId> {
+        match self.stop.load(Ordering::Relaxed) {
+            0 => None,
+            id => Some(id),
+        }
+    }
+
+    /// Trigger a last checkpoint, after which the sources pause until the job is torn down.
+    ///
+    /// Returns `None` if the job does not support checkpoints.
+    pub(crate) fn trigger_stop(&self) -> Option<CheckpointId> {
+        if self.disabled.get().is_some() {
+            return None;
+        }
+        let id = self.triggered.load(Ordering::Relaxed) + 1;
+        self.stop.store(id, Ordering::Relaxed);
+        self.triggered.fetch_max(id, Ordering::Relaxed);
+        debug!("triggering the last checkpoint {id}");
+        Some(id)
+    }
+
+    /// Give up the last checkpoint `id`: the paused sources resume, and the checkpoint completes
+    /// like the periodic ones.
+    pub(crate) fn cancel_stop(&self, id: CheckpointId) {
+        let _progress = self.progress.lock();
+        if self
+            .stop
+            .compare_exchange(id, 0, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            debug!("cancelling the last checkpoint {id}");
+        }
+        self.progress_changed.notify_all();
+    }
+
+    /// Block a source that emitted the barrier of the last checkpoint `stop`.
+    ///
+    /// Returns `true` when the job is torn down and `false` if the last checkpoint has been
+    /// cancelled with [`cancel_stop`](Self::cancel_stop).
+    pub(crate) fn pause(&self, stop: CheckpointId, is_aborted: impl Fn() -> bool) -> bool {
+        let mut progress = self.progress.lock();
+        loop {
+            if is_aborted() {
+                return true;
+            }
+            if self.stop() != Some(stop) {
+                return false;
+            }
+            self.progress_changed.wait(&mut progress);
+        }
+    }
+
+    /// Wake up the paused sources, the job is being aborted.
+    pub(crate) fn wake_paused(&self) {
+        let _progress = self.progress.lock();
+        self.progress_changed.notify_all();
+    }
+
+    /// Wait until all the workers are set up, the operators that do not support checkpoints
+    /// disable them during the setup.
+    ///
+    /// Returns an error if the checkpoints are disabled or the deadline expires first.
+    pub(crate) fn wait_ready(&self, deadline: Instant) -> Result<(), RescaleError> {
+        let mut progress = self.progress.lock();
+        while !progress.ready {
+            if self
+                .progress_changed
+                .wait_until(&mut progress, deadline)
+                .timed_out()
+            {
+                return Err(RescaleError::Timeout);
+            }
+        }
+        match self.disabled.get() {
+            Some(reason) => Err(RescaleError::Unsupported(reason.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Wait until the checkpoint `id` is complete on this host.
+    ///
+    /// Returns an error if the checkpoint will never complete, because all the workers finished
+    /// or the checkpoints have been disabled, or if the deadline expires first.
+    pub(crate) fn wait_complete(
+        &self,
+        id: CheckpointId,
+        deadline: Instant,
+    ) -> Result<(), RescaleError> {
+        let mut progress = self.progress.lock();
+        loop {
+            if progress.completed >= id {
+                return Ok(());
+            }
+            if let Some(reason) = self.disabled.get() {
+                return Err(RescaleError::Unsupported(reason.clone()));
+            }
+            // the workers register themselves when the job starts
+            let finished = !progress.workers.is_empty()
+                && progress
+                    .workers
+                    .iter()
+                    .all(|coord| progress.finished.contains(coord));
+            if finished {
+                return Err(RescaleError::NotPaused);
+            }
+            if self
+                .progress_changed
+                .wait_until(&mut progress, deadline)
+                .timed_out()
+            {
+                return Err(RescaleError::Timeout);
+            }
+        }
+    }
+
+    /// Stop taking checkpoints, an operator of the job does not support them.
+    ///
+    /// This is called during the setup of the operator: the job fails before its workers start,
+    /// see [`JobErrorKind::Checkpoint`](crate::JobErrorKind::Checkpoint).
+    pub(crate) fn disable(&self, reason: &str) {
+        if self.disabled.set(reason.to_string()).is_ok() {
+            debug!("checkpoints not supported: {reason}");
+            let _progress = self.progress.lock();
+            self.progress_changed.notify_all();
+        }
+    }
+
+    /// Why the checkpoints are not supported by the job, if they are not.
+    pub(crate) fn disabled(&self) -> Option<&str> {
+        self.disabled.get().map(String::as_str)
+    }
+
+    /// Delete the checkpoints of this host, the job completed and it won't be resumed.
+    pub(crate) fn clear(&self) {
+        for id in checkpoint_ids(&self.dir) {
+            let _ = fs::remove_dir_all(self.host_dir(id));
+            let _ = fs::remove_file(self.marker(id, self.host_id));
+            // the directory is removed by the last host
+            let _ = fs::remove_dir(self.checkpoint_dir(id));
+        }
+    }
+
+    fn checkpoint_dir(&self, id: CheckpointId) -> PathBuf {
+        self.dir.join(format!("{id:010}"))
+    }
+
+    fn host_dir(&self, id: CheckpointId) -> PathBuf {
+        self.checkpoint_dir(id)
+            .join(format!("h{:02}", self.host_id))
+    }
+
+    pub(crate) fn register_worker(&self, coord: Coord) {
+        self.progress.lock().workers.push(coord);
+    }
+
+    /// The barrier of checkpoint `id` reached the end of the worker.
+    pub(crate) fn ack(&self, coord: Coord, id: CheckpointId) {
+        let mut progress = self.progress.lock();
+        progress.acked.insert(coord, id);
+        self.try_complete(&mut progress);
+    }
+
+    /// The worker has terminated: it won't take part in the next checkpoints.
+    pub(crate) fn finish(&self, coord: Coord) {
+        let mut progress = self.progress.lock();
+        progress.finished.insert(coord);
+        self.try_complete(&mut progress);
+        self.progress_changed.notify_all();
+    }
+
+    fn try_complete(&self, progress: &mut Progress) {
+        loop {
+            let next = progress.completed + 1;
+            let mut acked = false;
+            for coord in &progress.workers {
+                match progress.acked.get(coord) {
+                    Some(&id) if id >= next => acked = true,
+                    _ if progress.finished.contains(coord) => {}
+                    _ => return,
+                }
+            }
+            // a checkpoint that no worker has seen is not complete
+            if !acked {
+                return;
+            }
+            progress.completed = next;
+            self.complete(next);
+            self.progress_changed.notify_all();
+        }
+    }
+
+    fn complete(&self, id: CheckpointId) {
+        let marker = self.marker(id, self.host_id);
+        if let Err(e) = fs::create_dir_all(self.host_dir(id)).and_then(|_| fs::write(&marker, b""))
+        {
+            error!("cannot complete checkpoint {id}: {e}");
+            return;
+        }
+        debug!("checkpoint {id} completed on host {}", self.host_id);
+        // the previous checkpoints are not needed once this is complete on all the hosts
+        if !(0..self.num_hosts).all(|h| self.marker(id, h as HostId).exists()) {
+            return;
+        }
+        for old in checkpoint_ids(&self.dir)
+            .into_iter()
+            .filter(|&old| old < id)
+        {
+            let _ = fs::remove_dir_all(self.host_dir(old));
+            let _ = fs::remove_file(self.marker(old, self.host_id));
+            let _ = fs::remove_dir(self.checkpoint_dir(old));
+        }
+    }
+
+    fn marker(&self, id: CheckpointId, host_id: HostId) -> PathBuf {
+        self.checkpoint_dir(id)
+            .join(format!("h{host_id:02}.complete"))
+    }
+
+    /// Periodically trigger a new checkpoint, until the returned sender is dropped.
+    ///
+    /// The timer is started once all the workers have been set up.
+    pub(crate) fn start_timer(self: &Arc<Self>) -> Sender<()> {
+        self.progress.lock().ready = true;
+        self.progress_changed.notify_all();
+        let (stop, stopped) = channel::<()>();
+        let this = self.clone();
+        std::thread::Builder::new()
+            .name("noir-checkpoint".into())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(this.interval) {
+                    if this.disabled.get().is_none() && this.stop().is_none() {
+                        let id = this.triggered.fetch_add(1, Ordering::Relaxed) + 1;
+                        debug!("triggering checkpoint {id}");
+                    }
+                }
+            })
+            .unwrap();
+        stop
+    }
+}
+
+/// The ids of the checkpoints inside the directory.
+fn checkpoint_ids(dir: &Path) -> Vec<CheckpointId> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    entries
+        .filter_map(|e| e.ok()?.file_name().to_str()?.parse().ok())
+        .collect()
+}
+
+/// The last checkpoint inside the directory that is complete on all the hosts.
+fn last_complete(dir: &Path, num_hosts: usize) -> Option<CheckpointId> {
+    checkpoint_ids(dir)
+        .into_iter()
+        .filter(|id| {
+            let checkpoint = dir.join(format!("{id:010}"));
+            (0..num_hosts).all(|h| checkpoint.join(format!("h{h:02}.complete")).exists())
+        })
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::config::CheckpointConfig;
+
+    #[test]
+    fn completion() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let config = EnvironmentConfig::local(1);
+        let checkpoint = CheckpointConfig::new(dir, Duration::from_secs(60));
+        let coordinator = CheckpointCoordinator::new(&config, &checkpoint);
+        let (a, b) = (Coord::new(0, 0, 0), Coord::new(1, 0, 0));
+        coordinator.register_worker(a);
+        coordinator.register_worker(b);
+
+        coordinator.ack(a, 1);
+        assert_eq!(last_complete(dir, 1), None);
+        coordinator.ack(b, 1);
+        assert_eq!(last_complete(dir, 1), Some(1));
+        // a finished worker does not hold back the checkpoints
+        coordinator.finish(b);
+        coordinator.ack(a, 2);
+        assert_eq!(last_complete(dir, 1), Some(2));
+        // the older checkpoints are removed
+        assert_eq!(checkpoint_ids(dir), vec![2]);
+
+        let coordinator = CheckpointCoordinator::new(&config, &checkpoint);
+        assert_eq!(coordinator.restored(), Some(2));
+        assert_eq!(coordinator.triggered(), 2);
+    }
+}
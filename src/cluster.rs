@@ -0,0 +1,153 @@
+//! Execution of a remote environment as a cluster of processes on this machine.
+//!
+//! With [`Launcher::Cluster`](crate::config::Launcher::Cluster) each host of the configuration is
+//! a new process of the current executable, started with the same arguments. The processes
+//! communicate through the network like in a remote execution, so the serialization of the data
+//! and the network layer are exercised without the need of real hosts reachable via SSH.
+
+use std::ffi::OsStr;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::time::Instant;
+
+use crate::config::{RemoteRuntimeConfig, CONFIG_ENV_VAR, HOST_ID_ENV_VAR};
+use crate::scheduler::HostId;
+use crate::TracingData;
+
+/// The worker processes of a cluster running on this machine.
+#[derive(Debug)]
+pub struct LocalCluster {
+    children: Vec<(HostId, Child)>,
+    config: RemoteRuntimeConfig,
+}
+
+impl LocalCluster {
+    /// Spawn a process of the current executable for each host of the configuration, passing
+    /// `args` as command line arguments.
+    ///
+    /// The output of the processes is forwarded to the output of this process when waiting for
+    /// them with [`wait`](LocalCluster::wait).
+    pub fn spawn<I, S>(config: &RemoteRuntimeConfig, args: I) -> std::io::Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let config_yaml = serde_yaml::to_string(config)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        let args: Vec<_> = args.into_iter().map(|a| a.as_ref().to_owned()).collect();
+        let executable = std::env::current_exe()?;
+        let mut children = Vec::with_capacity(config.hosts.len());
+        for host_id in 0..config.hosts.len() as HostId {
+            log::debug!("spawning cluster worker for host {host_id}");
+            let child = Command::new(&executable)
+                .args(&args)
+                .env(HOST_ID_ENV_VAR, host_id.to_string())
+                .env(CONFIG_ENV_VAR, &config_yaml)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+            children.push((host_id, child));
+        }
+        Ok(Self {
+            children,
+            config: config.clone(),
+        })
+    }
+
+    /// Wait for all the processes to exit, forwarding their output.
+    ///
+    /// Returns the bitwise or of the exit codes of the processes, `0` means that all of them
+    /// succeeded.
+    pub fn wait(mut self) -> i32 {
+        let mut tracing_data = TracingData::default();
+        let mut exit_code_or = 0;
+        std::thread::scope(|s| {
+            let mut handles = Vec::with_capacity(self.children.len());
+            for (host_id, child) in self.children.iter_mut() {
+                let host_id = *host_id;
+                let stdout = BufReader::new(child.stdout.take().unwrap());
+                let stderr = BufReader::new(child.stderr.take().unwrap());
+                handles.push(s.spawn(move || forward_output(host_id, stdout, stderr)));
+            }
+            for handle in handles {
+                if let Some(data) = handle.join().unwrap() {
+                    tracing_data += data;
+                }
+            }
+        });
+        for (host_id, child) in self.children.iter_mut() {
+            let exit_code = match child.wait() {
+                Ok(status) => status.code().unwrap_or(1),
+                Err(e) => {
+                    error!("Failed to wait for the worker of host {}: {:?}", host_id, e);
+                    1
+                }
+            };
+            info!("{}|Exit status: {}", host_id, exit_code);
+            exit_code_or |= exit_code;
+        }
+        if let Some(path) = &self.config.tracing_dir {
+            tracing_data.save(path);
+        }
+        exit_code_or
+    }
+}
+
+/// Forward the output of the process of a host to the output of this process, prefixing each line
+/// with the id of the host.
+///
+/// Returns the tracing data that the process printed on its standard error, if any.
+pub(crate) fn forward_output<O, E>(host_id: HostId, stdout: O, stderr: E) -> Option<TracingData>
+where
+    O: BufRead + Send,
+    E: BufRead,
+{
+    std::thread::scope(|s| {
+        s.spawn(move || {
+            for l in stdout.lines() {
+                println!(
+                    "{}|{}",
+                    host_id,
+                    l.unwrap_or_else(|e| format!("ERROR: {e}"))
+                );
+            }
+        });
+        let mut tracing = None;
+        for line in stderr.lines().map_while(Result::ok) {
+            if let Some(pos) = line.find("__noir2_TRACING_DATA__") {
+                let json_data = &line[(pos + "__noir2_TRACING_DATA__ ".len())..];
+                match serde_json::from_str(json_data) {
+                    Ok(data) => tracing = Some(data),
+                    Err(err) => {
+                        error!("Corrupted tracing data from host {}: {:?}", host_id, err);
+                    }
+                }
+            } else {
+                eprintln!("{host_id}|{line}");
+            }
+        }
+        tracing
+    })
+}
+
+/// Spawn the processes of the cluster and wait until all of them complete, after that exit from
+/// the process.
+///
+/// If this was already a spawned process do nothing.
+pub(crate) fn spawn_cluster_workers(config: RemoteRuntimeConfig) {
+    // if this process already comes from a the spawner do not spawn again!
+    if std::env::var_os(HOST_ID_ENV_VAR).is_some() {
+        return;
+    }
+
+    info!("starting {} cluster workers", config.hosts.len());
+    let start = Instant::now();
+    let cluster = LocalCluster::spawn(&config, std::env::args_os().skip(1))
+        .expect("Failed to spawn the cluster workers");
+    let exit_code = cluster.wait();
+    log::info!("total time: {:?}", start.elapsed());
+
+    // all the processes have finished, exit to avoid running the environment inside the spawner
+    // process
+    std::process::exit(exit_code);
+}
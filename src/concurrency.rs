@@ -0,0 +1,153 @@
+//! Bounded concurrency of the replicas of a host.
+//!
+//! Each replica always runs on its own thread. With
+//! [`Concurrency::Limited`](crate::config::Concurrency::Limited) a replica also has to hold one of
+//! the slots of the [`ConcurrencyLimit`] of the host to run its operators. Every time a replica is
+//! about to wait (for its input channel to have a message, for its output channel to have space,
+//! for the iteration state, ...) it gives its slot to another replica, and takes one back before
+//! continuing. This way at most `max_running` threads are runnable at the same time,
+//! independently of the number of blocks of the job, but the number of threads does not change:
+//! this is not a task scheduler, the operators are not suspended without blocking their thread.
+//!
+//! The replicas without a limit (i.e. with
+//! [`Concurrency::Unlimited`](crate::config::Concurrency::Unlimited)) are not affected by the
+//! functions of this module.
+
+use std::cell::{Cell, RefCell};
+use std::sync::Arc;
+
+use parking_lot::{Condvar, Mutex};
+
+/// The number of elements a replica processes before yielding its slot to the waiting replicas.
+pub(crate) const TIME_SLICE: usize = 1024;
+
+/// The slots to run on, shared by all the replicas of a host.
+#[derive(Debug)]
+pub(crate) struct ConcurrencyLimit {
+    free: Mutex<usize>,
+    available: Condvar,
+}
+
+impl ConcurrencyLimit {
+    pub(crate) fn new(max_running: usize) -> Self {
+        assert!(
+            max_running > 0,
+            "At least one replica must be allowed to run at the same time"
+        );
+        Self {
+            free: Mutex::new(max_running),
+            available: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self) {
+        let mut free = self.free.lock();
+        while *free == 0 {
+            self.available.wait(&mut free);
+        }
+        *free -= 1;
+    }
+
+    fn release(&self) {
+        *self.free.lock() += 1;
+        self.available.notify_one();
+    }
+
+    /// Whether some replicas are waiting for a slot.
+    fn is_contended(&self) -> bool {
+        *self.free.lock() == 0
+    }
+}
+
+thread_local! {
+    /// The limit of the replica running on this thread.
+    static LIMIT: RefCell<Option<Arc<ConcurrencyLimit>>> = const { RefCell::new(None) };
+    /// Whether the replica running on this thread is holding a slot of its limit.
+    static HOLDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run the current thread under the limit, waiting for a free slot.
+pub(crate) fn enter(limit: Arc<ConcurrencyLimit>) {
+    limit.acquire();
+    HOLDING.with(|h| h.set(true));
+    LIMIT.with(|l| *l.borrow_mut() = Some(limit));
+}
+
+/// Leave the limit of the current thread, releasing its slot.
+pub(crate) fn exit() {
+    if let Some(limit) = LIMIT.with(|l| l.borrow_mut().take()) {
+        if HOLDING.with(|h| h.replace(false)) {
+            limit.release();
+        }
+    }
+}
+
+/// Whether the current thread runs under a limit.
+#[inline]
+pub(crate) fn is_limited() -> bool {
+    LIMIT.with(|l| l.borrow().is_some())
+}
+
+/// Run a function that may block the current thread, yielding the slot of the limit while it
+/// runs.
+#[inline]
+pub(crate) fn blocking<R>(f: impl FnOnce() -> R) -> R {
+    let Some(limit) = LIMIT.with(|l| l.borrow().clone()) else {
+        return f();
+    };
+    HOLDING.with(|h| h.set(false));
+    limit.release();
+    let res = f();
+    limit.acquire();
+    HOLDING.with(|h| h.set(true));
+    res
+}
+
+/// Let a waiting replica run, if any.
+pub(crate) fn yield_now() {
+    let Some(limit) = LIMIT.with(|l| l.borrow().clone()) else {
+        return;
+    };
+    if limit.is_contended() {
+        blocking(std::thread::yield_now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn bounded_concurrency() {
+        let limit = Arc::new(ConcurrencyLimit::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let threads = (0..8)
+            .map(|_| {
+                let (limit, running, max_running) =
+                    (limit.clone(), running.clone(), max_running.clone());
+                std::thread::spawn(move || {
+                    enter(limit);
+                    for _ in 0..3 {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_running.fetch_max(now, Ordering::SeqCst);
+                        std::thread::sleep(Duration::from_millis(5));
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        // waiting does not count towards the running replicas
+                        blocking(|| std::thread::sleep(Duration::from_millis(5)));
+                    }
+                    exit();
+                })
+            })
+            .collect::<Vec<_>>();
+        for t in threads {
+            t.join().unwrap();
+        }
+        assert!(max_running.load(Ordering::SeqCst) <= 2);
+        assert_eq!(*limit.free.lock(), 2);
+    }
+}
@@ -3,9 +3,11 @@
 //! See the documentation of [`EnvironmentConfig`] for more details.
 
 use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::{bail, Result};
 #[cfg(feature = "clap")]
@@ -22,6 +24,11 @@ pub const HOST_ID_ENV_VAR: &str = "NOIR_HOST_ID";
 /// required to have it on all the hosts.
 pub const CONFIG_ENV_VAR: &str = "NOIR_CONFIG";
 
+/// The first port used by the hosts of [`EnvironmentConfig::cluster`].
+const CLUSTER_BASE_PORT: u16 = 9500;
+/// The maximum number of ports reserved for each host of [`EnvironmentConfig::cluster`].
+const CLUSTER_PORTS_PER_HOST: u16 = 1000;
+
 /// The runtime configuration of the environment,
 ///
 /// This configuration selects which runtime to use for this execution. The runtime is either local
@@ -62,6 +69,17 @@ pub const CONFIG_ENV_VAR: &str = "NOIR_CONFIG";
 /// let mut env = StreamEnvironment::new(config);
 /// ```
 ///
+/// ## Cluster environment
+///
+/// All the hosts are processes of this machine, communicating through the loopback interface.
+///
+/// ```no_run
+/// # use noir::{StreamEnvironment, EnvironmentConfig};
+/// let config = EnvironmentConfig::cluster(2, 4);
+/// let mut env = StreamEnvironment::new(config);
+/// env.spawn_remote_workers();
+/// ```
+///
 /// ## From command line arguments
 /// This reads from `std::env::args()` and reads the most common options (`--local`, `--remote`,
 /// `--verbose`). All the unparsed options will be returned into `args`. You can use `--help` to see
@@ -82,6 +100,114 @@ pub struct EnvironmentConfig {
     /// Skip the check that prevents two remote environments with different environments to be
     /// constructed.
     pub skip_single_remote_check: bool,
+    /// If specified the state of the operators is periodically saved, and the job resumes from
+    /// the last checkpoint. See the [`checkpoint`](crate::checkpoint) module.
+    pub checkpoint: Option<CheckpointConfig>,
+    /// How many replicas can run at the same time on each host.
+    pub concurrency: Concurrency,
+    /// How the threads of the replicas are pinned to the cores of each host.
+    pub affinity: CoreAffinity,
+    /// If specified the metrics of the job are collected while it runs and periodically
+    /// reported. See the [`metrics`](crate::metrics) module.
+    pub metrics: Option<MetricsConfig>,
+}
+
+/// How many replicas of the blocks can run at the same time on a host.
+///
+/// Each replica of each block always runs on its own thread.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Concurrency {
+    /// All the replicas run at the same time, the operating system schedules their threads.
+    #[default]
+    Unlimited,
+    /// At most `max_running` replicas run at the same time.
+    ///
+    /// A replica lets another one run when it waits for its input channel (or for space in its
+    /// output channel). This bounds the number of runnable threads of a job with many blocks,
+    /// reducing the contention for the cores, but not the number of threads: the waiting replicas
+    /// are parked threads, not suspended tasks.
+    Limited {
+        /// The number of replicas that can run at the same time, usually the number of cores.
+        max_running: usize,
+    },
+}
+
+/// How the threads of the replicas are pinned to the cores of a host.
+///
+/// The cores are the ones the process is allowed to run on (e.g. restricted by `taskset`), the
+/// policy is ignored on the platforms that don't support pinning threads.
+///
+/// The policies do not know about the NUMA nodes of the host: to keep the replicas of a job on a
+/// single node, restrict the process to the cores of that node (e.g. with `numactl`).
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum CoreAffinity {
+    /// The threads are not pinned, the operating system decides where they run.
+    #[default]
+    None,
+    /// The replica `r` of every block is pinned to the `r`-th core (modulo the number of cores).
+    ///
+    /// The replicas connected by a forward (non-shuffling) edge run on the same core.
+    Replica,
+    /// The replicas are pinned to the cores in a round-robin fashion, in the order they are
+    /// spawned.
+    RoundRobin,
+}
+
+/// Where and how often to take the checkpoints of the job.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CheckpointConfig {
+    /// The directory where the checkpoints are saved.
+    pub dir: PathBuf,
+    /// The time between two checkpoints.
+    pub interval: Duration,
+}
+
+impl CheckpointConfig {
+    pub fn new<P: Into<PathBuf>>(dir: P, interval: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            interval,
+        }
+    }
+}
+
+/// How often and where to report the metrics of the job.
+///
+/// More reporters can be added with
+/// [`StreamEnvironment::add_metrics_reporter`](crate::StreamEnvironment::add_metrics_reporter).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MetricsConfig {
+    /// The time between two reports.
+    pub interval: Duration,
+    /// If specified the metrics are exposed in the Prometheus text format over HTTP on this
+    /// address.
+    pub prometheus: Option<SocketAddr>,
+    /// Whether the metrics are periodically written to the log.
+    pub log: bool,
+}
+
+impl MetricsConfig {
+    /// Report the metrics every `interval`, without any built-in reporter.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            prometheus: None,
+            log: false,
+        }
+    }
+
+    /// Expose the metrics in the Prometheus text format over HTTP on `addr`.
+    pub fn prometheus(mut self, addr: SocketAddr) -> Self {
+        self.prometheus = Some(addr);
+        self
+    }
+
+    /// Periodically write the metrics to the log.
+    pub fn log(mut self) -> Self {
+        self.log = true;
+        self
+    }
 }
 
 /// Which kind of environment to use for the execution.
@@ -112,6 +238,24 @@ pub struct RemoteRuntimeConfig {
     /// Remove remote binaries after execution
     #[serde(default)]
     pub cleanup_executable: bool,
+    /// How the workers of the hosts are spawned.
+    #[serde(default)]
+    pub launcher: Launcher,
+}
+
+/// How the workers of a remote environment are spawned.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Launcher {
+    /// The executable is copied to each host via SCP and executed using SSH.
+    #[default]
+    Ssh,
+    /// Each host is a process spawned on this machine, the processes communicate through the
+    /// network like in a remote execution (usually on the loopback interface).
+    ///
+    /// The addresses of the hosts should be local to this machine, see
+    /// [`EnvironmentConfig::cluster`].
+    Cluster,
 }
 
 /// The configuration of a single remote host.
@@ -136,6 +280,25 @@ pub struct RemoteHostConfig {
     /// If specified the remote worker will be spawned under `perf`, and its output will be stored
     /// at this location.
     pub perf_path: Option<PathBuf>,
+    /// The labels of this host (e.g. `gpu`, `ingest` or `bigmem`).
+    ///
+    /// A stream can request a label with [`Stream::on_hosts`](crate::Stream::on_hosts), its
+    /// replicas will be placed only on the hosts with that label.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// The share of the replicas this host gets, relative to the other hosts.
+    ///
+    /// When some host sets a weight, the replicas of a block with
+    /// [`Replication::Limited`](crate::Replication::Limited) are split between the hosts
+    /// proportionally to their weights (without exceeding `num_cores`), and a block with
+    /// [`Replication::One`](crate::Replication::One) runs on the host with the largest weight. The
+    /// hosts without a weight count as having weight 1.
+    ///
+    /// When no host sets a weight, the replicas fill the cores of the hosts in the order they are
+    /// listed, and [`Replication::One`](crate::Replication::One) runs on the first host. The other
+    /// replications depend only on `num_cores`.
+    #[serde(default)]
+    pub weight: Option<CoordUInt>,
 }
 
 /// The information used to connect to a remote host via SSH.
@@ -217,6 +380,62 @@ impl EnvironmentConfig {
             runtime: ExecutionRuntime::Local(LocalRuntimeConfig { num_cores }),
             host_id: Some(0),
             skip_single_remote_check: false,
+            checkpoint: None,
+            concurrency: Default::default(),
+            affinity: Default::default(),
+            metrics: None,
+        }
+    }
+
+    /// Cluster environment that runs `num_hosts` processes on this machine, each one with
+    /// `cores_per_host` cores.
+    ///
+    /// The processes communicate through the loopback interface exactly like the hosts of a
+    /// remote environment, but they are spawned without SSH. Like in a remote environment,
+    /// [`StreamEnvironment::spawn_remote_workers`](crate::StreamEnvironment::spawn_remote_workers)
+    /// has to be called before building the streams.
+    ///
+    /// The ports after 9500 are split evenly between the hosts, up to 1000 ports per host.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if there are too many hosts to give each one a range of ports.
+    pub fn cluster(num_hosts: CoordUInt, cores_per_host: CoordUInt) -> EnvironmentConfig {
+        let available = (u16::MAX - CLUSTER_BASE_PORT) as CoordUInt;
+        let ports_per_host = (available / num_hosts.max(1)).min(CLUSTER_PORTS_PER_HOST as _);
+        assert!(
+            ports_per_host > 0,
+            "Too many hosts for a cluster: {num_hosts} hosts, at most {available} supported"
+        );
+        let hosts = (0..num_hosts)
+            .map(|host_id| RemoteHostConfig {
+                address: "127.0.0.1".into(),
+                base_port: host_id
+                    .checked_mul(ports_per_host)
+                    .and_then(|offset| u16::try_from(offset).ok())
+                    .and_then(|offset| CLUSTER_BASE_PORT.checked_add(offset))
+                    .expect("cluster port out of range"),
+                num_cores: cores_per_host,
+                ssh: Default::default(),
+                perf_path: None,
+                labels: Vec::new(),
+                weight: None,
+            })
+            .collect();
+        let config = RemoteRuntimeConfig {
+            hosts,
+            tracing_dir: None,
+            cleanup_executable: false,
+            launcher: Launcher::Cluster,
+        };
+        EnvironmentConfig {
+            runtime: ExecutionRuntime::Remote(config),
+            host_id: EnvironmentConfig::host_id(num_hosts),
+            skip_single_remote_check: false,
+            checkpoint: None,
+            concurrency: Default::default(),
+            affinity: Default::default(),
+            metrics: None,
         }
     }
 
@@ -249,6 +468,10 @@ impl EnvironmentConfig {
             runtime: ExecutionRuntime::Remote(config),
             host_id,
             skip_single_remote_check: false,
+            checkpoint: None,
+            concurrency: Default::default(),
+            affinity: Default::default(),
+            metrics: None,
         })
     }
 
@@ -286,6 +509,15 @@ impl EnvironmentConfig {
     }
 }
 
+impl RemoteHostConfig {
+    /// Whether this host has all the given labels.
+    pub fn has_labels<S: AsRef<str>>(&self, labels: &[S]) -> bool {
+        labels
+            .iter()
+            .all(|label| self.labels.iter().any(|l| l == label.as_ref()))
+    }
+}
+
 impl Display for RemoteHostConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "[{}:{}-]", self.address, self.base_port)
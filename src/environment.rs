@@ -5,7 +5,10 @@ use std::sync::Arc;
 use std::thread::available_parallelism;
 
 use crate::block::Block;
-use crate::config::{EnvironmentConfig, ExecutionRuntime, RemoteRuntimeConfig};
+use crate::cluster::spawn_cluster_workers;
+use crate::config::{EnvironmentConfig, ExecutionRuntime, Launcher, RemoteRuntimeConfig};
+use crate::job::{JobBuilder, JobError, JobHandle, JobReport};
+use crate::metrics::MetricsReporter;
 use crate::operator::iteration::IterationStateLock;
 use crate::operator::source::Source;
 use crate::operator::{Data, Operator};
@@ -93,9 +96,14 @@ impl StreamEnvironment {
 
     /// Spawn the remote workers via SSH and exit if this is the process that should spawn. If this
     /// is already a spawned process nothing is done.
+    ///
+    /// With [`Launcher::Cluster`] the workers are spawned as processes of this machine instead.
     pub fn spawn_remote_workers(&self) {
         match &self.inner.lock().config.runtime {
             ExecutionRuntime::Local(_) => {}
+            ExecutionRuntime::Remote(remote) if remote.launcher == Launcher::Cluster => {
+                spawn_cluster_workers(remote.clone());
+            }
             #[cfg(feature = "ssh")]
             ExecutionRuntime::Remote(remote) => {
                 spawn_remote_workers(remote.clone());
@@ -108,27 +116,204 @@ impl StreamEnvironment {
     }
 
     /// Start the computation. Await on the returned future to actually start the computation.
+    ///
+    /// If a worker of the job fails, all the other workers are stopped and the error of the
+    /// failed worker is returned, like [`execute_blocking`](StreamEnvironment::execute_blocking).
+    ///
+    /// See [`spawn`](StreamEnvironment::spawn) to get a handle to the running job.
     #[cfg(feature = "async-tokio")]
-    pub async fn execute(self) {
+    pub async fn execute(self) -> Result<JobReport, JobError> {
         let mut env = self.inner.lock();
         info!("starting execution ({} blocks)", env.block_count);
         let scheduler = env.scheduler.take().unwrap();
         let block_count = env.block_count;
         drop(env);
-        scheduler.start(block_count).await;
+        let result = scheduler.start(block_count).await;
         info!("finished execution");
+        result
     }
 
     /// Start the computation. Blocks until the computation is complete.
     ///
-    /// Execute on a thread or use the async version [`execute`]
-    /// for non-blocking alternatives
-    pub fn execute_blocking(self) {
+    /// If a worker of the job panics, all the other workers are stopped and a [`JobError`] is
+    /// returned, with the coordinates of the failed worker, the operator that panicked and the
+    /// panic message. In a remote environment the failure is sent to all the hosts, which return
+    /// the same error. Otherwise a [`JobReport`] summarizes the execution.
+    ///
+    /// See [`spawn`](StreamEnvironment::spawn) to run the job in background.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// env.stream(IteratorSource::new(0..10))
+    ///     .map(|x: i32| if x == 5 { panic!("bad item") } else { x })
+    ///     .for_each(|_| {});
+    ///
+    /// let error = env.execute_blocking().unwrap_err();
+    /// assert_eq!(error.operator, "Map");
+    /// assert_eq!(error.message, "bad item");
+    /// ```
+    pub fn execute_blocking(self) -> Result<JobReport, JobError> {
         let mut env = self.inner.lock();
         info!("starting execution ({} blocks)", env.block_count);
         let scheduler = env.scheduler.take().unwrap();
-        scheduler.start_blocking(env.block_count);
+        let result = scheduler.start_blocking(env.block_count);
         info!("finished execution");
+        result
+    }
+
+    /// Start the computation in background, returning a handle to the running job.
+    ///
+    /// The handle can be used to stop the job with [`JobHandle::cancel`], for example when it
+    /// reads from unbounded sources like a
+    /// [`ChannelSource`](crate::operator::source::ChannelSource), and to wait for its outcome
+    /// with [`JobHandle::wait`]. With the `async-tokio` feature the handle can also be awaited.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::ChannelSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let (tx, source) = ChannelSource::new(16);
+    /// let res = env.stream(source).collect_vec();
+    ///
+    /// let job = env.spawn();
+    /// tx.send(1).unwrap();
+    /// tx.send(2).unwrap();
+    /// # std::thread::sleep(std::time::Duration::from_millis(100));
+    /// job.cancel();
+    ///
+    /// let report = job.wait().unwrap();
+    /// assert!(report.cancelled);
+    /// assert_eq!(res.get().unwrap(), vec![1, 2]);
+    /// ```
+    pub fn spawn(self) -> JobHandle {
+        let mut env = self.inner.lock();
+        info!(
+            "starting execution in background ({} blocks)",
+            env.block_count
+        );
+        let scheduler = env.scheduler.take().unwrap();
+        let block_count = env.block_count;
+        drop(env);
+        let job = scheduler.job();
+        let thread = std::thread::Builder::new()
+            .name("noir-job".into())
+            .spawn(move || {
+                let result = scheduler.start_blocking(block_count);
+                info!("finished execution");
+                result
+            })
+            .unwrap();
+        JobHandle::new(job, thread)
+    }
+
+    /// Start in background a job that can change the number of its replicas while running, with
+    /// [`JobHandle::rescale`].
+    ///
+    /// The job graph is built by `build` inside a new environment with the given configuration,
+    /// and it's built again every time the job is rescaled: the sinks should be shared between
+    /// the executions (e.g. a channel, or a shared collection written with
+    /// [`Stream::for_each`]). The state is moved between the executions through the checkpoints,
+    /// so the configuration must have
+    /// [`checkpoint`](crate::EnvironmentConfig::checkpoint) set. Only the local runtime is
+    /// supported.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use std::sync::{Arc, Mutex};
+    /// # use std::time::Duration;
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::config::CheckpointConfig;
+    /// # use noir::operator::source::ChannelSource;
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// let mut config = EnvironmentConfig::local(2);
+    /// config.checkpoint = Some(CheckpointConfig::new(dir.path(), Duration::from_secs(60)));
+    ///
+    /// let (tx, source) = ChannelSource::new(16);
+    /// let sums = Arc::new(Mutex::new(Vec::new()));
+    /// let out = sums.clone();
+    /// let mut job = StreamEnvironment::execute_rescalable(config, move |env| {
+    ///     let out = out.clone();
+    ///     env.stream(source.share())
+    ///         .group_by_sum(|x: &u64| x % 2, |x| x)
+    ///         .for_each(move |kv| out.lock().unwrap().push(kv));
+    /// });
+    ///
+    /// tx.send(1).unwrap();
+    /// tx.send(2).unwrap();
+    /// job.rescale(4, Duration::from_secs(10)).unwrap();
+    /// tx.send(3).unwrap();
+    /// drop(tx);
+    /// job.wait().unwrap();
+    ///
+    /// let mut sums = sums.lock().unwrap().clone();
+    /// sums.sort();
+    /// assert_eq!(sums, vec![(0, 2), (1, 4)]);
+    /// ```
+    pub fn execute_rescalable<F>(config: EnvironmentConfig, build: F) -> JobHandle
+    where
+        F: Fn(&mut StreamEnvironment) + Send + Sync + 'static,
+    {
+        assert!(
+            matches!(config.runtime, ExecutionRuntime::Local(_)),
+            "Only the jobs with a local runtime can be rescaled"
+        );
+        assert!(
+            config.checkpoint.is_some(),
+            "A rescalable job requires the checkpoints to be enabled"
+        );
+        let build: JobBuilder = Arc::new(build);
+        JobHandle::rescalable(config, build)
+    }
+
+    /// Add a reporter of the live metrics of the job.
+    ///
+    /// The reporter is called every
+    /// [`MetricsConfig::interval`](crate::config::MetricsConfig::interval) while the job runs, and
+    /// once more when it completes. The metrics are collected only if
+    /// [`EnvironmentConfig::metrics`](crate::EnvironmentConfig::metrics) is set, see the
+    /// [`metrics`](crate::metrics) module.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use std::sync::{Arc, Mutex};
+    /// # use std::time::Duration;
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::config::MetricsConfig;
+    /// # use noir::metrics::{MetricsReporter, MetricsSnapshot};
+    /// # use noir::operator::source::IteratorSource;
+    /// struct Received(Arc<Mutex<u64>>);
+    ///
+    /// impl MetricsReporter for Received {
+    ///     fn report(&mut self, metrics: &MetricsSnapshot) {
+    ///         *self.0.lock().unwrap() = metrics.replicas.iter().map(|r| r.items_in).sum();
+    ///     }
+    /// }
+    ///
+    /// let mut config = EnvironmentConfig::local(2);
+    /// config.metrics = Some(MetricsConfig::new(Duration::from_secs(1)));
+    /// let mut env = StreamEnvironment::new(config);
+    /// let received = Arc::new(Mutex::new(0));
+    /// env.add_metrics_reporter(Received(received.clone()));
+    /// env.stream(IteratorSource::new(0..100)).shuffle().for_each(|_| {});
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(*received.lock().unwrap(), 100);
+    /// ```
+    pub fn add_metrics_reporter<R: MetricsReporter + 'static>(&mut self, reporter: R) {
+        let env = self.inner.lock();
+        let scheduler = env
+            .scheduler
+            .as_ref()
+            .expect("The environment has already been executed");
+        scheduler.job().metrics().add_reporter(Box::new(reporter));
     }
 
     /// Get the total number of processing cores in the cluster.
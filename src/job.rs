@@ -0,0 +1,686 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use parking_lot::{Condvar, Mutex};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::affinity::CorePlacement;
+use crate::checkpoint::{CheckpointCoordinator, CheckpointId};
+use crate::concurrency::{self, ConcurrencyLimit};
+use crate::config::{Concurrency, EnvironmentConfig, ExecutionRuntime};
+use crate::metrics::JobMetrics;
+use crate::network::{broadcast_control, ControlMessage, ControlPlane, Coord};
+use crate::scheduler::{BlockId, ExecutionMetadata, HostId, ReplicaId};
+use crate::{CoordUInt, StreamEnvironment};
+
+/// Summary of a job that completed successfully.
+#[derive(Debug, Clone)]
+pub struct JobReport {
+    /// Time elapsed from the start of the workers to the end of the job.
+    pub duration: Duration,
+    /// Number of blocks of the job graph.
+    pub blocks: usize,
+    /// Number of workers (replicas of the blocks) executed on this host.
+    pub workers: usize,
+    /// Whether the job was stopped by [`JobHandle::cancel`] before its sources were exhausted.
+    pub cancelled: bool,
+}
+
+/// Error returned when a worker of the job fails, or when the job cannot be started.
+///
+/// When a worker panics all the other workers of the job are stopped, on all the hosts. The error
+/// refers to the first worker that failed, the failures caused by the teardown of the job are only
+/// counted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobError {
+    /// What made the job fail.
+    pub kind: JobErrorKind,
+    /// Identifier of the block of the failed worker.
+    pub block_id: BlockId,
+    /// Identifier of the host of the failed worker.
+    pub host_id: HostId,
+    /// Identifier of the replica of the failed worker inside its host.
+    pub replica_id: ReplicaId,
+    /// The operator of the block of the failed worker that panicked.
+    ///
+    /// The sources and the operators running the functions of the user (like `map`, `filter`,
+    /// `flat_map`, `key_by`, `fold`, `rich_map` or `for_each`) are reported when they panic, the
+    /// panics of the other operators are reported as panics of the last operator of the block.
+    pub operator: String,
+    /// The panic message.
+    pub message: String,
+    /// The source location of the panic, if available.
+    pub location: Option<String>,
+    /// Number of workers of this host that failed, including the first one if it was on this
+    /// host.
+    pub failed_workers: usize,
+}
+
+/// What made a job fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobErrorKind {
+    /// A worker panicked.
+    Panic,
+    /// A block cannot be placed on the hosts of the configuration, for example because no host
+    /// has the labels it requested. No worker has been started: only the block and its last
+    /// operator are set.
+    Placement,
+    /// The checkpoints are enabled, but a source or an operator of the job does not support them.
+    /// The workers exit before processing any element: only the host is set.
+    Checkpoint,
+    /// The connections with the other hosts of the job cannot be set up. No worker has been
+    /// started: only the host is set.
+    Network,
+}
+
+impl std::fmt::Display for JobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            JobErrorKind::Panic => write!(
+                f,
+                "worker (b{:02}.h{:02}.r{:02}) failed in {}",
+                self.block_id, self.host_id, self.replica_id, self.operator
+            )?,
+            JobErrorKind::Placement => write!(
+                f,
+                "block b{:02} ending with {} cannot be placed",
+                self.block_id, self.operator
+            )?,
+            JobErrorKind::Network => write!(f, "host h{:02} cannot join the job", self.host_id)?,
+            JobErrorKind::Checkpoint => write!(
+                f,
+                "the job on host h{:02} cannot take checkpoints",
+                self.host_id
+            )?,
+        }
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for JobError {}
+
+/// Error returned when a job cannot be rescaled.
+#[derive(Debug, Clone, Error)]
+pub enum RescaleError {
+    /// The job was not started with
+    /// [`StreamEnvironment::execute_rescalable`](crate::StreamEnvironment::execute_rescalable), or
+    /// it does not run with a local runtime.
+    #[error("the job is not rescalable")]
+    NotRescalable,
+    /// The job could not be paused at a checkpoint because it already completed.
+    #[error("the job cannot be paused at a checkpoint")]
+    NotPaused,
+    /// A source or an operator of the job does not support checkpoints, so its state cannot be
+    /// moved to the new replicas.
+    #[error("the job cannot be rescaled: {0}")]
+    Unsupported(String),
+    /// The job was not paused within the timeout. It keeps running with the previous number of
+    /// replicas.
+    #[error("the job was not paused within the timeout")]
+    Timeout,
+}
+
+/// State of a job shared between all its workers on this host.
+#[derive(Debug, Default)]
+pub(crate) struct JobState {
+    /// Set when the workers should stop as soon as possible.
+    abort: AtomicBool,
+    /// Set when the sources should end their stream.
+    cancelled: AtomicBool,
+    /// Set when the job is torn down after its last checkpoint, to be restarted.
+    stopped: AtomicBool,
+    /// The first failure of the job.
+    failure: Mutex<Option<JobError>>,
+    /// Number of workers that failed.
+    failed_workers: AtomicUsize,
+    /// The checkpoints of the job, if enabled.
+    checkpoints: Option<Arc<CheckpointCoordinator>>,
+    /// The slots shared by the replicas of this host, with [`Concurrency::Limited`].
+    concurrency_limit: Option<Arc<ConcurrencyLimit>>,
+    /// The cores the replicas of this host are pinned to, if any.
+    affinity: Option<CorePlacement>,
+    /// The live metrics of the replicas of this host.
+    metrics: Arc<JobMetrics>,
+    /// The flags that end the stream of the sources of each block once they are all set, set by
+    /// the operators that need no more input.
+    source_stops: Mutex<HashMap<BlockId, Vec<Arc<StopFlag>>>>,
+    /// The connections to the other hosts of the job, if any.
+    control: Mutex<Option<ControlPlane>>,
+    /// Set when all the workers of this host have been set up.
+    started: Mutex<bool>,
+    started_changed: Condvar,
+}
+
+impl JobState {
+    pub(crate) fn new(config: &EnvironmentConfig) -> Self {
+        Self {
+            checkpoints: config
+                .checkpoint
+                .as_ref()
+                .map(|checkpoint| Arc::new(CheckpointCoordinator::new(config, checkpoint))),
+            concurrency_limit: match config.concurrency {
+                Concurrency::Unlimited => None,
+                Concurrency::Limited { max_running } => {
+                    Some(Arc::new(ConcurrencyLimit::new(max_running)))
+                }
+            },
+            affinity: CorePlacement::new(config.affinity),
+            metrics: Arc::new(JobMetrics::new(config.metrics.clone())),
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub(crate) fn concurrency_limit(&self) -> Option<&Arc<ConcurrencyLimit>> {
+        self.concurrency_limit.as_ref()
+    }
+
+    #[inline]
+    pub(crate) fn affinity(&self) -> Option<&CorePlacement> {
+        self.affinity.as_ref()
+    }
+
+    #[inline]
+    pub(crate) fn checkpoints(&self) -> Option<&Arc<CheckpointCoordinator>> {
+        self.checkpoints.as_ref()
+    }
+
+    #[inline]
+    pub(crate) fn metrics(&self) -> &Arc<JobMetrics> {
+        &self.metrics
+    }
+
+    #[inline]
+    pub(crate) fn is_aborted(&self) -> bool {
+        self.abort.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn abort(&self) {
+        self.abort.store(true, Ordering::Relaxed);
+        if let Some(checkpoints) = &self.checkpoints {
+            checkpoints.wake_paused();
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Cancel the job on this host and on all the other ones.
+    pub(crate) fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        if let Some(control) = self.control.lock().as_ref() {
+            control.broadcast(&ControlMessage::Cancel);
+        }
+    }
+
+    /// End the stream of the sources inside the block when all the `flags` are set.
+    pub(crate) fn stop_sources_on(&self, block_id: BlockId, flags: Vec<Arc<StopFlag>>) {
+        self.source_stops.lock().insert(block_id, flags);
+    }
+
+    /// The flags that end the stream of the sources inside the block once they are all set.
+    fn source_stops(&self, block_id: BlockId) -> Vec<Arc<StopFlag>> {
+        self.source_stops
+            .lock()
+            .get(&block_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    pub(crate) fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    /// Let the workers of this host process their elements, once all of them have been set up.
+    ///
+    /// If the checkpoints are enabled but an operator disabled them during its setup, the job
+    /// fails instead and the workers exit right away.
+    pub(crate) fn start(&self, host_id: HostId) {
+        let unsupported = self.checkpoints.as_ref().and_then(|c| c.disabled());
+        if let Some(reason) = unsupported {
+            log::error!("the job cannot take checkpoints: {reason}");
+            self.record_failure(JobError {
+                kind: JobErrorKind::Checkpoint,
+                block_id: 0,
+                host_id,
+                replica_id: 0,
+                operator: String::new(),
+                message: reason.to_string(),
+                location: None,
+                failed_workers: 0,
+            });
+            self.abort();
+        }
+        *self.started.lock() = true;
+        self.started_changed.notify_all();
+    }
+
+    /// Block a worker until all the workers of this host have been set up.
+    pub(crate) fn wait_started(&self) {
+        let mut started = self.started.lock();
+        while !*started {
+            self.started_changed.wait(&mut started);
+        }
+    }
+
+    /// Tear down the job after its last checkpoint completed.
+    ///
+    /// The failures of the workers caused by the teardown are not errors of the job.
+    pub(crate) fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        self.abort();
+    }
+
+    /// Start listening for the messages of the other hosts of the job.
+    ///
+    /// `addresses` are the addresses of the control sockets of all the hosts, nothing is started
+    /// if this is the only host. If the control socket cannot be bound the job fails, and its
+    /// workers exit as soon as they are started.
+    pub(crate) fn start_control(self: &Arc<Self>, host_id: HostId, addresses: Vec<(String, u16)>) {
+        if addresses.len() <= 1 {
+            return;
+        }
+        let job = Arc::downgrade(self);
+        let control = ControlPlane::start(host_id, addresses.clone(), move |message| {
+            let Some(job) = job.upgrade() else {
+                return;
+            };
+            match message {
+                ControlMessage::Failure(error) => {
+                    log::error!("job failed on host {}", error.host_id);
+                    job.record_failure(error);
+                    job.abort();
+                }
+                ControlMessage::Cancel => {
+                    log::info!("job cancelled by another host");
+                    job.cancelled.store(true, Ordering::Relaxed);
+                }
+            }
+        });
+        let control = match control {
+            Ok(control) => control,
+            Err(e) => {
+                let address = &addresses[host_id as usize];
+                log::error!("failed to bind the control socket at {address:?}: {e:?}");
+                let error = JobError {
+                    kind: JobErrorKind::Network,
+                    block_id: 0,
+                    host_id,
+                    replica_id: 0,
+                    operator: String::new(),
+                    message: format!("cannot bind the control socket at {address:?}: {e}"),
+                    location: None,
+                    failed_workers: 0,
+                };
+                self.record_failure(error.clone());
+                broadcast_control(host_id, &addresses, &ControlMessage::Failure(error));
+                self.abort();
+                return;
+            }
+        };
+        let mut slot = self.control.lock();
+        // the job may have been cancelled before the other hosts were reachable
+        if self.is_cancelled() {
+            control.broadcast(&ControlMessage::Cancel);
+        }
+        *slot = Some(control);
+    }
+
+    /// Stop listening for the messages of the other hosts, once the workers of this host
+    /// completed.
+    pub(crate) fn stop_control(&self) {
+        if let Some(mut control) = self.control.lock().take() {
+            control.stop();
+        }
+    }
+
+    /// Record the failure of a worker and stop all the others, on all the hosts.
+    pub(crate) fn fail(
+        &self,
+        coord: Coord,
+        operator: String,
+        payload: Box<dyn Any + Send>,
+        location: Option<String>,
+    ) {
+        self.failed_workers.fetch_add(1, Ordering::Relaxed);
+        let error = JobError {
+            kind: JobErrorKind::Panic,
+            block_id: coord.block_id,
+            host_id: coord.host_id,
+            replica_id: coord.replica_id,
+            operator,
+            message: panic_message(payload.as_ref()),
+            location,
+            failed_workers: 0,
+        };
+        if self.record_failure(error.clone()) {
+            // the other hosts are told before the connections to them are closed by the teardown
+            if let Some(control) = self.control.lock().as_ref() {
+                control.broadcast(&ControlMessage::Failure(error));
+            }
+        }
+        self.abort();
+    }
+
+    /// Record the failure of the job, returns whether it is the first one.
+    fn record_failure(&self, error: JobError) -> bool {
+        let mut failure = self.failure.lock();
+        if failure.is_some() {
+            return false;
+        }
+        *failure = Some(error);
+        true
+    }
+
+    /// The outcome of the job, once all its workers completed.
+    pub(crate) fn result(&self, report: JobReport) -> Result<JobReport, JobError> {
+        match self.failure.lock().clone() {
+            Some(mut error) => {
+                error.failed_workers = self.failed_workers.load(Ordering::Relaxed);
+                Err(error)
+            }
+            None => {
+                // a job that completed is not resumed
+                if !report.cancelled && !self.is_stopped() {
+                    if let Some(checkpoints) = &self.checkpoints {
+                        checkpoints.clear();
+                    }
+                }
+                Ok(report)
+            }
+        }
+    }
+}
+
+/// The payload the workers of a stopped job unwind with.
+struct JobStopped;
+
+/// A flag shared by the replicas of an operator, set while the operator needs no more input.
+///
+/// Each replica sets and clears only its own part of the flag, which stays set while at least one
+/// of the replicas needs no more input: a replica that starts again does not clear the flag set by
+/// the other ones.
+#[derive(Debug, Default)]
+pub(crate) struct StopFlag {
+    /// The number of replicas that set the flag.
+    replicas: AtomicUsize,
+}
+
+impl StopFlag {
+    /// Set or clear the part of the flag of a replica, `set` is the part of the replica.
+    pub(crate) fn update(&self, set: &mut bool, value: bool) {
+        if *set != value {
+            *set = value;
+            if value {
+                self.replicas.fetch_add(1, Ordering::Relaxed);
+            } else {
+                self.replicas.fetch_sub(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_set(&self) -> bool {
+        self.replicas.load(Ordering::Relaxed) > 0
+    }
+}
+
+/// Lets a source know that the job has been cancelled, that it should emit the barrier of a
+/// checkpoint, or that it should pause because the job is being stopped.
+///
+/// The sources obtain it during `setup` with [`SourceControl::new`]. When the job is cancelled
+/// they end their stream as if they were exhausted.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SourceControl {
+    job: Option<Arc<JobState>>,
+    /// The flags that end the stream of this source early, once they are all set.
+    stops: Vec<Arc<StopFlag>>,
+    /// The last checkpoint emitted by the source.
+    checkpoint: CheckpointId,
+    /// Whether an operator of the block asked to be woken up periodically.
+    heartbeats: bool,
+}
+
+impl SourceControl {
+    pub(crate) fn new(metadata: &ExecutionMetadata) -> Self {
+        let checkpoint = metadata
+            .job
+            .checkpoints()
+            .and_then(|c| c.restored())
+            .unwrap_or(0);
+        Self {
+            job: Some(metadata.job.clone()),
+            stops: metadata.job.source_stops(metadata.coord.block_id),
+            checkpoint,
+            heartbeats: metadata.wake_up_interval.is_some(),
+        }
+    }
+
+    /// Whether the source should end its stream, because the job has been cancelled or its
+    /// output is not needed anymore.
+    #[inline]
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.job.as_ref().is_some_and(|job| job.is_cancelled())
+            || (!self.stops.is_empty() && self.stops.iter().all(|s| s.is_set()))
+    }
+
+    /// Whether a source waiting for new elements should emit a `FlushBatch` when it wakes up,
+    /// because an operator of the block asked to be woken up periodically or because the job is
+    /// being aborted.
+    #[inline]
+    pub(crate) fn wake_up(&self) -> bool {
+        self.heartbeats || self.job.as_ref().is_some_and(|job| job.is_aborted())
+    }
+
+    /// Check whether the source emitted the barrier of the last checkpoint before the job is
+    /// stopped.
+    ///
+    /// In that case this blocks until the job is torn down, then unwinds the worker without
+    /// emitting anything else. If the rescaling is given up the source resumes.
+    pub(crate) fn check_stopped(&self) {
+        let Some(job) = &self.job else {
+            return;
+        };
+        let Some(checkpoints) = job.checkpoints() else {
+            return;
+        };
+        match checkpoints.stop() {
+            Some(stop) if self.checkpoint >= stop => {
+                let stopped =
+                    concurrency::blocking(|| checkpoints.pause(stop, || job.is_aborted()));
+                if stopped {
+                    std::panic::resume_unwind(Box::new(JobStopped));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The next checkpoint whose barrier should be emitted, if any.
+    #[inline]
+    pub(crate) fn checkpoint(&mut self) -> Option<CheckpointId> {
+        let triggered = self.job.as_ref()?.checkpoints()?.triggered();
+        if self.checkpoint < triggered {
+            self.checkpoint += 1;
+            Some(self.checkpoint)
+        } else {
+            None
+        }
+    }
+}
+
+/// Builds the job graph of a rescalable job inside a new environment.
+pub(crate) type JobBuilder = Arc<dyn Fn(&mut StreamEnvironment) + Send + Sync>;
+
+/// What is needed to start again a rescalable job.
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct Rescaler {
+    config: EnvironmentConfig,
+    #[derivative(Debug = "ignore")]
+    build: JobBuilder,
+}
+
+/// Handle to a job running in background, returned by
+/// [`StreamEnvironment::spawn`](crate::StreamEnvironment::spawn) and
+/// [`StreamEnvironment::execute_rescalable`](crate::StreamEnvironment::execute_rescalable).
+///
+/// Dropping the handle detaches the job, which keeps running until its completion.
+#[derive(Debug)]
+pub struct JobHandle {
+    job: Arc<JobState>,
+    /// The thread running the job, taken only while the job is restarted by
+    /// [`JobHandle::rescale`].
+    thread: Option<JoinHandle<Result<JobReport, JobError>>>,
+    rescaler: Option<Rescaler>,
+}
+
+impl JobHandle {
+    pub(crate) fn new(job: Arc<JobState>, thread: JoinHandle<Result<JobReport, JobError>>) -> Self {
+        Self {
+            job,
+            thread: Some(thread),
+            rescaler: None,
+        }
+    }
+
+    /// Build the job in a new environment and start it.
+    pub(crate) fn rescalable(config: EnvironmentConfig, build: JobBuilder) -> Self {
+        let mut env = StreamEnvironment::new(config.clone());
+        build(&mut env);
+        let mut handle = env.spawn();
+        handle.rescaler = Some(Rescaler { config, build });
+        handle
+    }
+
+    /// Change the number of replicas of the job, restarting it with `num_cores` cores.
+    ///
+    /// The sources pause after emitting the barrier of a last checkpoint. Once the checkpoint is
+    /// complete the job is torn down, and it's started again with the new number of cores,
+    /// resuming from that checkpoint. The keyed state is redistributed to the new replicas
+    /// following the hash of the keys: see the [`checkpoint`](crate::checkpoint) module for the
+    /// details.
+    ///
+    /// The elements sent to the sinks before the checkpoint are not sent again, the ones sent
+    /// after it are lost together with the stopped job: only the sources that pause right after
+    /// the barrier (like the ones provided by this crate) do not send any.
+    ///
+    /// If the last checkpoint does not complete within `timeout`, for example because an
+    /// operator is blocked, the sources resume and [`RescaleError::Timeout`] is returned: the job
+    /// keeps running with the previous number of replicas.
+    ///
+    /// Returns an error if the job was not started with
+    /// [`StreamEnvironment::execute_rescalable`](crate::StreamEnvironment::execute_rescalable), if
+    /// a source or an operator of the job does not support checkpoints, or if it cannot be paused
+    /// because it already completed.
+    ///
+    /// Only the jobs running with a local runtime can be rescaled: the number of replicas of a
+    /// distributed job is fixed by the cores of its hosts.
+    pub fn rescale(&mut self, num_cores: CoordUInt, timeout: Duration) -> Result<(), RescaleError> {
+        let Some(rescaler) = &mut self.rescaler else {
+            return Err(RescaleError::NotRescalable);
+        };
+        let ExecutionRuntime::Local(local) = &mut rescaler.config.runtime else {
+            return Err(RescaleError::NotRescalable);
+        };
+        let checkpoints = self
+            .job
+            .checkpoints()
+            .expect("A rescalable job takes checkpoints")
+            .clone();
+        let deadline = Instant::now() + timeout;
+        checkpoints.wait_ready(deadline)?;
+        let id = checkpoints.trigger_stop().ok_or(RescaleError::NotPaused)?;
+        if let Err(e) = checkpoints.wait_complete(id, deadline) {
+            checkpoints.cancel_stop(id);
+            return Err(e);
+        }
+
+        info!("rescaling the job to {num_cores} cores from checkpoint {id}");
+        local.num_cores = num_cores;
+        self.job.stop();
+        // the new job is started once the workers of the stopped one released their resources,
+        // like the sockets of the sources
+        let stopped = self.thread.take().expect("The job is running");
+        match stopped.join() {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => warn!("the job stopped for rescaling failed: {e}"),
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+        let mut env = StreamEnvironment::new(rescaler.config.clone());
+        (rescaler.build)(&mut env);
+        let new = env.spawn();
+        self.job = new.job;
+        self.thread = new.thread;
+        Ok(())
+    }
+
+    /// Ask the job to stop.
+    ///
+    /// All the sources end their stream at the next element, like if they were exhausted: the
+    /// `Terminate` propagates through the operators and the network, so the job completes in an
+    /// orderly way and the sinks get the results computed so far. The sources provided by this
+    /// crate that wait for their input, like the
+    /// [`ChannelSource`](crate::operator::source::ChannelSource) and the
+    /// [`SocketSource`](crate::operator::source::SocketSource), notice the cancellation within a
+    /// few milliseconds even if no element arrives. A custom source is not cancelled: it has to
+    /// end its stream by itself.
+    ///
+    /// In a distributed deployment the cancellation is sent to all the other hosts of the job, so
+    /// it's enough to call this from the process of one host. The hosts that already completed
+    /// their part of the job, or that did not start it yet, do not receive it.
+    pub fn cancel(&self) {
+        self.job.cancel();
+    }
+
+    /// Whether the job has completed, either successfully or with an error.
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(|t| t.is_finished())
+    }
+
+    /// Wait for the job to complete, returning its outcome.
+    pub fn wait(mut self) -> Result<JobReport, JobError> {
+        let thread = self.thread.take().expect("The job is running");
+        match thread.join() {
+            Ok(result) => result,
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+    }
+}
+
+/// Awaiting the handle waits for the job to complete without blocking the runtime, like
+/// [`JobHandle::wait`].
+#[cfg(feature = "async-tokio")]
+impl std::future::IntoFuture for JobHandle {
+    type Output = Result<JobReport, JobError>;
+    type IntoFuture = futures::future::BoxFuture<'static, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || self.wait())
+                .await
+                .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+        })
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
@@ -40,7 +40,7 @@ fn main() {
         // Collect result
         .collect_vec();
 
-    env.execute_blocking(); // Start execution (blocking)
+    env.execute_blocking().unwrap(); // Start execution (blocking)
     if let Some(result) = result.get() {
         // Print word counts
         result.into_iter().for_each(|(word, count)| println!("{word}: {count}"));
@@ -80,7 +80,7 @@ fn main() {
         .drop_key()
         .collect_vec();
 
-    env.execute_blocking(); // Start execution (blocking)
+    env.execute_blocking().unwrap(); // Start execution (blocking)
     if let Some(result) = result.get() {
         // Print word counts
         result.into_iter().for_each(|(word, count)| println!("{word}: {count}"));
@@ -126,7 +126,9 @@ pub use block::BatchMode;
 pub use block::Replication;
 pub use config::EnvironmentConfig;
 pub use environment::StreamEnvironment;
+pub use job::{JobError, JobErrorKind, JobHandle, JobReport, RescaleError};
 pub use operator::iteration::IterationStateHandle;
+pub use operator::sink::BroadcastVariable;
 pub use scheduler::ExecutionMetadata;
 pub use stream::{KeyedStream, Stream, WindowedStream};
 
@@ -134,12 +136,18 @@ use crate::block::BlockStructure;
 use crate::network::Coord;
 use crate::profiler::ProfilerResult;
 
+pub(crate) mod affinity;
 pub(crate) mod block;
 pub mod box_op;
 pub(crate) mod channel;
+pub mod checkpoint;
+pub mod cluster;
+pub(crate) mod concurrency;
 pub mod config;
 pub mod data_type;
 pub(crate) mod environment;
+pub(crate) mod job;
+pub mod metrics;
 pub(crate) mod network;
 pub mod operator;
 mod profiler;
@@ -169,6 +177,18 @@ pub(crate) struct TracingData {
     profilers: Vec<ProfilerResult>,
 }
 
+impl TracingData {
+    /// Save the tracing data in a new JSON file inside `dir`.
+    pub(crate) fn save(&self, dir: &std::path::Path) {
+        std::fs::create_dir_all(dir).expect("Cannot create tracing directory");
+        let now = chrono::Local::now();
+        let file_name = format!("{}.json", now.format("%Y-%m-%d-%H%M%S"));
+        let target = dir.join(file_name);
+        let mut target = std::fs::File::create(target).expect("Cannot create tracing json file");
+        serde_json::to_writer(&mut target, self).expect("Failed to write tracing json file");
+    }
+}
+
 impl Add for TracingData {
     type Output = TracingData;
 
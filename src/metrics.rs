@@ -0,0 +1,694 @@
+//! Live metrics of a running job.
+//!
+//! When [`EnvironmentConfig::metrics`](crate::EnvironmentConfig::metrics) is set, each replica of
+//! the job on this host keeps track of:
+//!
+//! - the number of elements received from the previous blocks and sent to the next ones;
+//! - the number of elements emitted by the first and the last operators of its chain;
+//! - the backlog of its input channels, i.e. the batches sent to the replica, by this host or
+//!   through the network, and not yet received;
+//! - the last watermark that left the replica, and how far it is behind the wall clock (assuming
+//!   the timestamps are milliseconds since the UNIX epoch);
+//! - the number of batches sent to the next blocks and the time their first element waited in the
+//!   batch before being sent.
+//!
+//! The operators of a block are fused in the same replica: the counts of the elements received
+//! and sent refer to the operators chain of the block, which is reported together with the
+//! metrics. The operators in the middle of the chain are not counted, since that would add work to
+//! each operator of the hot path: the elements emitted by the `Start` of the block and by its last
+//! operator show how many elements the chain filters or multiplies.
+//!
+//! When the metrics are disabled the replicas do not hold a handle to them, so nothing is counted.
+//!
+//! The metrics are periodically passed to the [`MetricsReporter`]s of the job, and once more when
+//! the job completes. The built-in reporters are [`PrometheusReporter`], which exposes the metrics
+//! in the Prometheus text format over HTTP, and [`LogReporter`], which writes them to the log.
+//! Other reporters can be added with
+//! [`StreamEnvironment::add_metrics_reporter`](crate::StreamEnvironment::add_metrics_reporter).
+//!
+//! In a distributed execution each host reports the metrics of its own replicas.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use parking_lot::{Mutex, RwLock};
+
+use crate::channel::Receiver;
+use crate::config::MetricsConfig;
+use crate::network::{Coord, NetworkMessage};
+use crate::operator::Timestamp;
+
+/// Receives the metrics of the job while it runs.
+pub trait MetricsReporter: Send {
+    /// Report the current metrics of the job.
+    fn report(&mut self, metrics: &MetricsSnapshot);
+}
+
+/// The metrics of the replicas of the job on this host, at a point in time.
+#[derive(Clone, Debug, Default)]
+pub struct MetricsSnapshot {
+    /// Time elapsed from the start of the job.
+    pub uptime: Duration,
+    /// The metrics of each replica, sorted by coordinate.
+    pub replicas: Vec<ReplicaSnapshot>,
+}
+
+/// The metrics of a replica of a block.
+#[derive(Clone, Debug)]
+pub struct ReplicaSnapshot {
+    /// The coordinate of the replica.
+    pub coord: Coord,
+    /// The chain of operators of the block.
+    pub operators: String,
+    /// Number of elements received from the previous blocks.
+    pub items_in: u64,
+    /// Number of elements sent to the next blocks.
+    pub items_out: u64,
+    /// The elements emitted by the `Start` and by the last operator of the chain, in order.
+    pub operator_items: Vec<OperatorSnapshot>,
+    /// Number of batches waiting in the input channels of the replica.
+    pub backlog: u64,
+    /// The last watermark that left the replica.
+    pub watermark: Option<Timestamp>,
+    /// How far the last watermark is behind the wall clock.
+    pub watermark_lag: Option<Duration>,
+    /// Number of batches sent to the next blocks.
+    pub batches: u64,
+    /// Total time the first element of the batches waited before the batch was sent.
+    pub batch_latency: Duration,
+    /// The longest time the first element of a batch waited before the batch was sent.
+    pub max_batch_latency: Duration,
+}
+
+/// The elements emitted by an operator of a replica.
+#[derive(Clone, Debug)]
+pub struct OperatorSnapshot {
+    /// The position of the operator in the chain of the block.
+    pub index: usize,
+    /// The name of the operator.
+    pub name: String,
+    /// Number of elements emitted by the operator.
+    pub items_out: u64,
+}
+
+/// An input channel of a replica.
+trait Queue: Send + Sync {
+    /// Number of messages waiting in the channel.
+    fn queued(&self) -> usize;
+}
+
+impl<T: Send + 'static> Queue for Receiver<T> {
+    fn queued(&self) -> usize {
+        self.len()
+    }
+}
+
+/// The counters of a replica, updated by its worker thread.
+#[derive(Default)]
+pub(crate) struct ReplicaMetrics {
+    /// The chain of operators of the block, `None` if the replica is not on this host.
+    operators: Mutex<Option<String>>,
+    items_in: AtomicU64,
+    items_out: AtomicU64,
+    /// The position, the name and the counter of the counted operators of the chain.
+    #[allow(clippy::type_complexity)]
+    operator_items: Mutex<Vec<(usize, String, Arc<AtomicU64>)>>,
+    /// The input channels of the replica, they are gone once it completes.
+    queues: Mutex<Vec<Weak<dyn Queue>>>,
+    watermark: Mutex<Option<Timestamp>>,
+    batches: AtomicU64,
+    batch_latency_us: AtomicU64,
+    max_batch_latency_us: AtomicU64,
+}
+
+impl std::fmt::Debug for ReplicaMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplicaMetrics")
+            .field("operators", &self.operators.lock())
+            .field("items_in", &self.items_in)
+            .field("items_out", &self.items_out)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReplicaMetrics {
+    /// The replica received `message`.
+    #[inline]
+    pub(crate) fn received<T>(&self, message: &NetworkMessage<T>) {
+        let amount = message.num_data_items() as u64;
+        self.items_in.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// The replica sent `message` to the next blocks.
+    #[inline]
+    pub(crate) fn sent<T>(&self, message: &NetworkMessage<T>) {
+        let amount = message.num_data_items() as u64;
+        self.items_out.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// The replica sent a batch whose first element waited `latency`.
+    #[inline]
+    pub(crate) fn batch_sent(&self, latency: Duration) {
+        let us = latency.as_micros() as u64;
+        self.batches.fetch_add(1, Ordering::Relaxed);
+        self.batch_latency_us.fetch_add(us, Ordering::Relaxed);
+        self.max_batch_latency_us.fetch_max(us, Ordering::Relaxed);
+    }
+
+    /// Count the batches waiting in `receiver` in the backlog of the replica.
+    pub(crate) fn watch<T: Send + 'static>(&self, receiver: &Arc<Receiver<T>>) {
+        let receiver: Arc<dyn Queue> = receiver.clone();
+        self.queues.lock().push(Arc::downgrade(&receiver));
+    }
+
+    /// The counter of the elements emitted by the operator at position `index` of the chain.
+    pub(crate) fn operator(&self, index: usize, name: &str) -> Arc<AtomicU64> {
+        let counter = Arc::new(AtomicU64::new(0));
+        self.operator_items
+            .lock()
+            .push((index, name.to_string(), counter.clone()));
+        counter
+    }
+
+    fn backlog(&self) -> u64 {
+        self.queues
+            .lock()
+            .iter()
+            .filter_map(|queue| queue.upgrade())
+            .map(|queue| queue.queued() as u64)
+            .sum()
+    }
+
+    fn operator_items(&self) -> Vec<OperatorSnapshot> {
+        let mut operators: Vec<_> = self
+            .operator_items
+            .lock()
+            .iter()
+            .map(|(index, name, counter)| OperatorSnapshot {
+                index: *index,
+                name: name.clone(),
+                items_out: counter.load(Ordering::Relaxed),
+            })
+            .collect();
+        operators.sort_by_key(|o| o.index);
+        operators
+    }
+}
+
+/// The metrics of the job on this host.
+#[derive(Default)]
+pub(crate) struct JobMetrics {
+    config: Option<MetricsConfig>,
+    start: Mutex<Option<Instant>>,
+    replicas: RwLock<HashMap<Coord, Arc<ReplicaMetrics>>>,
+    reporters: Mutex<Vec<Box<dyn MetricsReporter>>>,
+}
+
+impl std::fmt::Debug for JobMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JobMetrics")
+            .field("config", &self.config)
+            .field("replicas", &self.replicas.read().len())
+            .finish()
+    }
+}
+
+thread_local! {
+    /// The metrics of the replica the current worker thread is working on.
+    static CURRENT: RefCell<Option<Arc<ReplicaMetrics>>> = const { RefCell::new(None) };
+}
+
+impl JobMetrics {
+    pub(crate) fn new(config: Option<MetricsConfig>) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    pub(crate) fn add_reporter(&self, reporter: Box<dyn MetricsReporter>) {
+        self.reporters.lock().push(reporter);
+    }
+
+    fn replica(&self, coord: Coord) -> Arc<ReplicaMetrics> {
+        if let Some(replica) = self.replicas.read().get(&coord) {
+            return replica.clone();
+        }
+        self.replicas.write().entry(coord).or_default().clone()
+    }
+
+    /// The metrics of the replica, `None` if the metrics are disabled.
+    pub(crate) fn handle(&self, coord: Coord) -> Option<Arc<ReplicaMetrics>> {
+        self.is_enabled().then(|| self.replica(coord))
+    }
+
+    /// Register the replica running on the current thread.
+    pub(crate) fn enter(&self, coord: Coord, operators: String) {
+        let replica = self.replica(coord);
+        *replica.operators.lock() = Some(operators);
+        CURRENT.with(|c| *c.borrow_mut() = Some(replica));
+    }
+
+    /// The current metrics of the replicas of this host.
+    pub(crate) fn snapshot(&self) -> MetricsSnapshot {
+        let uptime = self
+            .start
+            .lock()
+            .map(|start| start.elapsed())
+            .unwrap_or_default();
+        let mut replicas: Vec<_> = self
+            .replicas
+            .read()
+            .iter()
+            .filter_map(|(&coord, replica)| {
+                let operators = replica.operators.lock().clone()?;
+                let watermark = *replica.watermark.lock();
+                Some(ReplicaSnapshot {
+                    coord,
+                    operators,
+                    items_in: replica.items_in.load(Ordering::Relaxed),
+                    items_out: replica.items_out.load(Ordering::Relaxed),
+                    operator_items: replica.operator_items(),
+                    backlog: replica.backlog(),
+                    watermark,
+                    watermark_lag: watermark.and_then(watermark_lag),
+                    batches: replica.batches.load(Ordering::Relaxed),
+                    batch_latency: Duration::from_micros(
+                        replica.batch_latency_us.load(Ordering::Relaxed),
+                    ),
+                    max_batch_latency: Duration::from_micros(
+                        replica.max_batch_latency_us.load(Ordering::Relaxed),
+                    ),
+                })
+            })
+            .collect();
+        replicas.sort_by_key(|r| r.coord);
+        MetricsSnapshot { uptime, replicas }
+    }
+
+    fn report(&self) {
+        let snapshot = self.snapshot();
+        for reporter in self.reporters.lock().iter_mut() {
+            reporter.report(&snapshot);
+        }
+    }
+
+    /// Periodically report the metrics, until the returned handle is dropped. Then the metrics are
+    /// reported one last time.
+    pub(crate) fn start_reporter(self: &Arc<Self>) -> Option<ReporterHandle> {
+        let config = self.config.as_ref()?;
+        *self.start.lock() = Some(Instant::now());
+        if let Some(addr) = config.prometheus {
+            match PrometheusReporter::bind(addr) {
+                Ok(reporter) => self.add_reporter(Box::new(reporter)),
+                Err(e) => error!("cannot expose the metrics on {addr}: {e}"),
+            }
+        }
+        if config.log {
+            self.add_reporter(Box::new(LogReporter));
+        }
+
+        let (stop, stopped) = channel::<()>();
+        let this = self.clone();
+        let interval = config.interval;
+        let thread = std::thread::Builder::new()
+            .name("noir-metrics".into())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    this.report();
+                }
+                this.report();
+                // the reporters (and their endpoints) live as long as the job
+                this.reporters.lock().clear();
+            })
+            .unwrap();
+        Some(ReporterHandle {
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+}
+
+/// Stops the reporting of the metrics when dropped, after the last report.
+pub(crate) struct ReporterHandle {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for ReporterHandle {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(feature = "timestamp")]
+fn watermark_lag(watermark: Timestamp) -> Option<Duration> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_millis() as i64;
+    Some(Duration::from_millis(
+        now.saturating_sub(watermark).max(0) as u64
+    ))
+}
+
+#[cfg(not(feature = "timestamp"))]
+fn watermark_lag(_watermark: Timestamp) -> Option<Duration> {
+    None
+}
+
+/// A watermark left the replica of the current thread.
+#[inline]
+pub(crate) fn watermark(ts: Timestamp) {
+    CURRENT.with(|c| {
+        if let Some(replica) = c.borrow().as_ref() {
+            *replica.watermark.lock() = Some(ts);
+        }
+    });
+}
+
+/// A Prometheus metric: its name, type, description and value for each replica.
+type MetricFamily = (
+    &'static str,
+    &'static str,
+    &'static str,
+    fn(&ReplicaSnapshot) -> Option<f64>,
+);
+
+/// Render the metrics in the Prometheus text exposition format.
+pub fn prometheus_text(metrics: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# HELP noir_uptime_seconds Time elapsed from the start of the job."
+    );
+    let _ = writeln!(out, "# TYPE noir_uptime_seconds gauge");
+    let _ = writeln!(out, "noir_uptime_seconds {}", metrics.uptime.as_secs_f64());
+
+    let families: [MetricFamily; 8] = [
+        (
+            "noir_items_in_total",
+            "counter",
+            "Elements received from the previous blocks.",
+            |r| Some(r.items_in as f64),
+        ),
+        (
+            "noir_items_out_total",
+            "counter",
+            "Elements sent to the next blocks.",
+            |r| Some(r.items_out as f64),
+        ),
+        (
+            "noir_backlog_batches",
+            "gauge",
+            "Batches waiting in the input channels of the replica.",
+            |r| Some(r.backlog as f64),
+        ),
+        (
+            "noir_watermark",
+            "gauge",
+            "The last watermark that left the replica.",
+            |r| r.watermark.map(timestamp_value),
+        ),
+        (
+            "noir_watermark_lag_seconds",
+            "gauge",
+            "How far the last watermark is behind the wall clock.",
+            |r| r.watermark_lag.map(|l| l.as_secs_f64()),
+        ),
+        (
+            "noir_batches_total",
+            "counter",
+            "Batches sent to the next blocks.",
+            |r| Some(r.batches as f64),
+        ),
+        (
+            "noir_batch_latency_seconds_total",
+            "counter",
+            "Total time the first element of the batches waited before being sent.",
+            |r| Some(r.batch_latency.as_secs_f64()),
+        ),
+        (
+            "noir_batch_latency_max_seconds",
+            "gauge",
+            "The longest time the first element of a batch waited before being sent.",
+            |r| Some(r.max_batch_latency.as_secs_f64()),
+        ),
+    ];
+    for (name, kind, help, value) in families {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} {kind}");
+        for replica in &metrics.replicas {
+            if let Some(value) = value(replica) {
+                let _ = writeln!(
+                    out,
+                    "{name}{{block=\"{}\",host=\"{}\",replica=\"{}\",operators=\"{}\"}} {value}",
+                    replica.coord.block_id,
+                    replica.coord.host_id,
+                    replica.coord.replica_id,
+                    escape_label(&replica.operators),
+                );
+            }
+        }
+    }
+
+    let name = "noir_operator_items_out_total";
+    let _ = writeln!(out, "# HELP {name} Elements emitted by the operator.");
+    let _ = writeln!(out, "# TYPE {name} counter");
+    for replica in &metrics.replicas {
+        for operator in &replica.operator_items {
+            let _ = writeln!(
+                out,
+                "{name}{{block=\"{}\",host=\"{}\",replica=\"{}\",index=\"{}\",operator=\"{}\"}} {}",
+                replica.coord.block_id,
+                replica.coord.host_id,
+                replica.coord.replica_id,
+                operator.index,
+                escape_label(&operator.name),
+                operator.items_out,
+            );
+        }
+    }
+    out
+}
+
+#[cfg(feature = "timestamp")]
+fn timestamp_value(ts: Timestamp) -> f64 {
+    ts as f64
+}
+
+#[cfg(not(feature = "timestamp"))]
+fn timestamp_value(_ts: Timestamp) -> f64 {
+    0.0
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Exposes the metrics in the Prometheus text format over HTTP.
+///
+/// Every request gets the metrics of the last report, whatever its path. The endpoint is closed
+/// when the reporter is dropped, i.e. when the job completes.
+#[derive(Debug)]
+pub struct PrometheusReporter {
+    addr: SocketAddr,
+    text: Arc<Mutex<String>>,
+    closed: Arc<AtomicBool>,
+}
+
+impl PrometheusReporter {
+    /// Start serving the metrics on `addr`.
+    pub fn bind(addr: SocketAddr) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        info!("exposing the metrics on http://{addr}/metrics");
+        let text = Arc::new(Mutex::new(String::new()));
+        let closed = Arc::new(AtomicBool::new(false));
+        let (served, stop) = (text.clone(), closed.clone());
+        std::thread::Builder::new()
+            .name("noir-metrics-http".into())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let body = served.lock().clone();
+                    if let Err(e) = stream.and_then(|stream| serve(stream, &body)) {
+                        warn!("metrics request failed: {e}");
+                    }
+                }
+            })?;
+        Ok(Self { addr, text, closed })
+    }
+
+    /// The address the metrics are served on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+fn serve(mut stream: TcpStream, body: &str) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+    // the request is not parsed, read until the end of its headers
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+impl MetricsReporter for PrometheusReporter {
+    fn report(&mut self, metrics: &MetricsSnapshot) {
+        *self.text.lock() = prometheus_text(metrics);
+    }
+}
+
+impl Drop for PrometheusReporter {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+        // wake up the listener
+        let _ = TcpStream::connect(self.addr);
+    }
+}
+
+/// Writes the metrics to the log, one line per replica.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogReporter;
+
+impl MetricsReporter for LogReporter {
+    fn report(&mut self, metrics: &MetricsSnapshot) {
+        info!("metrics after {:?}", metrics.uptime);
+        for r in &metrics.replicas {
+            let operators = if r.operator_items.is_empty() {
+                r.operators.clone()
+            } else {
+                r.operator_items
+                    .iter()
+                    .map(|o| format!("{} {}", o.name, o.items_out))
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            };
+            info!(
+                "{}: in {} out {} backlog {} watermark lag {:?} batches {} (max latency {:?}) | {}",
+                r.coord,
+                r.items_in,
+                r.items_out,
+                r.backlog,
+                r.watermark_lag,
+                r.batches,
+                r.max_batch_latency,
+                operators
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::StreamElement;
+
+    #[test]
+    fn prometheus_format() {
+        let snapshot = MetricsSnapshot {
+            uptime: Duration::from_secs(2),
+            replicas: vec![ReplicaSnapshot {
+                coord: Coord::new(1, 0, 3),
+                operators: "Start -> Map -> \"End\"".into(),
+                items_in: 10,
+                items_out: 7,
+                operator_items: vec![OperatorSnapshot {
+                    index: 1,
+                    name: "Map".into(),
+                    items_out: 9,
+                }],
+                backlog: 2,
+                watermark: None,
+                watermark_lag: None,
+                batches: 1,
+                batch_latency: Duration::from_millis(5),
+                max_batch_latency: Duration::from_millis(5),
+            }],
+        };
+        let text = prometheus_text(&snapshot);
+        let labels = r#"block="1",host="0",replica="3",operators="Start -> Map -> \"End\"""#;
+        assert!(text.contains("noir_uptime_seconds 2\n"));
+        assert!(text.contains(&format!("noir_items_in_total{{{labels}}} 10\n")));
+        assert!(text.contains(&format!("noir_backlog_batches{{{labels}}} 2\n")));
+        assert!(text.contains(
+            "noir_operator_items_out_total{block=\"1\",host=\"0\",replica=\"3\",index=\"1\",operator=\"Map\"} 9\n"
+        ));
+        assert!(text.contains(&format!(
+            "noir_batch_latency_max_seconds{{{labels}}} 0.005\n"
+        )));
+        assert!(!text.contains("noir_watermark{"));
+    }
+
+    #[test]
+    fn counters() {
+        let job = JobMetrics::new(Some(MetricsConfig::new(Duration::from_secs(1))));
+        let (a, b) = (Coord::new(0, 0, 0), Coord::new(1, 0, 0));
+        job.enter(a, "Source".into());
+        job.enter(b, "Sink".into());
+        let (sender, receiver) = (job.handle(a).unwrap(), job.handle(b).unwrap());
+
+        let items = (0..5).map(StreamElement::Item).collect();
+        sender.sent(&NetworkMessage::new_batch(items, a));
+        sender.operator(0, "Source").fetch_add(5, Ordering::Relaxed);
+        let mut items = vec![StreamElement::Item(0); 3];
+        items.push(StreamElement::FlushBatch);
+        receiver.received(&NetworkMessage::new_batch(items, a));
+
+        let (tx, rx) = crate::channel::bounded(4);
+        let rx = Arc::new(rx);
+        receiver.watch(&rx);
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+
+        let snapshot = job.snapshot();
+        assert_eq!(snapshot.replicas.len(), 2);
+        assert_eq!(snapshot.replicas[0].items_out, 5);
+        assert_eq!(snapshot.replicas[0].operator_items[0].items_out, 5);
+        assert_eq!(snapshot.replicas[1].items_in, 3);
+        assert_eq!(snapshot.replicas[1].backlog, 2);
+        // the channels of a completed replica are not counted
+        drop(rx);
+        assert_eq!(job.snapshot().replicas[1].backlog, 0);
+    }
+
+    #[test]
+    fn disabled() {
+        let job = JobMetrics::new(None);
+        assert!(job.handle(Coord::new(0, 0, 0)).is_none());
+    }
+}
@@ -0,0 +1,160 @@
+use std::io::Write;
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::job::JobError;
+use crate::scheduler::HostId;
+
+/// Timeout for connecting to a remote host and for reading a message from it.
+const CONTROL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A message about the whole job sent from a host to all the others.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum ControlMessage {
+    /// A worker of the sending host failed.
+    Failure(JobError),
+    /// The job has been cancelled on the sending host.
+    Cancel,
+}
+
+/// The connections between the hosts of a job that are not part of the job graph.
+///
+/// Each host listens on the port after the ones of its demultiplexers, and sends each message on a
+/// new connection to all the other hosts. The messages are sent on a best-effort basis: the hosts
+/// that already completed their part of the job do not receive them.
+#[derive(Debug)]
+pub(crate) struct ControlPlane {
+    host_id: HostId,
+    /// The address of the control socket of each host, indexed by host id.
+    addresses: Vec<(String, u16)>,
+    /// Set when the listener should exit.
+    stopped: Arc<AtomicBool>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl ControlPlane {
+    /// Bind the control socket of this host and handle the messages from the other hosts with
+    /// `on_message`.
+    pub(crate) fn start<F>(
+        host_id: HostId,
+        addresses: Vec<(String, u16)>,
+        on_message: F,
+    ) -> std::io::Result<Self>
+    where
+        F: Fn(ControlMessage) + Send + 'static,
+    {
+        let address = &addresses[host_id as usize];
+        let listener = TcpListener::bind((address.0.as_str(), address.1))?;
+        log::debug!("control socket of host {host_id} bound at {address:?}");
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stopped2 = stopped.clone();
+        let listener = std::thread::Builder::new()
+            .name(format!("control-{host_id}"))
+            .spawn(move || listen(listener, stopped2, on_message))
+            .unwrap();
+        Ok(Self {
+            host_id,
+            addresses,
+            stopped,
+            listener: Some(listener),
+        })
+    }
+
+    /// Send the message to all the other hosts.
+    pub(crate) fn broadcast(&self, message: &ControlMessage) {
+        broadcast(self.host_id, &self.addresses, message);
+    }
+
+    /// Stop listening for messages from the other hosts.
+    pub(crate) fn stop(&mut self) {
+        let Some(listener) = self.listener.take() else {
+            return;
+        };
+        self.stopped.store(true, Ordering::Release);
+        // wake up the listener blocked in `accept`
+        let _ = send(&self.addresses[self.host_id as usize], &[]);
+        // the last reference to the job may be dropped by the listener itself
+        if listener.thread().id() != std::thread::current().id() {
+            listener.join().unwrap();
+        }
+    }
+}
+
+impl Drop for ControlPlane {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Send the message from `host_id` to all the other hosts, even if the control socket of
+/// `host_id` is not listening.
+pub(crate) fn broadcast(host_id: HostId, addresses: &[(String, u16)], message: &ControlMessage) {
+    let message = bincode::serialize(message).expect("Failed to serialize control message");
+    for (to, address) in addresses.iter().enumerate() {
+        if to as HostId == host_id {
+            continue;
+        }
+        if let Err(e) = send(address, &message) {
+            log::warn!("cannot send control message to host {to} at {address:?}: {e}");
+        }
+    }
+}
+
+fn send(address: &(String, u16), message: &[u8]) -> std::io::Result<()> {
+    let addresses: Vec<SocketAddr> = (address.0.as_str(), address.1).to_socket_addrs()?.collect();
+    let mut last_error = None;
+    for address in addresses {
+        match TcpStream::connect_timeout(&address, CONTROL_TIMEOUT) {
+            Ok(mut stream) => {
+                stream.write_all(message)?;
+                return stream.shutdown(Shutdown::Write);
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| std::io::ErrorKind::AddrNotAvailable.into()))
+}
+
+fn listen<F: Fn(ControlMessage)>(listener: TcpListener, stopped: Arc<AtomicBool>, on_message: F) {
+    for stream in listener.incoming() {
+        if stopped.load(Ordering::Acquire) {
+            break;
+        }
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("failed to accept a control connection: {e:?}");
+                continue;
+            }
+        };
+        let _ = stream.set_read_timeout(Some(CONTROL_TIMEOUT));
+        match bincode::deserialize_from(stream) {
+            Ok(message) => on_message(message),
+            Err(e) => log::warn!("malformed control message: {e:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::ControlPlane;
+
+    #[test]
+    fn start_bind_error() {
+        let taken = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = taken.local_addr().unwrap().port();
+        let addresses = vec![
+            ("127.0.0.1".to_string(), port),
+            ("127.0.0.1".to_string(), 1),
+        ];
+        let error = ControlPlane::start(0, addresses, |_| {}).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AddrInUse);
+    }
+}
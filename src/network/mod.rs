@@ -2,12 +2,14 @@ use std::fmt::{Debug, Display, Formatter};
 
 use serde::{Deserialize, Serialize};
 
+pub(crate) use control::{broadcast as broadcast_control, ControlMessage, ControlPlane};
 pub(crate) use network_channel::*;
 pub(crate) use topology::*;
 
 use crate::operator::StreamElement;
 use crate::scheduler::{BlockId, HostId, ReplicaId};
 
+mod control;
 mod demultiplexer;
 mod multiplexer;
 mod network_channel;
@@ -116,6 +118,26 @@ impl<T> NetworkMessage<T> {
             NetworkData::Batch(v) => v.len(),
         }
     }
+
+    /// The number of elements of the stream in the batch, without the control messages.
+    pub(crate) fn num_data_items(&self) -> usize {
+        match &self.data {
+            NetworkData::Batch(v) => v
+                .iter()
+                .filter(|e| matches!(e, StreamElement::Item(_) | StreamElement::Timestamped(..)))
+                .count(),
+        }
+    }
+
+    /// The number of `StreamElement::Terminate` in the batch.
+    pub(crate) fn num_terminates(&self) -> usize {
+        match &self.data {
+            NetworkData::Batch(v) => v
+                .iter()
+                .filter(|e| matches!(e, StreamElement::Terminate))
+                .count(),
+        }
+    }
 }
 
 impl<T> IntoIterator for NetworkMessage<T> {
@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use thiserror::Error;
@@ -6,6 +7,7 @@ use crate::channel::{
     self, Receiver, RecvError, RecvTimeoutError, SelectResult, Sender, TryRecvError,
 };
 
+use crate::metrics::ReplicaMetrics;
 use crate::network::{NetworkMessage, ReceiverEndpoint};
 use crate::operator::ExchangeData;
 use crate::profiler::{get_profiler, Profiler};
@@ -21,10 +23,12 @@ pub(crate) fn local_channel<T: ExchangeData>(
         NetworkSender {
             receiver_endpoint,
             sender: SenderInner::Local(sender),
+            metrics: None,
         },
         NetworkReceiver {
             receiver_endpoint,
-            receiver,
+            receiver: Arc::new(receiver),
+            metrics: None,
         },
     )
 }
@@ -36,6 +40,7 @@ pub(crate) fn mux_sender<T: ExchangeData>(
     NetworkSender {
         receiver_endpoint,
         sender: SenderInner::Mux(tx),
+        metrics: None,
     }
 }
 
@@ -55,10 +60,27 @@ pub(crate) struct NetworkReceiver<In: ExchangeData> {
     pub receiver_endpoint: ReceiverEndpoint,
     /// The actual receiver where the users of this struct will wait upon.
     #[derivative(Debug = "ignore")]
-    receiver: Receiver<NetworkMessage<In>>,
+    receiver: Arc<Receiver<NetworkMessage<In>>>,
+    /// The metrics of the receiving replica, if enabled.
+    #[derivative(Debug = "ignore")]
+    metrics: Option<Arc<ReplicaMetrics>>,
 }
 
 impl<In: ExchangeData> NetworkReceiver<In> {
+    /// Count the messages received by the replica in `metrics`, together with the ones waiting in
+    /// the channel.
+    pub(crate) fn set_metrics(&mut self, metrics: Arc<ReplicaMetrics>) {
+        metrics.watch(&self.receiver);
+        self.metrics = Some(metrics);
+    }
+
+    #[inline]
+    fn record(&self, message: &NetworkMessage<In>) {
+        if let Some(metrics) = &self.metrics {
+            metrics.received(message);
+        }
+    }
+
     #[inline]
     fn profile_message<E>(
         &self,
@@ -70,6 +92,7 @@ impl<In: ExchangeData> NetworkReceiver<In> {
                 self.receiver_endpoint.coord,
                 message.num_items(),
             );
+            self.record(&message);
             message
         })
     }
@@ -98,7 +121,9 @@ impl<In: ExchangeData> NetworkReceiver<In> {
         &self,
         other: &NetworkReceiver<In2>,
     ) -> SelectResult<NetworkMessage<In>, NetworkMessage<In2>> {
-        self.receiver.select(&other.receiver)
+        let result = self.receiver.select(&other.receiver);
+        self.record_select(other, &result);
+        result
     }
 
     /// Same as `select`, with a timeout.
@@ -107,7 +132,51 @@ impl<In: ExchangeData> NetworkReceiver<In> {
         other: &NetworkReceiver<In2>,
         timeout: Duration,
     ) -> Result<SelectResult<NetworkMessage<In>, NetworkMessage<In2>>, RecvTimeoutError> {
-        self.receiver.select_timeout(&other.receiver, timeout)
+        let result = self.receiver.select_timeout(&other.receiver, timeout);
+        if let Ok(result) = &result {
+            self.record_select(other, result);
+        }
+        result
+    }
+
+    /// Receive a message from any sender of any of the provided receivers, returning also the
+    /// index of the receiver in the list.
+    pub fn select_any(
+        receivers: &[&NetworkReceiver<In>],
+    ) -> (usize, Result<NetworkMessage<In>, RecvError>) {
+        let inner = receivers.iter().map(|r| &*r.receiver).collect::<Vec<_>>();
+        let (index, result) = Receiver::select_any(&inner);
+        if let Ok(message) = &result {
+            receivers[index].record(message);
+        }
+        (index, result)
+    }
+
+    /// Same as `select_any`, with a timeout.
+    pub fn select_any_timeout(
+        receivers: &[&NetworkReceiver<In>],
+        timeout: Duration,
+    ) -> Result<(usize, Result<NetworkMessage<In>, RecvError>), RecvTimeoutError> {
+        let inner = receivers.iter().map(|r| &*r.receiver).collect::<Vec<_>>();
+        let (index, result) = Receiver::select_any_timeout(&inner, timeout)?;
+        if let Ok(message) = &result {
+            receivers[index].record(message);
+        }
+        Ok((index, result))
+    }
+
+    /// Update the metrics with the message received from this receiver or from `other`.
+    #[inline]
+    fn record_select<In2: ExchangeData>(
+        &self,
+        other: &NetworkReceiver<In2>,
+        result: &SelectResult<NetworkMessage<In>, NetworkMessage<In2>>,
+    ) {
+        match result {
+            SelectResult::A(Ok(message)) => self.record(message),
+            SelectResult::B(Ok(message)) => other.record(message),
+            _ => {}
+        }
     }
 }
 
@@ -124,6 +193,9 @@ pub(crate) struct NetworkSender<Out: ExchangeData> {
     /// The generic sender that will send the message either locally or remotely.
     #[derivative(Debug = "ignore")]
     sender: SenderInner<Out>,
+    /// The metrics of the sending replica, if enabled.
+    #[derivative(Debug = "ignore")]
+    metrics: Option<Arc<ReplicaMetrics>>,
 }
 
 #[derive(Clone)]
@@ -133,12 +205,26 @@ enum SenderInner<Out: ExchangeData> {
 }
 
 impl<Out: ExchangeData> NetworkSender<Out> {
+    /// Count the messages sent by the replica in `metrics`.
+    pub(crate) fn set_metrics(&mut self, metrics: Arc<ReplicaMetrics>) {
+        self.metrics = Some(metrics);
+    }
+
+    /// The metrics of the sending replica, if enabled.
+    #[inline]
+    pub(crate) fn metrics(&self) -> Option<&ReplicaMetrics> {
+        self.metrics.as_deref()
+    }
+
     pub fn send(&self, message: NetworkMessage<Out>) -> Result<(), NetworkSendError> {
         get_profiler().items_out(
             message.sender,
             self.receiver_endpoint.coord,
             message.num_items(),
         );
+        if let Some(metrics) = &self.metrics {
+            metrics.sent(&message);
+        }
 
         match &self.sender {
             SenderInner::Mux(tx) => tx
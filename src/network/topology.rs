@@ -3,6 +3,7 @@ use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
 use std::marker::PhantomData;
+use std::sync::Arc;
 #[cfg(not(feature = "async-tokio"))]
 use std::thread::JoinHandle;
 
@@ -13,6 +14,7 @@ use typemap_rev::{TypeMap, TypeMapKey};
 
 use crate::channel::Sender;
 use crate::config::{EnvironmentConfig, ExecutionRuntime};
+use crate::metrics::JobMetrics;
 use crate::network::demultiplexer::DemuxHandle;
 use crate::network::multiplexer::MultiplexingSender;
 use crate::network::{
@@ -66,6 +68,8 @@ struct SenderMetadata {
 pub(crate) struct NetworkTopology {
     /// Configuration of the environment.
     config: EnvironmentConfig,
+    /// The metrics of the replicas, attached to their senders and receivers.
+    metrics: Arc<JobMetrics>,
     /// All the registered receivers.
     ///
     /// Since the `NetworkReceiver` is generic over the element type we cannot simply store them
@@ -123,6 +127,8 @@ pub(crate) struct NetworkTopology {
     /// The mapping between the coordinate of a demultiplexer of a block to the actual address/port
     /// of that demultiplexer in the network.
     demultiplexer_addresses: HashMap<DemuxCoord, (String, u16), crate::block::CoordHasherBuilder>,
+    /// The address of the control socket of each host, after the ports of its demultiplexers.
+    control_addresses: Vec<(String, u16)>,
 
     /// The set of join handles of the various threads spawned by the topology.
     #[cfg(not(feature = "async-tokio"))]
@@ -136,6 +142,7 @@ impl NetworkTopology {
     pub(crate) fn new(config: EnvironmentConfig) -> Self {
         NetworkTopology {
             config,
+            metrics: Default::default(),
             receivers: Some(TypeMap::new()),
             senders: Some(TypeMap::new()),
             demultiplexers: Some(TypeMap::new()),
@@ -147,6 +154,7 @@ impl NetworkTopology {
             used_receivers: Default::default(),
             registered_receivers: Default::default(),
             demultiplexer_addresses: Default::default(),
+            control_addresses: Default::default(),
             #[cfg(not(feature = "async-tokio"))]
             join_handles: Default::default(),
             #[cfg(feature = "async-tokio")]
@@ -154,6 +162,11 @@ impl NetworkTopology {
         }
     }
 
+    /// Attach the metrics of the job to the senders and the receivers of the replicas.
+    pub(crate) fn set_metrics(&mut self, metrics: Arc<JobMetrics>) {
+        self.metrics = metrics;
+    }
+
     #[cfg(feature = "async-tokio")]
     /// Knowing that the computation ended, tear down the topology wait for all of its thread to
     /// exit.
@@ -196,7 +209,11 @@ impl NetworkTopology {
                             None
                         } else {
                             let receiver_endpoint = ReceiverEndpoint::new(c, coord.block_id);
-                            Some((receiver_endpoint, self.get_sender(receiver_endpoint)))
+                            let mut sender = self.get_sender(receiver_endpoint);
+                            if let Some(metrics) = self.metrics.handle(coord) {
+                                sender.set_metrics(metrics);
+                            }
+                            Some((receiver_endpoint, sender))
                         }
                     })
                     .collect()
@@ -263,13 +280,18 @@ impl NetworkTopology {
         if !entry.contains_key(&receiver_endpoint) {
             self.register_channel::<T>(receiver_endpoint);
         }
-        self.receivers
+        let mut receiver = self
+            .receivers
             .as_mut()
             .unwrap()
             .get_mut::<ReceiverKey<T>>()
             .unwrap()
             .remove(&receiver_endpoint)
-            .unwrap()
+            .unwrap();
+        if let Some(metrics) = self.metrics.handle(receiver_endpoint.coord) {
+            receiver.set_metrics(metrics);
+        }
+        receiver
     }
 
     fn register_demux<T: ExchangeData>(
@@ -524,6 +546,25 @@ impl NetworkTopology {
             log::debug!("demux {} socket: {:?}", coord, address);
             self.demultiplexer_addresses.insert(coord, address);
         }
+        self.control_addresses = config
+            .hosts
+            .iter()
+            .enumerate()
+            .map(|(host_id, host)| {
+                let port_offset = used_ports.get(&(host_id as HostId)).copied();
+                (
+                    host.address.clone(),
+                    host.base_port + port_offset.unwrap_or(0),
+                )
+            })
+            .collect();
+    }
+
+    /// The address of the control socket of each host, empty if the job is not remote.
+    ///
+    /// This is available after `build`.
+    pub(crate) fn control_addresses(&self) -> &[(String, u16)] {
+        &self.control_addresses
     }
 
     /// Finalize the topology and start mutliplexers and demultiplexers
@@ -1,7 +1,9 @@
 use std::fmt::Display;
 use std::marker::PhantomData;
 
-use crate::block::{BlockStructure, OperatorStructure};
+use crate::block::{BlockStructure, OperatorStructure, Replication};
+use crate::operator::source::{Source, IDLE_HEARTBEAT};
+use crate::operator::watermark::{IdleSignal, WatermarkGenerator, IDLE_WATERMARK};
 use crate::operator::{Data, Operator, StreamElement, Timestamp};
 use crate::scheduler::ExecutionMetadata;
 
@@ -10,12 +12,15 @@ pub struct AddTimestamp<Out: Data, TimestampGen, WatermarkGen, OperatorChain>
 where
     OperatorChain: Operator<Out>,
     TimestampGen: FnMut(&Out) -> Timestamp + Clone + Send + 'static,
-    WatermarkGen: FnMut(&Out, &Timestamp) -> Option<Timestamp> + Clone + Send + 'static,
+    WatermarkGen: WatermarkGenerator<Out>,
 {
     prev: OperatorChain,
     timestamp_gen: TimestampGen,
     watermark_gen: WatermarkGen,
     pending_watermark: Option<Timestamp>,
+    pending_flush: bool,
+    /// Shared with the end of the block, which sends the idleness to the next blocks.
+    idle: IdleSignal,
     _out: PhantomData<Out>,
 }
 
@@ -24,7 +29,7 @@ impl<Out: Data, TimestampGen, WatermarkGen, OperatorChain> Display
 where
     OperatorChain: Operator<Out>,
     TimestampGen: FnMut(&Out) -> Timestamp + Clone + Send + 'static,
-    WatermarkGen: FnMut(&Out, &Timestamp) -> Option<Timestamp> + Clone + Send + 'static,
+    WatermarkGen: WatermarkGenerator<Out>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} -> AddTimestamp", self.prev)
@@ -36,9 +41,9 @@ impl<Out: Data, TimestampGen, WatermarkGen, OperatorChain>
 where
     OperatorChain: Operator<Out>,
     TimestampGen: FnMut(&Out) -> Timestamp + Clone + Send + 'static,
-    WatermarkGen: FnMut(&Out, &Timestamp) -> Option<Timestamp> + Clone + Send + 'static,
+    WatermarkGen: WatermarkGenerator<Out>,
 {
-    pub(super) fn new(
+    pub(super) fn with_generator(
         prev: OperatorChain,
         timestamp_gen: TimestampGen,
         watermark_gen: WatermarkGen,
@@ -48,39 +53,86 @@ where
             timestamp_gen,
             watermark_gen,
             pending_watermark: None,
+            pending_flush: false,
+            idle: Default::default(),
             _out: Default::default(),
         }
     }
 }
 
+impl<Out: Data, TimestampGen, WatermarkGen, OperatorChain>
+    AddTimestamp<Out, TimestampGen, WatermarkGen, OperatorChain>
+where
+    OperatorChain: Operator<Out>,
+    TimestampGen: FnMut(&Out) -> Timestamp + Clone + Send + 'static,
+    WatermarkGen: FnMut(&Out, &Timestamp) -> Option<Timestamp> + Clone + Send + 'static,
+{
+    pub(super) fn new(
+        prev: OperatorChain,
+        timestamp_gen: TimestampGen,
+        watermark_gen: WatermarkGen,
+    ) -> Self {
+        Self::with_generator(prev, timestamp_gen, watermark_gen)
+    }
+}
+
 impl<Out: Data, TimestampGen, WatermarkGen, OperatorChain> Operator<Out>
     for AddTimestamp<Out, TimestampGen, WatermarkGen, OperatorChain>
 where
     OperatorChain: Operator<Out>,
     TimestampGen: FnMut(&Out) -> Timestamp + Clone + Send + 'static,
-    WatermarkGen: FnMut(&Out, &Timestamp) -> Option<Timestamp> + Clone + Send + 'static,
+    WatermarkGen: WatermarkGenerator<Out>,
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        if self.watermark_gen.needs_heartbeats() {
+            metadata.wake_up_every(IDLE_HEARTBEAT);
+        }
         self.prev.setup(metadata);
+        // the clones of this operator must not share the idleness of their replicas
+        self.idle = IdleSignal::default();
+        metadata.idle_signal = Some(self.idle.clone());
     }
 
     #[inline]
     fn next(&mut self) -> StreamElement<Out> {
         if let Some(ts) = self.pending_watermark.take() {
+            if ts == IDLE_WATERMARK {
+                // the marker is sent to the next blocks by the end of this block
+                self.idle.set_idle(true);
+                return StreamElement::FlushBatch;
+            }
             return StreamElement::Watermark(ts);
         }
+        if self.pending_flush {
+            self.pending_flush = false;
+            return StreamElement::FlushBatch;
+        }
 
         let elem = self.prev.next();
         match elem {
             StreamElement::Item(item) => {
+                if self.idle.is_idle() {
+                    self.idle.set_idle(false);
+                }
                 let ts = (self.timestamp_gen)(&item);
-                let watermark = (self.watermark_gen)(&item, &ts);
+                let watermark = self.watermark_gen.on_event(&item, ts);
                 self.pending_watermark = watermark;
                 StreamElement::Timestamped(item, ts)
             }
-            StreamElement::FlushAndRestart
-            | StreamElement::FlushBatch
-            | StreamElement::Terminate => elem,
+            StreamElement::FlushBatch => match self.watermark_gen.on_idle() {
+                Some(IDLE_WATERMARK) => {
+                    // the marker is sent to the next blocks by the end of this block
+                    self.idle.set_idle(true);
+                    elem
+                }
+                // send the watermark before flushing, so that it is not left in the batch
+                Some(ts) => {
+                    self.pending_flush = true;
+                    StreamElement::Watermark(ts)
+                }
+                None => elem,
+            },
+            StreamElement::FlushAndRestart | StreamElement::Terminate => elem,
             _ => panic!("AddTimestamp received invalid variant: {}", elem.variant()),
         }
     }
@@ -92,6 +144,18 @@ where
     }
 }
 
+impl<Out: Data, TimestampGen, WatermarkGen, OperatorChain> Source<Out>
+    for AddTimestamp<Out, TimestampGen, WatermarkGen, OperatorChain>
+where
+    OperatorChain: Source<Out>,
+    TimestampGen: FnMut(&Out) -> Timestamp + Clone + Send + 'static,
+    WatermarkGen: WatermarkGenerator<Out>,
+{
+    fn replication(&self) -> Replication {
+        self.prev.replication()
+    }
+}
+
 #[derive(Clone)]
 pub struct DropTimestamp<Out: Data, OperatorChain>
 where
@@ -150,7 +214,10 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use crate::operator::add_timestamps::AddTimestamp;
+    use crate::operator::watermark::BoundedOutOfOrderness;
     use crate::operator::{Operator, StreamElement};
     use crate::test::FakeOperator;
 
@@ -178,4 +245,26 @@ mod tests {
         }
         assert_eq!(oper.next(), StreamElement::Terminate);
     }
+
+    #[test]
+    fn add_timestamps_idle() {
+        let mut fake_operator = FakeOperator::new(0..2u64);
+        fake_operator.push(StreamElement::FlushBatch);
+
+        let mut oper = AddTimestamp::with_generator(
+            fake_operator,
+            |n| *n as i64,
+            BoundedOutOfOrderness::new(0).idle_timeout(Duration::ZERO),
+        );
+
+        assert_eq!(oper.next(), StreamElement::Timestamped(0, 0));
+        assert_eq!(oper.next(), StreamElement::Watermark(-1));
+        assert_eq!(oper.next(), StreamElement::Timestamped(1, 1));
+        assert_eq!(oper.next(), StreamElement::Watermark(0));
+        assert!(!oper.idle.is_idle());
+        // the idleness is not emitted as a watermark inside the block
+        assert_eq!(oper.next(), StreamElement::FlushBatch);
+        assert!(oper.idle.is_idle());
+        assert_eq!(oper.next(), StreamElement::Terminate);
+    }
 }
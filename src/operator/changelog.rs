@@ -0,0 +1,514 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::ops::AddAssign;
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::block::{group_by_hash, BlockStructure, GroupHasherBuilder, OperatorStructure};
+use crate::checkpoint::{CheckpointId, Checkpointed, StateCodec};
+use crate::operator::{
+    Data, DataKey, ExchangeData, ExchangeDataKey, KeyerFn, Operator, StreamElement, Timestamp,
+};
+use crate::scheduler::ExecutionMetadata;
+use crate::{KeyedStream, Stream};
+
+/// When the changelog aggregations emit the updated value of a key.
+///
+/// See [`KeyedStream::fold_changelog`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChangelogMode {
+    /// Emit the updated value of a key after every element.
+    #[default]
+    EveryChange,
+    /// Emit the last value of the keys updated since the previous watermark, before forwarding
+    /// the next one.
+    OnWatermark,
+    /// Emit the last value of the keys updated in an interval, which starts with the first update
+    /// after the previous emission.
+    ///
+    /// The values are also emitted before forwarding a watermark, to keep them in order with it.
+    Interval(Duration),
+}
+
+#[derive(Derivative)]
+#[derivative(Debug, Clone)]
+struct ChangelogFold<K: DataKey, I: Data, O: Data, F, OperatorChain>
+where
+    F: Fn(&mut O, I) + Send + Clone,
+    OperatorChain: Operator<(K, I)>,
+{
+    prev: OperatorChain,
+    #[derivative(Debug = "ignore")]
+    fold: F,
+    init: O,
+    mode: ChangelogMode,
+    accumulators: HashMap<K, O, GroupHasherBuilder>,
+    /// The largest timestamp of the elements of each key.
+    timestamps: HashMap<K, Timestamp, GroupHasherBuilder>,
+    /// The keys updated since the last emission, for the throttled modes.
+    changed: HashSet<K, GroupHasherBuilder>,
+    /// When the oldest of the pending updates happened.
+    pending_since: Option<Instant>,
+    ready: VecDeque<StreamElement<(K, O)>>,
+    /// Accumulators and timestamps saved in the checkpoints.
+    checkpoint: Checkpointed<Vec<(K, O, Option<Timestamp>)>>,
+    _in: PhantomData<I>,
+}
+
+impl<K: DataKey, I: Data, O: Data, F, OperatorChain> Display
+    for ChangelogFold<K, I, O, F, OperatorChain>
+where
+    F: Fn(&mut O, I) + Send + Clone,
+    OperatorChain: Operator<(K, I)>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> ChangelogFold<{} -> {}>",
+            self.prev,
+            std::any::type_name::<(K, I)>(),
+            std::any::type_name::<(K, O)>()
+        )
+    }
+}
+
+impl<K: DataKey, I: Data, O: Data, F, OperatorChain> ChangelogFold<K, I, O, F, OperatorChain>
+where
+    F: Fn(&mut O, I) + Send + Clone,
+    OperatorChain: Operator<(K, I)>,
+{
+    fn new(prev: OperatorChain, init: O, fold: F, mode: ChangelogMode) -> Self {
+        Self {
+            prev,
+            fold,
+            init,
+            mode,
+            accumulators: Default::default(),
+            timestamps: Default::default(),
+            changed: Default::default(),
+            pending_since: None,
+            ready: Default::default(),
+            checkpoint: Default::default(),
+            _in: Default::default(),
+        }
+    }
+
+    /// Save the accumulators in the checkpoints of the job.
+    fn checkpointed(mut self) -> Self
+    where
+        K: Serialize + DeserializeOwned,
+        O: Serialize + DeserializeOwned,
+    {
+        self.checkpoint = Checkpointed::new(Some(StateCodec::new()));
+        self
+    }
+
+    fn snapshot(&self, id: CheckpointId) {
+        let accumulators = self
+            .accumulators
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone(), self.timestamps.get(k).copied()))
+            .collect();
+        self.checkpoint.snapshot(id, &accumulators);
+    }
+
+    fn restore(&mut self, key: K, acc: O, ts: Option<Timestamp>) {
+        if let Some(ts) = ts {
+            self.timestamps.insert(key.clone(), ts);
+        }
+        self.accumulators.insert(key, acc);
+    }
+
+    /// The current value of a key, with its timestamp if any.
+    fn updated(&self, key: K) -> StreamElement<(K, O)> {
+        let acc = self.accumulators[&key].clone();
+        match self.timestamps.get(&key) {
+            Some(&ts) => StreamElement::Timestamped((key, acc), ts),
+            None => StreamElement::Item((key, acc)),
+        }
+    }
+
+    /// Emit the value of the keys updated since the last emission.
+    fn emit_changed(&mut self) {
+        for key in std::mem::take(&mut self.changed) {
+            let elem = self.updated(key);
+            self.ready.push_back(elem);
+        }
+        self.pending_since = None;
+    }
+
+    /// Emit the value of the keys updated since the last emission, if the oldest update is older
+    /// than the interval.
+    fn emit_elapsed(&mut self) {
+        if let (ChangelogMode::Interval(interval), Some(since)) = (self.mode, self.pending_since) {
+            if since.elapsed() >= interval {
+                self.emit_changed();
+            }
+        }
+    }
+
+    /// Fold an element with the accumulator of its key, emitting the new value if required.
+    fn process_item(&mut self, key: K, value: I, ts: Option<Timestamp>) {
+        let acc = self
+            .accumulators
+            .entry(key.clone())
+            .or_insert_with(|| self.init.clone());
+        (self.fold)(acc, value);
+        if let Some(ts) = ts {
+            self.timestamps
+                .entry(key.clone())
+                .and_modify(|entry| *entry = (*entry).max(ts))
+                .or_insert(ts);
+        }
+        if self.mode == ChangelogMode::EveryChange {
+            let elem = self.updated(key);
+            self.ready.push_back(elem);
+        } else {
+            self.changed.insert(key);
+            self.pending_since.get_or_insert_with(Instant::now);
+        }
+    }
+}
+
+impl<K: DataKey, I: Data, O: Data, F, OperatorChain> Operator<(K, O)>
+    for ChangelogFold<K, I, O, F, OperatorChain>
+where
+    F: Fn(&mut O, I) + Send + Clone,
+    OperatorChain: Operator<(K, I)>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        if let ChangelogMode::Interval(interval) = self.mode {
+            metadata.wake_up_every(interval.max(Duration::from_millis(1)));
+        }
+        self.prev.setup(metadata);
+        let restored = self.checkpoint.setup(metadata);
+        if let Some(previous) = self.checkpoint.rescaled(metadata) {
+            // keep the keys this replica is responsible for after rescaling
+            let replicas = metadata.replicas.len() as u64;
+            for (_, accumulators) in previous {
+                for (key, acc, ts) in accumulators {
+                    if group_by_hash(&key) % replicas == metadata.global_id {
+                        self.restore(key, acc, ts);
+                    }
+                }
+            }
+        } else if let Some(accumulators) = restored {
+            for (key, acc, ts) in accumulators {
+                self.restore(key, acc, ts);
+            }
+        }
+    }
+
+    fn next(&mut self) -> StreamElement<(K, O)> {
+        loop {
+            if let Some(elem) = self.ready.pop_front() {
+                return elem;
+            }
+            self.emit_elapsed();
+            if let Some(elem) = self.ready.pop_front() {
+                return elem;
+            }
+            match self.prev.next() {
+                StreamElement::Item((k, v)) => self.process_item(k, v, None),
+                StreamElement::Timestamped((k, v), ts) => self.process_item(k, v, Some(ts)),
+                StreamElement::Watermark(ts) => {
+                    self.emit_changed();
+                    self.ready.push_back(StreamElement::Watermark(ts));
+                }
+                StreamElement::FlushBatch => {
+                    // the updates are flushed together with the batch
+                    self.emit_elapsed();
+                    self.ready.push_back(StreamElement::FlushBatch);
+                }
+                StreamElement::Checkpoint(id) => {
+                    // the state saved has no pending changes
+                    self.emit_changed();
+                    if self.checkpoint.is_enabled() {
+                        self.snapshot(id);
+                    }
+                    self.ready.push_back(StreamElement::Checkpoint(id));
+                }
+                StreamElement::FlushAndRestart => {
+                    self.emit_changed();
+                    self.accumulators.clear();
+                    self.timestamps.clear();
+                    self.ready.push_back(StreamElement::FlushAndRestart);
+                }
+                StreamElement::Terminate => {
+                    self.emit_changed();
+                    self.ready.push_back(StreamElement::Terminate);
+                }
+            }
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<(K, O), _>("ChangelogFold"))
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeData,
+    Op: Operator<I> + 'static,
+{
+    /// Find, for each partition of the stream, the sum of the values of the items, emitting the
+    /// updated sum of a partition every time it changes.
+    ///
+    /// This is like [`Stream::group_by_sum`], but the results are emitted while the stream goes
+    /// on, according to `mode` (see [`KeyedStream::fold_changelog`]), so that the sums are
+    /// available on unbounded streams. The items are not aggregated locally before being sent to
+    /// the network.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # use noir::operator::ChangelogMode;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..5)));
+    /// let res = s
+    ///     .group_by_sum_changelog(|&n| n % 2, |n| n, ChangelogMode::EveryChange)
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 0), (0, 2), (0, 6), (1, 1), (1, 4)]);
+    /// ```
+    pub fn group_by_sum_changelog<K, V, Fk, Fv>(
+        self,
+        keyer: Fk,
+        get_value: Fv,
+        mode: ChangelogMode,
+    ) -> KeyedStream<K, V, impl Operator<(K, V)>>
+    where
+        Fk: KeyerFn<K, I> + Fn(&I) -> K,
+        Fv: Fn(I) -> V + Clone + Send + 'static,
+        V: ExchangeData + AddAssign,
+        K: ExchangeDataKey,
+    {
+        self.group_by(keyer)
+            .add_operator(|prev| {
+                let fold = move |acc: &mut Option<V>, value| match acc {
+                    None => *acc = Some(get_value(value)),
+                    Some(acc) => *acc += get_value(value),
+                };
+                ChangelogFold::new(prev, None, fold, mode).checkpointed()
+            })
+            .map(|(_, o)| o.unwrap())
+    }
+
+    /// Count, for each partition of the stream, the number of items, emitting the updated count
+    /// of a partition every time it changes.
+    ///
+    /// This is like [`Stream::group_by_count`], but the results are emitted while the stream
+    /// goes on, according to `mode` (see [`KeyedStream::fold_changelog`]), so that the counts
+    /// are available on unbounded streams. The items are not counted locally before being sent
+    /// to the network.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # use noir::operator::ChangelogMode;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..5)));
+    /// let res = s
+    ///     .group_by_count_changelog(|&n| n % 2, ChangelogMode::EveryChange)
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 1), (0, 2), (0, 3), (1, 1), (1, 2)]);
+    /// ```
+    pub fn group_by_count_changelog<K, Fk>(
+        self,
+        keyer: Fk,
+        mode: ChangelogMode,
+    ) -> KeyedStream<K, usize, impl Operator<(K, usize)>>
+    where
+        Fk: KeyerFn<K, I> + Fn(&I) -> K,
+        K: ExchangeDataKey,
+    {
+        self.group_by(keyer).add_operator(|prev| {
+            ChangelogFold::new(prev, 0, |count: &mut usize, _| *count += 1, mode).checkpointed()
+        })
+    }
+}
+
+impl<K, I, Op> KeyedStream<K, I, Op>
+where
+    K: DataKey,
+    I: Data,
+    Op: Operator<(K, I)> + 'static,
+{
+    /// Perform the folding operation separately for each key, emitting the updated accumulator
+    /// of a key every time it changes.
+    ///
+    /// Unlike [`KeyedStream::fold`], which emits the accumulators only at the end of the stream,
+    /// this operator emits a _changelog_ of the accumulators while the stream goes on, so that it
+    /// can be used on unbounded streams without a window. The updates are emitted according to
+    /// `mode`:
+    /// - [`ChangelogMode::EveryChange`] emits the new accumulator of a key after each of its
+    ///   elements;
+    /// - [`ChangelogMode::OnWatermark`] emits the last accumulator of the keys updated since the
+    ///   previous watermark, when the next watermark arrives;
+    /// - [`ChangelogMode::Interval`] emits the last accumulator of the keys updated in an
+    ///   interval, when the interval elapses after the first of the updates.
+    ///
+    /// The pending updates are always emitted at the end of the stream. Each update has the
+    /// largest timestamp of the elements of its key, if any. The accumulators are kept until the
+    /// end of the stream, and saved in the checkpoints of the job (see
+    /// [`checkpoint`](crate::checkpoint)).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # use noir::operator::ChangelogMode;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..5))).group_by(|&n| n % 2);
+    /// let res = s
+    ///     .fold_changelog(0, |acc, value| *acc += value, ChangelogMode::EveryChange)
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 0), (0, 2), (0, 6), (1, 1), (1, 4)]);
+    /// ```
+    pub fn fold_changelog<O, F>(
+        self,
+        init: O,
+        f: F,
+        mode: ChangelogMode,
+    ) -> KeyedStream<K, O, impl Operator<(K, O)>>
+    where
+        F: Fn(&mut O, I) + Send + Clone + 'static,
+        K: ExchangeDataKey,
+        O: ExchangeData,
+    {
+        self.add_operator(|prev| ChangelogFold::new(prev, init, f, mode).checkpointed())
+    }
+
+    /// Perform the reduction operation separately for each key, emitting the updated value of a
+    /// key every time it changes.
+    ///
+    /// This is like [`KeyedStream::reduce`], but the values are emitted according to `mode` while
+    /// the stream goes on (see [`KeyedStream::fold_changelog`]).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # use noir::operator::ChangelogMode;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..5))).group_by(|&n| n % 2);
+    /// let res = s
+    ///     .reduce_changelog(|acc, value| *acc = (*acc).max(value), ChangelogMode::EveryChange)
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 0), (0, 2), (0, 4), (1, 1), (1, 3)]);
+    /// ```
+    pub fn reduce_changelog<F>(
+        self,
+        f: F,
+        mode: ChangelogMode,
+    ) -> KeyedStream<K, I, impl Operator<(K, I)>>
+    where
+        F: Fn(&mut I, I) + Send + Clone + 'static,
+        K: ExchangeDataKey,
+        I: ExchangeData,
+    {
+        self.fold_changelog(
+            None,
+            move |acc, value| match acc {
+                None => *acc = Some(value),
+                Some(acc) => f(acc, value),
+            },
+            mode,
+        )
+        .map(|(_, value)| value.unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::operator::changelog::{ChangelogFold, ChangelogMode};
+    use crate::operator::{Operator, StreamElement};
+    use crate::test::FakeOperator;
+
+    #[test]
+    fn changelog_every_change() {
+        let fake_operator = FakeOperator::new(vec![(0, 1), (1, 2), (0, 3)].into_iter());
+        let mut fold =
+            ChangelogFold::new(fake_operator, 0, |a, b| *a += b, ChangelogMode::EveryChange);
+        assert_eq!(fold.next(), StreamElement::Item((0, 1)));
+        assert_eq!(fold.next(), StreamElement::Item((1, 2)));
+        assert_eq!(fold.next(), StreamElement::Item((0, 4)));
+        assert_eq!(fold.next(), StreamElement::Terminate);
+    }
+
+    #[test]
+    #[cfg(feature = "timestamp")]
+    fn changelog_on_watermark() {
+        use itertools::Itertools;
+
+        let mut fake_operator = FakeOperator::empty();
+        fake_operator.push(StreamElement::Timestamped((0, 1), 1));
+        fake_operator.push(StreamElement::Timestamped((1, 2), 2));
+        fake_operator.push(StreamElement::Timestamped((0, 3), 3));
+        fake_operator.push(StreamElement::Watermark(3));
+        fake_operator.push(StreamElement::Timestamped((1, 4), 4));
+        let mut fold =
+            ChangelogFold::new(fake_operator, 0, |a, b| *a += b, ChangelogMode::OnWatermark);
+
+        let first = [fold.next(), fold.next()];
+        let first = first.into_iter().sorted_by_key(|e| e.timestamp().copied());
+        let expected = vec![
+            StreamElement::Timestamped((1, 2), 2),
+            StreamElement::Timestamped((0, 4), 3),
+        ];
+        assert_eq!(first.collect_vec(), expected);
+        assert_eq!(fold.next(), StreamElement::Watermark(3));
+        assert_eq!(fold.next(), StreamElement::Timestamped((1, 6), 4));
+        assert_eq!(fold.next(), StreamElement::Terminate);
+    }
+
+    #[test]
+    fn changelog_interval() {
+        let mut fake_operator = FakeOperator::empty();
+        fake_operator.push(StreamElement::Item((0, 1)));
+        fake_operator.push(StreamElement::Item((0, 2)));
+        fake_operator.push(StreamElement::FlushBatch);
+        let mode = ChangelogMode::Interval(Duration::from_millis(10));
+        let mut fold = ChangelogFold::new(fake_operator, 0, |a, b| *a += b, mode);
+        assert_eq!(fold.next(), StreamElement::FlushBatch);
+        std::thread::sleep(Duration::from_millis(20));
+        // the updates are emitted by the next message after the interval
+        assert_eq!(fold.next(), StreamElement::Item((0, 3)));
+        assert_eq!(fold.next(), StreamElement::Terminate);
+    }
+}
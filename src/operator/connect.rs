@@ -0,0 +1,203 @@
+use std::marker::PhantomData;
+
+use itertools::Either;
+
+use crate::block::NextStrategy;
+use crate::operator::merge::MergeElement;
+use crate::operator::start::{BinaryElement, Start};
+use crate::operator::{Data, ExchangeData, Operator};
+use crate::stream::Stream;
+
+/// Two streams with different types of elements, processed together by a single operator.
+///
+/// This is obtained with [`Stream::connect`]. The operator can keep a state, shared between the
+/// elements of the two streams, set with [`ConnectedStreams::with_state`].
+pub struct ConnectedStreams<I1, I2, S, Op>
+where
+    I1: ExchangeData,
+    I2: ExchangeData,
+    Op: Operator<MergeElement<I1, I2>>,
+{
+    inner: Stream<MergeElement<I1, I2>, Op>,
+    state: S,
+    _in: PhantomData<(I1, I2)>,
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeData,
+    Op: Operator<I> + 'static,
+{
+    /// Connect this stream with another stream of a different type, for processing the elements of
+    /// both with a single stateful operator, like [`ConnectedStreams::co_map`].
+    ///
+    /// Both the streams must have the same parallelism, like with [`Stream::merge`].
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let numbers = env.stream(IteratorSource::new((0..3)));
+    /// let words = env.stream(IteratorSource::new(vec!["a", "bb"].into_iter().map(String::from)));
+    /// let res = numbers
+    ///     .connect(words)
+    ///     .co_map(|_, n| n, |_, w| w.len() as i32)
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![0, 1, 1, 2, 2]);
+    /// ```
+    pub fn connect<I2, Op2>(
+        self,
+        oth: Stream<I2, Op2>,
+    ) -> ConnectedStreams<I, I2, (), impl Operator<MergeElement<I, I2>>>
+    where
+        I2: ExchangeData,
+        Op2: Operator<I2> + 'static,
+    {
+        let inner = self
+            .binary_connection(
+                oth,
+                Start::multiple,
+                NextStrategy::only_one(),
+                NextStrategy::only_one(),
+            )
+            .filter_map(|e| match e {
+                BinaryElement::Left(item) => Some(MergeElement::Left(item)),
+                BinaryElement::Right(item) => Some(MergeElement::Right(item)),
+                _ => None,
+            });
+        ConnectedStreams {
+            inner,
+            state: (),
+            _in: PhantomData,
+        }
+    }
+}
+
+impl<I1, I2, S, Op> ConnectedStreams<I1, I2, S, Op>
+where
+    I1: ExchangeData,
+    I2: ExchangeData,
+    S: Data,
+    Op: Operator<MergeElement<I1, I2>> + 'static,
+{
+    /// Set the initial state of the operator processing the two streams.
+    ///
+    /// Each replica starts with a copy of `state`, that is then passed to the functions processing
+    /// the elements of both the streams.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let increments = env.stream(IteratorSource::new(vec![1, 2, 3].into_iter()));
+    /// let resets = env.stream(IteratorSource::new(vec![()].into_iter()));
+    /// let res = increments
+    ///     .connect(resets)
+    ///     .with_state(0)
+    ///     .co_map(
+    ///         |total, n| {
+    ///             *total += n;
+    ///             *total
+    ///         },
+    ///         |total, _| {
+    ///             *total = 0;
+    ///             0
+    ///         },
+    ///     )
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// // the values depend on when the reset is received
+    /// assert_eq!(res.get().unwrap().len(), 4);
+    /// ```
+    pub fn with_state<S2: Data>(self, state: S2) -> ConnectedStreams<I1, I2, S2, Op> {
+        ConnectedStreams {
+            inner: self.inner,
+            state,
+            _in: PhantomData,
+        }
+    }
+
+    /// Map the elements of the two streams into a single stream, with `f1` for the elements of the
+    /// first stream and `f2` for the elements of the second one.
+    ///
+    /// Both functions receive a mutable reference to the state of the replica, see
+    /// [`ConnectedStreams::with_state`].
+    ///
+    /// **Note**: the order in which the elements of the two streams are processed is not specified.
+    pub fn co_map<O, F1, F2>(self, mut f1: F1, mut f2: F2) -> Stream<O, impl Operator<O>>
+    where
+        O: Data,
+        F1: FnMut(&mut S, I1) -> O + Send + Clone + 'static,
+        F2: FnMut(&mut S, I2) -> O + Send + Clone + 'static,
+    {
+        let mut state = self.state;
+        self.inner.rich_map(move |e| match e {
+            MergeElement::Left(item) => f1(&mut state, item),
+            MergeElement::Right(item) => f2(&mut state, item),
+        })
+    }
+
+    /// Map the elements of the two streams into many elements of a single stream, with `f1` for
+    /// the elements of the first stream and `f2` for the elements of the second one.
+    ///
+    /// Both functions receive a mutable reference to the state of the replica, see
+    /// [`ConnectedStreams::with_state`].
+    ///
+    /// **Note**: the order in which the elements of the two streams are processed is not specified.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let numbers = env.stream(IteratorSource::new((0..3)));
+    /// let words = env.stream(IteratorSource::new(vec!["a b", "c"].into_iter().map(String::from)));
+    /// let res = numbers
+    ///     .connect(words)
+    ///     .co_flat_map(
+    ///         |_, n| vec![n.to_string(); n],
+    ///         |_, w| w.split(' ').map(String::from).collect::<Vec<_>>(),
+    ///     )
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec!["1", "2", "2", "a", "b", "c"]);
+    /// ```
+    pub fn co_flat_map<O, It1, It2, F1, F2>(
+        self,
+        mut f1: F1,
+        mut f2: F2,
+    ) -> Stream<O, impl Operator<O>>
+    where
+        O: Data,
+        It1: IntoIterator<Item = O>,
+        <It1 as IntoIterator>::IntoIter: Clone + Send + 'static,
+        It2: IntoIterator<Item = O>,
+        <It2 as IntoIterator>::IntoIter: Clone + Send + 'static,
+        F1: FnMut(&mut S, I1) -> It1 + Send + Clone + 'static,
+        F2: FnMut(&mut S, I2) -> It2 + Send + Clone + 'static,
+    {
+        let mut state = self.state;
+        self.inner.rich_flat_map(move |e| match e {
+            MergeElement::Left(item) => Either::Left(f1(&mut state, item).into_iter()),
+            MergeElement::Right(item) => Either::Right(f2(&mut state, item).into_iter()),
+        })
+    }
+}
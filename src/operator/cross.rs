@@ -0,0 +1,204 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+
+use crate::block::{BlockStructure, NextStrategy, OperatorStructure};
+use crate::operator::start::{BinaryElement, BinaryStartOperator, Start};
+use crate::operator::{ExchangeData, Operator, StreamElement};
+use crate::scheduler::ExecutionMetadata;
+use crate::Stream;
+
+/// Pair each element on the left with all the elements on the right.
+///
+/// The right side is kept in memory, the left elements are paired as soon as the right side has
+/// ended.
+#[derive(Clone, Debug)]
+struct Cross<Out1: ExchangeData, Out2: ExchangeData> {
+    prev: BinaryStartOperator<Out1, Out2>,
+    /// The left elements received before the end of the right side.
+    left: VecDeque<Out1>,
+    right: Vec<Out2>,
+    right_ended: bool,
+    /// The left element being paired and the index of the next right element to pair it with.
+    current: Option<(Out1, usize)>,
+}
+
+impl<Out1: ExchangeData, Out2: ExchangeData> Display for Cross<Out1, Out2> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> Cross<{}, {}>",
+            self.prev,
+            std::any::type_name::<Out1>(),
+            std::any::type_name::<Out2>()
+        )
+    }
+}
+
+impl<Out1: ExchangeData, Out2: ExchangeData> Cross<Out1, Out2> {
+    fn new(prev: BinaryStartOperator<Out1, Out2>) -> Self {
+        Self {
+            prev,
+            left: Default::default(),
+            right: Default::default(),
+            right_ended: false,
+            current: None,
+        }
+    }
+
+    /// The next pair with the left elements already received, if the right side has ended.
+    fn next_pair(&mut self) -> Option<(Out1, Out2)> {
+        if !self.right_ended {
+            return None;
+        }
+        loop {
+            match &mut self.current {
+                Some((item, index)) if *index < self.right.len() => {
+                    *index += 1;
+                    return Some((item.clone(), self.right[*index - 1].clone()));
+                }
+                _ => self.current = Some((self.left.pop_front()?, 0)),
+            }
+        }
+    }
+}
+
+impl<Out1: ExchangeData, Out2: ExchangeData> Operator<(Out1, Out2)> for Cross<Out1, Out2> {
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+        // the buffered elements are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("cross is not supported");
+        }
+    }
+
+    fn next(&mut self) -> StreamElement<(Out1, Out2)> {
+        loop {
+            if let Some(pair) = self.next_pair() {
+                return StreamElement::Item(pair);
+            }
+            match self.prev.next() {
+                StreamElement::Item(BinaryElement::Left(item)) => self.left.push_back(item),
+                StreamElement::Item(BinaryElement::Right(item)) => self.right.push(item),
+                StreamElement::Item(BinaryElement::RightEnd) => self.right_ended = true,
+                StreamElement::Item(BinaryElement::LeftEnd) => {}
+                StreamElement::FlushAndRestart => {
+                    // both sides have ended, so all the left elements have been paired
+                    debug_assert!(self.left.is_empty());
+                    self.right.clear();
+                    self.right_ended = false;
+                    self.current = None;
+                    return StreamElement::FlushAndRestart;
+                }
+                StreamElement::Terminate => return StreamElement::Terminate,
+                StreamElement::FlushBatch => return StreamElement::FlushBatch,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
+                StreamElement::Watermark(_) | StreamElement::Timestamped(_, _) => {
+                    panic!("Cannot yet cross timestamped streams")
+                }
+            }
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<(Out1, Out2), _>("Cross"))
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeData,
+    Op: Operator<I> + 'static,
+{
+    /// Compute the Cartesian product of this stream with another one, pairing every element of
+    /// this stream with every element of `oth`.
+    ///
+    /// The elements of `oth` are broadcast to all the replicas, that keep them in memory: `oth`
+    /// should be the smaller stream. The elements of this stream are not moved between replicas,
+    /// and are paired as soon as `oth` has ended.
+    ///
+    /// **Note**: the order of the pairs is unspecified.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    /// let s1 = env.stream(IteratorSource::new(vec!['A', 'B'].into_iter()));
+    /// let s2 = env.stream(IteratorSource::new(vec![1, 2, 3].into_iter()));
+    /// let res = s1.cross(s2).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(
+    ///     res,
+    ///     vec![('A', 1), ('A', 2), ('A', 3), ('B', 1), ('B', 2), ('B', 3)]
+    /// );
+    /// ```
+    pub fn cross<I2, Op2>(self, oth: Stream<I2, Op2>) -> Stream<(I, I2), impl Operator<(I, I2)>>
+    where
+        I2: ExchangeData,
+        Op2: Operator<I2> + 'static,
+    {
+        self.binary_connection(
+            oth,
+            Start::multiple,
+            NextStrategy::only_one(),
+            NextStrategy::all(),
+        )
+        .add_operator(Cross::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::{Coord, NetworkMessage, NetworkSender};
+    use crate::operator::cross::Cross;
+    use crate::operator::start::Start;
+    use crate::operator::{Operator, StreamElement};
+    use crate::test::FakeNetworkTopology;
+
+    #[test]
+    fn cross() {
+        let mut t = FakeNetworkTopology::new(2, 1);
+
+        let (coord_l, sender_l) = t.senders_mut()[0].pop().unwrap();
+        let (coord_r, sender_r) = t.senders_mut()[1].pop().unwrap();
+
+        let start = Start::multiple(coord_l.block_id, coord_r.block_id, false, false, None);
+        let mut cross = Cross::<i32, i32>::new(start);
+        cross.setup(&mut t.metadata());
+
+        let send = |sender: &NetworkSender<i32>, from: Coord, data: Vec<StreamElement<i32>>| {
+            sender.send(NetworkMessage::new_batch(data, from)).unwrap();
+        };
+
+        send(&sender_l, coord_l, vec![StreamElement::Item(1)]);
+        send(
+            &sender_r,
+            coord_r,
+            vec![
+                StreamElement::Item(10),
+                StreamElement::Item(20),
+                StreamElement::FlushAndRestart,
+            ],
+        );
+        assert_eq!(cross.next(), StreamElement::Item((1, 10)));
+        assert_eq!(cross.next(), StreamElement::Item((1, 20)));
+
+        send(
+            &sender_l,
+            coord_l,
+            vec![StreamElement::Item(2), StreamElement::FlushAndRestart],
+        );
+        assert_eq!(cross.next(), StreamElement::Item((2, 10)));
+        assert_eq!(cross.next(), StreamElement::Item((2, 20)));
+        assert_eq!(cross.next(), StreamElement::FlushAndRestart);
+    }
+}
@@ -0,0 +1,517 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::{
+    group_by_hash, BlockStructure, GroupHasherBuilder, NextStrategy, OperatorStructure,
+};
+use crate::checkpoint::{CheckpointId, Checkpointed, StateCodec};
+use crate::operator::end::End;
+use crate::operator::{Data, ExchangeData, ExchangeDataKey, KeyerFn, Operator, StreamElement};
+use crate::scheduler::ExecutionMetadata;
+use crate::{KeyedStream, Stream};
+
+/// Forward only the first element of each key, and drop the following ones.
+///
+/// With a time-to-live a key is forgotten after some time since its first element, and the next
+/// element with that key is forwarded again.
+#[derive(Derivative)]
+#[derivative(Debug, Clone)]
+pub struct Distinct<I: Data, K: ExchangeDataKey, Keyer, PreviousOperators>
+where
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<I>,
+{
+    prev: PreviousOperators,
+    #[derivative(Debug = "ignore")]
+    keyer: Keyer,
+    ttl: Option<Duration>,
+    seen: HashSet<K, GroupHasherBuilder>,
+    /// When the keys in `seen` expire, sorted by time. Empty without a time-to-live.
+    expiry: VecDeque<(Instant, K)>,
+    /// The keys seen and, with a time-to-live, how long they will be remembered.
+    checkpoint: Checkpointed<Vec<(K, Option<Duration>)>>,
+    _in: PhantomData<I>,
+}
+
+impl<I: Data, K: ExchangeDataKey, Keyer, PreviousOperators> Display
+    for Distinct<I, K, Keyer, PreviousOperators>
+where
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<I>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> Distinct<{}>",
+            self.prev,
+            std::any::type_name::<K>()
+        )
+    }
+}
+
+impl<I: Data, K: ExchangeDataKey, Keyer, PreviousOperators> Distinct<I, K, Keyer, PreviousOperators>
+where
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<I>,
+{
+    pub(super) fn new(prev: PreviousOperators, keyer: Keyer, ttl: Option<Duration>) -> Self {
+        Self {
+            prev,
+            keyer,
+            ttl,
+            seen: Default::default(),
+            expiry: Default::default(),
+            checkpoint: Checkpointed::new(Some(StateCodec::new())),
+            _in: Default::default(),
+        }
+    }
+
+    fn snapshot(&self, id: CheckpointId) {
+        let state = if self.ttl.is_some() {
+            let now = Instant::now();
+            self.expiry
+                .iter()
+                .map(|(expire, key)| (key.clone(), Some(expire.saturating_duration_since(now))))
+                .collect()
+        } else {
+            self.seen.iter().map(|key| (key.clone(), None)).collect()
+        };
+        self.checkpoint.snapshot(id, &state);
+    }
+
+    fn restore(&mut self, keys: Vec<(K, Option<Duration>)>) {
+        let now = Instant::now();
+        for (key, remaining) in keys {
+            if self.seen.insert(key.clone()) {
+                if let Some(remaining) = remaining {
+                    self.expiry.push_back((now + remaining, key));
+                }
+            }
+        }
+    }
+
+    /// Forget the keys whose time-to-live has elapsed.
+    fn expire(&mut self, now: Instant) {
+        while let Some((expire, _)) = self.expiry.front() {
+            if *expire > now {
+                break;
+            }
+            let (_, key) = self.expiry.pop_front().unwrap();
+            self.seen.remove(&key);
+        }
+    }
+
+    /// Whether this is the first element with its key.
+    fn is_new(&mut self, item: &I) -> bool {
+        let key = (self.keyer)(item);
+        match self.ttl {
+            Some(ttl) => {
+                let now = Instant::now();
+                self.expire(now);
+                if self.seen.contains(&key) {
+                    return false;
+                }
+                self.seen.insert(key.clone());
+                self.expiry.push_back((now + ttl, key));
+                true
+            }
+            None => self.seen.insert(key),
+        }
+    }
+}
+
+impl<I: Data, K: ExchangeDataKey, Keyer, PreviousOperators> Operator<I>
+    for Distinct<I, K, Keyer, PreviousOperators>
+where
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<I>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+        let restored = self.checkpoint.setup(metadata);
+        if let Some(previous) = self.checkpoint.rescaled(metadata) {
+            // remembering more keys than needed is harmless: every replica keeps all of them
+            for (_, keys) in previous {
+                self.restore(keys);
+            }
+            self.expiry
+                .make_contiguous()
+                .sort_by_key(|(expire, _)| *expire);
+        } else if let Some(keys) = restored {
+            self.restore(keys);
+        }
+    }
+
+    fn next(&mut self) -> StreamElement<I> {
+        loop {
+            match self.prev.next() {
+                StreamElement::Item(item) | StreamElement::Timestamped(item, _)
+                    if !self.is_new(&item) => {}
+                StreamElement::Checkpoint(id) => {
+                    self.snapshot(id);
+                    return StreamElement::Checkpoint(id);
+                }
+                StreamElement::FlushAndRestart => {
+                    self.seen.clear();
+                    self.expiry.clear();
+                    return StreamElement::FlushAndRestart;
+                }
+                element => return element,
+            }
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<I, _>("Distinct"))
+    }
+}
+
+/// The number of bits of the hash used to pick the register of a [`HyperLogLog`].
+const HLL_PRECISION: u32 = 14;
+
+/// A HyperLogLog sketch, estimating the number of distinct elements inserted.
+///
+/// The sketch uses `2^14` registers, for a relative standard error of about 0.8%. Two sketches
+/// can be merged to estimate the distinct elements of the union of their inputs.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; 1 << HLL_PRECISION],
+        }
+    }
+}
+
+impl HyperLogLog {
+    pub(crate) fn insert<T: Hash>(&mut self, item: &T) {
+        // a different seed than `group_by_hash`, since the elements may already be partitioned by
+        // their hash
+        let mut hasher = wyhash::WyHash::with_seed(0xfedcba9876543210);
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let index = (hash >> (64 - HLL_PRECISION)) as usize;
+        // the position of the first 1 in the remaining bits, the last register bit stops the count
+        let rank = ((hash << HLL_PRECISION) | (1 << (HLL_PRECISION - 1))).leading_zeros() + 1;
+        self.registers[index] = self.registers[index].max(rank as u8);
+    }
+
+    pub(crate) fn merge(&mut self, other: Self) {
+        for (register, other) in self.registers.iter_mut().zip(other.registers) {
+            *register = (*register).max(other);
+        }
+    }
+
+    pub(crate) fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum = self
+            .registers
+            .iter()
+            .map(|&r| 2f64.powi(-(r as i32)))
+            .sum::<f64>();
+        let estimate = alpha * m * m / sum;
+
+        // small range correction
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeData,
+    Op: Operator<I> + 'static,
+{
+    /// Remove the duplicated elements of the stream, keeping only the first element of each key.
+    ///
+    /// The key of the elements is obtained with `keyer`. Each replica first drops the duplicates
+    /// it sees, then the remaining elements are partitioned by key and deduplicated again: only
+    /// the first copy of each element is sent to the network.
+    ///
+    /// The elements are forwarded as soon as they arrive, hence this operator can be used also on
+    /// unbounded streams. Since every key is remembered until the stream ends, consider
+    /// [`Stream::distinct_by_within`] if the number of keys is not bounded.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..10)));
+    /// let res = s.distinct_by(|&n| n % 3).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![0, 1, 2]);
+    /// ```
+    pub fn distinct_by<K, Fk>(self, keyer: Fk) -> Stream<I, impl Operator<I>>
+    where
+        K: ExchangeDataKey,
+        Fk: KeyerFn<K, I>,
+    {
+        self.distinct_by_inner(keyer, None)
+    }
+
+    /// Remove the duplicated elements of the stream, keeping only the first element of each key
+    /// seen in the last `ttl` of processing time.
+    ///
+    /// This works like [`Stream::distinct_by`], but a key is forgotten `ttl` after the element
+    /// that was forwarded: the next element with the same key is forwarded again. This bounds the
+    /// memory used on unbounded streams.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..10)));
+    /// let res = s
+    ///     .distinct_by_within(|&n| n % 3, Duration::from_secs(60))
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![0, 1, 2]);
+    /// ```
+    pub fn distinct_by_within<K, Fk>(self, keyer: Fk, ttl: Duration) -> Stream<I, impl Operator<I>>
+    where
+        K: ExchangeDataKey,
+        Fk: KeyerFn<K, I>,
+    {
+        self.distinct_by_inner(keyer, Some(ttl))
+    }
+
+    fn distinct_by_inner<K, Fk>(
+        self,
+        keyer: Fk,
+        ttl: Option<Duration>,
+    ) -> Stream<I, impl Operator<I>>
+    where
+        K: ExchangeDataKey,
+        Fk: KeyerFn<K, I>,
+    {
+        let keyer_shuffle = keyer.clone();
+        let next_strategy = NextStrategy::GroupBy(
+            move |item: &I| group_by_hash(&keyer_shuffle(item)),
+            Default::default(),
+        );
+        let keyer_global = keyer.clone();
+        self.add_operator(|prev| Distinct::new(prev, keyer, ttl))
+            .split_block(End::new, next_strategy)
+            .add_operator(|prev| Distinct::new(prev, keyer_global, ttl))
+    }
+
+    /// Estimate the number of distinct elements of the stream.
+    ///
+    /// Each replica builds a HyperLogLog sketch of its elements, the sketches are then merged into
+    /// the final estimate. The relative standard error of the estimate is about 0.8%, while only
+    /// 16KiB per replica are sent to the network.
+    ///
+    /// **Note**: this operator emits the estimate only when the stream ends, but it keeps only the
+    /// sketch in memory, not the elements.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..100_000).map(|n| n % 1000)));
+    /// let res = s.approx_count_distinct().collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let count = res.get().unwrap()[0];
+    /// assert!((980..=1020).contains(&count));
+    /// ```
+    pub fn approx_count_distinct(self) -> Stream<u64, impl Operator<u64>>
+    where
+        I: Hash,
+    {
+        self.fold_assoc(
+            HyperLogLog::default(),
+            |sketch, item| sketch.insert(&item),
+            |sketch, other| sketch.merge(other),
+        )
+        .map(|sketch| sketch.estimate())
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeDataKey,
+    Op: Operator<I> + 'static,
+{
+    /// Remove the duplicated elements of the stream.
+    ///
+    /// This is equivalent to [`Stream::distinct_by`] using the whole element as the key.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new(vec![1, 2, 1, 3, 2, 1].into_iter()));
+    /// let res = s.distinct().collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![1, 2, 3]);
+    /// ```
+    pub fn distinct(self) -> Stream<I, impl Operator<I>> {
+        self.distinct_by(|item| item.clone())
+    }
+}
+
+impl<K, I, Op> KeyedStream<K, I, Op>
+where
+    K: ExchangeDataKey,
+    I: Data,
+    Op: Operator<(K, I)> + 'static,
+{
+    /// Remove the duplicated elements of each key, keeping only the first element for each value
+    /// returned by `keyer`.
+    ///
+    /// Since all the elements of a key are already in the same replica, this operator does not
+    /// send anything to the network.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..10))).group_by(|&n| n % 2);
+    /// let res = s.distinct_by(|&n| n % 3).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 0), (0, 2), (0, 4), (1, 1), (1, 3), (1, 5)]);
+    /// ```
+    pub fn distinct_by<D, Fd>(self, keyer: Fd) -> KeyedStream<K, I, impl Operator<(K, I)>>
+    where
+        D: ExchangeDataKey,
+        Fd: KeyerFn<D, I>,
+    {
+        self.add_operator(|prev| {
+            Distinct::new(prev, move |(k, v): &(K, I)| (k.clone(), keyer(v)), None)
+        })
+    }
+}
+
+impl<K, I, Op> KeyedStream<K, I, Op>
+where
+    K: ExchangeDataKey,
+    I: ExchangeDataKey,
+    Op: Operator<(K, I)> + 'static,
+{
+    /// Remove the duplicated elements of each key.
+    ///
+    /// This is equivalent to [`KeyedStream::distinct_by`] using the whole element as the key.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env
+    ///     .stream(IteratorSource::new(vec![(0, 'a'), (1, 'a'), (0, 'a'), (0, 'b')].into_iter()))
+    ///     .group_by(|&(k, _)| k)
+    ///     .map(|(_, (_, c))| c);
+    /// let res = s.distinct().collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 'a'), (0, 'b'), (1, 'a')]);
+    /// ```
+    pub fn distinct(self) -> KeyedStream<K, I, impl Operator<(K, I)>> {
+        self.distinct_by(|item| item.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::operator::distinct::{Distinct, HyperLogLog};
+    use crate::operator::{Operator, StreamElement};
+    use crate::test::FakeOperator;
+
+    #[test]
+    fn distinct_keeps_first() {
+        let fake = FakeOperator::new(vec![(1, 'a'), (2, 'b'), (1, 'c'), (3, 'd')].into_iter());
+        let mut distinct = Distinct::new(fake, |&(k, _): &(i32, char)| k, None);
+        assert_eq!(distinct.next(), StreamElement::Item((1, 'a')));
+        assert_eq!(distinct.next(), StreamElement::Item((2, 'b')));
+        assert_eq!(distinct.next(), StreamElement::Item((3, 'd')));
+        assert_eq!(distinct.next(), StreamElement::Terminate);
+    }
+
+    #[test]
+    fn distinct_ttl_expires() {
+        let mut fake = FakeOperator::empty();
+        fake.push(StreamElement::Item(1));
+        fake.push(StreamElement::Item(1));
+        let mut distinct = Distinct::new(fake, |&n: &i32| n, Some(Duration::ZERO));
+        assert_eq!(distinct.next(), StreamElement::Item(1));
+        assert_eq!(distinct.next(), StreamElement::Item(1));
+        assert_eq!(distinct.next(), StreamElement::Terminate);
+    }
+
+    #[test]
+    fn hyperloglog_estimate() {
+        for count in [0u64, 10, 1_000, 100_000] {
+            let mut sketch = HyperLogLog::default();
+            let mut other = HyperLogLog::default();
+            for n in 0..count {
+                sketch.insert(&n);
+                other.insert(&(n / 2));
+            }
+            sketch.merge(other);
+            let estimate = sketch.estimate() as f64;
+            assert!(
+                (estimate - count as f64).abs() <= count as f64 * 0.03,
+                "estimated {estimate} for {count} distinct elements"
+            );
+        }
+    }
+}
@@ -5,6 +5,8 @@ use crate::block::{
     BatchMode, Batcher, BlockStructure, Connection, NextStrategy, OperatorStructure,
 };
 use crate::network::{Coord, ReceiverEndpoint};
+#[cfg(feature = "timestamp")]
+use crate::operator::watermark::{IdleSignal, IDLE_WATERMARK};
 use crate::operator::{ExchangeData, KeyerFn, Operator, StreamElement};
 use crate::scheduler::{BlockId, ExecutionMetadata};
 
@@ -37,6 +39,8 @@ where
     senders: Vec<(ReceiverEndpoint, Batcher<Out>)>,
     feedback_id: Option<BlockId>,
     ignore_block_ids: Vec<BlockId>,
+    #[cfg(feature = "timestamp")]
+    idle: Option<IdleSignal>,
 }
 
 impl<Out: ExchangeData, OperatorChain, IndexFn> Display for End<Out, OperatorChain, IndexFn>
@@ -72,6 +76,8 @@ where
             senders: Default::default(),
             feedback_id: None,
             ignore_block_ids: Default::default(),
+            #[cfg(feature = "timestamp")]
+            idle: None,
         }
     }
 
@@ -136,6 +142,10 @@ where
 
         self.setup_senders();
 
+        #[cfg(feature = "timestamp")]
+        {
+            self.idle = metadata.idle_signal.clone();
+        }
         self.coord = Some(metadata.coord);
     }
 
@@ -146,7 +156,8 @@ where
             // Broadcast messages
             StreamElement::Watermark(_)
             | StreamElement::Terminate
-            | StreamElement::FlushAndRestart => {
+            | StreamElement::FlushAndRestart
+            | StreamElement::Checkpoint(_) => {
                 for block in self.block_senders.iter() {
                     for &sender_idx in block.indexes.iter() {
                         let sender = &mut self.senders[sender_idx];
@@ -172,12 +183,23 @@ where
                     self.senders[sender_idx].1.enqueue(message.clone());
                 }
             }
-            StreamElement::FlushBatch => {}
+            StreamElement::FlushBatch => {
+                // the replica became idle: only the next blocks receive the marker
+                #[cfg(feature = "timestamp")]
+                if self.idle.as_mut().is_some_and(|idle| idle.poll()) {
+                    for (_, batcher) in self.senders.iter_mut() {
+                        batcher.enqueue(StreamElement::Watermark(IDLE_WATERMARK));
+                    }
+                }
+            }
         };
 
-        // Flushing messages
+        // Flushing messages, the barriers are not held back since the next blocks are waiting for
+        // them to be aligned
         match to_return {
-            StreamElement::FlushAndRestart | StreamElement::FlushBatch => {
+            StreamElement::FlushAndRestart
+            | StreamElement::FlushBatch
+            | StreamElement::Checkpoint(_) => {
                 for (_, batcher) in self.senders.iter_mut() {
                     batcher.flush();
                 }
@@ -4,6 +4,7 @@ use std::marker::PhantomData;
 use crate::block::{BlockStructure, OperatorStructure};
 use crate::operator::{Data, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 
 #[derive(Clone)]
 pub struct Filter<Out: Data, PreviousOperator, Predicate>
@@ -12,6 +13,7 @@ where
     PreviousOperator: Operator<Out> + 'static,
 {
     prev: PreviousOperator,
+    operator: OperatorIndex,
     predicate: Predicate,
     _out: PhantomData<Out>,
 }
@@ -39,6 +41,7 @@ where
     pub(super) fn new(prev: PreviousOperator, predicate: Predicate) -> Self {
         Self {
             prev,
+            operator: OperatorIndex::default(),
             predicate,
             _out: Default::default(),
         }
@@ -53,12 +56,15 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
     }
 
     #[inline]
     fn next(&mut self) -> StreamElement<Out> {
         loop {
-            match self.prev.next() {
+            let element = self.prev.next();
+            let _operator = self.operator.enter();
+            match element {
                 StreamElement::Item(ref item) | StreamElement::Timestamped(ref item, _)
                     if !(self.predicate)(item) => {}
                 element => return element,
@@ -4,6 +4,7 @@ use std::marker::PhantomData;
 use crate::block::{BlockStructure, OperatorStructure};
 use crate::operator::{Data, Operator};
 
+use crate::worker::OperatorIndex;
 use crate::ExecutionMetadata;
 
 use super::StreamElement;
@@ -15,6 +16,7 @@ where
     PreviousOperator: Operator<In> + 'static,
 {
     prev: PreviousOperator,
+    operator: OperatorIndex,
     predicate: Predicate,
     _in: PhantomData<In>,
     _out: PhantomData<Out>,
@@ -45,6 +47,7 @@ where
     pub(super) fn new(prev: PreviousOperator, predicate: Predicate) -> Self {
         Self {
             prev,
+            operator: OperatorIndex::default(),
             predicate,
             _in: Default::default(),
             _out: Default::default(),
@@ -60,12 +63,15 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
     }
 
     #[inline]
     fn next(&mut self) -> StreamElement<Out> {
         loop {
-            match self.prev.next() {
+            let element = self.prev.next();
+            let _operator = self.operator.enter();
+            match element {
                 StreamElement::Item(item) => {
                     if let Some(el) = (self.predicate)(item) {
                         return StreamElement::Item(el);
@@ -79,6 +85,7 @@ where
                 StreamElement::Watermark(w) => return StreamElement::Watermark(w),
                 StreamElement::Terminate => return StreamElement::Terminate,
                 StreamElement::FlushAndRestart => return StreamElement::FlushAndRestart,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
             }
         }
@@ -5,6 +5,7 @@ use std::marker::PhantomData;
 use crate::block::{BlockStructure, OperatorStructure};
 use crate::operator::{Data, DataKey, Operator, StreamElement, Timestamp};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 
 #[derive(Derivative)]
 #[derivative(Debug)]
@@ -18,6 +19,7 @@ where
     F: Fn(In) -> Iter + Clone + Send + 'static,
 {
     prev: PreviousOperators,
+    operator: OperatorIndex,
     f: F,
     // used to store elements that have not been returned by next() yet
     // buffer: VecDeque<StreamElement<NewOut>>,
@@ -45,6 +47,7 @@ where
     fn clone(&self) -> Self {
         Self {
             prev: self.prev.clone(),
+            operator: self.operator,
             f: self.f.clone(),
             frontiter: None,
             #[cfg(feature = "timestamp")]
@@ -87,6 +90,7 @@ where
     pub(super) fn new(prev: PreviousOperators, f: F) -> Self {
         Self {
             prev,
+            operator: OperatorIndex::default(),
             f,
             frontiter: None,
             #[cfg(feature = "timestamp")]
@@ -109,11 +113,13 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
     }
 
     fn next(&mut self) -> StreamElement<Out> {
         loop {
             if let Some(ref mut inner) = self.frontiter {
+                let _operator = self.operator.enter();
                 match inner.next() {
                     None => self.frontiter = None,
                     #[cfg(not(feature = "timestamp"))]
@@ -125,7 +131,9 @@ where
                     },
                 }
             }
-            match self.prev.next() {
+            let element = self.prev.next();
+            let _operator = self.operator.enter();
+            match element {
                 #[cfg(not(feature = "timestamp"))]
                 StreamElement::Item(inner) | StreamElement::Timestamped(inner, _) => {
                     self.frontiter = Some((self.f)(inner).into_iter());
@@ -145,6 +153,7 @@ where
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
                 StreamElement::Terminate => return StreamElement::Terminate,
                 StreamElement::FlushAndRestart => return StreamElement::FlushAndRestart,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
             }
         }
     }
@@ -169,6 +178,7 @@ where
     F: Fn((&Key, In)) -> Iter + Clone + Send + 'static,
 {
     prev: PreviousOperators,
+    operator: OperatorIndex,
     f: F,
     // used to store elements that have not been returned by next() yet
     // buffer: VecDeque<StreamElement<NewOut>>,
@@ -197,6 +207,7 @@ where
     fn clone(&self) -> Self {
         Self {
             prev: self.prev.clone(),
+            operator: self.operator,
             f: self.f.clone(),
             frontiter: None,
             timestamp: self.timestamp,
@@ -243,6 +254,7 @@ where
     pub(super) fn new(prev: PreviousOperators, f: F) -> Self {
         Self {
             prev,
+            operator: OperatorIndex::default(),
             f,
             frontiter: None,
             timestamp: None,
@@ -266,12 +278,14 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
     }
 
     #[inline]
     fn next(&mut self) -> StreamElement<(Key, Out)> {
         loop {
             if let Some((ref key, ref mut inner)) = self.frontiter {
+                let _operator = self.operator.enter();
                 match inner.next() {
                     None => self.frontiter = None,
                     #[cfg(not(feature = "timestamp"))]
@@ -283,7 +297,9 @@ where
                     },
                 }
             }
-            match self.prev.next() {
+            let element = self.prev.next();
+            let _operator = self.operator.enter();
+            match element {
                 #[cfg(not(feature = "timestamp"))]
                 StreamElement::Item((key, inner)) | StreamElement::Timestamped((key, inner), _) => {
                     let iter = (self.f)((&key, inner)).into_iter();
@@ -305,6 +321,7 @@ where
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
                 StreamElement::Terminate => return StreamElement::Terminate,
                 StreamElement::FlushAndRestart => return StreamElement::FlushAndRestart,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
             }
         }
     }
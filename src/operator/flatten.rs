@@ -114,6 +114,7 @@ where
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
                 StreamElement::Terminate => return StreamElement::Terminate,
                 StreamElement::FlushAndRestart => return StreamElement::FlushAndRestart,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
             }
         }
     }
@@ -236,6 +237,7 @@ where
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
                 StreamElement::Terminate => return StreamElement::Terminate,
                 StreamElement::FlushAndRestart => return StreamElement::FlushAndRestart,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
             }
         }
     }
@@ -1,9 +1,14 @@
 use std::fmt::Display;
 use std::marker::PhantomData;
 
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
 use crate::block::{BlockStructure, OperatorStructure};
+use crate::checkpoint::{Checkpointed, StateCodec};
 use crate::operator::{Data, Operator, StreamElement, Timestamp};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 
 #[derive(Clone, Derivative)]
 #[derivative(Debug)]
@@ -13,6 +18,7 @@ where
     PreviousOperators: Operator<Out>,
 {
     prev: PreviousOperators,
+    operator: OperatorIndex,
     #[derivative(Debug = "ignore")]
     fold: F,
     init: NewOut,
@@ -21,6 +27,10 @@ where
     max_watermark: Option<Timestamp>,
     received_end: bool,
     received_end_iter: bool,
+    /// Accumulators restored from other replicas, after rescaling the job.
+    partials: Vec<StreamElement<NewOut>>,
+    /// Accumulator, timestamp and watermark saved in the checkpoints.
+    checkpoint: Checkpointed<(Option<NewOut>, Option<Timestamp>, Option<Timestamp>)>,
     _out: PhantomData<Out>,
 }
 
@@ -49,6 +59,7 @@ where
     pub(super) fn new(prev: PreviousOperators, init: NewOut, fold: F) -> Self {
         Fold {
             prev,
+            operator: OperatorIndex::default(),
             fold,
             init,
             accumulator: None,
@@ -56,9 +67,20 @@ where
             max_watermark: None,
             received_end: false,
             received_end_iter: false,
+            partials: Default::default(),
+            checkpoint: Default::default(),
             _out: Default::default(),
         }
     }
+
+    /// Save the accumulator in the checkpoints of the job.
+    pub(super) fn checkpointed(mut self) -> Self
+    where
+        NewOut: Serialize + DeserializeOwned,
+    {
+        self.checkpoint = Checkpointed::new(Some(StateCodec::new()));
+        self
+    }
 }
 
 impl<Out: Data, NewOut: Data, F, PreviousOperators> Operator<NewOut>
@@ -69,12 +91,46 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
+        let restored = self.checkpoint.setup(metadata);
+        if let Some(previous) = self.checkpoint.rescaled(metadata) {
+            // the partial accumulators are assigned to the new replicas in round-robin, the extra
+            // ones are sent downstream as they are
+            let replicas = metadata.replicas.len() as u64;
+            for (replica_id, (accumulator, timestamp, max_watermark)) in previous {
+                if replica_id % replicas != metadata.global_id {
+                    continue;
+                }
+                self.max_watermark = self.max_watermark.max(max_watermark);
+                let Some(accumulator) = accumulator else {
+                    continue;
+                };
+                if self.accumulator.is_none() {
+                    self.accumulator = Some(accumulator);
+                    self.timestamp = timestamp;
+                } else {
+                    self.partials.push(match timestamp {
+                        Some(ts) => StreamElement::Timestamped(accumulator, ts),
+                        None => StreamElement::Item(accumulator),
+                    });
+                }
+            }
+        } else if let Some((accumulator, timestamp, max_watermark)) = restored {
+            self.accumulator = accumulator;
+            self.timestamp = timestamp;
+            self.max_watermark = max_watermark;
+        }
     }
 
     #[inline]
     fn next(&mut self) -> StreamElement<NewOut> {
+        if let Some(elem) = self.partials.pop() {
+            return elem;
+        }
         while !self.received_end {
-            match self.prev.next() {
+            let element = self.prev.next();
+            let _operator = self.operator.enter();
+            match element {
                 StreamElement::Terminate => self.received_end = true,
                 StreamElement::FlushAndRestart => {
                     self.received_end = true;
@@ -98,6 +154,13 @@ where
                 }
                 // this block wont sent anything until the stream ends
                 StreamElement::FlushBatch => {}
+                StreamElement::Checkpoint(id) => {
+                    if self.checkpoint.is_enabled() {
+                        let state = (self.accumulator.clone(), self.timestamp, self.max_watermark);
+                        self.checkpoint.snapshot(id, &state);
+                    }
+                    return StreamElement::Checkpoint(id);
+                }
             }
         }
 
@@ -73,6 +73,10 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the accumulator is not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("fold_batch is not supported");
+        }
     }
 
     #[inline]
@@ -80,6 +84,7 @@ where
         while !self.received_end {
             match self.prev.next() {
                 StreamElement::Terminate => self.received_end = true,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::FlushAndRestart => {
                     self.received_end = true;
                     self.received_end_iter = true;
@@ -4,6 +4,7 @@ use std::marker::PhantomData;
 use crate::block::{BlockStructure, OperatorStructure};
 use crate::operator::{Data, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 
 #[derive(Clone, Derivative)]
 #[derivative(Debug)]
@@ -13,6 +14,7 @@ where
     PreviousOperators: Operator<Out>,
 {
     prev: PreviousOperators,
+    operator: OperatorIndex,
     #[derivative(Debug = "ignore")]
     f: F,
     _out: PhantomData<Out>,
@@ -26,6 +28,7 @@ where
     pub fn new(prev: PreviousOperators, f: F) -> Self {
         Self {
             prev,
+            operator: OperatorIndex::default(),
             f,
             _out: PhantomData,
         }
@@ -49,11 +52,13 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
     }
 
     #[inline]
     fn next(&mut self) -> StreamElement<Out> {
         let el = self.prev.next();
+        let _operator = self.operator.enter();
         match &el {
             StreamElement::Item(t) | StreamElement::Timestamped(t, _) => {
                 (self.f)(t);
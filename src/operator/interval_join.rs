@@ -145,6 +145,10 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the elements waiting for a match are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("interval joins are not supported");
+        }
     }
 
     fn next(&mut self) -> StreamElement<(Key, (Out, Out2))> {
@@ -180,6 +184,7 @@ where
                 StreamElement::Item(_) => panic!("Interval Join only supports timestamped streams"),
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
                 StreamElement::Terminate => return StreamElement::Terminate,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
             }
 
             self.advance();
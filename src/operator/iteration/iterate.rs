@@ -109,7 +109,8 @@ impl<Out: ExchangeData, State: ExchangeData> Iterate<Out, State> {
             StreamElement::Item(_)
             | StreamElement::Timestamped(_, _)
             | StreamElement::Watermark(_)
-            | StreamElement::FlushBatch => item,
+            | StreamElement::FlushBatch
+            | StreamElement::Checkpoint(_) => item,
             StreamElement::Terminate => {
                 log::debug!("Iterate at {} is terminating", self.coord);
                 let message = NetworkMessage::new_single(StreamElement::Terminate, self.coord);
@@ -207,6 +208,10 @@ impl<Out: ExchangeData, State: ExchangeData> Iterate<Out, State> {
 impl<Out: ExchangeData, State: ExchangeData + Sync> Operator<Out> for Iterate<Out, State> {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.coord = metadata.coord;
+        // the state of the iterations is not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("iterations are not supported");
+        }
 
         let endpoint = ReceiverEndpoint::new(metadata.coord, self.input_block_id);
         self.input_receiver = Some(metadata.network.get_receiver(endpoint));
@@ -224,7 +229,11 @@ impl<Out: ExchangeData, State: ExchangeData + Sync> Operator<Out> for Iterate<Ou
             ),
             metadata.coord.block_id,
         );
-        self.output_sender = Some(metadata.network.get_sender(output_endpoint));
+        let mut output_sender = metadata.network.get_sender(output_endpoint);
+        if let Some(metrics) = metadata.job.metrics().handle(metadata.coord) {
+            output_sender.set_metrics(metrics);
+        }
+        self.output_sender = Some(output_sender);
 
         self.state.setup(metadata);
     }
@@ -353,7 +362,7 @@ where
     /// );
     /// let state = state.collect_vec();
     /// let items = items.collect_vec();
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(state.get().unwrap(), vec![10 + 11 + 12 + 20 + 21 + 22 + 30 + 31 + 32]);
     /// assert_eq!(items.get().unwrap(), vec![30, 31, 32]);
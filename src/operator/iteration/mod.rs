@@ -4,6 +4,8 @@ use std::sync::{Arc, Condvar, Mutex};
 
 use serde::{Deserialize, Serialize};
 
+use crate::concurrency;
+
 mod iterate;
 mod iterate_delta;
 mod iteration_end;
@@ -187,9 +189,10 @@ impl IterationStateLock {
 
     /// Block the thread if the current generation of the lock is lower that the requested one.
     pub fn wait_for_update(&self, generation: usize) {
-        let _gen = self
-            .cond_var
-            .wait_while(self.generation.lock().unwrap(), |r| *r < generation)
-            .unwrap();
+        let _gen = concurrency::blocking(|| {
+            self.cond_var
+                .wait_while(self.generation.lock().unwrap(), |r| *r < generation)
+                .unwrap()
+        });
     }
 }
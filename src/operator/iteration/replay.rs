@@ -108,6 +108,7 @@ where
             }
             // messages to forward without replaying
             StreamElement::FlushBatch => StreamElement::FlushBatch,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
             StreamElement::Terminate => {
                 log::debug!("Replay at {} is terminating", self.coord);
                 StreamElement::Terminate
@@ -150,6 +151,10 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.coord = metadata.coord;
+        // the state of the iterations is not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("iterations are not supported");
+        }
         self.prev.setup(metadata);
         self.state.setup(metadata);
     }
@@ -247,7 +252,7 @@ where
     ///     |_state| true,
     /// );
     /// let state = state.collect_vec();
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(state.get().unwrap(), vec![3 * (10 + 11 + 12)]);
     /// ```
@@ -2,6 +2,7 @@ use std::sync::{Arc, Barrier};
 
 use lazy_init::Lazy;
 
+use crate::concurrency;
 use crate::network::{Coord, NetworkReceiver, ReceiverEndpoint};
 use crate::operator::iteration::{IterationStateHandle, IterationStateLock, StateFeedback};
 use crate::operator::ExchangeData;
@@ -123,9 +124,10 @@ impl<State: ExchangeData> IterationStateHandler<State> {
         }
         // make sure that the state is set before any replica on this host is able to start again,
         // reading the old state
-        self.state_barrier
-            .get_or_create(|| Barrier::new(self.num_local_replicas))
-            .wait();
+        let barrier = self
+            .state_barrier
+            .get_or_create(|| Barrier::new(self.num_local_replicas));
+        concurrency::blocking(|| barrier.wait());
 
         if self.is_local_leader {
             // now the state has been set, accessing it is safe again
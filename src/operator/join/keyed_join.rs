@@ -201,6 +201,10 @@ impl<K: DataKey + ExchangeData, V1: ExchangeData, V2: ExchangeData>
     fn setup(&mut self, metadata: &mut crate::ExecutionMetadata) {
         self.prev.setup(metadata);
         self.coord = Some(metadata.coord);
+        // the elements waiting for a match are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("joins are not supported");
+        }
     }
 
     fn next(&mut self) -> crate::operator::StreamElement<(K, OuterJoinTuple<V1, V2>)> {
@@ -226,6 +230,7 @@ impl<K: DataKey + ExchangeData, V1: ExchangeData, V2: ExchangeData>
                 }
                 StreamElement::Terminate => return StreamElement::Terminate,
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::Watermark(_) | StreamElement::Timestamped(_, _) => {
                     panic!("Cannot yet join timestamped streams")
                 }
@@ -343,6 +348,10 @@ impl<K: DataKey + ExchangeData + Debug, V1: ExchangeData + Debug, V2: ExchangeDa
     fn setup(&mut self, metadata: &mut crate::ExecutionMetadata) {
         self.coord = Some(metadata.coord);
         self.prev.setup(metadata);
+        // the elements waiting for a match are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("joins are not supported");
+        }
     }
 
     fn next(&mut self) -> crate::operator::StreamElement<(K, InnerJoinTuple<V1, V2>)> {
@@ -362,6 +371,7 @@ impl<K: DataKey + ExchangeData + Debug, V1: ExchangeData + Debug, V2: ExchangeDa
                 }
                 StreamElement::Terminate => return StreamElement::Terminate,
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::Watermark(_) | StreamElement::Timestamped(_, _) => {
                     panic!("Cannot yet join timestamped streams")
                 }
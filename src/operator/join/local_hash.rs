@@ -196,6 +196,10 @@ impl<
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.coord = metadata.coord;
         self.prev.setup(metadata);
+        // the hash tables of the join are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("joins are not supported");
+        }
     }
 
     fn next(&mut self) -> StreamElement<(Key, OuterJoinTuple<Out1, Out2>)> {
@@ -265,6 +269,7 @@ impl<
                 }
                 StreamElement::Terminate => return StreamElement::Terminate,
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::Watermark(_) | StreamElement::Timestamped(_, _) => {
                     panic!("Cannot yet join timestamped streams")
                 }
@@ -164,6 +164,10 @@ impl<
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the sides of the join are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("joins are not supported");
+        }
     }
 
     fn next(&mut self) -> StreamElement<(Key, (Option<Out1>, Option<Out2>))> {
@@ -213,6 +217,7 @@ impl<
                     return StreamElement::FlushAndRestart;
                 }
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::Terminate => return StreamElement::Terminate,
             }
         }
@@ -5,6 +5,7 @@ use crate::block::{BlockStructure, OperatorStructure};
 use crate::operator::{Data, DataKey};
 use crate::operator::{Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 
 #[derive(Clone, Derivative)]
 #[derivative(Debug)]
@@ -14,6 +15,7 @@ where
     OperatorChain: Operator<Out>,
 {
     prev: OperatorChain,
+    operator: OperatorIndex,
     #[derivative(Debug = "ignore")]
     keyer: Keyer,
     _key: PhantomData<Key>,
@@ -44,6 +46,7 @@ where
     pub fn new(prev: OperatorChain, keyer: Keyer) -> Self {
         Self {
             prev,
+            operator: OperatorIndex::default(),
             keyer,
             _key: Default::default(),
             _out: Default::default(),
@@ -59,11 +62,14 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
     }
 
     #[inline]
     fn next(&mut self) -> StreamElement<(Key, Out)> {
-        match self.prev.next() {
+        let element = self.prev.next();
+        let _operator = self.operator.enter();
+        match element {
             StreamElement::Item(t) => StreamElement::Item(((self.keyer)(&t), t)),
             StreamElement::Timestamped(t, ts) => {
                 StreamElement::Timestamped(((self.keyer)(&t), t), ts)
@@ -72,6 +78,7 @@ where
             StreamElement::Terminate => StreamElement::Terminate,
             StreamElement::FlushAndRestart => StreamElement::FlushAndRestart,
             StreamElement::FlushBatch => StreamElement::FlushBatch,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
         }
     }
 
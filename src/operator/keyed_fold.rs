@@ -1,13 +1,18 @@
 use core::iter::Iterator;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Display;
 use std::marker::PhantomData;
 
-use crate::block::{BlockStructure, OperatorStructure};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
+use crate::block::{group_by_hash, BlockStructure, OperatorStructure};
+use crate::checkpoint::{CheckpointId, Checkpointed, StateCodec};
+use crate::operator::retention::{KeyRetention, StateRetention};
 use crate::operator::{Data, DataKey, Operator, StreamElement, Timestamp};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 
 #[derive(Derivative)]
 #[derivative(Debug, Clone)]
@@ -17,6 +22,7 @@ where
     PreviousOperators: Operator<(Key, Out)>,
 {
     prev: PreviousOperators,
+    operator: OperatorIndex,
     #[derivative(Debug = "ignore")]
     fold: F,
     init: NewOut,
@@ -26,6 +32,15 @@ where
     max_watermark: Option<Timestamp>,
     received_end: bool,
     received_end_iter: bool,
+    /// Accumulators of keys already restored from another replica, after rescaling the job.
+    partials: Vec<StreamElement<(Key, NewOut)>>,
+    /// The keys to forget before the end of the stream, if any.
+    retention: Option<KeyRetention<Key>>,
+    /// Accumulators of the forgotten keys, to emit before the end of the stream.
+    evicted: VecDeque<StreamElement<(Key, NewOut)>>,
+    /// Accumulators, timestamps and watermark saved in the checkpoints.
+    #[allow(clippy::type_complexity)]
+    checkpoint: Checkpointed<(Vec<(Key, NewOut, Option<Timestamp>)>, Option<Timestamp>)>,
     _out: PhantomData<Out>,
 }
 
@@ -54,6 +69,7 @@ where
     pub(super) fn new(prev: PreviousOperators, init: NewOut, fold: F) -> Self {
         KeyedFold {
             prev,
+            operator: OperatorIndex::default(),
             fold,
             init,
             accumulators: Default::default(),
@@ -62,12 +78,85 @@ where
             max_watermark: None,
             received_end: false,
             received_end_iter: false,
+            partials: Default::default(),
+            retention: None,
+            evicted: Default::default(),
+            checkpoint: Default::default(),
             _out: Default::default(),
         }
     }
 
+    /// Save the accumulators in the checkpoints of the job.
+    pub(super) fn checkpointed(mut self) -> Self
+    where
+        Key: Serialize + DeserializeOwned,
+        NewOut: Serialize + DeserializeOwned,
+    {
+        self.checkpoint = Checkpointed::new(Some(StateCodec::new()));
+        self
+    }
+
+    /// Forget the keys according to `retention`, instead of keeping them until the end of the
+    /// stream.
+    pub(super) fn with_retention(mut self, retention: StateRetention) -> Self {
+        self.retention = Some(KeyRetention::new(retention));
+        self
+    }
+
+    fn snapshot(&self, id: CheckpointId) {
+        let accumulators = self
+            .accumulators
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone(), self.timestamps.get(k).copied()))
+            .collect();
+        self.checkpoint
+            .snapshot(id, &(accumulators, self.max_watermark));
+    }
+
+    /// Restore the accumulator of a key saved in a checkpoint.
+    ///
+    /// The replicas of a local pre-aggregation may have saved an accumulator for the same key:
+    /// the duplicates are sent downstream as they are, to be combined by the global aggregation.
+    fn restore(&mut self, key: Key, acc: NewOut, ts: Option<Timestamp>) {
+        if self.accumulators.contains_key(&key) {
+            self.partials.push(match ts {
+                Some(ts) => StreamElement::Timestamped((key, acc), ts),
+                None => StreamElement::Item((key, acc)),
+            });
+            return;
+        }
+        if let Some(retention) = &mut self.retention {
+            retention.touch(&key, ts);
+        }
+        if let Some(ts) = ts {
+            self.timestamps.insert(key.clone(), ts);
+        }
+        self.accumulators.insert(key, acc);
+    }
+
+    /// Forget the keys that expired or exceed the maximum number of keys after the element,
+    /// emitting their accumulators if asked.
+    fn evict(&mut self, element: &StreamElement<(Key, Out)>) {
+        let Some(retention) = &mut self.retention else {
+            return;
+        };
+        for key in retention.update(element) {
+            let ts = self.timestamps.remove(&key);
+            let Some(acc) = self.accumulators.remove(&key) else {
+                continue;
+            };
+            if retention.emit_evicted() {
+                self.evicted.push_back(match ts {
+                    Some(ts) => StreamElement::Timestamped((key, acc), ts),
+                    None => StreamElement::Item((key, acc)),
+                });
+            }
+        }
+    }
+
     /// Process a new item, folding it with the accumulator inside the hashmap.
     fn process_item(&mut self, key: Key, value: Out) {
+        let _operator = self.operator.enter();
         match self.accumulators.entry(key) {
             Entry::Vacant(entry) => {
                 let mut acc = self.init.clone();
@@ -88,13 +177,43 @@ where
     PreviousOperators: Operator<(Key, Out)>,
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        if let Some(interval) = self.retention.as_ref().and_then(|r| r.wake_up_interval()) {
+            metadata.wake_up_every(interval);
+        }
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
+        let restored = self.checkpoint.setup(metadata);
+        if let Some(previous) = self.checkpoint.rescaled(metadata) {
+            // keep the keys this replica is responsible for after rescaling
+            let replicas = metadata.replicas.len() as u64;
+            for (_, (accumulators, max_watermark)) in previous {
+                for (key, acc, ts) in accumulators {
+                    if group_by_hash(&key) % replicas == metadata.global_id {
+                        self.restore(key, acc, ts);
+                    }
+                }
+                self.max_watermark = self.max_watermark.max(max_watermark);
+            }
+        } else if let Some((accumulators, max_watermark)) = restored {
+            for (key, acc, ts) in accumulators {
+                self.restore(key, acc, ts);
+            }
+            self.max_watermark = max_watermark;
+        }
     }
 
     #[inline]
     fn next(&mut self) -> StreamElement<(Key, NewOut)> {
+        if let Some(elem) = self.partials.pop() {
+            return elem;
+        }
         while !self.received_end {
-            match self.prev.next() {
+            if let Some(elem) = self.evicted.pop_front() {
+                return elem;
+            }
+            let elem = self.prev.next();
+            self.evict(&elem);
+            match elem {
                 StreamElement::Terminate => self.received_end = true,
                 StreamElement::FlushAndRestart => {
                     self.received_end = true;
@@ -113,11 +232,21 @@ where
                         .and_modify(|entry| *entry = (*entry).max(ts))
                         .or_insert(ts);
                 }
-                // this block won't sent anything until the stream ends
+                // this block won't sent anything until the stream ends, or a key is forgotten
                 StreamElement::FlushBatch => {}
+                StreamElement::Checkpoint(id) => {
+                    if self.checkpoint.is_enabled() {
+                        self.snapshot(id);
+                    }
+                    return StreamElement::Checkpoint(id);
+                }
             }
         }
 
+        if let Some(retention) = &mut self.retention {
+            retention.clear();
+        }
+        self.ready.extend(self.evicted.drain(..));
         // move all the accumulators into a faster vec
         if !self.accumulators.is_empty() {
             // take a reference to move into the closure, avoiding moving "self"
@@ -163,6 +292,8 @@ mod tests {
     use itertools::Itertools;
 
     use crate::operator::keyed_fold::KeyedFold;
+    #[cfg(feature = "timestamp")]
+    use crate::operator::retention::StateRetention;
     use crate::operator::{Operator, StreamElement};
     use crate::test::FakeOperator;
 
@@ -242,4 +373,25 @@ mod tests {
         assert_eq!(keyed_fold.next(), StreamElement::FlushAndRestart);
         assert_eq!(keyed_fold.next(), StreamElement::Terminate);
     }
+
+    #[test]
+    #[cfg(feature = "timestamp")]
+    fn test_keyed_fold_evicted_order() {
+        let mut fake_operator = FakeOperator::empty();
+        fake_operator.push(StreamElement::Timestamped((0, 1), 1));
+        fake_operator.push(StreamElement::Timestamped((1, 2), 2));
+        fake_operator.push(StreamElement::Timestamped((2, 3), 10));
+        fake_operator.push(StreamElement::Watermark(5));
+
+        let retention = StateRetention::new().event_time_ttl(2).emit_evicted();
+        let mut keyed_fold =
+            KeyedFold::new(fake_operator, 0, |a, b| *a += b).with_retention(retention);
+
+        // the keys forgotten together are emitted in the order they expired
+        assert_eq!(keyed_fold.next(), StreamElement::Timestamped((0, 1), 1));
+        assert_eq!(keyed_fold.next(), StreamElement::Timestamped((1, 2), 2));
+        assert_eq!(keyed_fold.next(), StreamElement::Timestamped((2, 3), 10));
+        assert_eq!(keyed_fold.next(), StreamElement::Watermark(5));
+        assert_eq!(keyed_fold.next(), StreamElement::Terminate);
+    }
 }
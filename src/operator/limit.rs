@@ -0,0 +1,194 @@
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use crate::block::{BlockStructure, OperatorStructure};
+use crate::job::StopFlag;
+use crate::operator::{Data, ExchangeData, Operator, StreamElement};
+use crate::scheduler::ExecutionMetadata;
+use crate::{Replication, Stream};
+
+/// Forward at most `limit` elements, then drop the others.
+///
+/// When the limit is reached the replica sets its part of the flag, so that the sources upstream
+/// can end their stream. Inside an iteration the count and the part of the flag of the replica
+/// start again at each iteration.
+#[derive(Derivative)]
+#[derivative(Debug, Clone)]
+pub struct Limit<I: Data, PreviousOperators>
+where
+    PreviousOperators: Operator<I>,
+{
+    prev: PreviousOperators,
+    limit: usize,
+    count: usize,
+    /// Set when the limit is reached, shared by all the replicas of this host.
+    stop: Arc<StopFlag>,
+    /// Whether this replica set its part of `stop`.
+    stopped: bool,
+    _in: PhantomData<I>,
+}
+
+impl<I: Data, PreviousOperators: Operator<I>> Display for Limit<I, PreviousOperators> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> Limit<{}>", self.prev, self.limit)
+    }
+}
+
+impl<I: Data, PreviousOperators: Operator<I>> Limit<I, PreviousOperators> {
+    fn new(prev: PreviousOperators, limit: usize, stop: Arc<StopFlag>) -> Self {
+        Self {
+            prev,
+            limit,
+            count: 0,
+            stop,
+            stopped: false,
+            _in: Default::default(),
+        }
+    }
+}
+
+impl<I: Data, PreviousOperators: Operator<I>> Operator<I> for Limit<I, PreviousOperators> {
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+        // with no elements to emit the sources can stop right away
+        self.stop.update(&mut self.stopped, self.limit == 0);
+        // the count is not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("limit is not supported");
+        }
+    }
+
+    fn next(&mut self) -> StreamElement<I> {
+        loop {
+            match self.prev.next() {
+                StreamElement::Item(_) | StreamElement::Timestamped(_, _)
+                    if self.count >= self.limit => {}
+                element @ (StreamElement::Item(_) | StreamElement::Timestamped(_, _)) => {
+                    self.count += 1;
+                    if self.count == self.limit {
+                        self.stop.update(&mut self.stopped, true);
+                    }
+                    return element;
+                }
+                StreamElement::FlushAndRestart => {
+                    self.count = 0;
+                    self.stop.update(&mut self.stopped, self.limit == 0);
+                    return StreamElement::FlushAndRestart;
+                }
+                element => return element,
+            }
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<I, _>("Limit"))
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeData,
+    Op: Operator<I> + 'static,
+{
+    /// Keep only the first `n` elements of the stream.
+    ///
+    /// Each replica forwards at most `n` elements to a single replica, that emits the first `n`
+    /// it receives: which elements are kept is not deterministic when the stream has many
+    /// replicas.
+    ///
+    /// As soon as a replica of this host has forwarded `n` elements the output is complete. The
+    /// sources this stream reads from that are running on this host end their stream, like if
+    /// they were exhausted, if all the streams reading from them are limited and all their limits
+    /// are reached. This allows limiting unbounded streams. The sources that feed other streams
+    /// keep running, and the elements past the limit are dropped.
+    ///
+    /// **Note**: only the sources provided by this crate support ending their stream early.
+    ///
+    /// **Note**: inside an iteration the limit applies to each iteration. Its output has a single
+    /// replica, so the body of the iteration cannot end with it (e.g. add a `shuffle` after it).
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// // an unbounded source
+    /// let s = env.stream(IteratorSource::new((0..).map(|n| n * 2)));
+    /// let res = s.limit(3).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap(), vec![0, 2, 4]);
+    /// ```
+    pub fn limit(self, n: usize) -> Stream<I, impl Operator<I>> {
+        let stop = Arc::new(StopFlag::default());
+        self.env
+            .lock()
+            .scheduler_mut()
+            .register_limit(self.block.id, stop.clone());
+
+        let stop_global = stop.clone();
+        self.add_operator(|prev| Limit::new(prev, n, stop))
+            .replication(Replication::One)
+            .add_operator(|prev| Limit::new(prev, n, stop_global))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::job::StopFlag;
+    use crate::operator::limit::Limit;
+    use crate::operator::{Operator, StreamElement};
+    use crate::test::FakeOperator;
+
+    #[test]
+    fn limit_stops_upstream() {
+        let stop = Arc::new(StopFlag::default());
+        let fake = FakeOperator::new(0..10u8);
+        let mut limit = Limit::new(fake, 2, stop.clone());
+        assert_eq!(limit.next(), StreamElement::Item(0));
+        assert!(!stop.is_set());
+        assert_eq!(limit.next(), StreamElement::Item(1));
+        assert!(stop.is_set());
+        assert_eq!(limit.next(), StreamElement::Terminate);
+    }
+
+    #[test]
+    fn limit_restarts() {
+        let stop = Arc::new(StopFlag::default());
+        let mut fake = FakeOperator::new(0..3u8);
+        fake.push(StreamElement::FlushAndRestart);
+        fake.push(StreamElement::Item(3));
+        let mut limit = Limit::new(fake, 1, stop.clone());
+        assert_eq!(limit.next(), StreamElement::Item(0));
+        assert!(stop.is_set());
+        assert_eq!(limit.next(), StreamElement::FlushAndRestart);
+        assert!(!stop.is_set());
+        assert_eq!(limit.next(), StreamElement::Item(3));
+        assert!(stop.is_set());
+    }
+
+    #[test]
+    fn limit_restart_keeps_other_replicas() {
+        let stop = Arc::new(StopFlag::default());
+        let mut fake = FakeOperator::new(0..1u8);
+        fake.push(StreamElement::FlushAndRestart);
+        let mut first = Limit::new(fake, 1, stop.clone());
+        let mut second = Limit::new(FakeOperator::new(0..1u8), 1, stop.clone());
+        assert_eq!(first.next(), StreamElement::Item(0));
+        assert_eq!(second.next(), StreamElement::Item(0));
+        // the first replica starts again, the second one still needs no more input
+        assert_eq!(first.next(), StreamElement::FlushAndRestart);
+        assert!(stop.is_set());
+        assert_eq!(second.next(), StreamElement::Terminate);
+        assert!(stop.is_set());
+    }
+}
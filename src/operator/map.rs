@@ -4,6 +4,7 @@ use std::marker::PhantomData;
 use crate::block::{BlockStructure, OperatorStructure};
 use crate::operator::{Data, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 
 #[derive(Clone, Derivative)]
 #[derivative(Debug)]
@@ -13,6 +14,7 @@ where
     PreviousOperators: Operator<Out>,
 {
     prev: PreviousOperators,
+    operator: OperatorIndex,
     #[derivative(Debug = "ignore")]
     f: F,
     _out: PhantomData<Out>,
@@ -44,6 +46,7 @@ where
     pub(super) fn new(prev: PreviousOperators, f: F) -> Self {
         Self {
             prev,
+            operator: OperatorIndex::default(),
             f,
             _out: Default::default(),
             _new_out: Default::default(),
@@ -59,11 +62,14 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
     }
 
     #[inline]
     fn next(&mut self) -> StreamElement<NewOut> {
-        self.prev.next().map(&self.f)
+        let element = self.prev.next();
+        let _operator = self.operator.enter();
+        element.map(&self.f)
     }
 
     fn structure(&self) -> BlockStructure {
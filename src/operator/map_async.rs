@@ -6,6 +6,7 @@ use flume::{Receiver, Sender};
 use futures::{Future, StreamExt};
 
 use crate::block::{BlockStructure, OperatorStructure};
+use crate::concurrency;
 use crate::operator::{Data, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
 use crate::BatchMode;
@@ -177,7 +178,11 @@ where
             self.pending > 0,
             "map_async trier receiving batches, but pending is equal to 0"
         );
-        self.buffer = Some(self.o_rx.recv().unwrap().into_iter());
+        self.buffer = Some(
+            concurrency::blocking(|| self.o_rx.recv())
+                .unwrap()
+                .into_iter(),
+        );
         self.pending -= 1;
     }
 }
@@ -0,0 +1,135 @@
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use crate::block::{BlockStructure, OperatorStructure};
+use crate::checkpoint::{Checkpointed, StateCodec};
+use crate::operator::{Data, ExchangeData, Operator, StreamElement};
+use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
+use crate::Stream;
+
+#[derive(Derivative)]
+#[derivative(Debug, Clone)]
+pub struct MapWithState<I: Data, O: Data, S: ExchangeData, F, PreviousOperators>
+where
+    F: FnMut(&mut S, I) -> O + Clone + Send,
+    PreviousOperators: Operator<I>,
+{
+    prev: PreviousOperators,
+    operator: OperatorIndex,
+    #[derivative(Debug = "ignore")]
+    f: F,
+    state: S,
+    checkpoint: Checkpointed<S>,
+    _in: PhantomData<I>,
+    _out: PhantomData<O>,
+}
+
+impl<I: Data, O: Data, S: ExchangeData, F, PreviousOperators> Display
+    for MapWithState<I, O, S, F, PreviousOperators>
+where
+    F: FnMut(&mut S, I) -> O + Clone + Send,
+    PreviousOperators: Operator<I>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> MapWithState<{} -> {}>",
+            self.prev,
+            std::any::type_name::<I>(),
+            std::any::type_name::<O>()
+        )
+    }
+}
+
+impl<I: Data, O: Data, S: ExchangeData, F, PreviousOperators>
+    MapWithState<I, O, S, F, PreviousOperators>
+where
+    F: FnMut(&mut S, I) -> O + Clone + Send,
+    PreviousOperators: Operator<I>,
+{
+    fn new(prev: PreviousOperators, init: S, f: F) -> Self {
+        Self {
+            prev,
+            operator: OperatorIndex::default(),
+            f,
+            state: init,
+            checkpoint: Checkpointed::new(Some(StateCodec::new())),
+            _in: Default::default(),
+            _out: Default::default(),
+        }
+    }
+}
+
+impl<I: Data, O: Data, S: ExchangeData, F, PreviousOperators> Operator<O>
+    for MapWithState<I, O, S, F, PreviousOperators>
+where
+    F: FnMut(&mut S, I) -> O + Clone + Send,
+    PreviousOperators: Operator<I>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
+        if let Some(state) = self.checkpoint.setup(metadata) {
+            self.state = state;
+        }
+    }
+
+    #[inline]
+    fn next(&mut self) -> StreamElement<O> {
+        let element = self.prev.next();
+        let _operator = self.operator.enter();
+        match element {
+            StreamElement::Checkpoint(id) => {
+                self.checkpoint.snapshot(id, &self.state);
+                StreamElement::Checkpoint(id)
+            }
+            element => element.map(|item| (self.f)(&mut self.state, item)),
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<O, _>("MapWithState"))
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: Data,
+    Op: Operator<I> + 'static,
+{
+    /// Map the elements of the stream using a function that can update an explicit state.
+    ///
+    /// Each replica starts with its own copy of `init`. Unlike the state captured by the closure
+    /// of [`Stream::rich_map`], this state is saved in the checkpoints of the job (see
+    /// [`checkpoint`](crate::checkpoint)) and restored when the job resumes.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((1..=5)));
+    /// let res = s
+    ///     .rich_map_with_state(0, |sum, x| {
+    ///         *sum += x;
+    ///         *sum
+    ///     })
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap(), vec![1, 3, 6, 10, 15]);
+    /// ```
+    pub fn rich_map_with_state<O, S, F>(self, init: S, f: F) -> Stream<O, impl Operator<O>>
+    where
+        F: FnMut(&mut S, I) -> O + Send + Clone + 'static,
+        S: ExchangeData,
+        O: Data,
+    {
+        self.add_operator(|prev| MapWithState::new(prev, init, f))
+    }
+}
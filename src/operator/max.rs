@@ -40,7 +40,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// let res = s.max(|&n| n).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![4]);
     /// ```
@@ -90,7 +90,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// let res = s.max_assoc(|&n| n).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![4]);
     /// ```
@@ -159,7 +159,7 @@ where
     /// let s = env.stream(IteratorSource::new([NoirData::NoirType(NoirType::from(0.0)), NoirData::NoirType(NoirType::from(8.0)), NoirData::NoirType(NoirType::from(6.0))].into_iter()));
     /// let res = s.max_noir_data(true).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirData::NoirType(NoirType::from(8.0))]);
     /// ```
@@ -224,6 +224,10 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the partial maximum is not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("max is not supported");
+        }
     }
 
     #[inline]
@@ -231,6 +235,7 @@ where
         while !self.received_end {
             match self.prev.next() {
                 StreamElement::Terminate => self.received_end = true,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::FlushAndRestart => {
                     self.received_end = true;
                     self.received_end_iter = true;
@@ -27,7 +27,7 @@ where
     ///     .mean(|&n| n as f64)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![4.5]);
     /// ```
@@ -177,7 +177,7 @@ where
     ///     .mean_noir_data(true)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirData::Row(vec![NoirType::from(2.0), NoirType::from(3.0)])]);
     /// ```
@@ -78,6 +78,10 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the heaps of the elements are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("exact median is not supported");
+        }
     }
 
     fn next(&mut self) -> StreamElement<NewOut> {
@@ -132,6 +136,7 @@ where
                     }
                 }
                 StreamElement::FlushBatch => {}
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::FlushAndRestart => {
                     self.received_end = true;
                     self.received_end_iter = true;
@@ -193,7 +198,8 @@ where
     /// **Note**: this operator will retain all the messages of the stream and emit the values only
     /// when the stream ends. Therefore this is not properly _streaming_.
     ///
-    /// **Note**: since this operator compute the exact median it cannot be parallelized.
+    /// **Note**: since this operator compute the exact median it cannot be parallelized, see
+    /// [`Stream::quantiles_exact`] for a distributed version.
     ///
     /// ## Example
     ///
@@ -205,7 +211,7 @@ where
     /// let s = env.stream(IteratorSource::new([NoirType::from(0.0), NoirType::from(8.0), NoirType::from(6.0)].into_iter()));
     /// let res = s.median(|v| v).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirType::from(6.0)]);
     /// ```
@@ -401,6 +407,10 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the heaps of the elements are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("exact median is not supported");
+        }
     }
 
     fn next(&mut self) -> StreamElement<NoirData> {
@@ -425,6 +435,7 @@ where
                     self.handle_end();
                 }
                 StreamElement::FlushBatch => {}
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::FlushAndRestart => {
                     self.received_end = true;
                     self.handle_end();
@@ -574,7 +585,8 @@ where
     /// **Note**: this operator will retain all the messages of the stream and emit the values only
     /// when the stream ends. Therefore this is not properly _streaming_.
     ///
-    /// **Note**: since this operator compute the exact median it cannot be parallelized.
+    /// **Note**: since this operator compute the exact median it cannot be parallelized, see
+    /// [`Stream::quantiles_exact`] for a distributed version.
     ///
     /// ## Example
     ///
@@ -586,7 +598,7 @@ where
     /// let s = env.stream(IteratorSource::new([NoirData::NoirType(NoirType::from(0.0)), NoirData::NoirType(NoirType::from(8.0)), NoirData::NoirType(NoirType::from(6.0))].into_iter()));
     /// let res = s.quantile_exact(0.5, true).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirData::NoirType(NoirType::from(6.0))]);
     /// ```
@@ -31,7 +31,7 @@ where
     /// let s2 = env.stream(IteratorSource::new((10..20)));
     /// let res = s1.merge(s2).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -57,6 +57,50 @@ where
         })
     }
 
+    /// Merge the items of this stream with the items of many other streams with the same type.
+    ///
+    /// Unlike chaining [`Stream::merge`], all the streams are received by a single new block. Like
+    /// [`Stream::merge`], all the streams must have the same parallelism.
+    ///
+    /// The other streams must also have the same operators: the ones built with different
+    /// operators can be put in the same `Vec` with [`Stream::into_boxed`].
+    ///
+    /// **Note**: the order of the resulting items is not specified.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s1 = env.stream(IteratorSource::new((0..10)));
+    /// let others = vec![
+    ///     env.stream(IteratorSource::new((10..20))).into_boxed(),
+    ///     env.stream(IteratorSource::new((20..40)))
+    ///         .filter(|x| x % 2 == 0)
+    ///         .into_boxed(),
+    /// ];
+    /// let res = s1.union_all(others).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// let expected = (0..20).chain((20..40).step_by(2)).collect::<Vec<_>>();
+    /// assert_eq!(res, expected);
+    /// ```
+    pub fn union_all<OperatorChain2>(
+        self,
+        others: Vec<Stream<Out, OperatorChain2>>,
+    ) -> Stream<Out, impl Operator<Out>>
+    where
+        OperatorChain2: Operator<Out> + 'static,
+    {
+        self.union_connection(others)
+    }
+
     #[cfg(feature = "timestamp")]
     pub(crate) fn merge_distinct<Out2, OperatorChain2>(
         self,
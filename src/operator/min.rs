@@ -38,7 +38,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// let res = s.min(|&n| n).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![0]);
     /// ```
@@ -88,7 +88,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// let res = s.min_assoc(|&n| n).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![0]);
     /// ```
@@ -157,7 +157,7 @@ where
     /// let s = env.stream(IteratorSource::new([NoirData::NoirType(NoirType::from(0.0)), NoirData::NoirType(NoirType::from(8.0)), NoirData::NoirType(NoirType::from(6.0))].into_iter()));
     /// let res = s.min_noir_data(true).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirData::NoirType(NoirType::from(0.0))]);
     /// ```
@@ -222,6 +222,10 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the partial minimum is not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("min is not supported");
+        }
     }
 
     #[inline]
@@ -229,6 +233,7 @@ where
         while !self.received_end {
             match self.prev.next() {
                 StreamElement::Terminate => self.received_end = true,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::FlushAndRestart => {
                     self.received_end = true;
                     self.received_end_iter = true;
@@ -90,7 +90,7 @@ where
     ///     .drop_none()
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirData::Row(vec![NoirType::from(1), NoirType::from(2)])]);
     /// ```
@@ -116,7 +116,7 @@ where
     ///     .drop_columns(vec![1])
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirData::NoirType(NoirType::from(2)), NoirData::NoirType(NoirType::from(4))]);
     /// ```
@@ -173,7 +173,7 @@ where
     ///     .fill_constant(NoirType::from(2))
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirData::Row(vec![NoirType::from(1), NoirType::from(2)]), NoirData::Row(vec![NoirType::from(2), NoirType::from(4)])]);
     /// ```
@@ -216,7 +216,7 @@ where
     ///     })
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirData::Row(vec![NoirType::from(1), NoirType::from(2)]), NoirData::Row(vec![NoirType::from(8), NoirType::from(4)])]);
     /// ```
@@ -297,7 +297,7 @@ where
         ///     .fill_max()
         ///     .collect_vec();
         ///
-        /// env.execute_blocking();
+        /// env.execute_blocking().unwrap();
         ///
         /// assert_eq!(res.get().unwrap(), vec![NoirData::Row(vec![NoirType::from(1), NoirType::from(2)]), NoirData::Row(vec![NoirType::from(1), NoirType::from(4)])]);
         /// ```
@@ -316,7 +316,7 @@ where
     ///     .fill_mean()
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirData::Row(vec![NoirType::from(1), NoirType::from(2)]), NoirData::Row(vec![NoirType::from(1.0), NoirType::from(4)])]);
     /// ```
@@ -377,7 +377,7 @@ where
     ///     .fill_min()
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![NoirData::Row(vec![NoirType::from(1), NoirType::from(2)]), NoirData::Row(vec![NoirType::from(1), NoirType::from(4)])]);
     /// ```
@@ -440,6 +440,10 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the buffered rows are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("fill forward is not supported");
+        }
     }
 
     #[inline]
@@ -451,6 +455,7 @@ where
         while !self.received_end {
             match self.prev.next() {
                 StreamElement::Terminate => self.received_end = true,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::FlushAndRestart => {
                     self.received_end = true;
                     self.received_end_iter = true;
@@ -8,37 +8,47 @@ use std::collections::VecDeque;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::ops::{AddAssign, Div};
+use std::path::PathBuf;
 
 #[cfg(feature = "crossbeam")]
-use crossbeam_channel::{unbounded, Receiver, Sender};
+use crossbeam_channel::{bounded, unbounded, Receiver, Sender};
 #[cfg(not(feature = "crossbeam"))]
-use flume::{unbounded, Receiver};
+use flume::{bounded, unbounded, Receiver};
 #[cfg(feature = "async-tokio")]
 use futures::Future;
 use serde::{Deserialize, Serialize};
 
 pub(crate) use start::*;
 
+pub use changelog::ChangelogMode;
+pub use connect::ConnectedStreams;
+pub use process::{ListState, MapState, ProcessContext, Timer, ValueState};
+pub use retention::StateRetention;
 pub use rich_map_custom::ElementGenerator;
+pub use try_map::{ErrorCounter, ErrorPolicy};
 
 use crate::block::{group_by_hash, BlockStructure, NextStrategy, Replication};
+use crate::checkpoint::CheckpointId;
 use crate::scheduler::ExecutionMetadata;
-use crate::{BatchMode, KeyedStream, Stream};
+use crate::{BatchMode, CoordUInt, KeyedStream, Stream};
 
 use self::fold_batch::FoldBatch;
 #[cfg(feature = "async-tokio")]
 use self::map_async::MapAsync;
 use self::map_memo::MapMemo;
+use self::sink::broadcast_variable::BroadcastVariableSink;
 use self::sink::collect::Collect;
 use self::sink::collect_channel::CollectChannelSink;
 use self::sink::collect_count::CollectCountSink;
-use self::sink::collect_vec::CollectVecSink;
+use self::sink::collect_vec::{CollectVecOrderedSink, CollectVecSink, TagReplica};
+use self::sink::csv_parts::CsvPartsSink;
 use self::sink::for_each::ForEach;
-use self::sink::{StreamOutput, StreamOutputRef};
+use self::sink::{BroadcastVariable, StreamOutput, StreamOutputRef};
 #[cfg(feature = "timestamp")]
 use self::{
     add_timestamps::{AddTimestamp, DropTimestamp},
     interval_join::IntervalJoin,
+    watermark::WatermarkGenerator,
 };
 use self::{
     end::End,
@@ -62,7 +72,11 @@ use self::{
 #[cfg(feature = "timestamp")]
 mod add_timestamps;
 mod batch_mode;
+mod changelog;
+mod connect;
 mod covariance;
+mod cross;
+mod distinct;
 pub(crate) mod end;
 mod entropy;
 mod filter;
@@ -78,10 +92,12 @@ pub(crate) mod iteration;
 pub mod join;
 mod key_by;
 mod keyed_fold;
+mod limit;
 mod map;
 #[cfg(feature = "async-tokio")]
 mod map_async;
 mod map_memo;
+mod map_with_state;
 mod max;
 mod mean;
 mod median_exact;
@@ -90,17 +106,27 @@ mod min;
 mod missing_data;
 mod mode;
 mod pearson;
+mod process;
 mod quantile_approx;
+mod quantiles_exact;
 mod reorder;
 mod replication;
+mod retention;
 mod rich_map;
 mod rich_map_custom;
 mod route;
+mod sample;
+pub mod side_output;
 pub mod sink;
 mod skewness_kurtosis;
+mod sort;
 pub mod source;
 mod start;
+mod top_k;
+mod try_map;
 mod variance;
+#[cfg(feature = "timestamp")]
+pub mod watermark;
 pub mod window;
 mod zip;
 
@@ -164,6 +190,12 @@ pub enum StreamElement<Out> {
     /// mark the end of an iteration. Therefore an operator may be prepared to received new data
     /// after this message, but should not retain the internal state.
     FlushAndRestart,
+    /// Barrier of a checkpoint.
+    ///
+    /// The operators that keep some state should snapshot it when they receive this message,
+    /// before processing the following ones, and forward it. See the
+    /// [`checkpoint`](crate::checkpoint) module.
+    Checkpoint(CheckpointId),
 }
 
 /// An operator represents a unit of computation. It's always included inside a chain of operators,
@@ -200,6 +232,7 @@ impl<Out> StreamElement<Out> {
             StreamElement::Terminate => StreamElement::Terminate,
             StreamElement::FlushAndRestart => StreamElement::FlushAndRestart,
             StreamElement::FlushBatch => StreamElement::FlushBatch,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(*id),
         }
     }
 
@@ -212,6 +245,7 @@ impl<Out> StreamElement<Out> {
             StreamElement::Terminate => StreamElement::Terminate,
             StreamElement::FlushAndRestart => StreamElement::FlushAndRestart,
             StreamElement::FlushBatch => StreamElement::FlushBatch,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
         }
     }
 
@@ -229,6 +263,7 @@ impl<Out> StreamElement<Out> {
             StreamElement::Terminate => StreamElement::Terminate,
             StreamElement::FlushAndRestart => StreamElement::FlushAndRestart,
             StreamElement::FlushBatch => StreamElement::FlushBatch,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
         }
     }
 
@@ -241,6 +276,7 @@ impl<Out> StreamElement<Out> {
             StreamElement::FlushBatch => "FlushBatch",
             StreamElement::Terminate => "Terminate",
             StreamElement::FlushAndRestart => "FlushAndRestart",
+            StreamElement::Checkpoint(_) => "Checkpoint",
         }
     }
 
@@ -260,6 +296,7 @@ impl<Out> StreamElement<Out> {
             StreamElement::Terminate => StreamElement::Terminate,
             StreamElement::FlushAndRestart => StreamElement::FlushAndRestart,
             StreamElement::FlushBatch => StreamElement::FlushBatch,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
         }
     }
 
@@ -271,6 +308,7 @@ impl<Out> StreamElement<Out> {
             StreamElement::FlushBatch => None,
             StreamElement::Terminate => None,
             StreamElement::FlushAndRestart => None,
+            StreamElement::Checkpoint(_) => None,
         }
     }
 }
@@ -286,6 +324,7 @@ impl<Key, Out> StreamElement<(Key, Out)> {
             StreamElement::Terminate => (None, StreamElement::Terminate),
             StreamElement::FlushAndRestart => (None, StreamElement::FlushAndRestart),
             StreamElement::FlushBatch => (None, StreamElement::FlushBatch),
+            StreamElement::Checkpoint(id) => (None, StreamElement::Checkpoint(id)),
         }
     }
 
@@ -297,6 +336,7 @@ impl<Key, Out> StreamElement<(Key, Out)> {
             StreamElement::Terminate => None,
             StreamElement::FlushAndRestart => None,
             StreamElement::FlushBatch => None,
+            StreamElement::Checkpoint(_) => None,
         }
     }
 }
@@ -347,9 +387,49 @@ where
         self.add_operator(|prev| AddTimestamp::new(prev, timestamp_gen, watermark_gen))
     }
 
+    /// Given a stream without timestamps nor watermarks, tag each item with a timestamp and insert
+    /// the watermarks decided by a [`WatermarkGenerator`] strategy.
+    ///
+    /// The strategies in [`watermark`](crate::operator::watermark) handle out of order elements,
+    /// emit the watermarks periodically in processing time and detect idle sources. When the
+    /// source has no element ready the generator is polled anyway, so it can emit watermarks even
+    /// if no element arrives.
+    ///
+    /// Idleness is tracked per replica by the next block, therefore this operator should be
+    /// applied right after the source (see also
+    /// [`Source::with_timestamps`](crate::operator::source::Source::with_timestamps)).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// use noir::operator::watermark::BoundedOutOfOrderness;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    ///
+    /// let s = env.stream(IteratorSource::new((0..10)));
+    /// s.add_timestamps_with(
+    ///     |&n| n,
+    ///     BoundedOutOfOrderness::new(5).periodic(Duration::from_millis(100)),
+    /// );
+    /// ```
+    #[cfg(feature = "timestamp")]
+    pub fn add_timestamps_with<F, W>(
+        self,
+        timestamp_gen: F,
+        watermark_gen: W,
+    ) -> Stream<I, AddTimestamp<I, F, W, Op>>
+    where
+        F: FnMut(&I) -> Timestamp + Clone + Send + 'static,
+        W: WatermarkGenerator<I>,
+    {
+        self.add_operator(|prev| AddTimestamp::with_generator(prev, timestamp_gen, watermark_gen))
+    }
+
     #[cfg(feature = "timestamp")]
     pub fn drop_timestamps(self) -> Stream<I, DropTimestamp<I, Op>> {
-        self.add_operator(|prev| DropTimestamp::new(prev))
+        self.add_operator(DropTimestamp::new)
     }
     /// Change the batch mode for this stream.
     ///
@@ -386,7 +466,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10)));
     /// let res = s.filter_map(|n| if n % 2 == 0 { Some(n * 3) } else { None }).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![0, 6, 12, 18, 24])
     /// ```
@@ -411,7 +491,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10)));
     /// let res = s.filter(|&n| n % 2 == 0).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![0, 2, 4, 6, 8])
     /// ```
@@ -425,7 +505,7 @@ where
     /// # TODO
     /// Reorder timestamped items
     pub fn reorder(self) -> Stream<I, impl Operator<I>> {
-        self.add_operator(|prev| Reorder::new(prev))
+        self.add_operator(Reorder::new)
     }
 
     /// Remove from the stream all the elements for which the provided function returns `None` and
@@ -461,7 +541,7 @@ where
     ///     }
     /// }).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![1, 1 + 2, /* 1 + 2 - 5, */ 1 + 2 - 5 + 3, 1 + 2 - 5 + 3 + 1]);
     /// ```
@@ -503,7 +583,7 @@ where
     ///     }
     /// }).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![1, 1 + 2, 1 + 2 + 3, 1 + 2 + 3 + 4, 1 + 2 + 3 + 4 + 5]);
     /// ```    
@@ -524,7 +604,7 @@ where
     ///     }
     /// }).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5)]);
     /// ```
@@ -551,7 +631,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// let res = s.map(|n| n * 10).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![0, 10, 20, 30, 40]);
     /// ```
@@ -665,7 +745,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// let res = s.fold(0, |acc, value| *acc += value).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![0 + 1 + 2 + 3 + 4]);
     /// ```
@@ -711,7 +791,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// let res = s.fold_assoc(0, |acc, value| *acc += value, |acc, value| *acc += value).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![0 + 1 + 2 + 3 + 4]);
     /// ```
@@ -721,9 +801,9 @@ where
         G: Fn(&mut O, O) + Send + Clone + 'static,
         O: ExchangeData,
     {
-        self.add_operator(|prev| Fold::new(prev, init.clone(), local))
+        self.add_operator(|prev| Fold::new(prev, init.clone(), local).checkpointed())
             .replication(Replication::One)
-            .add_operator(|prev| Fold::new(prev, init, global))
+            .add_operator(|prev| Fold::new(prev, init, global).checkpointed())
     }
 
     pub fn fold_batch<O, F>(self, init: O, f: F, batch_size: usize) -> Stream<O, impl Operator<O>>
@@ -788,7 +868,7 @@ where
     ///     .group_by_fold(|&n| n % 2, 0, |acc, value| *acc += value, |acc, value| *acc += value)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -818,11 +898,11 @@ where
             // key_by with given keyer
             .add_operator(|prev| KeyBy::new(prev, keyer.clone()))
             // local fold
-            .add_operator(|prev| KeyedFold::new(prev, init.clone(), local))
+            .add_operator(|prev| KeyedFold::new(prev, init.clone(), local).checkpointed())
             // group by key
             .split_block(End::new, next_strategy)
             // global fold
-            .add_operator(|prev| KeyedFold::new(prev, init.clone(), global));
+            .add_operator(|prev| KeyedFold::new(prev, init.clone(), global).checkpointed());
 
         KeyedStream(new_stream)
     }
@@ -841,7 +921,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// let res = s.key_by(|&n| n % 2).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -866,7 +946,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// s.inspect(|n| println!("Item: {}", n)).for_each(std::mem::drop);
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     /// ```
     pub fn inspect<F>(self, f: F) -> Stream<I, impl Operator<I>>
     where
@@ -908,7 +988,7 @@ where
     ///     }
     /// }).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![(0, 1), (0, 2), (1, 2), (0, 3), (1, 3), (2, 3)]);
     /// ```
@@ -963,7 +1043,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..3)));
     /// let res = s.flat_map(|n| vec![n, n]).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![0, 0, 1, 1, 2, 2]);
     /// ```
@@ -989,7 +1069,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// s.for_each(|n| println!("Item: {}", n));
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     /// ```
     pub fn for_each<F>(self, f: F)
     where
@@ -1082,7 +1162,7 @@ where
     ///     .group_by_max_element(|&n| n % 2, |&n| n)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -1116,6 +1196,9 @@ where
     ///
     /// **Note**: this is similar to the SQL: `SELECT SUM(value) ... GROUP BY key`
     ///
+    /// **Note**: the sums are emitted only when the stream ends, use
+    /// [`Stream::group_by_sum_changelog`] to emit them while the stream goes on.
+    ///
     /// **Note**: the type of the result does not have to be a number, any type that implements
     /// `AddAssign` is accepted.
     ///
@@ -1131,7 +1214,7 @@ where
     ///     .group_by_sum(|&n| n % 2, |n| n)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -1195,7 +1278,7 @@ where
     ///     .group_by_avg(|&n| n % 2, |&n| n as f64)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_by_key(|(k, _)| *k);
@@ -1246,6 +1329,9 @@ where
     ///
     /// **Note**: this is similar to the SQL: `SELECT COUNT(*) ... GROUP BY key`
     ///
+    /// **Note**: the counts are emitted only when the stream ends, use
+    /// [`Stream::group_by_count_changelog`] to emit them while the stream goes on.
+    ///
     /// **Note**: this operator will split the current block.
     ///
     /// ## Example
@@ -1258,7 +1344,7 @@ where
     ///     .group_by_count(|&n| n % 2)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_by_key(|(k, _)| *k);
@@ -1303,7 +1389,7 @@ where
     ///     .group_by_min_element(|&n| n % 2, |&n| n)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -1360,7 +1446,7 @@ where
     ///     .group_by_reduce(|&n| n % 2, |acc, value| *acc += value)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -1435,7 +1521,9 @@ where
     /// **Note**: this operator is pretty advanced, some operators may need to be fully replicated
     /// and will fail otherwise.
     pub fn replication(self, replication: Replication) -> Stream<I, SimpleStartOperator<I>> {
+        let host_labels = self.block.scheduler_requirements.host_labels.clone();
         let mut new_stream = self.split_block(End::new, NextStrategy::only_one());
+        new_stream.block.scheduler_requirements.host_labels = host_labels;
         new_stream
             .block
             .scheduler_requirements
@@ -1443,6 +1531,40 @@ where
         new_stream
     }
 
+    /// Run the operators of the current block only on the remote hosts with the given label (see
+    /// [`RemoteHostConfig::labels`](crate::config::RemoteHostConfig::labels)).
+    ///
+    /// The current block starts from the source or from the last operator that moved the data
+    /// between the replicas (e.g. `shuffle`, `group_by`, ...). The requested labels are
+    /// accumulated, a host must have all of them. In a local environment the labels are ignored.
+    ///
+    /// **Note**: if no host has the requested labels the job fails without starting, with a
+    /// [`JobError`](crate::JobError) of kind [`Placement`](crate::JobErrorKind::Placement).
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    /// let s = env.stream(IteratorSource::new((0..10)));
+    /// let res = s
+    ///     .group_by_sum(|x| x % 2, |x| x)
+    ///     // the sums are computed only on the hosts labeled `bigmem`
+    ///     .on_hosts("bigmem")
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 20), (1, 25)]);
+    /// ```
+    pub fn on_hosts(mut self, label: impl Into<String>) -> Self {
+        self.block.scheduler_requirements.host_label(label.into());
+        self
+    }
+
     /// Reduce the stream into a stream that emits a single value.
     ///
     /// The reducing operator consists in adding to the current accumulation value  the value of the
@@ -1474,7 +1596,7 @@ where
     /// let s = env.stream_iter(0..5);
     /// let res = s.reduce(|a, b| a + b).collect::<Vec<_>>();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![0 + 1 + 2 + 3 + 4]);
     /// ```
@@ -1519,7 +1641,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5)));
     /// let res = s.reduce_assoc(|a, b| a + b).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![0 + 1 + 2 + 3 + 4]);
     /// ```
@@ -1627,7 +1749,7 @@ where
     /// // 6 8
     /// routes.next().unwrap().for_each(|i| eprintln!("route2: {i}"));
     /// // 5 7 9 ignored
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     /// ```
     pub fn route(self) -> RouterBuilder<I, Op> {
         RouterBuilder::new(self)
@@ -1705,7 +1827,7 @@ where
     /// let s2 = env.stream(IteratorSource::new(vec![1, 2, 3].into_iter()));
     /// let res = s1.zip(s2).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![('A', 1), ('B', 2), ('C', 3)]);
     /// ```
@@ -1746,7 +1868,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10u32)));
     /// let rx = s.collect_channel();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     /// let mut v = Vec::new();
     /// while let Ok(x) = rx.recv() {
     ///     v.push(x)
@@ -1777,7 +1899,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10u32)));
     /// let rx = s.collect_channel();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     /// let mut v = Vec::new();
     /// while let Ok(x) = rx.recv() {
     ///     v.push(x)
@@ -1791,6 +1913,90 @@ where
         rx
     }
 
+    /// Close the stream and send resulting items to a bounded channel on a single host.
+    ///
+    /// Like [`Stream::collect_channel`], but the channel holds at most `capacity` items: when it
+    /// is full the sink blocks, applying backpressure to the whole pipeline until the receiver
+    /// catches up. The items coming from the same replica of the previous block are received in
+    /// the order they were produced.
+    ///
+    /// **Note**: since the job stops when the channel is full, the receiver must be consumed
+    /// while the job is running (e.g. from another thread), not after
+    /// [`StreamEnvironment::execute_blocking`](crate::StreamEnvironment::execute_blocking)
+    /// returns.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..10u32)));
+    /// let rx = s.collect_channel_bounded(2);
+    ///
+    /// let consumer = std::thread::spawn(move || {
+    ///     let mut v = Vec::new();
+    ///     while let Ok(x) = rx.recv() {
+    ///         v.push(x)
+    ///     }
+    ///     v
+    /// });
+    /// env.execute_blocking().unwrap();
+    /// assert_eq!(consumer.join().unwrap(), (0..10u32).collect::<Vec<_>>());
+    /// ```
+    pub fn collect_channel_bounded(self, capacity: usize) -> Receiver<I> {
+        let (tx, rx) = bounded(capacity);
+        self.replication(Replication::One)
+            .add_operator(|prev| CollectChannelSink::new(prev, tx))
+            .finalize_block();
+        rx
+    }
+
+    /// Close the stream and send resulting items to a bounded channel on each single host.
+    ///
+    /// Like [`Stream::collect_channel_parallel`], but the channel holds at most `capacity` items
+    /// and applies backpressure when full (see [`Stream::collect_channel_bounded`]). The items
+    /// of each replica are received in the order they were produced, interleaved with the ones
+    /// of the other replicas.
+    pub fn collect_channel_parallel_bounded(self, capacity: usize) -> Receiver<I> {
+        let (tx, rx) = bounded(capacity);
+        self.add_operator(|prev| CollectChannelSink::new(prev, tx))
+            .finalize_block();
+        rx
+    }
+
+    /// Close the stream and send resulting items to a bounded channel on each single host,
+    /// together with the index of the replica that produced them.
+    ///
+    /// Like [`Stream::collect_channel_parallel_bounded`], each pair is `(replica, item)` where
+    /// `replica` is the global index of the replica of this block. The items with the same
+    /// replica index are received in the order they were produced by that replica.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..10u32)));
+    /// let rx = s.collect_channel_parallel_tagged(16);
+    ///
+    /// env.execute_blocking().unwrap();
+    /// let mut v = Vec::new();
+    /// while let Ok((_replica, x)) = rx.recv() {
+    ///     v.push(x)
+    /// }
+    /// assert_eq!(v, (0..10u32).collect::<Vec<_>>());
+    /// ```
+    pub fn collect_channel_parallel_tagged(self, capacity: usize) -> Receiver<(CoordUInt, I)> {
+        let (tx, rx) = bounded(capacity);
+        self.add_operator(|prev| CollectChannelSink::tagged(prev, tx))
+            .finalize_block();
+        rx
+    }
+
     /// Close the stream and store all the resulting items into a [`Vec`] on a single host.
     ///
     /// If the stream is distributed among multiple replicas, a bottleneck is placed where all the
@@ -1809,7 +2015,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10)));
     /// let res = s.collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), (0..10).collect::<Vec<_>>());
     /// ```
@@ -1840,7 +2046,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10)));
     /// let res = s.collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), (0..10).collect::<Vec<_>>());
     /// ```
@@ -1852,6 +2058,95 @@ where
         StreamOutput::from(output)
     }
 
+    /// Close the stream and store all the resulting items into a [`Vec`] on a single host,
+    /// following the order of the replicas.
+    ///
+    /// The vector contains all the items of the first replica of the current block, in the order
+    /// they were produced, then all the ones of the second replica, and so on. After
+    /// [`Stream::sort_by_key`] this is the sorted stream.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    /// let s = env.stream(IteratorSource::new((0..10)));
+    /// let res = s.shuffle().sort_by_key(|&n| n).collect_vec_ordered();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap(), (0..10).collect::<Vec<_>>());
+    /// ```
+    pub fn collect_vec_ordered(self) -> StreamOutput<Vec<I>> {
+        let output = StreamOutputRef::default();
+        self.add_operator(TagReplica::new)
+            .replication(Replication::One)
+            .add_operator(|prev| CollectVecOrderedSink::new(prev, output.clone()))
+            .finalize_block();
+        StreamOutput::from(output)
+    }
+
+    /// Close the stream and materialise all its items into a [`BroadcastVariable`], a read-only
+    /// dataset that can be used inside the closures of the operators of other streams.
+    ///
+    /// The items are sent once to each host, where a single replica collects them: all the
+    /// replicas of a host share the same copy of the data. This is meant for small datasets, like
+    /// the centroids of a clustering or a lookup table.
+    ///
+    /// [`BroadcastVariable::get`] blocks until this stream has ended, so the stream must not
+    /// depend on the operators reading the variable, or the job will deadlock.
+    ///
+    /// **Note**: the order of the items is unspecified.
+    ///
+    /// **Note**: the variable is materialised only once and is never refreshed. If this stream is
+    /// inside an iteration, the variable holds the items of the first iteration only; to use a
+    /// value that changes at each iteration, use the state of the iteration instead.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    /// let centroids = env
+    ///     .stream(IteratorSource::new(vec![0, 10, 20].into_iter()))
+    ///     .broadcast_variable();
+    /// let s = env.stream(IteratorSource::new(vec![1, 12, 19].into_iter()));
+    /// let res = s
+    ///     .map(move |n: i32| {
+    ///         // the nearest centroid
+    ///         let centroids = centroids.get();
+    ///         *centroids.iter().min_by_key(|&c| (n - c).abs()).unwrap()
+    ///     })
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![0, 10, 20]);
+    /// ```
+    pub fn broadcast_variable(self) -> BroadcastVariable<I>
+    where
+        I: Sync,
+    {
+        let variable = BroadcastVariable::new();
+        let mut new_stream = self.split_block(End::new, NextStrategy::all());
+        new_stream
+            .block
+            .scheduler_requirements
+            .replication(Replication::Host);
+        new_stream
+            .add_operator(|prev| BroadcastVariableSink::new(prev, variable.clone()))
+            .finalize_block();
+        variable
+    }
+
     /// Close the stream and store all the resulting items into a collection on a single host.
     ///
     /// If the stream is distributed among multiple replicas, parallelism will
@@ -1870,7 +2165,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10)));
     /// let res = s.collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), (0..10).collect::<Vec<_>>());
     /// ```
@@ -1881,6 +2176,34 @@ where
             .finalize_block();
         StreamOutput::from(output)
     }
+
+    /// Close the stream and write the items of each replica to its own CSV file inside `dir`.
+    ///
+    /// The directory is created if missing, the files are named after the global index of the
+    /// replicas: `part-00000.csv`, `part-00001.csv`, ... Each file contains the items of its
+    /// replica in the order they were produced. After [`Stream::sort_by_key`], reading the files
+    /// sorted by name gives the sorted stream. The part files of a previous run are overwritten,
+    /// the extra ones of a run with more replicas are removed.
+    ///
+    /// The items are serialized with [`csv::Writer::serialize`], the files can be read back with
+    /// a [`CsvSource`](crate::operator::source::CsvSource).
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    /// let s = env.stream(IteratorSource::new((0..10).map(|n| (n, n * n))));
+    /// s.shuffle().sort_by_key(|&(n, _)| n).write_csv_parts("squares");
+    ///
+    /// env.execute_blocking().unwrap();
+    /// ```
+    pub fn write_csv_parts(self, dir: impl Into<PathBuf>) {
+        let dir = dir.into();
+        self.add_operator(|prev| CsvPartsSink::new(prev, dir))
+            .finalize_block();
+    }
 }
 
 impl<I, O, It, Op> Stream<I, Op>
@@ -1907,7 +2230,7 @@ where
     /// ].into_iter()));
     /// let res = s.flatten().collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// assert_eq!(res.get().unwrap(), vec![1, 2, 3, 4, 5]);
     /// ```
@@ -2001,9 +2324,26 @@ where
         self.add_operator(|prev| AddTimestamp::new(prev, timestamp_gen, watermark_gen))
     }
 
+    /// Like [`KeyedStream::add_timestamps`], but the watermarks are inserted by a
+    /// [`WatermarkGenerator`] strategy.
+    ///
+    /// See [`Stream::add_timestamps_with`] for the details.
+    #[cfg(feature = "timestamp")]
+    pub fn add_timestamps_with<F, W>(
+        self,
+        timestamp_gen: F,
+        watermark_gen: W,
+    ) -> KeyedStream<K, I, impl Operator<(K, I)>>
+    where
+        F: FnMut(&(K, I)) -> Timestamp + Clone + Send + 'static,
+        W: WatermarkGenerator<(K, I)>,
+    {
+        self.add_operator(|prev| AddTimestamp::with_generator(prev, timestamp_gen, watermark_gen))
+    }
+
     #[cfg(feature = "timestamp")]
     pub fn drop_timestamps(self) -> KeyedStream<K, I, impl Operator<(K, I)>> {
-        self.add_operator(|prev| DropTimestamp::new(prev))
+        self.add_operator(DropTimestamp::new)
     }
 
     /// Change the batch mode for this stream.
@@ -2041,7 +2381,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10))).group_by(|&n| n % 2);
     /// let res = s.filter_map(|(_key, n)| if n % 3 == 0 { Some(n * 4) } else { None }).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -2070,7 +2410,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10))).group_by(|&n| n % 2);
     /// let res = s.filter(|&(_key, n)| n % 3 == 0).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -2097,7 +2437,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..3))).group_by(|&n| n % 2);
     /// let res = s.flat_map(|(_key, n)| vec![n, n]).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -2125,7 +2465,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5))).group_by(|&n| n % 2);
     /// s.inspect(|(key, n)| println!("Item: {} has key {}", n, key)).for_each(std::mem::drop);
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     /// ```
     pub fn inspect<F>(self, f: F) -> KeyedStream<K, I, impl Operator<(K, I)>>
     where
@@ -2150,7 +2490,8 @@ where
     /// [`KeyedStream::reduce`] if the output type is the same as the input type.
     ///
     /// **Note**: this operator will retain all the messages of the stream and emit the values only
-    /// when the stream ends. Therefore this is not properly _streaming_.
+    /// when the stream ends. Therefore this is not properly _streaming_. Consider using
+    /// [`KeyedStream::fold_changelog`] to emit the updated values while the stream goes on.
     ///
     /// **Note**: this operator will split the current block.
     ///
@@ -2165,7 +2506,7 @@ where
     ///     .fold(0, |acc, value| *acc += value)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -2179,6 +2520,46 @@ where
         self.add_operator(|prev| KeyedFold::new(prev, init, f))
     }
 
+    /// Perform the folding operation separately for each key, forgetting the keys according to
+    /// `retention`.
+    ///
+    /// This is like [`KeyedStream::fold`], but the accumulator of a key is dropped when the key
+    /// expires or exceeds the maximum number of keys (see [`StateRetention`]), and emitted if
+    /// [`StateRetention::emit_evicted`] is set. A key that receives a new element after being
+    /// forgotten starts again from `init`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # use noir::operator::StateRetention;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..5))).group_by(|&n| n % 2);
+    /// let retention = StateRetention::new().max_keys(1).emit_evicted();
+    /// let res = s
+    ///     .fold_with_retention(0, |acc, value| *acc += value, retention)
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 0), (0, 2), (0, 4), (1, 1), (1, 3)]);
+    /// ```
+    pub fn fold_with_retention<O, F>(
+        self,
+        init: O,
+        f: F,
+        retention: StateRetention,
+    ) -> KeyedStream<K, O, impl Operator<(K, O)>>
+    where
+        F: Fn(&mut O, I) + Send + Clone + 'static,
+        O: Data,
+    {
+        self.add_operator(|prev| KeyedFold::new(prev, init, f).with_retention(retention))
+    }
+
     /// Perform the reduction operation separately for each key.
     ///
     /// Note that there is a difference between `stream.group_by(keyer).reduce(...)` and
@@ -2195,7 +2576,8 @@ where
     /// consider using [`KeyedStream::fold`].
     ///
     /// **Note**: this operator will retain all the messages of the stream and emit the values only
-    /// when the stream ends. Therefore this is not properly _streaming_.
+    /// when the stream ends. Therefore this is not properly _streaming_. Consider using
+    /// [`KeyedStream::reduce_changelog`] to emit the updated values while the stream goes on.
     ///
     /// **Note**: this operator will split the current block.
     ///
@@ -2210,7 +2592,7 @@ where
     ///     .reduce(|acc, value| *acc += value)
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -2240,7 +2622,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5))).group_by(|&n| n % 2);
     /// let res = s.map(|(_key, n)| 10 * n).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -2262,7 +2644,7 @@ where
     /// # TODO
     /// Reorder timestamped items
     pub fn reorder(self) -> KeyedStream<K, I, impl Operator<(K, I)>> {
-        self.add_operator(|prev| Reorder::new(prev))
+        self.add_operator(Reorder::new)
     }
 
     /// Map the elements of the stream into new elements. The mapping function can be stateful.
@@ -2277,6 +2659,55 @@ where
         self.add_operator(|prev| RichMap::new(prev, f))
     }
 
+    /// Map the elements of the stream into new elements with a stateful function cloned for each
+    /// key, forgetting the keys according to `retention`.
+    ///
+    /// This is like [`KeyedStream::rich_map`], but the function of a key is dropped when the key
+    /// expires or exceeds the maximum number of keys (see [`StateRetention`]). A key that receives
+    /// a new element after being forgotten starts again from a new clone of `f`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if [`StateRetention::emit_evicted`] is set: the function of a key has no final value
+    /// to emit when the key is forgotten.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # use noir::operator::StateRetention;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new(vec![1, 1, 2, 1].into_iter())).group_by(|&n| n);
+    /// let res = s
+    ///     .rich_map_with_retention(
+    ///         {
+    ///             let mut count = 0;
+    ///             move |_| {
+    ///                 count += 1;
+    ///                 count
+    ///             }
+    ///         },
+    ///         StateRetention::new().max_keys(1),
+    ///     )
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap(), vec![(1, 1), (1, 2), (2, 1), (1, 1)]);
+    /// ```
+    pub fn rich_map_with_retention<O, F>(
+        self,
+        f: F,
+        retention: StateRetention,
+    ) -> KeyedStream<K, O, impl Operator<(K, O)>>
+    where
+        F: FnMut((&K, I)) -> O + Clone + Send + 'static,
+        O: Data,
+    {
+        self.add_operator(|prev| RichMap::new(prev, f).with_retention(retention))
+    }
+
     /// Apply a mapping operation to each element of the stream, the resulting stream will be the
     /// flattened values of the result of the mapping. The mapping function can be stateful.
     ///
@@ -2331,7 +2762,7 @@ where
     /// let stream = env.stream(IteratorSource::new((0..4))).group_by(|&n| n % 2);
     /// let res = stream.unkey().collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable(); // the output order is nondeterministic
@@ -2353,7 +2784,7 @@ where
     /// let stream = env.stream(IteratorSource::new((0..4))).group_by(|&n| n % 2);
     /// let res = stream.drop_key().collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable(); // the output order is nondeterministic
@@ -2363,6 +2794,14 @@ where
         self.0.map(|(_k, v)| v)
     }
 
+    /// Run the operators of the current block only on the remote hosts with the given label.
+    ///
+    /// See [`Stream::on_hosts`] for the details.
+    pub fn on_hosts(mut self, label: impl Into<String>) -> Self {
+        self.0.block.scheduler_requirements.host_label(label.into());
+        self
+    }
+
     /// Apply the given function to all the elements of the stream, consuming the stream.
     ///
     /// ## Example
@@ -2374,7 +2813,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..5))).group_by(|&n| n % 2);
     /// s.for_each(|(key, n)| println!("Item: {} has key {}", n, key));
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     /// ```
     pub fn for_each<F>(self, f: F)
     where
@@ -2435,7 +2874,7 @@ where
     /// let s2 = env.stream(IteratorSource::new((3..5))).group_by(|&n| n % 2);
     /// let res = s1.merge(s2).collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable(); // the output order is nondeterministic
@@ -2502,7 +2941,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10u32)));
     /// let rx = s.collect_channel();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     /// let mut v = Vec::new();
     /// while let Ok(x) = rx.recv() {
     ///     v.push(x)
@@ -2529,7 +2968,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..10u32)));
     /// let rx = s.collect_channel();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     /// let mut v = Vec::new();
     /// while let Ok(x) = rx.recv() {
     ///     v.push(x)
@@ -2540,6 +2979,28 @@ where
         self.unkey().collect_channel_parallel()
     }
 
+    /// Close the stream and send resulting items to a bounded channel on a single host.
+    ///
+    /// See [`Stream::collect_channel_bounded`].
+    pub fn collect_channel_bounded(self, capacity: usize) -> Receiver<(K, I)> {
+        self.unkey().collect_channel_bounded(capacity)
+    }
+
+    /// Close the stream and send resulting items to a bounded channel on each single host.
+    ///
+    /// See [`Stream::collect_channel_parallel_bounded`].
+    pub fn collect_channel_parallel_bounded(self, capacity: usize) -> Receiver<(K, I)> {
+        self.unkey().collect_channel_parallel_bounded(capacity)
+    }
+
+    /// Close the stream and send resulting items to a bounded channel on each single host,
+    /// together with the index of the replica that produced them.
+    ///
+    /// See [`Stream::collect_channel_parallel_tagged`].
+    pub fn collect_channel_parallel_tagged(self, capacity: usize) -> Receiver<(CoordUInt, (K, I))> {
+        self.unkey().collect_channel_parallel_tagged(capacity)
+    }
+
     /// Close the stream and store all the resulting items into a [`Vec`] on a single host.
     ///
     /// If the stream is distributed among multiple replicas, a bottleneck is placed where all the
@@ -2560,7 +3021,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..3))).group_by(|&n| n % 2);
     /// let res = s.collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable(); // the output order is nondeterministic
@@ -2589,7 +3050,7 @@ where
     /// let s = env.stream(IteratorSource::new((0..3))).group_by(|&n| n % 2);
     /// let res = s.collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable(); // the output order is nondeterministic
@@ -2628,7 +3089,7 @@ where
     ///     .group_by(|v| v[0] % 2);
     /// let res = s.flatten().collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -0,0 +1,785 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt::Display;
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::block::{group_by_hash, BlockStructure, OperatorStructure};
+use crate::checkpoint::{CheckpointId, Checkpointed, StateCodec};
+use crate::operator::{Data, ExchangeData, ExchangeDataKey, Operator, StreamElement, Timestamp};
+use crate::scheduler::ExecutionMetadata;
+use crate::KeyedStream;
+
+/// How often the block is woken up to check the processing-time timers when no message arrives.
+const WAKE_UP_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A timer registered with a [`ProcessContext`], passed to the function that handles it when it
+/// fires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Timer {
+    /// The timer fired because the watermark reached this timestamp.
+    EventTime(Timestamp),
+    /// The timer fired because the clock reached this time.
+    ProcessingTime(SystemTime),
+}
+
+/// A single value in the state of a key of [`KeyedStream::process`], initially empty.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValueState<T>(Option<T>);
+
+impl<T> Default for ValueState<T> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T> ValueState<T> {
+    /// The value, if it has been set.
+    pub fn get(&self) -> Option<&T> {
+        self.0.as_ref()
+    }
+
+    /// A mutable reference to the value, if it has been set.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.0.as_mut()
+    }
+
+    /// Set the value, returning the previous one.
+    pub fn set(&mut self, value: T) -> Option<T> {
+        self.0.replace(value)
+    }
+
+    /// Remove the value, returning it.
+    pub fn take(&mut self) -> Option<T> {
+        self.0.take()
+    }
+
+    /// Remove the value.
+    pub fn clear(&mut self) {
+        self.0 = None;
+    }
+}
+
+/// A list of values in the state of a key of [`KeyedStream::process`], initially empty.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ListState<T>(Vec<T>);
+
+impl<T> Default for ListState<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> ListState<T> {
+    /// Append a value at the end of the list.
+    pub fn add(&mut self, value: T) {
+        self.0.push(value);
+    }
+
+    /// The values of the list, in insertion order.
+    pub fn get(&self) -> &[T] {
+        &self.0
+    }
+
+    /// Replace all the values of the list.
+    pub fn update(&mut self, values: Vec<T>) {
+        self.0 = values;
+    }
+
+    /// Remove all the values of the list, returning them.
+    pub fn take(&mut self) -> Vec<T> {
+        std::mem::take(&mut self.0)
+    }
+
+    /// Remove all the values of the list.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// The number of values in the list.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A map in the state of a key of [`KeyedStream::process`], initially empty.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Serialize, V: Serialize",
+    deserialize = "K: Deserialize<'de> + Eq + Hash, V: Deserialize<'de>"
+))]
+pub struct MapState<K, V>(HashMap<K, V>);
+
+impl<K, V> Default for MapState<K, V> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<K: Eq + Hash, V> MapState<K, V> {
+    /// The value of `key`, if any.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.0.get(key)
+    }
+
+    /// A mutable reference to the value of `key`, if any.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.0.get_mut(key)
+    }
+
+    /// The value of `key`, inserting `default` if there is none.
+    pub fn get_or_insert(&mut self, key: K, default: V) -> &mut V {
+        self.0.entry(key).or_insert(default)
+    }
+
+    /// Set the value of `key`, returning the previous one.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.0.insert(key, value)
+    }
+
+    /// Remove the value of `key`, returning it.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.0.remove(key)
+    }
+
+    /// Whether `key` has a value.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.0.contains_key(key)
+    }
+
+    /// The entries of the map, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.0.iter()
+    }
+
+    /// Remove all the entries of the map.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// The number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// The state and the timers of a single key.
+#[derive(Clone, Debug)]
+struct KeyState<S> {
+    state: S,
+    event_timers: BTreeSet<Timestamp>,
+    processing_timers: BTreeSet<SystemTime>,
+}
+
+impl<S> KeyState<S> {
+    fn new(state: S) -> Self {
+        Self {
+            state,
+            event_timers: Default::default(),
+            processing_timers: Default::default(),
+        }
+    }
+}
+
+/// The context of a key, given to the functions of [`KeyedStream::process`].
+///
+/// The context gives access to the key being processed and its state, allows emitting any number
+/// of elements and registering the timers of the key.
+pub struct ProcessContext<'a, K, S, O> {
+    key: &'a K,
+    entry: &'a mut KeyState<S>,
+    init: &'a S,
+    timestamp: Option<Timestamp>,
+    #[cfg(feature = "timestamp")]
+    watermark: Option<Timestamp>,
+    #[cfg(feature = "timestamp")]
+    event_queue: &'a mut BTreeMap<Timestamp, Vec<K>>,
+    processing_queue: &'a mut BTreeMap<SystemTime, Vec<K>>,
+    ready: &'a mut VecDeque<StreamElement<(K, O)>>,
+    /// Whether the state has been cleared, and not used since.
+    cleared: bool,
+}
+
+impl<'a, K: Clone, S: Clone, O> ProcessContext<'a, K, S, O> {
+    /// The key being processed.
+    pub fn key(&self) -> &K {
+        self.key
+    }
+
+    /// The state of the key being processed.
+    ///
+    /// The state of a key seen for the first time is a copy of the initial state passed to
+    /// [`KeyedStream::process`].
+    pub fn state(&mut self) -> &mut S {
+        self.cleared = false;
+        &mut self.entry.state
+    }
+
+    /// Reset the state of the key to the initial one.
+    ///
+    /// A key with a cleared state and without pending timers is forgotten by the operator.
+    pub fn clear_state(&mut self) {
+        self.entry.state = self.init.clone();
+        self.cleared = true;
+    }
+
+    /// Emit an element with the key being processed.
+    ///
+    /// The element has the timestamp returned by [`ProcessContext::timestamp`], if any.
+    pub fn emit(&mut self, item: O) {
+        let item = (self.key.clone(), item);
+        self.ready.push_back(match self.timestamp {
+            Some(ts) => StreamElement::Timestamped(item, ts),
+            None => StreamElement::Item(item),
+        });
+    }
+
+    /// The timestamp of the element being processed, or of the event-time timer that fired.
+    ///
+    /// When a processing-time timer fires this is the last watermark received.
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        self.timestamp
+    }
+
+    /// The last watermark received by the operator.
+    #[cfg(feature = "timestamp")]
+    pub fn watermark(&self) -> Option<Timestamp> {
+        self.watermark
+    }
+
+    /// Register a timer that fires when the watermark reaches `ts`.
+    ///
+    /// Registering again the same timestamp for the same key has no effect.
+    #[cfg(feature = "timestamp")]
+    pub fn register_event_time_timer(&mut self, ts: Timestamp) {
+        if self.entry.event_timers.insert(ts) {
+            self.event_queue
+                .entry(ts)
+                .or_default()
+                .push(self.key.clone());
+        }
+    }
+
+    /// Delete the event-time timer of the key registered for `ts`, if any.
+    #[cfg(feature = "timestamp")]
+    pub fn delete_event_time_timer(&mut self, ts: Timestamp) {
+        self.entry.event_timers.remove(&ts);
+    }
+
+    /// Register a timer that fires after `delay`, returning the time it fires at.
+    ///
+    /// The timers are checked when a message reaches the operator, and at least every few
+    /// milliseconds if no message arrives. The timers still pending at the end of the stream fire
+    /// early, before the end is forwarded.
+    pub fn register_processing_time_timer(&mut self, delay: Duration) -> SystemTime {
+        let time = SystemTime::now() + delay;
+        if self.entry.processing_timers.insert(time) {
+            self.processing_queue
+                .entry(time)
+                .or_default()
+                .push(self.key.clone());
+        }
+        time
+    }
+
+    /// Delete the processing-time timer of the key that fires at `time`, if any.
+    pub fn delete_processing_time_timer(&mut self, time: SystemTime) {
+        self.entry.processing_timers.remove(&time);
+    }
+
+    /// Delete all the timers of the key, both event-time and processing-time ones.
+    pub fn delete_all_timers(&mut self) {
+        self.entry.event_timers.clear();
+        self.entry.processing_timers.clear();
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug, Clone)]
+struct Process<K, I, S, O, F, Ft, OperatorChain>
+where
+    K: ExchangeDataKey,
+    I: Data,
+    S: ExchangeData,
+    O: Data,
+    F: FnMut(&mut ProcessContext<K, S, O>, I) + Clone + Send,
+    Ft: FnMut(&mut ProcessContext<K, S, O>, Timer) + Clone + Send,
+    OperatorChain: Operator<(K, I)>,
+{
+    prev: OperatorChain,
+    #[derivative(Debug = "ignore")]
+    on_element: F,
+    #[derivative(Debug = "ignore")]
+    on_timer: Ft,
+    init: S,
+    keys: HashMap<K, KeyState<S>, crate::block::GroupHasherBuilder>,
+    /// The keys with an event-time timer, by timestamp. Deleted timers are skipped when fired.
+    event_queue: BTreeMap<Timestamp, Vec<K>>,
+    /// The keys with a processing-time timer, by time. Deleted timers are skipped when fired.
+    processing_queue: BTreeMap<SystemTime, Vec<K>>,
+    watermark: Option<Timestamp>,
+    ready: VecDeque<StreamElement<(K, O)>>,
+    /// For each key its state, the event-time and the processing-time timers, with the last
+    /// watermark.
+    #[allow(clippy::type_complexity)]
+    checkpoint: Checkpointed<(
+        Vec<(K, S, Vec<Timestamp>, Vec<SystemTime>)>,
+        Option<Timestamp>,
+    )>,
+    _in: PhantomData<I>,
+}
+
+impl<K, I, S, O, F, Ft, OperatorChain> Display for Process<K, I, S, O, F, Ft, OperatorChain>
+where
+    K: ExchangeDataKey,
+    I: Data,
+    S: ExchangeData,
+    O: Data,
+    F: FnMut(&mut ProcessContext<K, S, O>, I) + Clone + Send,
+    Ft: FnMut(&mut ProcessContext<K, S, O>, Timer) + Clone + Send,
+    OperatorChain: Operator<(K, I)>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> Process<{} -> {}>",
+            self.prev,
+            std::any::type_name::<I>(),
+            std::any::type_name::<O>()
+        )
+    }
+}
+
+impl<K, I, S, O, F, Ft, OperatorChain> Process<K, I, S, O, F, Ft, OperatorChain>
+where
+    K: ExchangeDataKey,
+    I: Data,
+    S: ExchangeData,
+    O: Data,
+    F: FnMut(&mut ProcessContext<K, S, O>, I) + Clone + Send,
+    Ft: FnMut(&mut ProcessContext<K, S, O>, Timer) + Clone + Send,
+    OperatorChain: Operator<(K, I)>,
+{
+    fn new(prev: OperatorChain, init: S, on_element: F, on_timer: Ft) -> Self {
+        Self {
+            prev,
+            on_element,
+            on_timer,
+            init,
+            keys: Default::default(),
+            event_queue: Default::default(),
+            processing_queue: Default::default(),
+            watermark: None,
+            ready: Default::default(),
+            checkpoint: Checkpointed::new(Some(StateCodec::new())),
+            _in: PhantomData,
+        }
+    }
+
+    fn snapshot(&self, id: CheckpointId) {
+        let keys = self
+            .keys
+            .iter()
+            .map(|(key, entry)| {
+                (
+                    key.clone(),
+                    entry.state.clone(),
+                    entry.event_timers.iter().copied().collect(),
+                    entry.processing_timers.iter().copied().collect(),
+                )
+            })
+            .collect();
+        self.checkpoint.snapshot(id, &(keys, self.watermark));
+    }
+
+    /// Restore the state and the timers of a key saved in a checkpoint.
+    ///
+    /// The processing-time timers that passed while the job was stopped fire as soon as possible.
+    fn restore(
+        &mut self,
+        key: K,
+        state: S,
+        event_timers: Vec<Timestamp>,
+        processing_timers: Vec<SystemTime>,
+    ) {
+        let mut entry = KeyState::new(state);
+        for ts in event_timers {
+            entry.event_timers.insert(ts);
+            self.event_queue.entry(ts).or_default().push(key.clone());
+        }
+        for time in processing_timers {
+            entry.processing_timers.insert(time);
+            self.processing_queue
+                .entry(time)
+                .or_default()
+                .push(key.clone());
+        }
+        self.keys.insert(key, entry);
+    }
+
+    /// Call `f` with the context of `key`, forgetting the key if its state has been cleared and it
+    /// has no more timers.
+    fn with_context(
+        &mut self,
+        key: K,
+        timestamp: Option<Timestamp>,
+        f: impl FnOnce(&mut F, &mut Ft, &mut ProcessContext<K, S, O>),
+    ) {
+        let entry = self
+            .keys
+            .entry(key.clone())
+            .or_insert_with(|| KeyState::new(self.init.clone()));
+        let mut ctx = ProcessContext {
+            key: &key,
+            entry,
+            init: &self.init,
+            timestamp,
+            #[cfg(feature = "timestamp")]
+            watermark: self.watermark,
+            #[cfg(feature = "timestamp")]
+            event_queue: &mut self.event_queue,
+            processing_queue: &mut self.processing_queue,
+            ready: &mut self.ready,
+            cleared: false,
+        };
+        f(&mut self.on_element, &mut self.on_timer, &mut ctx);
+        let forget = ctx.cleared
+            && ctx.entry.event_timers.is_empty()
+            && ctx.entry.processing_timers.is_empty();
+        if forget {
+            self.keys.remove(&key);
+        }
+    }
+
+    /// Fire the event-time timers up to `watermark`, or all of them at the end of the stream.
+    fn fire_event_timers(&mut self, watermark: Option<Timestamp>) {
+        while let Some(entry) = self.event_queue.first_entry() {
+            let ts = *entry.key();
+            if watermark.is_some_and(|watermark| ts > watermark) {
+                break;
+            }
+            for key in entry.remove() {
+                let registered = self
+                    .keys
+                    .get_mut(&key)
+                    .is_some_and(|entry| entry.event_timers.remove(&ts));
+                if registered {
+                    self.with_context(key, Some(ts), |_, on_timer, ctx| {
+                        on_timer(ctx, Timer::EventTime(ts))
+                    });
+                }
+            }
+        }
+    }
+
+    /// Fire the processing-time timers up to `now`, or all of them at the end of the stream.
+    fn fire_processing_timers(&mut self, now: Option<SystemTime>) {
+        while let Some(entry) = self.processing_queue.first_entry() {
+            let time = *entry.key();
+            if now.is_some_and(|now| time > now) {
+                break;
+            }
+            for key in entry.remove() {
+                let registered = self
+                    .keys
+                    .get_mut(&key)
+                    .is_some_and(|entry| entry.processing_timers.remove(&time));
+                if registered {
+                    self.with_context(key, self.watermark, |_, on_timer, ctx| {
+                        on_timer(ctx, Timer::ProcessingTime(time))
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl<K, I, S, O, F, Ft, OperatorChain> Operator<(K, O)>
+    for Process<K, I, S, O, F, Ft, OperatorChain>
+where
+    K: ExchangeDataKey,
+    I: Data,
+    S: ExchangeData,
+    O: Data,
+    F: FnMut(&mut ProcessContext<K, S, O>, I) + Clone + Send,
+    Ft: FnMut(&mut ProcessContext<K, S, O>, Timer) + Clone + Send,
+    OperatorChain: Operator<(K, I)>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        // the processing-time timers have to fire even if no message arrives
+        metadata.wake_up_every(WAKE_UP_INTERVAL);
+        self.prev.setup(metadata);
+        let restored = self.checkpoint.setup(metadata);
+        if let Some(previous) = self.checkpoint.rescaled(metadata) {
+            // keep the keys this replica is responsible for after rescaling
+            let replicas = metadata.replicas.len() as u64;
+            for (_, (keys, watermark)) in previous {
+                for (key, state, event_timers, processing_timers) in keys {
+                    if group_by_hash(&key) % replicas == metadata.global_id {
+                        self.restore(key, state, event_timers, processing_timers);
+                    }
+                }
+                self.watermark = self.watermark.max(watermark);
+            }
+        } else if let Some((keys, watermark)) = restored {
+            for (key, state, event_timers, processing_timers) in keys {
+                self.restore(key, state, event_timers, processing_timers);
+            }
+            self.watermark = watermark;
+        }
+    }
+
+    fn next(&mut self) -> StreamElement<(K, O)> {
+        loop {
+            if let Some(elem) = self.ready.pop_front() {
+                return elem;
+            }
+            let elem = self.prev.next();
+            // the timers that passed fire before the new element
+            self.fire_processing_timers(Some(SystemTime::now()));
+            match elem {
+                StreamElement::Item((key, item)) => {
+                    self.with_context(key, None, |on_element, _, ctx| on_element(ctx, item))
+                }
+                StreamElement::Timestamped((key, item), ts) => {
+                    self.with_context(key, Some(ts), |on_element, _, ctx| on_element(ctx, item))
+                }
+                StreamElement::Watermark(ts) => {
+                    self.watermark = Some(ts);
+                    self.fire_event_timers(Some(ts));
+                    self.ready.push_back(StreamElement::Watermark(ts));
+                }
+                StreamElement::Checkpoint(id) => {
+                    if self.checkpoint.is_enabled() {
+                        self.snapshot(id);
+                    }
+                    return StreamElement::Checkpoint(id);
+                }
+                StreamElement::FlushBatch => return StreamElement::FlushBatch,
+                // the end of the stream passes all the timers, otherwise they would never fire
+                StreamElement::FlushAndRestart => {
+                    self.fire_event_timers(None);
+                    self.fire_processing_timers(None);
+                    self.ready.push_back(StreamElement::FlushAndRestart);
+                }
+                StreamElement::Terminate => {
+                    self.fire_event_timers(None);
+                    self.fire_processing_timers(None);
+                    self.ready.push_back(StreamElement::Terminate);
+                }
+            }
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<(K, O), _>("Process"))
+    }
+}
+
+impl<K, I, Op> KeyedStream<K, I, Op>
+where
+    K: ExchangeDataKey,
+    I: Data,
+    Op: Operator<(K, I)> + 'static,
+{
+    /// Process the elements of each key with a function that has access to a per-key state and
+    /// timers.
+    ///
+    /// Each key starts with a copy of `init` as its state. The state can be any serializable type;
+    /// [`ValueState`], [`ListState`] and [`MapState`] hold a single value, a list and a map, and
+    /// many of them can be combined in a tuple or a struct. The state is accessed through the
+    /// [`ProcessContext`] passed to `on_element`, together with the key, the timestamp of the
+    /// element and a way to emit any number of elements for the key.
+    ///
+    /// The context also registers the timers of the key:
+    /// - the event-time timers fire when the watermark reaches their timestamp, or at the end of
+    ///   the stream;
+    /// - the processing-time timers fire when the clock reaches their time, or at the end of the
+    ///   stream.
+    ///
+    /// When a timer fires, `on_timer` is called with the context of its key. The elements emitted
+    /// by an event-time timer have its timestamp, the ones emitted by a processing-time timer
+    /// have the timestamp of the last watermark.
+    ///
+    /// The state and the timers of each key are saved in the checkpoints of the job (see
+    /// [`checkpoint`](crate::checkpoint)). A key is forgotten after its state is cleared with
+    /// [`ProcessContext::clear_state`] if it has no pending timers.
+    ///
+    /// ## Example
+    ///
+    /// Sum the elements of each key that arrive within 3 time units from the first one.
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # use noir::operator::ValueState;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..10)));
+    /// let res = s
+    ///     .add_timestamps(|&n| n, |_, &ts| Some(ts))
+    ///     .group_by(|&n| n % 2)
+    ///     .process(
+    ///         ValueState::default(),
+    ///         |ctx, n| {
+    ///             if let Some(sum) = ctx.state().get_mut() {
+    ///                 *sum += n;
+    ///             } else {
+    ///                 ctx.state().set(n);
+    ///                 let ts = ctx.timestamp().unwrap();
+    ///                 ctx.register_event_time_timer(ts + 3);
+    ///             }
+    ///         },
+    ///         |ctx, _timer| {
+    ///             let sum = ctx.state().take().unwrap();
+    ///             ctx.emit(sum);
+    ///             ctx.clear_state();
+    ///         },
+    ///     )
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 2), (0, 8), (0, 10), (1, 4), (1, 9), (1, 12)]);
+    /// ```
+    pub fn process<S, O, F, Ft>(
+        self,
+        init: S,
+        on_element: F,
+        on_timer: Ft,
+    ) -> KeyedStream<K, O, impl Operator<(K, O)>>
+    where
+        S: ExchangeData,
+        O: Data,
+        F: FnMut(&mut ProcessContext<K, S, O>, I) + Clone + Send + 'static,
+        Ft: FnMut(&mut ProcessContext<K, S, O>, Timer) + Clone + Send + 'static,
+    {
+        self.add_operator(|prev| Process::new(prev, init, on_element, on_timer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::operator::process::{Process, Timer};
+    use crate::operator::{Operator, StreamElement};
+    use crate::test::FakeOperator;
+
+    #[test]
+    fn process_state() {
+        let fake_operator = FakeOperator::new(vec![(0, 1), (1, 2), (0, 3)].into_iter());
+        let mut process = Process::new(
+            fake_operator,
+            Vec::new(),
+            |ctx, n: i32| {
+                ctx.state().push(n);
+                let sum = ctx.state().iter().sum::<i32>();
+                ctx.emit(sum);
+            },
+            |_, _| {},
+        );
+
+        assert_eq!(process.next(), StreamElement::Item((0, 1)));
+        assert_eq!(process.next(), StreamElement::Item((1, 2)));
+        assert_eq!(process.next(), StreamElement::Item((0, 4)));
+        assert_eq!(process.next(), StreamElement::Terminate);
+    }
+
+    #[test]
+    fn process_clear_state() {
+        let fake_operator = FakeOperator::new(vec![(0, 1), (0, 2), (0, 3)].into_iter());
+        let mut process = Process::new(
+            fake_operator,
+            0,
+            |ctx, n: i32| {
+                *ctx.state() += n;
+                let sum = *ctx.state();
+                if n == 2 {
+                    ctx.clear_state();
+                }
+                ctx.emit(sum);
+            },
+            |_, _| {},
+        );
+
+        assert_eq!(process.next(), StreamElement::Item((0, 1)));
+        assert_eq!(process.next(), StreamElement::Item((0, 3)));
+        assert!(process.keys.is_empty());
+        assert_eq!(process.next(), StreamElement::Item((0, 3)));
+        assert_eq!(process.next(), StreamElement::Terminate);
+    }
+
+    #[test]
+    #[cfg(feature = "timestamp")]
+    fn process_event_time_timers() {
+        let mut fake_operator = FakeOperator::empty();
+        fake_operator.push(StreamElement::Timestamped((0, 10), 1));
+        fake_operator.push(StreamElement::Timestamped((1, 20), 2));
+        fake_operator.push(StreamElement::Timestamped((0, 30), 3));
+        fake_operator.push(StreamElement::Watermark(5));
+        fake_operator.push(StreamElement::Watermark(13));
+
+        let mut process = Process::new(
+            fake_operator,
+            0,
+            |ctx, n: i32| {
+                *ctx.state() += n;
+                // fire 10 units after the first element of the key
+                let ts = ctx.timestamp().unwrap();
+                ctx.delete_event_time_timer(ts - 2 + 10);
+                ctx.register_event_time_timer(ts + 10);
+            },
+            |ctx, timer| {
+                assert_eq!(timer, Timer::EventTime(ctx.timestamp().unwrap()));
+                let sum = *ctx.state();
+                ctx.emit(sum);
+            },
+        );
+
+        assert_eq!(process.next(), StreamElement::Watermark(5));
+        assert_eq!(process.next(), StreamElement::Timestamped((1, 20), 12));
+        assert_eq!(process.next(), StreamElement::Timestamped((0, 40), 13));
+        assert_eq!(process.next(), StreamElement::Watermark(13));
+        assert_eq!(process.next(), StreamElement::Terminate);
+    }
+
+    #[test]
+    fn process_processing_time_timers() {
+        let mut fake_operator = FakeOperator::new(vec![(0, 1), (1, 2)].into_iter());
+        fake_operator.push(StreamElement::FlushBatch);
+        let mut process = Process::new(
+            fake_operator,
+            (),
+            |ctx, n: i32| {
+                ctx.register_processing_time_timer(Duration::from_millis(10 * n as u64));
+            },
+            |ctx, timer| {
+                assert!(matches!(timer, Timer::ProcessingTime(_)));
+                ctx.emit(());
+            },
+        );
+
+        assert_eq!(process.next(), StreamElement::FlushBatch);
+        std::thread::sleep(Duration::from_millis(15));
+        assert_eq!(process.next(), StreamElement::Item((0, ())));
+        // the timer of the second key fires early at the end of the stream
+        assert_eq!(process.next(), StreamElement::Item((1, ())));
+        assert_eq!(process.next(), StreamElement::Terminate);
+    }
+}
@@ -0,0 +1,432 @@
+use nanorand::{tls_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::operator::{ExchangeData, Operator};
+use crate::Stream;
+
+/// The number of values sampled by each replica in every bucket of a round.
+const SAMPLE_SIZE: usize = 64;
+/// The maximum number of pivots used to split the candidate range of a quantile.
+const MAX_PIVOTS: usize = 64;
+/// When the candidate range of a quantile contains at most this many values, they are all sent to
+/// the leader of the iteration, which picks the exact one.
+const COLLECT_THRESHOLD: u64 = 1 << 16;
+
+/// The bucket of the candidate range of a quantile a value falls in.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct Location {
+    quantile: u32,
+    bucket: u32,
+    /// Whether all the values of the bucket should be kept instead of sampled.
+    keep_all: bool,
+}
+
+/// The values of a bucket seen during a round.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Bucket<V> {
+    count: u64,
+    min: Option<V>,
+    max: Option<V>,
+    /// A uniform sample of the values of the bucket, or all of them if the quantile is collecting.
+    sample: Vec<V>,
+}
+
+impl<V> Default for Bucket<V> {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            min: None,
+            max: None,
+            sample: Default::default(),
+        }
+    }
+}
+
+impl<V: Ord + Clone> Bucket<V> {
+    fn push(&mut self, value: V, keep_all: bool) {
+        self.count += 1;
+        if !matches!(&self.min, Some(min) if *min <= value) {
+            self.min = Some(value.clone());
+        }
+        if !matches!(&self.max, Some(max) if *max >= value) {
+            self.max = Some(value.clone());
+        }
+        if keep_all || self.sample.len() < SAMPLE_SIZE {
+            self.sample.push(value);
+        } else {
+            let index = tls_rng().generate_range(0..self.count) as usize;
+            if index < SAMPLE_SIZE {
+                self.sample[index] = value;
+            }
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.count += other.count;
+        if let Some(min) = other.min {
+            if !matches!(&self.min, Some(current) if *current <= min) {
+                self.min = Some(min);
+            }
+        }
+        if let Some(max) = other.max {
+            if !matches!(&self.max, Some(current) if *current >= max) {
+                self.max = Some(max);
+            }
+        }
+        self.sample.extend(other.sample);
+    }
+}
+
+/// The statistics of a round of the selection, for each quantile and each of its buckets.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RoundStats<V> {
+    buckets: Vec<Vec<Bucket<V>>>,
+}
+
+impl<V> Default for RoundStats<V> {
+    fn default() -> Self {
+        Self {
+            buckets: Default::default(),
+        }
+    }
+}
+
+impl<V: Ord + Clone> RoundStats<V> {
+    fn push(&mut self, value: V, locations: Vec<Location>) {
+        for location in locations {
+            let quantile = location.quantile as usize;
+            let bucket = location.bucket as usize;
+            if self.buckets.len() <= quantile {
+                self.buckets.resize_with(quantile + 1, Default::default);
+            }
+            let buckets = &mut self.buckets[quantile];
+            if buckets.len() <= bucket {
+                buckets.resize_with(bucket + 1, Default::default);
+            }
+            buckets[bucket].push(value.clone(), location.keep_all);
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        if self.buckets.len() < other.buckets.len() {
+            self.buckets
+                .resize_with(other.buckets.len(), Default::default);
+        }
+        for (buckets, other) in self.buckets.iter_mut().zip(other.buckets) {
+            if buckets.len() < other.len() {
+                buckets.resize_with(other.len(), Default::default);
+            }
+            for (bucket, other) in buckets.iter_mut().zip(other) {
+                bucket.merge(other);
+            }
+        }
+    }
+}
+
+/// The search of a single quantile.
+///
+/// The rank of the quantile is known to be inside the candidate range `(lower, upper]`, that is
+/// split in buckets by the pivots.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct QuantileTarget<V> {
+    probability: f64,
+    /// The rank of the quantile in the whole stream, known after the first round.
+    rank: u64,
+    lower: Option<V>,
+    upper: Option<V>,
+    /// The number of values not greater than `lower`.
+    below: u64,
+    pivots: Vec<V>,
+    keep_all: bool,
+    result: Option<V>,
+}
+
+impl<V: Ord + Clone> QuantileTarget<V> {
+    fn locate(&self, value: &V) -> Option<(u32, bool)> {
+        if self.result.is_some()
+            || matches!(&self.lower, Some(lower) if value <= lower)
+            || matches!(&self.upper, Some(upper) if value > upper)
+        {
+            return None;
+        }
+        let bucket = self.pivots.partition_point(|pivot| pivot < value);
+        Some((bucket as u32, self.keep_all))
+    }
+
+    fn refine(&mut self, buckets: Vec<Bucket<V>>) {
+        // the rank of the quantile inside the candidate range
+        let mut offset = self.rank - self.below;
+        if self.keep_all {
+            let mut values = buckets.into_iter().next().unwrap_or_default().sample;
+            glidesort::sort(&mut values);
+            self.result = Some(values.swap_remove(offset as usize));
+            return;
+        }
+
+        let mut buckets = buckets.into_iter();
+        for index in 0..=self.pivots.len() {
+            let bucket = buckets.next().unwrap_or_default();
+            if offset >= bucket.count {
+                offset -= bucket.count;
+                continue;
+            }
+
+            if index > 0 {
+                self.lower = Some(self.pivots[index - 1].clone());
+            }
+            if index < self.pivots.len() {
+                self.upper = Some(self.pivots[index].clone());
+            }
+            self.below = self.rank - offset;
+
+            let (min, max) = (bucket.min.unwrap(), bucket.max.unwrap());
+            if min == max {
+                self.result = Some(min);
+            } else if bucket.count <= COLLECT_THRESHOLD {
+                self.keep_all = true;
+                self.pivots.clear();
+            } else {
+                self.pivots = choose_pivots(bucket.sample, min);
+            }
+            return;
+        }
+        unreachable!("The rank of the quantile is outside of its candidate range");
+    }
+}
+
+/// Pick at most [`MAX_PIVOTS`] pivots evenly spaced in the sample.
+///
+/// The minimum of the range is always a pivot: this guarantees that every round makes progress,
+/// since its bucket contains only copies of the same value.
+fn choose_pivots<V: Ord + Clone>(mut sample: Vec<V>, min: V) -> Vec<V> {
+    sample.push(min);
+    glidesort::sort(&mut sample);
+    sample.dedup();
+    if sample.len() <= MAX_PIVOTS {
+        return sample;
+    }
+    (0..MAX_PIVOTS)
+        .map(|i| sample[i * sample.len() / MAX_PIVOTS].clone())
+        .collect()
+}
+
+/// The 0-based rank of the `probability`-quantile of `total` values, using the nearest-rank
+/// definition.
+fn nearest_rank(probability: f64, total: u64) -> u64 {
+    ((probability * total as f64).ceil() as u64).clamp(1, total) - 1
+}
+
+/// The state of the distributed selection of many quantiles.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Selection<V> {
+    targets: Vec<QuantileTarget<V>>,
+    /// The number of values in the stream, known after the first round.
+    total: Option<u64>,
+    /// The statistics of the current round, merged from all the replicas.
+    round: RoundStats<V>,
+}
+
+impl<V: Ord + Clone> Selection<V> {
+    fn new(probabilities: Vec<f64>) -> Self {
+        let targets = probabilities
+            .into_iter()
+            .map(|probability| {
+                assert!(
+                    (0.0..=1.0).contains(&probability),
+                    "The probability of a quantile must be in [0, 1], got {probability}"
+                );
+                QuantileTarget {
+                    probability,
+                    rank: 0,
+                    lower: None,
+                    upper: None,
+                    below: 0,
+                    pivots: Vec::new(),
+                    keep_all: false,
+                    result: None,
+                }
+            })
+            .collect();
+        Self {
+            targets,
+            total: None,
+            round: Default::default(),
+        }
+    }
+
+    fn locate(&self, value: &V) -> Vec<Location> {
+        self.targets
+            .iter()
+            .enumerate()
+            .filter_map(|(quantile, target)| {
+                target.locate(value).map(|(bucket, keep_all)| Location {
+                    quantile: quantile as u32,
+                    bucket,
+                    keep_all,
+                })
+            })
+            .collect()
+    }
+
+    /// Use the statistics of the last round to narrow the candidate range of the quantiles.
+    ///
+    /// Returns whether another round is needed.
+    fn refine(&mut self) -> bool {
+        let mut round = std::mem::take(&mut self.round).buckets.into_iter();
+        if self.total.is_none() {
+            // in the first round every quantile has a single bucket with all the values
+            let total = round
+                .as_slice()
+                .first()
+                .and_then(|buckets| buckets.first())
+                .map(|bucket| bucket.count)
+                .unwrap_or(0);
+            self.total = Some(total);
+            if total == 0 {
+                return false;
+            }
+            for target in &mut self.targets {
+                target.rank = nearest_rank(target.probability, total);
+            }
+        }
+
+        for target in &mut self.targets {
+            let buckets = round.next().unwrap_or_default();
+            if target.result.is_none() {
+                target.refine(buckets);
+            }
+        }
+        self.targets.iter().any(|target| target.result.is_none())
+    }
+
+    fn into_results(self) -> Vec<V> {
+        self.targets
+            .into_iter()
+            .filter_map(|target| target.result)
+            .collect()
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeData,
+    Op: Operator<I> + 'static,
+{
+    /// Compute the exact quantiles of the values of the stream, for many probabilities at once.
+    ///
+    /// The quantiles use the nearest-rank definition: the `p`-quantile of `n` values is the
+    /// smallest value with at least `ceil(p * n)` values not greater than it.
+    ///
+    /// The values are never gathered on a single replica: each replica keeps its own values and
+    /// the quantiles are found with some rounds of distributed selection. At every round the
+    /// replicas build a histogram of the values in the candidate range of each quantile, split by
+    /// some pivots sampled in the previous round, and the range shrinks to the bucket containing
+    /// the quantile. When the range is small enough its values are sent to a single replica that
+    /// picks the exact quantile.
+    ///
+    /// The output is a single vector with a quantile for each probability, in the same order. If
+    /// the stream is empty the vector is empty.
+    ///
+    /// **Note**: this operator is built on top of [`Stream::replay`], hence it retains all the
+    /// values of the stream and emits the result only when the stream ends, and it cannot be used
+    /// when the stream has limited parallelism.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    /// let s = env.stream(IteratorSource::new(1..=1000)).shuffle();
+    /// let res = s.quantiles_exact(vec![0.5, 0.9, 0.99], |n| n).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap(), vec![vec![500, 900, 990]]);
+    /// ```
+    pub fn quantiles_exact<V, F>(
+        self,
+        probabilities: Vec<f64>,
+        get_value: F,
+    ) -> Stream<Vec<V>, impl Operator<Vec<V>>>
+    where
+        V: ExchangeData + Ord + Sync,
+        F: Fn(I) -> V + Send + Clone + 'static,
+    {
+        self.map(move |item| (get_value(item), Vec::<Location>::new()))
+            .replay(
+                usize::MAX,
+                Selection::new(probabilities),
+                |s, state| {
+                    s.map(move |(value, _)| {
+                        let locations = state.get().locate(&value);
+                        (value, locations)
+                    })
+                    .filter(|(_, locations)| !locations.is_empty())
+                },
+                |round: &mut RoundStats<V>, (value, locations)| round.push(value, locations),
+                |selection, round| selection.round.merge(round),
+                |selection| selection.refine(),
+            )
+            .map(Selection::into_results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn select(values: &[u32], probabilities: Vec<f64>) -> (Vec<u32>, usize) {
+        let mut selection = Selection::new(probabilities);
+        let mut rounds = 0;
+        loop {
+            rounds += 1;
+            let mut round = RoundStats::default();
+            for &value in values {
+                round.push(value, selection.locate(&value));
+            }
+            selection.round.merge(round);
+            if !selection.refine() {
+                return (selection.into_results(), rounds);
+            }
+        }
+    }
+
+    #[test]
+    fn nearest_rank_quantiles() {
+        let values = (0..1_000u32).rev().collect::<Vec<_>>();
+        let (res, _) = select(&values, vec![0.0, 0.25, 0.5, 0.999, 1.0]);
+        assert_eq!(res, vec![0, 249, 499, 998, 999]);
+    }
+
+    #[test]
+    fn many_rounds() {
+        let values = (0..1_000_000u32)
+            .map(|n| n.wrapping_mul(2_654_435_761) % 300_000)
+            .collect::<Vec<_>>();
+        let mut sorted = values.clone();
+        sorted.sort();
+        let probabilities = vec![0.1, 0.5, 0.9, 0.99];
+        let expected = probabilities
+            .iter()
+            .map(|&p| sorted[nearest_rank(p, sorted.len() as u64) as usize])
+            .collect::<Vec<_>>();
+        let (res, rounds) = select(&values, probabilities);
+        assert_eq!(res, expected);
+        assert!(rounds > 2);
+    }
+
+    #[test]
+    fn repeated_values() {
+        let values = (0..200_000u32).map(|n| n % 3).collect::<Vec<_>>();
+        let (res, _) = select(&values, vec![0.2, 0.5, 0.9]);
+        assert_eq!(res, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn empty() {
+        let (res, rounds) = select(&[], vec![0.5]);
+        assert!(res.is_empty());
+        assert_eq!(rounds, 1);
+    }
+}
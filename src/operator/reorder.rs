@@ -80,6 +80,10 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the elements waiting for their watermark are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("reorder is not supported");
+        }
     }
 
     #[inline]
@@ -95,6 +99,7 @@ where
                     glidesort::sort_with_vec(self.buffer.make_contiguous(), &mut self.scratch);
                 }
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
                 StreamElement::FlushAndRestart => {
                     self.received_end = true;
                     glidesort::sort_with_vec(self.buffer.make_contiguous(), &mut self.scratch);
@@ -19,4 +19,22 @@ mod tests {
         );
         assert_ne!(old_block_id, new_block_id);
     }
+
+    #[test]
+    fn test_per_core_replication() {
+        let double = Replication::new_per_core(2, 1);
+        assert_eq!(double.clamp(4), 8);
+        let quarter = Replication::new_per_core(1, 4);
+        assert_eq!(quarter.clamp(8), 2);
+        assert_eq!(quarter.clamp(6), 2);
+        assert_eq!(quarter.clamp(1), 1);
+
+        assert_eq!(double.intersect(quarter), quarter);
+        assert_eq!(quarter.intersect(Replication::Unlimited), quarter);
+        assert_eq!(
+            double.intersect(Replication::new_limited(3)),
+            Replication::new_limited(3)
+        );
+        assert_eq!(double.intersect(Replication::One), Replication::One);
+    }
 }
@@ -0,0 +1,286 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use crate::block::GroupHasherBuilder;
+use crate::operator::{DataKey, StreamElement, Timestamp};
+
+/// How long the keyed operators keep the state of a key, to bound the memory used by unbounded
+/// streams with many keys.
+///
+/// By default the state of every key is kept until the end of the stream. A key can be forgotten
+/// after a time-to-live since its last element, by processing time or by event time, and the
+/// number of keys can be bounded, forgetting the least recently updated ones first. The keys are
+/// tracked separately by each replica of the operator.
+///
+/// The keyed operators that support it are [`KeyedStream::fold_with_retention`],
+/// [`KeyedStream::rich_map_with_retention`] and [`WindowedStream::with_retention`].
+///
+/// [`KeyedStream::fold_with_retention`]: crate::KeyedStream::fold_with_retention
+/// [`KeyedStream::rich_map_with_retention`]: crate::KeyedStream::rich_map_with_retention
+/// [`WindowedStream::with_retention`]: crate::WindowedStream::with_retention
+///
+/// ## Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use noir::operator::StateRetention;
+/// // forget the keys without elements for a minute, keeping at most 10000 of them
+/// let retention = StateRetention::new()
+///     .processing_time_ttl(Duration::from_secs(60))
+///     .max_keys(10_000)
+///     .emit_evicted();
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StateRetention {
+    ttl: Option<Ttl>,
+    max_keys: Option<usize>,
+    emit_evicted: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Ttl {
+    ProcessingTime(Duration),
+    #[cfg(feature = "timestamp")]
+    EventTime(Timestamp),
+}
+
+impl StateRetention {
+    /// Keep the state of every key until the end of the stream.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Forget a key when it has not received any element for `ttl`.
+    ///
+    /// The keys are checked when a message reaches the operator, and periodically if no message
+    /// arrives.
+    pub fn processing_time_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(Ttl::ProcessingTime(ttl));
+        self
+    }
+
+    /// Forget a key when the watermark passes the largest timestamp of its elements by more than
+    /// `ttl`.
+    ///
+    /// The keys whose elements have no timestamp are never forgotten because of the time-to-live.
+    #[cfg(feature = "timestamp")]
+    pub fn event_time_ttl(mut self, ttl: Timestamp) -> Self {
+        self.ttl = Some(Ttl::EventTime(ttl));
+        self
+    }
+
+    /// Keep at most `max_keys` keys, forgetting first the least recently updated ones.
+    ///
+    /// With an event-time time-to-live, the keys with the oldest elements are forgotten first, and
+    /// the keys whose elements have no timestamp last.
+    pub fn max_keys(mut self, max_keys: usize) -> Self {
+        assert!(max_keys > 0, "At least one key has to be kept");
+        self.max_keys = Some(max_keys);
+        self
+    }
+
+    /// Emit the final value of a key when it is forgotten, like at the end of the stream.
+    ///
+    /// This is supported by the operators that emit a value at the end of the stream, and not by
+    /// [`KeyedStream::rich_map_with_retention`](crate::KeyedStream::rich_map_with_retention).
+    pub fn emit_evicted(mut self) -> Self {
+        self.emit_evicted = true;
+        self
+    }
+}
+
+/// The keys of an operator ordered by the time of their last update, to find the ones to forget
+/// according to a [`StateRetention`].
+#[derive(Clone, Debug)]
+pub(crate) struct KeyRetention<K> {
+    retention: StateRetention,
+    /// The position of each key in `order`, with the instant of its last update.
+    keys: HashMap<K, (Rank, Instant), GroupHasherBuilder>,
+    /// The keys, the least recently updated first.
+    order: BTreeMap<Rank, K>,
+    /// The number of updates so far, to order the keys.
+    updates: u64,
+    watermark: Option<Timestamp>,
+}
+
+/// The position of a key: the largest timestamp of its elements, if it's used for the
+/// time-to-live, and the number of the last update.
+///
+/// The keys without a timestamp have [`NO_TIMESTAMP`], so that with an event-time time-to-live
+/// they come after the keys that can expire.
+type Rank = (Timestamp, u64);
+
+/// The timestamp in the [`Rank`] of the keys whose elements have no timestamp.
+const NO_TIMESTAMP: Timestamp = Timestamp::MAX;
+
+impl<K: DataKey> KeyRetention<K> {
+    pub(crate) fn new(retention: StateRetention) -> Self {
+        Self {
+            retention,
+            keys: Default::default(),
+            order: Default::default(),
+            updates: 0,
+            watermark: None,
+        }
+    }
+
+    /// Whether the operator should emit the final value of the keys it forgets.
+    pub(crate) fn emit_evicted(&self) -> bool {
+        self.retention.emit_evicted
+    }
+
+    /// How often the operator has to be woken up to forget the keys in time, if no message
+    /// arrives.
+    pub(crate) fn wake_up_interval(&self) -> Option<Duration> {
+        match self.retention.ttl {
+            Some(Ttl::ProcessingTime(ttl)) => Some((ttl / 4).max(Duration::from_millis(1))),
+            _ => None,
+        }
+    }
+
+    /// Update the key of an element, or the watermark, returning the keys to forget.
+    ///
+    /// If the key of the element has expired, it is forgotten before being updated: the element
+    /// has to be processed starting from an empty state.
+    pub(crate) fn update<T>(&mut self, element: &StreamElement<(K, T)>) -> Vec<K> {
+        let mut evicted = self.evict();
+        match element {
+            StreamElement::Item((key, _)) => self.touch(key, None),
+            StreamElement::Timestamped((key, _), ts) => self.touch(key, Some(*ts)),
+            StreamElement::Watermark(ts) => self.watermark = Some(*ts),
+            _ => {}
+        }
+        evicted.extend(self.evict());
+        evicted
+    }
+
+    /// Update a key, with the timestamp of its element if any.
+    pub(crate) fn touch(&mut self, key: &K, ts: Option<Timestamp>) {
+        let previous = self.keys.get(key).map(|(rank, _)| *rank);
+        let ts = match (self.retention.ttl, ts) {
+            #[cfg(feature = "timestamp")]
+            (Some(Ttl::EventTime(_)), ts) => previous
+                .map(|(prev, _)| prev)
+                .filter(|&prev| prev != NO_TIMESTAMP)
+                .max(ts),
+            _ => None,
+        };
+        if let Some(previous) = previous {
+            self.order.remove(&previous);
+        }
+        let rank = (ts.unwrap_or(NO_TIMESTAMP), self.updates);
+        self.updates += 1;
+        self.order.insert(rank, key.clone());
+        self.keys.insert(key.clone(), (rank, Instant::now()));
+    }
+
+    /// Stop tracking a key whose state has been dropped by the operator.
+    pub(crate) fn remove(&mut self, key: &K) {
+        if let Some((rank, _)) = self.keys.remove(key) {
+            self.order.remove(&rank);
+        }
+    }
+
+    /// Stop tracking all the keys, at the end of the stream.
+    pub(crate) fn clear(&mut self) {
+        self.keys.clear();
+        self.order.clear();
+    }
+
+    /// Remove and return the keys to forget.
+    fn evict(&mut self) -> Vec<K> {
+        let now = Instant::now();
+        let mut evicted = Vec::new();
+        while let Some((rank, key)) = self.order.first_key_value() {
+            let expired = match (self.retention.ttl, rank.0, self.watermark) {
+                (Some(Ttl::ProcessingTime(ttl)), _, _) => {
+                    now.duration_since(self.keys[key].1) > ttl
+                }
+                #[cfg(feature = "timestamp")]
+                (Some(Ttl::EventTime(ttl)), ts, Some(watermark)) if ts != NO_TIMESTAMP => {
+                    ts.saturating_add(ttl) < watermark
+                }
+                _ => false,
+            };
+            let exceeding = self
+                .retention
+                .max_keys
+                .is_some_and(|max_keys| self.order.len() > max_keys);
+            if !expired && !exceeding {
+                break;
+            }
+            let (_, key) = self.order.pop_first().unwrap();
+            self.keys.remove(&key);
+            evicted.push(key);
+        }
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::operator::retention::{KeyRetention, StateRetention};
+    use crate::operator::StreamElement;
+
+    #[test]
+    fn max_keys() {
+        let mut retention = KeyRetention::new(StateRetention::new().max_keys(2));
+        for key in [1, 2, 1] {
+            assert!(retention.update(&StreamElement::Item((key, ()))).is_empty());
+        }
+        assert_eq!(retention.update(&StreamElement::Item((3, ()))), vec![2]);
+        assert_eq!(retention.update(&StreamElement::Item((4, ()))), vec![1]);
+    }
+
+    #[test]
+    fn processing_time_ttl() {
+        let retention = StateRetention::new().processing_time_ttl(Duration::from_millis(20));
+        let mut retention = KeyRetention::new(retention);
+        assert!(retention.update(&StreamElement::Item((1, ()))).is_empty());
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(retention.update(&StreamElement::Item((2, ()))), vec![1]);
+        std::thread::sleep(Duration::from_millis(30));
+        // the key is forgotten before being updated again
+        assert_eq!(retention.update(&StreamElement::Item((2, ()))), vec![2]);
+        assert!(retention.update(&StreamElement::Item((2, ()))).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "timestamp")]
+    fn event_time_ttl() {
+        let mut retention = KeyRetention::new(StateRetention::new().event_time_ttl(10));
+        assert!(retention
+            .update(&StreamElement::Timestamped((1, ()), 5))
+            .is_empty());
+        assert!(retention
+            .update(&StreamElement::Timestamped((2, ()), 3))
+            .is_empty());
+        assert!(retention
+            .update(&StreamElement::Timestamped((2, ()), 12))
+            .is_empty());
+        let watermark = |ts| StreamElement::<(i32, ())>::Watermark(ts);
+        assert!(retention.update(&watermark(15)).is_empty());
+        assert_eq!(retention.update(&watermark(16)), vec![1]);
+        assert_eq!(retention.update(&watermark(30)), vec![2]);
+    }
+
+    #[test]
+    #[cfg(feature = "timestamp")]
+    fn event_time_ttl_without_timestamp() {
+        let mut retention = KeyRetention::new(StateRetention::new().event_time_ttl(10));
+        // the key without timestamp does not prevent the others from expiring
+        assert!(retention.update(&StreamElement::Item((1, ()))).is_empty());
+        assert!(retention
+            .update(&StreamElement::Timestamped((2, ()), 5))
+            .is_empty());
+        assert_eq!(
+            retention.update(&StreamElement::<(i32, ())>::Watermark(16)),
+            vec![2]
+        );
+        assert!(retention
+            .update(&StreamElement::<(i32, ())>::Watermark(1000))
+            .is_empty());
+    }
+}
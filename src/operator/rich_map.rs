@@ -3,8 +3,10 @@ use std::fmt::Display;
 use std::marker::PhantomData;
 
 use crate::block::{BlockStructure, OperatorStructure};
+use crate::operator::retention::{KeyRetention, StateRetention};
 use crate::operator::{Data, DataKey, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 
 #[derive(Debug)]
 pub struct RichMap<Key: DataKey, Out: Data, NewOut: Data, F, OperatorChain>
@@ -13,8 +15,11 @@ where
     OperatorChain: Operator<(Key, Out)>,
 {
     prev: OperatorChain,
+    operator: OperatorIndex,
     maps_fn: HashMap<Key, F, crate::block::GroupHasherBuilder>,
     init_map: F,
+    /// The keys whose function has to be dropped, if any.
+    retention: Option<KeyRetention<Key>>,
     _out: PhantomData<Out>,
     _new_out: PhantomData<NewOut>,
 }
@@ -28,8 +33,10 @@ where
     fn clone(&self) -> Self {
         Self {
             prev: self.prev.clone(),
+            operator: self.operator,
             maps_fn: self.maps_fn.clone(),
             init_map: self.init_map.clone(),
+            retention: self.retention.clone(),
             _out: self._out,
             _new_out: self._new_out,
         }
@@ -62,12 +69,27 @@ where
     pub(super) fn new(prev: OperatorChain, f: F) -> Self {
         Self {
             prev,
+            operator: OperatorIndex::default(),
             maps_fn: Default::default(),
             init_map: f,
+            retention: None,
             _out: Default::default(),
             _new_out: Default::default(),
         }
     }
+
+    /// Drop the functions of the keys according to `retention`, instead of keeping them until
+    /// the end of the stream.
+    pub(super) fn with_retention(mut self, retention: StateRetention) -> Self {
+        let retention = KeyRetention::new(retention);
+        // a mapping function has no final value to emit when its key is forgotten
+        assert!(
+            !retention.emit_evicted(),
+            "rich_map_with_retention cannot emit the evicted keys"
+        );
+        self.retention = Some(retention);
+        self
+    }
 }
 
 impl<Key: DataKey, Out: Data, NewOut: Data, F, OperatorChain> Operator<(Key, NewOut)>
@@ -77,15 +99,29 @@ where
     OperatorChain: Operator<(Key, Out)>,
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        if let Some(interval) = self.retention.as_ref().and_then(|r| r.wake_up_interval()) {
+            metadata.wake_up_every(interval);
+        }
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
+        // the state of the closures is not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("rich_map is not supported");
+        }
     }
 
     #[inline]
     fn next(&mut self) -> StreamElement<(Key, NewOut)> {
         let element = self.prev.next();
+        let _operator = self.operator.enter();
         if matches!(element, StreamElement::FlushAndRestart) {
             // self.maps_fn.clear();
         }
+        if let Some(retention) = &mut self.retention {
+            for key in retention.update(&element) {
+                self.maps_fn.remove(&key);
+            }
+        }
         element.map(|(key, value)| {
             let map_fn = if let Some(map_fn) = self.maps_fn.get_mut(&key) {
                 map_fn
@@ -76,6 +76,10 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the state of the generator is not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("rich_map_custom is not supported");
+        }
     }
 
     fn next(&mut self) -> StreamElement<NewOut> {
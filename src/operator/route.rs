@@ -6,6 +6,8 @@ use std::fmt::{Debug, Display};
 
 use crate::block::{BatchMode, Batcher, BlockStructure, Connection, OperatorStructure};
 use crate::network::{Coord, ReceiverEndpoint};
+#[cfg(feature = "timestamp")]
+use crate::operator::watermark::{IdleSignal, IDLE_WATERMARK};
 use crate::operator::{KeyerFn, StreamElement};
 use crate::scheduler::{BlockId, ExecutionMetadata};
 
@@ -110,6 +112,8 @@ where
 
     endpoints: Vec<Endpoint<Out>>,
     routes: Vec<(BlockId, FilterFn<Out>)>,
+    #[cfg(feature = "timestamp")]
+    idle: Option<IdleSignal>,
 }
 
 impl<Out: ExchangeData, OperatorChain, IndexFn> Display for RoutingEnd<Out, OperatorChain, IndexFn>
@@ -145,6 +149,8 @@ where
             endpoints: Default::default(),
             routes,
             senders: Default::default(),
+            #[cfg(feature = "timestamp")]
+            idle: None,
         }
     }
 
@@ -211,6 +217,10 @@ where
 
         self.setup_endpoints();
 
+        #[cfg(feature = "timestamp")]
+        {
+            self.idle = metadata.idle_signal.clone();
+        }
         self.coord = Some(metadata.coord);
     }
 
@@ -225,7 +235,8 @@ where
             // Broadcast messages
             StreamElement::Watermark(_)
             | StreamElement::Terminate
-            | StreamElement::FlushAndRestart => {
+            | StreamElement::FlushAndRestart
+            | StreamElement::Checkpoint(_) => {
                 for e in self.endpoints.iter() {
                     for &sender_idx in e.block_senders.indexes.iter() {
                         let sender = &mut self.senders[sender_idx];
@@ -251,7 +262,15 @@ where
                     log::trace!("router ignoring message");
                 }
             }
-            StreamElement::FlushBatch => {}
+            StreamElement::FlushBatch => {
+                // the replica became idle: only the next blocks receive the marker
+                #[cfg(feature = "timestamp")]
+                if self.idle.as_mut().is_some_and(|idle| idle.poll()) {
+                    for (_, batcher) in self.senders.iter_mut() {
+                        batcher.enqueue(StreamElement::Watermark(IDLE_WATERMARK));
+                    }
+                }
+            }
         };
 
         // Flushing messages
@@ -328,6 +347,6 @@ mod tests {
             .for_each(|i| eprintln!("route2: {i}"));
         // 5 7 9 ignored
 
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
     }
 }
@@ -0,0 +1,527 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use nanorand::{Rng, WyRand};
+use serde::{Deserialize, Serialize};
+
+use crate::block::{group_by_hash, BlockStructure, GroupHasherBuilder, OperatorStructure};
+use crate::operator::{
+    Data, DataKey, ExchangeData, ExchangeDataKey, KeyerFn, Operator, StreamElement,
+};
+use crate::scheduler::ExecutionMetadata;
+use crate::{KeyedStream, Stream};
+
+/// Derive the seed of a random generator from the seed given by the user.
+///
+/// Every replica (or key) gets its own independent generator, that is the same in every run.
+fn derive_seed<T: Hash>(seed: u64, stream: T) -> u64 {
+    let mut hasher = wyhash::WyHash::with_seed(seed);
+    stream.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Keep each element with probability `fraction`.
+#[derive(Derivative)]
+#[derivative(Debug, Clone)]
+pub struct SampleFraction<I: Data, PreviousOperators>
+where
+    PreviousOperators: Operator<I>,
+{
+    prev: PreviousOperators,
+    fraction: f64,
+    seed: u64,
+    rng: WyRand,
+    _in: PhantomData<I>,
+}
+
+impl<I: Data, PreviousOperators: Operator<I>> Display for SampleFraction<I, PreviousOperators> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> SampleFraction<{}>",
+            self.prev,
+            std::any::type_name::<I>()
+        )
+    }
+}
+
+impl<I: Data, PreviousOperators: Operator<I>> SampleFraction<I, PreviousOperators> {
+    fn new(prev: PreviousOperators, fraction: f64, seed: u64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&fraction),
+            "The fraction to sample must be in [0, 1], got {fraction}"
+        );
+        Self {
+            prev,
+            fraction,
+            seed,
+            rng: WyRand::new_seed(seed),
+            _in: Default::default(),
+        }
+    }
+
+    fn keep(&mut self) -> bool {
+        // 53 random bits give a uniform f64 in [0, 1)
+        let value = (self.rng.generate::<u64>() >> 11) as f64 / (1u64 << 53) as f64;
+        value < self.fraction
+    }
+}
+
+impl<I: Data, PreviousOperators: Operator<I>> Operator<I> for SampleFraction<I, PreviousOperators> {
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+        self.rng = WyRand::new_seed(derive_seed(self.seed, metadata.global_id));
+    }
+
+    fn next(&mut self) -> StreamElement<I> {
+        loop {
+            match self.prev.next() {
+                StreamElement::Item(_) | StreamElement::Timestamped(_, _) if !self.keep() => {}
+                element => return element,
+            }
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<I, _>("SampleFraction"))
+    }
+}
+
+/// A uniform sample of at most `k` elements seen by a replica.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct Reservoir<I> {
+    /// The replica that sampled the elements.
+    replica: u64,
+    /// The number of elements the sample was drawn from.
+    seen: u64,
+    items: Vec<I>,
+}
+
+impl<I> Reservoir<I> {
+    fn new(replica: u64) -> Self {
+        Self {
+            replica,
+            seen: 0,
+            items: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, item: I, k: usize, rng: &mut WyRand) {
+        self.seen += 1;
+        if self.items.len() < k {
+            self.items.push(item);
+        } else {
+            let index = rng.generate_range(0..self.seen) as usize;
+            if index < k {
+                self.items[index] = item;
+            }
+        }
+    }
+
+    /// Merge the samples of many replicas into a uniform sample of at most `k` elements of the
+    /// union of their inputs.
+    ///
+    /// Each element is drawn from a replica with probability proportional to the number of
+    /// elements of that replica not drawn yet, which makes the result exactly uniform.
+    fn merge(mut parts: Vec<Self>, k: usize, rng: &mut WyRand) -> Vec<I> {
+        // the order of arrival of the parts is not deterministic
+        parts.sort_by_key(|part| part.replica);
+        let mut remaining = parts.iter().map(|part| part.seen).sum::<u64>();
+        let mut sample = Vec::with_capacity(k.min(remaining as usize));
+        while sample.len() < k && remaining > 0 {
+            let mut index = rng.generate_range(0..remaining);
+            let part = parts
+                .iter_mut()
+                .find(|part| {
+                    if index < part.seen {
+                        true
+                    } else {
+                        index -= part.seen;
+                        false
+                    }
+                })
+                .unwrap();
+            let position = rng.generate_range(0..part.items.len());
+            sample.push(part.items.swap_remove(position));
+            part.seen -= 1;
+            remaining -= 1;
+        }
+        sample
+    }
+}
+
+/// Sample at most `k` elements for each key, emitting the samples when the stream ends.
+#[derive(Derivative)]
+#[derivative(Debug, Clone)]
+pub struct ReservoirSample<I: Data, K: DataKey, Keyer, PreviousOperators>
+where
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<I>,
+{
+    prev: PreviousOperators,
+    #[derivative(Debug = "ignore")]
+    keyer: Keyer,
+    k: usize,
+    seed: u64,
+    rng: WyRand,
+    replica: u64,
+    reservoirs: HashMap<K, Reservoir<I>, GroupHasherBuilder>,
+    ready: Vec<(K, Reservoir<I>)>,
+    received_end: bool,
+    received_end_iter: bool,
+}
+
+impl<I: Data, K: DataKey, Keyer, PreviousOperators> Display
+    for ReservoirSample<I, K, Keyer, PreviousOperators>
+where
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<I>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> ReservoirSample<{}>",
+            self.prev,
+            std::any::type_name::<I>()
+        )
+    }
+}
+
+impl<I: Data, K: DataKey, Keyer, PreviousOperators> ReservoirSample<I, K, Keyer, PreviousOperators>
+where
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<I>,
+{
+    fn new(prev: PreviousOperators, keyer: Keyer, k: usize, seed: u64) -> Self {
+        Self {
+            prev,
+            keyer,
+            k,
+            seed,
+            rng: WyRand::new_seed(seed),
+            replica: 0,
+            reservoirs: Default::default(),
+            ready: Default::default(),
+            received_end: false,
+            received_end_iter: false,
+        }
+    }
+}
+
+impl<I: Data, K: DataKey, Keyer, PreviousOperators> Operator<(K, Reservoir<I>)>
+    for ReservoirSample<I, K, Keyer, PreviousOperators>
+where
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<I>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+        self.replica = metadata.global_id;
+        self.rng = WyRand::new_seed(derive_seed(self.seed, metadata.global_id));
+        // the reservoirs are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("reservoir sampling is not supported");
+        }
+    }
+
+    fn next(&mut self) -> StreamElement<(K, Reservoir<I>)> {
+        while !self.received_end {
+            match self.prev.next() {
+                StreamElement::Item(item) | StreamElement::Timestamped(item, _) => {
+                    let replica = self.replica;
+                    self.reservoirs
+                        .entry((self.keyer)(&item))
+                        .or_insert_with(|| Reservoir::new(replica))
+                        .push(item, self.k, &mut self.rng);
+                }
+                StreamElement::Terminate => self.received_end = true,
+                StreamElement::FlushAndRestart => {
+                    self.received_end = true;
+                    self.received_end_iter = true;
+                }
+                // the samples are emitted only at the end, so the watermarks are meaningless
+                StreamElement::Watermark(_) | StreamElement::FlushBatch => {}
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
+            }
+        }
+
+        if !self.reservoirs.is_empty() {
+            self.ready.extend(self.reservoirs.drain());
+        }
+        if let Some(item) = self.ready.pop() {
+            return StreamElement::Item(item);
+        }
+
+        // the end was not really the end... just the end of one iteration!
+        if self.received_end_iter {
+            self.received_end_iter = false;
+            self.received_end = false;
+            return StreamElement::FlushAndRestart;
+        }
+
+        StreamElement::Terminate
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<(K, Reservoir<I>), _>(
+                "ReservoirSample",
+            ))
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: Data,
+    Op: Operator<I> + 'static,
+{
+    /// Keep each element of the stream independently with probability `fraction`.
+    ///
+    /// Each replica draws from its own random generator, derived from `seed` and the index of the
+    /// replica: running the same job on the same input gives the same sample.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..1000)));
+    /// let res = s.sample_fraction(0.1, 42).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let res = res.get().unwrap();
+    /// assert!(res.len() > 50 && res.len() < 150);
+    /// ```
+    pub fn sample_fraction(self, fraction: f64, seed: u64) -> Stream<I, impl Operator<I>> {
+        self.add_operator(|prev| SampleFraction::new(prev, fraction, seed))
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeData,
+    Op: Operator<I> + 'static,
+{
+    /// Sample `k` elements of the stream uniformly at random, without replacement.
+    ///
+    /// Each replica keeps a reservoir of `k` elements, then the reservoirs are merged on a single
+    /// replica into an exact uniform sample of the whole stream. If the stream has less than `k`
+    /// elements all of them are emitted.
+    ///
+    /// The random generators are derived from `seed`: running the same job on the same input
+    /// gives the same sample.
+    ///
+    /// **Note**: this operator will retain `k` elements per replica and emit the sample only when
+    /// the stream ends. Therefore this is not properly _streaming_.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..1000)));
+    /// let res = s.sample_reservoir(10, 42).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap().len(), 10);
+    /// ```
+    pub fn sample_reservoir(self, k: usize, seed: u64) -> Stream<I, impl Operator<I>> {
+        self.add_operator(|prev| ReservoirSample::new(prev, |_: &I| (), k, seed))
+            .fold(Vec::new(), |parts, (_, part)| parts.push(part))
+            .flat_map(move |parts| {
+                let mut rng = WyRand::new_seed(seed);
+                Reservoir::merge(parts, k, &mut rng)
+            })
+    }
+
+    /// Sample `k` elements for each key uniformly at random, without replacement.
+    ///
+    /// The stream is partitioned using the `keyer` function. Each replica keeps a reservoir of
+    /// `k` elements for each key, then the reservoirs of the same key are merged into an exact
+    /// uniform sample of the elements with that key.
+    ///
+    /// The random generators are derived from `seed`: running the same job on the same input
+    /// gives the same sample.
+    ///
+    /// **Note**: this operator will retain `k` elements per key and emit the samples only when the
+    /// stream ends. Therefore this is not properly _streaming_.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..1000)));
+    /// let res = s.sample_stratified_by(|&n| n % 3, 2, 42).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let res = res.get().unwrap();
+    /// assert_eq!(res.len(), 3 * 2);
+    /// assert!(res.iter().all(|(k, n)| n % 3 == *k));
+    /// ```
+    pub fn sample_stratified_by<K, Fk>(
+        self,
+        keyer: Fk,
+        k: usize,
+        seed: u64,
+    ) -> KeyedStream<K, I, impl Operator<(K, I)>>
+    where
+        K: ExchangeDataKey,
+        Fk: KeyerFn<K, I>,
+    {
+        self.add_operator(|prev| ReservoirSample::new(prev, keyer, k, seed))
+            .group_by(|(key, _)| key.clone())
+            .fold(Vec::new(), |parts, (_, part)| parts.push(part))
+            .flat_map(move |(key, parts)| {
+                let mut rng = WyRand::new_seed(derive_seed(seed, group_by_hash(key)));
+                Reservoir::merge(parts, k, &mut rng)
+            })
+    }
+}
+
+impl<K, I, Op> KeyedStream<K, I, Op>
+where
+    K: DataKey,
+    I: Data,
+    Op: Operator<(K, I)> + 'static,
+{
+    /// Keep each element of the stream independently with probability `fraction`.
+    ///
+    /// See [`Stream::sample_fraction`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..1000))).group_by(|&n| n % 2);
+    /// let res = s.sample_fraction(0.1, 42).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let res = res.get().unwrap();
+    /// assert!(res.iter().all(|(k, n)| n % 2 == *k));
+    /// ```
+    pub fn sample_fraction(
+        self,
+        fraction: f64,
+        seed: u64,
+    ) -> KeyedStream<K, I, impl Operator<(K, I)>> {
+        self.add_operator(|prev| SampleFraction::new(prev, fraction, seed))
+    }
+
+    /// Sample `k` elements for each key uniformly at random, without replacement.
+    ///
+    /// Since all the elements of a key are already in the same replica, the sample of each key is
+    /// drawn locally and nothing is sent to the network. The random generators are derived from
+    /// `seed`: running the same job on the same input gives the same sample.
+    ///
+    /// **Note**: this operator will retain `k` elements per key and emit the samples only when the
+    /// stream ends. Therefore this is not properly _streaming_.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..1000))).group_by(|&n| n % 2);
+    /// let res = s.sample_reservoir(3, 42).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let res = res.get().unwrap();
+    /// assert_eq!(res.len(), 2 * 3);
+    /// ```
+    pub fn sample_reservoir(self, k: usize, seed: u64) -> KeyedStream<K, I, impl Operator<(K, I)>> {
+        let stream = self
+            .0
+            .add_operator(|prev| {
+                ReservoirSample::new(prev, |(key, _): &(K, I)| key.clone(), k, seed)
+            })
+            .flat_map(|(_, reservoir)| reservoir.items);
+        KeyedStream(stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nanorand::WyRand;
+
+    use crate::operator::sample::{Reservoir, SampleFraction};
+    use crate::operator::{Operator, StreamElement};
+    use crate::test::FakeOperator;
+
+    fn sample_fraction(seed: u64) -> Vec<u32> {
+        let fake = FakeOperator::new(0..1000u32);
+        let mut sample = SampleFraction::new(fake, 0.25, seed);
+        let mut res = vec![];
+        while let StreamElement::Item(n) = sample.next() {
+            res.push(n);
+        }
+        res
+    }
+
+    #[test]
+    fn sample_fraction_is_reproducible() {
+        let res = sample_fraction(7);
+        assert!(res.len() > 150 && res.len() < 350);
+        assert_eq!(res, sample_fraction(7));
+        assert_ne!(res, sample_fraction(8));
+    }
+
+    #[test]
+    fn reservoir_merge_is_uniform() {
+        // a replica saw 0..100 and another 100..400: each element should be drawn with the same
+        // probability, regardless of its replica
+        let mut rng = WyRand::new_seed(42);
+        let mut counts = [0u32; 400];
+        for _ in 0..2000 {
+            let mut parts = vec![Reservoir::new(0), Reservoir::new(1)];
+            for n in 0..100 {
+                parts[0].push(n, 10, &mut rng);
+            }
+            for n in 100..400 {
+                parts[1].push(n, 10, &mut rng);
+            }
+            let sample = Reservoir::merge(parts, 10, &mut rng);
+            assert_eq!(sample.len(), 10);
+            for n in sample {
+                counts[n] += 1;
+            }
+        }
+        // each element is expected 2000 * 10 / 400 = 50 times
+        let first = counts[..100].iter().sum::<u32>() as f64 / 100.0;
+        let second = counts[100..].iter().sum::<u32>() as f64 / 300.0;
+        assert!((first - 50.0).abs() < 5.0, "{first}");
+        assert!((second - 50.0).abs() < 5.0, "{second}");
+    }
+
+    #[test]
+    fn reservoir_merge_small_input() {
+        let mut rng = WyRand::new_seed(42);
+        let mut part = Reservoir::new(0);
+        for n in 0..3 {
+            part.push(n, 10, &mut rng);
+        }
+        let mut sample = Reservoir::merge(vec![part, Reservoir::new(1)], 10, &mut rng);
+        sample.sort();
+        assert_eq!(sample, vec![0, 1, 2]);
+    }
+}
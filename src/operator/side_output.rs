@@ -0,0 +1,591 @@
+//! Side outputs: send elements of different types to other streams from a single operator.
+//!
+//! The tags of the side outputs are declared up front with [`OutputTags`], then
+//! [`Stream::flat_map_with_side_outputs`](crate::Stream::flat_map_with_side_outputs) applies a
+//! function that can emit elements to the main output and to any of the tags. Each tag becomes a
+//! stream of its own, which can be retrieved from [`SideStreams`].
+
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::block::{
+    BatchMode, Batcher, BlockStructure, Connection, NextStrategy, OperatorStructure,
+    SchedulerRequirements,
+};
+use crate::environment::StreamEnvironmentInner;
+use crate::network::ReceiverEndpoint;
+use crate::operator::iteration::IterationStateLock;
+#[cfg(feature = "timestamp")]
+use crate::operator::watermark::{IdleSignal, IDLE_WATERMARK};
+use crate::operator::{
+    Data, ExchangeData, Operator, SimpleStartOperator, Start, StreamElement, Timestamp,
+};
+use crate::scheduler::{BlockId, ExecutionMetadata};
+use crate::stream::Stream;
+
+/// Typed identifier of a side output, created with [`OutputTags::tag`].
+#[derive(Derivative)]
+#[derivative(Debug, Clone(bound = ""), Copy(bound = ""))]
+pub struct OutputTag<T> {
+    id: usize,
+    #[derivative(Debug = "ignore")]
+    _t: PhantomData<fn(T)>,
+}
+
+/// The set of side outputs of an operator.
+#[derive(Default)]
+pub struct OutputTags {
+    builders: Vec<Box<dyn SideBuilder>>,
+}
+
+impl OutputTags {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare a new side output with elements of type `T`.
+    pub fn tag<T: ExchangeData>(&mut self) -> OutputTag<T> {
+        let id = self.builders.len();
+        self.builders
+            .push(Box::new(TypedSideBuilder::<T>(PhantomData)));
+        OutputTag {
+            id,
+            _t: PhantomData,
+        }
+    }
+}
+
+/// Handle given to the function of
+/// [`Stream::flat_map_with_side_outputs`](crate::Stream::flat_map_with_side_outputs) to emit the
+/// elements.
+///
+/// The emitted elements inherit the timestamp of the element being processed.
+pub struct SideOutput<'a, Out> {
+    main: &'a mut VecDeque<Out>,
+    senders: &'a mut [Box<dyn SideSender>],
+    timestamp: Option<Timestamp>,
+    replica: usize,
+}
+
+impl<'a, Out> SideOutput<'a, Out> {
+    /// Emit an element to the main output.
+    pub fn emit(&mut self, item: Out) {
+        self.main.push_back(item);
+    }
+
+    /// Emit an element to the side output identified by `tag`.
+    pub fn emit_to<T: ExchangeData>(&mut self, tag: &OutputTag<T>, item: T) {
+        let sender = self
+            .senders
+            .get_mut(tag.id)
+            .and_then(|s| s.as_any().downcast_mut::<TypedSideSender<T>>())
+            .expect("OutputTag does not belong to this operator");
+        sender.send(item, self.timestamp, self.replica);
+    }
+}
+
+/// The streams of the side outputs of an operator.
+///
+/// Each stream is retrieved with [`SideStreams::take`]. The streams that are not taken are closed
+/// discarding their elements when this is dropped.
+pub struct SideStreams {
+    streams: HashMap<usize, Box<dyn Any>>,
+    builders: Vec<Box<dyn SideBuilder>>,
+}
+
+impl SideStreams {
+    /// Get the stream of the side output identified by `tag`.
+    ///
+    /// Panics if the stream has already been taken or if the tag belongs to another operator.
+    pub fn take<T: ExchangeData>(&mut self, tag: &OutputTag<T>) -> Stream<T, impl Operator<T>> {
+        let stream = self
+            .streams
+            .remove(&tag.id)
+            .expect("The stream of this OutputTag has already been taken")
+            .downcast::<Stream<SideItem<T>, SimpleStartOperator<SideItem<T>>>>()
+            .expect("OutputTag does not belong to this operator");
+        stream.map(|item| item.0)
+    }
+}
+
+impl Drop for SideStreams {
+    fn drop(&mut self) {
+        for (id, stream) in self.streams.drain() {
+            self.builders[id].discard(stream);
+        }
+    }
+}
+
+/// Wrapper of the elements sent to a side output.
+///
+/// It avoids mixing up the side outputs with the main output of the block when they have the
+/// same type, since the connections between blocks are identified by the type.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct SideItem<T>(T);
+
+/// Type erased constructor of the stream and the sender of a side output.
+trait SideBuilder: Send {
+    fn new_stream(
+        &self,
+        env: &Arc<Mutex<StreamEnvironmentInner>>,
+        from: BlockId,
+        batch_mode: BatchMode,
+        iteration_ctx: Vec<Arc<IterationStateLock>>,
+        scheduler_requirements: SchedulerRequirements,
+    ) -> (BlockId, Box<dyn Any>);
+
+    fn new_sender(&self, block_id: BlockId) -> Box<dyn SideSender>;
+
+    fn discard(&self, stream: Box<dyn Any>);
+}
+
+struct TypedSideBuilder<T>(PhantomData<fn(T)>);
+
+impl<T: ExchangeData> SideBuilder for TypedSideBuilder<T> {
+    fn new_stream(
+        &self,
+        env: &Arc<Mutex<StreamEnvironmentInner>>,
+        from: BlockId,
+        batch_mode: BatchMode,
+        iteration_ctx: Vec<Arc<IterationStateLock>>,
+        scheduler_requirements: SchedulerRequirements,
+    ) -> (BlockId, Box<dyn Any>) {
+        let mut env_lock = env.lock();
+        let source = Start::single(from, iteration_ctx.last().cloned());
+        let mut block = env_lock.new_block::<SideItem<T>, _>(source, batch_mode, iteration_ctx);
+        block.scheduler_requirements = scheduler_requirements;
+        env_lock.connect_blocks::<SideItem<T>>(from, block.id);
+        drop(env_lock);
+
+        let block_id = block.id;
+        let stream = Stream {
+            block,
+            env: env.clone(),
+        };
+        (block_id, Box::new(stream))
+    }
+
+    fn new_sender(&self, block_id: BlockId) -> Box<dyn SideSender> {
+        Box::new(TypedSideSender::<T> {
+            block_id,
+            senders: Default::default(),
+        })
+    }
+
+    fn discard(&self, stream: Box<dyn Any>) {
+        if let Ok(stream) =
+            stream.downcast::<Stream<SideItem<T>, SimpleStartOperator<SideItem<T>>>>()
+        {
+            stream.for_each(|_| {});
+        }
+    }
+}
+
+/// Type erased connection from a replica to the blocks of a side output.
+trait SideSender: Send {
+    fn setup(&mut self, metadata: &mut ExecutionMetadata, batch_mode: BatchMode);
+
+    /// Send a control message to all the replicas of the side output.
+    fn broadcast(&mut self, message: &StreamElement<()>);
+
+    fn flush(&mut self);
+
+    fn end(&mut self);
+
+    fn connection(&self) -> Connection;
+
+    fn clone_box(&self) -> Box<dyn SideSender>;
+
+    fn as_any(&mut self) -> &mut dyn Any;
+}
+
+struct TypedSideSender<T: ExchangeData> {
+    block_id: BlockId,
+    senders: Vec<(ReceiverEndpoint, Batcher<SideItem<T>>)>,
+}
+
+impl<T: ExchangeData> TypedSideSender<T> {
+    fn send(&mut self, item: T, timestamp: Option<Timestamp>, replica: usize) {
+        if self.senders.is_empty() {
+            return;
+        }
+        let item = SideItem(item);
+        let message = match timestamp {
+            Some(ts) => StreamElement::Timestamped(item, ts),
+            None => StreamElement::Item(item),
+        };
+        let index = replica % self.senders.len();
+        self.senders[index].1.enqueue(message);
+    }
+}
+
+impl<T: ExchangeData> SideSender for TypedSideSender<T> {
+    fn setup(&mut self, metadata: &mut ExecutionMetadata, batch_mode: BatchMode) {
+        let mut senders = metadata.network.get_senders(metadata.coord);
+        // other side outputs may have the same type
+        senders.retain(|(endpoint, _)| endpoint.coord.block_id == self.block_id);
+        senders.sort_unstable_by_key(|(endpoint, _)| *endpoint);
+        self.senders = senders
+            .into_iter()
+            .map(|(endpoint, sender)| (endpoint, Batcher::new(sender, batch_mode, metadata.coord)))
+            .collect();
+    }
+
+    fn broadcast(&mut self, message: &StreamElement<()>) {
+        for (_, batcher) in self.senders.iter_mut() {
+            batcher.enqueue(message.clone().map(|_| unreachable!()));
+        }
+    }
+
+    fn flush(&mut self) {
+        for (_, batcher) in self.senders.iter_mut() {
+            batcher.flush();
+        }
+    }
+
+    fn end(&mut self) {
+        for (_, batcher) in self.senders.drain(..) {
+            batcher.end();
+        }
+    }
+
+    fn connection(&self) -> Connection {
+        Connection::new::<SideItem<T>, _>(self.block_id, &NextStrategy::random())
+    }
+
+    fn clone_box(&self) -> Box<dyn SideSender> {
+        Box::new(Self {
+            block_id: self.block_id,
+            senders: Default::default(),
+        })
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct FlatMapSideOutputs<In: Data, Out: Data, F, PreviousOperators>
+where
+    F: FnMut(In, &mut SideOutput<Out>) + Clone + Send + 'static,
+    PreviousOperators: Operator<In>,
+{
+    prev: PreviousOperators,
+    #[derivative(Debug = "ignore")]
+    f: F,
+    batch_mode: BatchMode,
+    #[derivative(Debug = "ignore")]
+    senders: Vec<Box<dyn SideSender>>,
+    buffer: VecDeque<Out>,
+    timestamp: Option<Timestamp>,
+    replica: usize,
+    #[cfg(feature = "timestamp")]
+    idle: Option<IdleSignal>,
+    _in: PhantomData<In>,
+}
+
+impl<In: Data, Out: Data, F, PreviousOperators> Clone
+    for FlatMapSideOutputs<In, Out, F, PreviousOperators>
+where
+    F: FnMut(In, &mut SideOutput<Out>) + Clone + Send + 'static,
+    PreviousOperators: Operator<In>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            prev: self.prev.clone(),
+            f: self.f.clone(),
+            batch_mode: self.batch_mode,
+            senders: self.senders.iter().map(|s| s.clone_box()).collect(),
+            buffer: Default::default(),
+            timestamp: None,
+            replica: 0,
+            #[cfg(feature = "timestamp")]
+            idle: None,
+            _in: PhantomData,
+        }
+    }
+}
+
+impl<In: Data, Out: Data, F, PreviousOperators> Display
+    for FlatMapSideOutputs<In, Out, F, PreviousOperators>
+where
+    F: FnMut(In, &mut SideOutput<Out>) + Clone + Send + 'static,
+    PreviousOperators: Operator<In>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> FlatMapSideOutputs<{} -> {}, {} sides>",
+            self.prev,
+            std::any::type_name::<In>(),
+            std::any::type_name::<Out>(),
+            self.senders.len()
+        )
+    }
+}
+
+impl<In: Data, Out: Data, F, PreviousOperators> FlatMapSideOutputs<In, Out, F, PreviousOperators>
+where
+    F: FnMut(In, &mut SideOutput<Out>) + Clone + Send + 'static,
+    PreviousOperators: Operator<In>,
+{
+    fn process(&mut self, item: In, timestamp: Option<Timestamp>) {
+        let mut output = SideOutput {
+            main: &mut self.buffer,
+            senders: &mut self.senders,
+            timestamp,
+            replica: self.replica,
+        };
+        (self.f)(item, &mut output);
+        self.timestamp = timestamp;
+    }
+}
+
+impl<In: Data, Out: Data, F, PreviousOperators> Operator<Out>
+    for FlatMapSideOutputs<In, Out, F, PreviousOperators>
+where
+    F: FnMut(In, &mut SideOutput<Out>) + Clone + Send + 'static,
+    PreviousOperators: Operator<In>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+        self.replica = metadata.global_id as usize;
+        for sender in self.senders.iter_mut() {
+            sender.setup(metadata, self.batch_mode);
+        }
+        #[cfg(feature = "timestamp")]
+        {
+            self.idle = metadata.idle_signal.clone();
+        }
+    }
+
+    fn next(&mut self) -> StreamElement<Out> {
+        loop {
+            if let Some(item) = self.buffer.pop_front() {
+                return match self.timestamp {
+                    Some(ts) => StreamElement::Timestamped(item, ts),
+                    None => StreamElement::Item(item),
+                };
+            }
+
+            match self.prev.next() {
+                StreamElement::Item(item) => self.process(item, None),
+                StreamElement::Timestamped(item, ts) => self.process(item, Some(ts)),
+                StreamElement::Watermark(ts) => {
+                    let message = StreamElement::Watermark(ts);
+                    self.senders.iter_mut().for_each(|s| s.broadcast(&message));
+                    return StreamElement::Watermark(ts);
+                }
+                StreamElement::FlushBatch => {
+                    // the replica became idle: only the next blocks receive the marker
+                    #[cfg(feature = "timestamp")]
+                    if self.idle.as_mut().is_some_and(|idle| idle.poll()) {
+                        let message = StreamElement::Watermark(IDLE_WATERMARK);
+                        self.senders.iter_mut().for_each(|s| s.broadcast(&message));
+                    }
+                    self.senders.iter_mut().for_each(|s| s.flush());
+                    return StreamElement::FlushBatch;
+                }
+                StreamElement::Checkpoint(id) => {
+                    let message = StreamElement::Checkpoint(id);
+                    self.senders.iter_mut().for_each(|s| s.broadcast(&message));
+                    return StreamElement::Checkpoint(id);
+                }
+                StreamElement::FlushAndRestart => {
+                    for sender in self.senders.iter_mut() {
+                        sender.broadcast(&StreamElement::FlushAndRestart);
+                        sender.flush();
+                    }
+                    return StreamElement::FlushAndRestart;
+                }
+                StreamElement::Terminate => {
+                    for sender in self.senders.iter_mut() {
+                        sender.broadcast(&StreamElement::Terminate);
+                        sender.end();
+                    }
+                    return StreamElement::Terminate;
+                }
+            }
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        let mut operator = OperatorStructure::new::<Out, _>("FlatMapSideOutputs");
+        operator
+            .connections
+            .extend(self.senders.iter().map(|s| s.connection()));
+        self.prev.structure().add_operator(operator)
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: Data,
+    Op: Operator<I> + 'static,
+{
+    /// Apply a function to each element that can emit any number of elements to the main output
+    /// and to a set of typed side outputs, each of which becomes a separate stream.
+    ///
+    /// The side outputs are declared in advance with [`OutputTags`]. The function receives each
+    /// element and a [`SideOutput`] handle: [`SideOutput::emit`] sends an element to the main
+    /// output, while [`SideOutput::emit_to`] sends it to the side output of a tag. The function
+    /// is cloned for each replica, so it can keep some state.
+    ///
+    /// The returned [`SideStreams`] gives the stream of each tag. The side streams have the same
+    /// parallelism of the current block, and each elements keeps the timestamp of the element
+    /// that produced it.
+    ///
+    /// **Note**: the main output stays in the current block, while each side output starts a new
+    /// block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// use noir::operator::side_output::OutputTags;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new(["1", "2", "x"].into_iter()));
+    ///
+    /// let mut tags = OutputTags::new();
+    /// let errors = tags.tag::<String>();
+    /// let (numbers, mut sides) = s.flat_map_with_side_outputs(tags, move |s, out| {
+    ///     match s.parse::<u32>() {
+    ///         Ok(n) => out.emit(n),
+    ///         Err(e) => out.emit_to(&errors, format!("{s}: {e}")),
+    ///     }
+    /// });
+    /// let numbers = numbers.collect_vec();
+    /// let errors = sides.take(&errors).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(numbers.get().unwrap(), vec![1, 2]);
+    /// assert_eq!(errors.get().unwrap().len(), 1);
+    /// ```
+    pub fn flat_map_with_side_outputs<O, F>(
+        self,
+        tags: OutputTags,
+        f: F,
+    ) -> (Stream<O, impl Operator<O>>, SideStreams)
+    where
+        O: Data,
+        F: FnMut(I, &mut SideOutput<O>) + Clone + Send + 'static,
+    {
+        let block_id = self.block.id;
+        let batch_mode = self.block.batch_mode;
+        let mut streams = HashMap::new();
+        let mut senders = Vec::new();
+        for (id, builder) in tags.builders.iter().enumerate() {
+            let (side_block_id, stream) = builder.new_stream(
+                &self.env,
+                block_id,
+                batch_mode,
+                self.block.iteration_ctx.clone(),
+                self.block.scheduler_requirements.clone(),
+            );
+            streams.insert(id, stream);
+            senders.push(builder.new_sender(side_block_id));
+        }
+
+        let stream = self.add_operator(|prev| FlatMapSideOutputs {
+            prev,
+            f,
+            batch_mode,
+            senders,
+            buffer: Default::default(),
+            timestamp: None,
+            replica: 0,
+            #[cfg(feature = "timestamp")]
+            idle: None,
+            _in: PhantomData,
+        });
+        let sides = SideStreams {
+            streams,
+            builders: tags.builders,
+        };
+        (stream, sides)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::config::EnvironmentConfig;
+    use crate::environment::StreamEnvironment;
+    use crate::operator::side_output::OutputTags;
+    use crate::operator::source;
+
+    #[test]
+    fn side_outputs() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        let source = source::IteratorSource::new(
+            ["1", "2", "x", "3", "y", "4"].into_iter().map(String::from),
+        );
+
+        let mut tags = OutputTags::new();
+        let errors = tags.tag::<String>();
+        let lengths = tags.tag::<usize>();
+        let (numbers, mut sides) =
+            env.stream(source)
+                .shuffle()
+                .flat_map_with_side_outputs(tags, move |s: String, out| {
+                    out.emit_to(&lengths, s.len());
+                    match s.parse::<u32>() {
+                        Ok(n) => out.emit(n),
+                        Err(_) => out.emit_to(&errors, s),
+                    }
+                });
+        let numbers = numbers.collect_vec();
+        let errors = sides.take(&errors).collect_vec();
+        let lengths = sides.take(&lengths).collect_vec();
+        env.execute_blocking().unwrap();
+
+        let numbers = numbers.get().unwrap().into_iter().sorted().collect_vec();
+        assert_eq!(numbers, vec![1, 2, 3, 4]);
+        let errors = errors.get().unwrap().into_iter().sorted().collect_vec();
+        assert_eq!(errors, vec!["x".to_string(), "y".to_string()]);
+        assert_eq!(lengths.get().unwrap(), vec![1; 6]);
+    }
+
+    #[test]
+    fn side_outputs_same_type_and_discarded() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        let source = source::IteratorSource::new(0..100u32);
+
+        let mut tags = OutputTags::new();
+        let even = tags.tag::<u32>();
+        let odd = tags.tag::<u32>();
+        let _ignored = tags.tag::<u32>();
+        let (main, mut sides) =
+            env.stream(source)
+                .shuffle()
+                .flat_map_with_side_outputs(tags, move |n: u32, out| {
+                    let tag = if n % 2 == 1 { &odd } else { &even };
+                    out.emit_to(tag, n);
+                    out.emit(n);
+                });
+        let main = main.collect_vec();
+        let even = sides.take(&even).collect_vec();
+        let odd = sides.take(&odd).collect_vec();
+        drop(sides);
+        env.execute_blocking().unwrap();
+
+        let main = main.get().unwrap().into_iter().sorted().collect_vec();
+        assert_eq!(main, (0..100).collect_vec());
+        let even = even.get().unwrap().into_iter().sorted().collect_vec();
+        assert_eq!(even, (0..100).step_by(2).collect_vec());
+        let odd = odd.get().unwrap().into_iter().sorted().collect_vec();
+        assert_eq!(odd, (1..100).step_by(2).collect_vec());
+    }
+}
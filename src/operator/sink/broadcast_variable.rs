@@ -0,0 +1,225 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+
+use crate::block::{BlockStructure, OperatorKind, OperatorStructure};
+use crate::concurrency;
+use crate::operator::sink::Sink;
+use crate::operator::{ExchangeData, Operator, StreamElement};
+use crate::scheduler::ExecutionMetadata;
+
+/// A read-only dataset materialised once per host, obtained with
+/// [`Stream::broadcast_variable`](crate::Stream::broadcast_variable).
+///
+/// The handle can be cloned and moved inside the closures of the operators of other streams, all
+/// the clones on the same host share the same content.
+///
+/// The content is set when the producing stream ends (or completes its first iteration) and never
+/// changes afterwards.
+pub struct BroadcastVariable<T> {
+    /// The content of the variable, `None` if the stream producing it failed.
+    content: Arc<OnceCell<Option<Vec<T>>>>,
+}
+
+impl<T> Clone for BroadcastVariable<T> {
+    fn clone(&self) -> Self {
+        Self {
+            content: self.content.clone(),
+        }
+    }
+}
+
+impl<T> Debug for BroadcastVariable<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BroadcastVariable")
+            .field("ready", &self.content.get().is_some())
+            .finish()
+    }
+}
+
+impl<T> BroadcastVariable<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            content: Default::default(),
+        }
+    }
+
+    /// Obtain the elements of the variable, in no particular order.
+    ///
+    /// If the stream producing the variable has not ended yet, this blocks the current replica
+    /// until it does.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the stream producing the variable failed on this host.
+    pub fn get(&self) -> &[T] {
+        let content = match self.content.get() {
+            Some(content) => content,
+            None => concurrency::blocking(|| self.content.wait()),
+        };
+        content
+            .as_deref()
+            .expect("The stream of the broadcast variable failed")
+    }
+
+    /// Obtain the elements of the variable, or `None` if the stream producing it has not ended
+    /// yet.
+    pub fn try_get(&self) -> Option<&[T]> {
+        self.content.get().map(|content| {
+            content
+                .as_deref()
+                .expect("The stream of the broadcast variable failed")
+        })
+    }
+}
+
+/// Collect the elements into a [`BroadcastVariable`], publishing them when the stream ends.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct BroadcastVariableSink<Out: ExchangeData + Sync, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    prev: PreviousOperators,
+    items: Vec<Out>,
+    #[derivative(Debug = "ignore")]
+    variable: BroadcastVariable<Out>,
+    /// Whether this replica is running, only the running replica publishes the variable.
+    started: bool,
+}
+
+impl<Out: ExchangeData + Sync, PreviousOperators> BroadcastVariableSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    pub(crate) fn new(prev: PreviousOperators, variable: BroadcastVariable<Out>) -> Self {
+        Self {
+            prev,
+            items: Vec::new(),
+            variable,
+            started: false,
+        }
+    }
+
+    fn publish(&mut self, content: Option<Vec<Out>>) {
+        // the variable is materialised only once: the items of the following iterations are
+        // discarded, see `Stream::broadcast_variable`
+        let _ = self.variable.content.set(content);
+    }
+}
+
+impl<Out: ExchangeData + Sync, PreviousOperators> Display
+    for BroadcastVariableSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> BroadcastVariableSink", self.prev)
+    }
+}
+
+impl<Out: ExchangeData + Sync, PreviousOperators> Operator<()>
+    for BroadcastVariableSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+        self.started = true;
+        // restoring the sources would materialise only part of the stream
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("broadcast variables are not supported");
+        }
+    }
+
+    fn next(&mut self) -> StreamElement<()> {
+        match self.prev.next() {
+            StreamElement::Item(t) | StreamElement::Timestamped(t, _) => {
+                self.items.push(t);
+                StreamElement::Item(())
+            }
+            StreamElement::Watermark(w) => StreamElement::Watermark(w),
+            StreamElement::FlushAndRestart => {
+                let items = std::mem::take(&mut self.items);
+                self.publish(Some(items));
+                StreamElement::FlushAndRestart
+            }
+            StreamElement::Terminate => {
+                let items = std::mem::take(&mut self.items);
+                self.publish(Some(items));
+                StreamElement::Terminate
+            }
+            StreamElement::FlushBatch => StreamElement::FlushBatch,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        let mut operator = OperatorStructure::new::<Out, _>("BroadcastVariableSink");
+        operator.kind = OperatorKind::Sink;
+        self.prev.structure().add_operator(operator)
+    }
+}
+
+impl<Out: ExchangeData + Sync, PreviousOperators> Clone
+    for BroadcastVariableSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.prev.clone(), self.variable.clone())
+    }
+}
+
+impl<Out: ExchangeData + Sync, PreviousOperators> Sink
+    for BroadcastVariableSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+}
+
+impl<Out: ExchangeData + Sync, PreviousOperators> Drop
+    for BroadcastVariableSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    fn drop(&mut self) {
+        // unblock the readers of the variable if this replica failed before the end of the stream
+        if self.started {
+            self.publish(None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operator::sink::broadcast_variable::{BroadcastVariable, BroadcastVariableSink};
+    use crate::operator::{Operator, StreamElement};
+    use crate::test::{FakeNetworkTopology, FakeOperator};
+
+    #[test]
+    fn broadcast_variable_sink() {
+        let variable = BroadcastVariable::new();
+        let fake = FakeOperator::new(0..3u8);
+        let mut sink = BroadcastVariableSink::new(fake, variable.clone());
+        sink.setup(&mut FakeNetworkTopology::<u8>::new(0, 0).metadata());
+
+        assert_eq!(sink.next(), StreamElement::Item(()));
+        assert_eq!(sink.next(), StreamElement::Item(()));
+        assert_eq!(sink.next(), StreamElement::Item(()));
+        assert!(variable.try_get().is_none());
+        assert_eq!(sink.next(), StreamElement::Terminate);
+        assert_eq!(variable.get(), &[0, 1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "broadcast variable failed")]
+    fn broadcast_variable_failed() {
+        let variable = BroadcastVariable::new();
+        let mut sink = BroadcastVariableSink::new(FakeOperator::<u8>::empty(), variable.clone());
+        sink.setup(&mut FakeNetworkTopology::<u8>::new(0, 0).metadata());
+        drop(sink);
+        variable.get();
+    }
+}
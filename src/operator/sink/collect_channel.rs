@@ -1,22 +1,39 @@
 use std::fmt::Display;
 
 use crate::block::{BlockStructure, OperatorKind, OperatorStructure};
+use crate::concurrency;
 use crate::operator::sink::Sink;
-use crate::operator::{ExchangeData, Operator, StreamElement};
+use crate::operator::{Data, ExchangeData, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::CoordUInt;
 
 #[cfg(feature = "crossbeam")]
-use crossbeam_channel::Sender;
+use crossbeam_channel::{Sender, TrySendError};
 #[cfg(not(feature = "crossbeam"))]
-use flume::Sender;
+use flume::{Sender, TrySendError};
 
-#[derive(Debug, Clone)]
-pub struct CollectChannelSink<Out: ExchangeData, PreviousOperators>
+/// Send an element to the channel of the sink, yielding the worker while the channel is full.
+///
+/// The elements sent after the receiver is dropped are discarded.
+fn send<T>(tx: &Sender<T>, item: T) {
+    if let Err(TrySendError::Full(item)) = tx.try_send(item) {
+        let _ = concurrency::blocking(|| tx.send(item));
+    }
+}
+
+/// Sink that sends the items to a channel, after passing them to a tagging function together
+/// with the global index of the replica.
+#[derive(Derivative)]
+#[derivative(Debug, Clone)]
+pub struct CollectChannelSink<Out: ExchangeData, PreviousOperators, T = Out>
 where
     PreviousOperators: Operator<Out>,
 {
     prev: PreviousOperators,
-    tx: Option<Sender<Out>>,
+    replica: CoordUInt,
+    #[derivative(Debug = "ignore")]
+    tag: fn(CoordUInt, Out) -> T,
+    tx: Option<Sender<T>>,
 }
 
 impl<Out: ExchangeData, PreviousOperators> CollectChannelSink<Out, PreviousOperators>
@@ -24,11 +41,37 @@ where
     PreviousOperators: Operator<Out>,
 {
     pub(crate) fn new(prev: PreviousOperators, tx: Sender<Out>) -> Self {
-        Self { prev, tx: Some(tx) }
+        Self::with_tag(prev, tx, |_, item| item)
     }
 }
 
-impl<Out: ExchangeData, PreviousOperators> Display for CollectChannelSink<Out, PreviousOperators>
+impl<Out: ExchangeData, PreviousOperators>
+    CollectChannelSink<Out, PreviousOperators, (CoordUInt, Out)>
+where
+    PreviousOperators: Operator<Out>,
+{
+    /// Send each item together with the index of the replica that produced it.
+    pub(crate) fn tagged(prev: PreviousOperators, tx: Sender<(CoordUInt, Out)>) -> Self {
+        Self::with_tag(prev, tx, |replica, item| (replica, item))
+    }
+}
+
+impl<Out: ExchangeData, PreviousOperators, T> CollectChannelSink<Out, PreviousOperators, T>
+where
+    PreviousOperators: Operator<Out>,
+{
+    fn with_tag(prev: PreviousOperators, tx: Sender<T>, tag: fn(CoordUInt, Out) -> T) -> Self {
+        Self {
+            prev,
+            replica: 0,
+            tag,
+            tx: Some(tx),
+        }
+    }
+}
+
+impl<Out: ExchangeData, PreviousOperators, T> Display
+    for CollectChannelSink<Out, PreviousOperators, T>
 where
     PreviousOperators: Operator<Out>,
 {
@@ -37,19 +80,22 @@ where
     }
 }
 
-impl<Out: ExchangeData, PreviousOperators> Operator<()>
-    for CollectChannelSink<Out, PreviousOperators>
+impl<Out: ExchangeData, PreviousOperators, T: Data> Operator<()>
+    for CollectChannelSink<Out, PreviousOperators, T>
 where
     PreviousOperators: Operator<Out>,
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.replica = metadata.global_id;
         self.prev.setup(metadata);
     }
 
     fn next(&mut self) -> StreamElement<()> {
         match self.prev.next() {
             StreamElement::Item(t) | StreamElement::Timestamped(t, _) => {
-                let _ = self.tx.as_ref().map(|tx| tx.send(t));
+                if let Some(tx) = &self.tx {
+                    send(tx, (self.tag)(self.replica, t));
+                }
                 StreamElement::Item(())
             }
             StreamElement::Watermark(w) => StreamElement::Watermark(w),
@@ -59,6 +105,7 @@ where
             }
             StreamElement::FlushBatch => StreamElement::FlushBatch,
             StreamElement::FlushAndRestart => StreamElement::FlushAndRestart,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
         }
     }
 
@@ -69,8 +116,10 @@ where
     }
 }
 
-impl<Out: ExchangeData, PreviousOperators> Sink for CollectChannelSink<Out, PreviousOperators> where
-    PreviousOperators: Operator<Out>
+impl<Out: ExchangeData, PreviousOperators, T: Data> Sink
+    for CollectChannelSink<Out, PreviousOperators, T>
+where
+    PreviousOperators: Operator<Out>,
 {
 }
 
@@ -87,11 +136,53 @@ mod tests {
         let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
         let source = source::IteratorSource::new(0..10u8);
         let rx = env.stream(source).collect_channel();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         let mut v = Vec::new();
         while let Ok(x) = rx.recv() {
             v.push(x)
         }
         assert_eq!(v, (0..10).collect_vec());
     }
+
+    #[test]
+    fn collect_channel_bounded() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        let source = source::IteratorSource::new(0..1000u32);
+        let rx = env.stream(source).collect_channel_bounded(4);
+        let consumer = std::thread::spawn(move || {
+            let mut v = Vec::new();
+            while let Ok(x) = rx.recv() {
+                v.push(x)
+            }
+            v
+        });
+        env.execute_blocking().unwrap();
+        assert_eq!(consumer.join().unwrap(), (0..1000).collect_vec());
+    }
+
+    #[test]
+    fn collect_channel_parallel_tagged() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        let source = source::ParallelIteratorSource::new(|id, instances| {
+            (0..100u64).filter(move |n| n % instances == id)
+        });
+        let rx = env.stream(source).collect_channel_parallel_tagged(2);
+        let consumer = std::thread::spawn(move || {
+            let mut v = Vec::new();
+            while let Ok(x) = rx.recv() {
+                v.push(x)
+            }
+            v
+        });
+        env.execute_blocking().unwrap();
+        let res = consumer.join().unwrap();
+        assert_eq!(res.len(), 100);
+        // the items of each replica are received in order
+        let by_replica = res.into_iter().into_group_map();
+        assert_eq!(by_replica.len(), 4);
+        for (replica, items) in by_replica {
+            let expected = (0..100u64).filter(|n| n % 4 == replica).collect_vec();
+            assert_eq!(items, expected);
+        }
+    }
 }
@@ -59,6 +59,7 @@ where
             }
             StreamElement::FlushBatch => StreamElement::FlushBatch,
             StreamElement::FlushAndRestart => StreamElement::FlushAndRestart,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
         }
     }
 
@@ -96,7 +97,7 @@ mod tests {
         let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
         let source = source::IteratorSource::new(0..10u8);
         let res = env.stream(source).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         assert_eq!(res.get().unwrap(), (0..10).collect_vec());
     }
 }
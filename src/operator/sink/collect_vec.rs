@@ -1,9 +1,11 @@
 use std::fmt::Display;
+use std::marker::PhantomData;
 
 use crate::block::{BlockStructure, OperatorKind, OperatorStructure};
 use crate::operator::sink::{Sink, StreamOutputRef};
 use crate::operator::{ExchangeData, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::CoordUInt;
 
 #[derive(Debug)]
 pub struct CollectVecSink<Out: ExchangeData, PreviousOperators>
@@ -63,6 +65,7 @@ where
             }
             StreamElement::FlushBatch => StreamElement::FlushBatch,
             StreamElement::FlushAndRestart => StreamElement::FlushAndRestart,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
         }
     }
 
@@ -87,6 +90,150 @@ where
     }
 }
 
+/// Tag each item with the global index of the replica that produced it.
+#[derive(Clone, Debug)]
+pub(crate) struct TagReplica<Out: ExchangeData, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    prev: PreviousOperators,
+    replica: CoordUInt,
+    _out: PhantomData<Out>,
+}
+
+impl<Out: ExchangeData, PreviousOperators> TagReplica<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    pub(crate) fn new(prev: PreviousOperators) -> Self {
+        Self {
+            prev,
+            replica: 0,
+            _out: PhantomData,
+        }
+    }
+}
+
+impl<Out: ExchangeData, PreviousOperators> Display for TagReplica<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> TagReplica", self.prev)
+    }
+}
+
+impl<Out: ExchangeData, PreviousOperators> Operator<(CoordUInt, Out)>
+    for TagReplica<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.replica = metadata.global_id;
+        self.prev.setup(metadata);
+    }
+
+    #[inline]
+    fn next(&mut self) -> StreamElement<(CoordUInt, Out)> {
+        self.prev.next().map(|item| (self.replica, item))
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<(CoordUInt, Out), _>("TagReplica"))
+    }
+}
+
+/// Like [`CollectVecSink`], but the items are ordered by the replica they come from.
+#[derive(Debug)]
+pub struct CollectVecOrderedSink<Out: ExchangeData, PreviousOperators>
+where
+    PreviousOperators: Operator<(CoordUInt, Out)>,
+{
+    prev: PreviousOperators,
+    /// The items received from each replica, in order.
+    replicas: Option<Vec<Vec<Out>>>,
+    output: StreamOutputRef<Vec<Out>>,
+}
+
+impl<Out: ExchangeData, PreviousOperators> CollectVecOrderedSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<(CoordUInt, Out)>,
+{
+    pub(crate) fn new(prev: PreviousOperators, output: StreamOutputRef<Vec<Out>>) -> Self {
+        Self {
+            prev,
+            replicas: Some(Vec::new()),
+            output,
+        }
+    }
+}
+
+impl<Out: ExchangeData, PreviousOperators> Display for CollectVecOrderedSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<(CoordUInt, Out)>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> CollectVecOrderedSink", self.prev)
+    }
+}
+
+impl<Out: ExchangeData, PreviousOperators> Operator<()>
+    for CollectVecOrderedSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<(CoordUInt, Out)>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+    }
+
+    fn next(&mut self) -> StreamElement<()> {
+        match self.prev.next() {
+            StreamElement::Item((replica, t)) | StreamElement::Timestamped((replica, t), _) => {
+                if let Some(replicas) = self.replicas.as_mut() {
+                    let replica = replica as usize;
+                    if replicas.len() <= replica {
+                        replicas.resize_with(replica + 1, Vec::new);
+                    }
+                    replicas[replica].push(t);
+                }
+                StreamElement::Item(())
+            }
+            StreamElement::Watermark(w) => StreamElement::Watermark(w),
+            StreamElement::Terminate => {
+                if let Some(replicas) = self.replicas.take() {
+                    *self.output.lock().unwrap() = Some(replicas.into_iter().flatten().collect());
+                }
+                StreamElement::Terminate
+            }
+            StreamElement::FlushBatch => StreamElement::FlushBatch,
+            StreamElement::FlushAndRestart => StreamElement::FlushAndRestart,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        let mut operator = OperatorStructure::new::<Out, _>("CollectVecOrderedSink");
+        operator.kind = OperatorKind::Sink;
+        self.prev.structure().add_operator(operator)
+    }
+}
+
+impl<Out: ExchangeData, PreviousOperators> Sink for CollectVecOrderedSink<Out, PreviousOperators> where
+    PreviousOperators: Operator<(CoordUInt, Out)>
+{
+}
+
+impl<Out: ExchangeData, PreviousOperators> Clone for CollectVecOrderedSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<(CoordUInt, Out)>,
+{
+    fn clone(&self) -> Self {
+        panic!("CollectVecOrderedSink cannot be cloned, replication should be 1");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use itertools::Itertools;
@@ -100,7 +247,7 @@ mod tests {
         let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
         let source = source::IteratorSource::new(0..10u8);
         let res = env.stream(source).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         assert_eq!(res.get().unwrap(), (0..10).collect_vec());
     }
 }
@@ -0,0 +1,150 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use crate::block::{BlockStructure, OperatorKind, OperatorStructure};
+use crate::operator::sink::Sink;
+use crate::operator::{ExchangeData, Operator, StreamElement};
+use crate::scheduler::ExecutionMetadata;
+
+/// Write the items of each replica to its own CSV file inside a directory.
+///
+/// The files are named after the global index of the replicas (`part-00000.csv`,
+/// `part-00001.csv`, ...), so sorting them by name follows the order of the replicas. The part
+/// files left by a previous run with more replicas are removed.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct CsvPartsSink<Out: ExchangeData, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    prev: PreviousOperators,
+    dir: PathBuf,
+    #[derivative(Debug = "ignore")]
+    writer: Option<csv::Writer<File>>,
+    _out: PhantomData<Out>,
+}
+
+impl<Out: ExchangeData, PreviousOperators> CsvPartsSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    pub(crate) fn new(prev: PreviousOperators, dir: PathBuf) -> Self {
+        Self {
+            prev,
+            dir,
+            writer: None,
+            _out: PhantomData,
+        }
+    }
+}
+
+impl<Out: ExchangeData, PreviousOperators> Display for CsvPartsSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> CsvPartsSink", self.prev)
+    }
+}
+
+impl<Out: ExchangeData, PreviousOperators> Operator<()> for CsvPartsSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+
+        std::fs::create_dir_all(&self.dir).unwrap_or_else(|err| {
+            panic!(
+                "CsvPartsSink: error while creating directory {:?}: {:?}",
+                self.dir, err
+            )
+        });
+        remove_stale_parts(&self.dir, metadata.replicas.len());
+        let path = self.dir.join(format!("part-{:05}.csv", metadata.global_id));
+        let writer = csv::Writer::from_path(&path).unwrap_or_else(|err| {
+            panic!("CsvPartsSink: error while creating file {path:?}: {err:?}")
+        });
+        self.writer = Some(writer);
+    }
+
+    fn next(&mut self) -> StreamElement<()> {
+        let writer = self
+            .writer
+            .as_mut()
+            .expect("CsvPartsSink was not initialized");
+        match self.prev.next() {
+            StreamElement::Item(t) | StreamElement::Timestamped(t, _) => {
+                writer.serialize(t).expect("Error while writing CSV record");
+                StreamElement::Item(())
+            }
+            StreamElement::Watermark(w) => StreamElement::Watermark(w),
+            StreamElement::Terminate => {
+                writer.flush().expect("Error while flushing CSV file");
+                StreamElement::Terminate
+            }
+            StreamElement::FlushBatch => StreamElement::FlushBatch,
+            StreamElement::FlushAndRestart => StreamElement::FlushAndRestart,
+            StreamElement::Checkpoint(id) => StreamElement::Checkpoint(id),
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        let mut operator = OperatorStructure::new::<Out, _>("CsvPartsSink");
+        operator.kind = OperatorKind::Sink;
+        self.prev.structure().add_operator(operator)
+    }
+}
+
+/// Remove the part files inside `dir` that are not written by any of the `replicas`.
+///
+/// Every replica does it, since in a remote environment the directory may be local to each host.
+fn remove_stale_parts(dir: &Path, replicas: usize) {
+    let entries = std::fs::read_dir(dir).unwrap_or_else(|err| {
+        panic!("CsvPartsSink: error while reading directory {dir:?}: {err:?}")
+    });
+    for entry in entries.filter_map(Result::ok) {
+        let name = entry.file_name();
+        let index = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("part-")?.strip_suffix(".csv"))
+            .filter(|index| index.len() == 5)
+            .and_then(|index| index.parse::<usize>().ok());
+        if !matches!(index, Some(index) if index >= replicas) {
+            continue;
+        }
+        match std::fs::remove_file(entry.path()) {
+            // another replica removed it first
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                panic!("CsvPartsSink: error while removing stale file {name:?}: {err:?}")
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<Out: ExchangeData, PreviousOperators> Sink for CsvPartsSink<Out, PreviousOperators> where
+    PreviousOperators: Operator<Out>
+{
+}
+
+impl<Out: ExchangeData, PreviousOperators> Clone for CsvPartsSink<Out, PreviousOperators>
+where
+    PreviousOperators: Operator<Out>,
+{
+    fn clone(&self) -> Self {
+        assert!(
+            self.writer.is_none(),
+            "CsvPartsSink must be cloned before calling setup"
+        );
+        Self {
+            prev: self.prev.clone(),
+            dir: self.dir.clone(),
+            writer: None,
+            _out: PhantomData,
+        }
+    }
+}
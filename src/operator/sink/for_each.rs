@@ -6,6 +6,7 @@ use crate::operator::sink::Sink;
 use crate::operator::{Data, DataKey, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
 use crate::stream::{KeyedStream, Stream};
+use crate::worker::OperatorIndex;
 
 #[derive(Clone, Derivative)]
 #[derivative(Debug)]
@@ -15,6 +16,7 @@ where
     PreviousOperators: Operator<Out>,
 {
     prev: PreviousOperators,
+    operator: OperatorIndex,
     #[derivative(Debug = "ignore")]
     f: F,
     _out: PhantomData<Out>,
@@ -28,6 +30,7 @@ where
     pub(crate) fn new(prev: PreviousOperators, f: F) -> Self {
         Self {
             prev,
+            operator: OperatorIndex::default(),
             f,
             _out: PhantomData,
         }
@@ -51,11 +54,14 @@ where
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        self.operator = OperatorIndex::after(self.prev.structure());
     }
 
     fn next(&mut self) -> StreamElement<()> {
         loop {
-            match self.prev.next() {
+            let element = self.prev.next();
+            let _operator = self.operator.enter();
+            match element {
                 StreamElement::Item(t) | StreamElement::Timestamped(t, _) => {
                     (self.f)(t);
                 }
@@ -63,6 +69,7 @@ where
                 StreamElement::Terminate => return StreamElement::Terminate,
                 StreamElement::FlushBatch => return StreamElement::FlushBatch,
                 StreamElement::FlushAndRestart => return StreamElement::FlushAndRestart,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
             }
         }
     }
@@ -109,7 +116,7 @@ mod tests {
         env.stream(source).for_each(move |x| {
             sum.fetch_add(x, Ordering::Release);
         });
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         assert_eq!(sum2.load(Ordering::Acquire), (0..10).sum::<u8>());
     }
 
@@ -124,7 +131,7 @@ mod tests {
             .for_each(move |(p, x)| {
                 sum.fetch_add(x * (p + 1), Ordering::Release);
             });
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         assert_eq!(
             sum2.load(Ordering::Acquire),
             (0..10).map(|x| x * (x % 2 + 1)).sum::<u8>()
@@ -7,10 +7,14 @@ use std::sync::{Arc, Mutex};
 
 use crate::operator::Operator;
 
+pub use broadcast_variable::BroadcastVariable;
+
+pub(super) mod broadcast_variable;
 pub(super) mod collect;
 pub(super) mod collect_channel;
 pub(super) mod collect_count;
 pub(super) mod collect_vec;
+pub(super) mod csv_parts;
 pub(super) mod for_each;
 
 /// This trait marks all the operators that can be used as sinks.
@@ -0,0 +1,451 @@
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use nanorand::{tls_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::block::{BlockStructure, NextStrategy, OperatorStructure};
+use crate::operator::end::End;
+use crate::operator::start::{BinaryElement, Start};
+use crate::operator::{ExchangeData, KeyerFn, Operator, StreamElement};
+use crate::scheduler::ExecutionMetadata;
+use crate::Stream;
+
+/// The number of keys sampled by each replica to choose the ranges of the sort.
+const SAMPLE_SIZE: usize = 1000;
+
+/// A uniform sample of the keys of a stream.
+///
+/// Each replica keeps a reservoir of [`SAMPLE_SIZE`] keys, when the samples of many replicas are
+/// merged each key is weighted by the number of keys it represents.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct KeySample<K> {
+    /// The number of keys pushed in the reservoir.
+    seen: u64,
+    reservoir: Vec<K>,
+    /// The keys of the merged samples, with their weight.
+    merged: Vec<(K, f64)>,
+}
+
+impl<K> Default for KeySample<K> {
+    fn default() -> Self {
+        Self {
+            seen: 0,
+            reservoir: Default::default(),
+            merged: Default::default(),
+        }
+    }
+}
+
+impl<K: Ord + Clone> KeySample<K> {
+    pub(crate) fn push(&mut self, key: K) {
+        self.seen += 1;
+        if self.reservoir.len() < SAMPLE_SIZE {
+            self.reservoir.push(key);
+        } else {
+            let index = tls_rng().generate_range(0..self.seen) as usize;
+            if index < SAMPLE_SIZE {
+                self.reservoir[index] = key;
+            }
+        }
+    }
+
+    pub(crate) fn merge(&mut self, other: Self) {
+        self.merged.extend(other.into_weighted());
+    }
+
+    fn into_weighted(self) -> impl Iterator<Item = (K, f64)> {
+        let weight = self.seen as f64 / self.reservoir.len().max(1) as f64;
+        self.merged
+            .into_iter()
+            .chain(self.reservoir.into_iter().map(move |key| (key, weight)))
+    }
+
+    /// Split the sampled keys in `ranges` ranges with about the same weight.
+    ///
+    /// Returns the upper bound (inclusive) of all the ranges but the last one.
+    pub(crate) fn boundaries(self, ranges: usize) -> Vec<K> {
+        let mut keys = self.into_weighted().collect::<Vec<_>>();
+        glidesort::sort_by(&mut keys, |(a, _), (b, _)| a.cmp(b));
+        let total = keys.iter().map(|(_, weight)| weight).sum::<f64>();
+
+        let mut boundaries = Vec::with_capacity(ranges.saturating_sub(1));
+        let mut cumulative = 0.0;
+        for (key, weight) in keys {
+            cumulative += weight;
+            while boundaries.len() + 1 < ranges
+                && cumulative * ranges as f64 >= total * (boundaries.len() + 1) as f64
+            {
+                boundaries.push(key.clone());
+            }
+        }
+        boundaries
+    }
+}
+
+/// The index of the range `key` belongs to.
+#[inline]
+fn range_of<K: Ord>(boundaries: &[K], key: &K) -> u64 {
+    boundaries.partition_point(|b| b < key) as u64
+}
+
+/// Assign each item to the range of its key, the ranges are chosen from the sample of the keys
+/// coming from the right side.
+///
+/// The items are held back until the sample is complete, i.e. until the stream ends, then they are
+/// tagged with the index of their range: the following block has one replica for each range.
+#[derive(Derivative)]
+#[derivative(Clone, Debug)]
+pub(crate) struct RangePartition<I, K, Keyer, PreviousOperators>
+where
+    I: ExchangeData,
+    K: ExchangeData + Ord,
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<BinaryElement<I, KeySample<K>>>,
+{
+    prev: PreviousOperators,
+    #[derivative(Debug = "ignore")]
+    keyer: Keyer,
+    /// The number of ranges, one for each replica.
+    ranges: usize,
+    sample: KeySample<K>,
+    boundaries: Option<Vec<K>>,
+    buffer: VecDeque<StreamElement<I>>,
+    /// The stream ended, `FlushAndRestart` is emitted after the buffer.
+    restart: bool,
+}
+
+impl<I, K, Keyer, PreviousOperators> Display for RangePartition<I, K, Keyer, PreviousOperators>
+where
+    I: ExchangeData,
+    K: ExchangeData + Ord,
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<BinaryElement<I, KeySample<K>>>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> RangePartition<{}>",
+            self.prev,
+            std::any::type_name::<K>()
+        )
+    }
+}
+
+impl<I, K, Keyer, PreviousOperators> RangePartition<I, K, Keyer, PreviousOperators>
+where
+    I: ExchangeData,
+    K: ExchangeData + Ord,
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<BinaryElement<I, KeySample<K>>>,
+{
+    pub(crate) fn new(prev: PreviousOperators, keyer: Keyer) -> Self {
+        Self {
+            prev,
+            keyer,
+            ranges: 1,
+            sample: Default::default(),
+            boundaries: None,
+            buffer: Default::default(),
+            restart: false,
+        }
+    }
+
+    fn choose_boundaries(&mut self) {
+        let sample = std::mem::take(&mut self.sample);
+        self.boundaries = Some(sample.boundaries(self.ranges));
+    }
+}
+
+impl<I, K, Keyer, PreviousOperators> Operator<(u64, I)>
+    for RangePartition<I, K, Keyer, PreviousOperators>
+where
+    I: ExchangeData,
+    K: ExchangeData + Ord,
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<BinaryElement<I, KeySample<K>>>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+        self.ranges = metadata.replicas.len();
+        // the items held back are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("sort is not supported");
+        }
+    }
+
+    fn next(&mut self) -> StreamElement<(u64, I)> {
+        loop {
+            if let Some(boundaries) = &self.boundaries {
+                if let Some(element) = self.buffer.pop_front() {
+                    return element.map(|item| (range_of(boundaries, &(self.keyer)(&item)), item));
+                }
+                if self.restart {
+                    self.restart = false;
+                    self.boundaries = None;
+                    return StreamElement::FlushAndRestart;
+                }
+            }
+
+            match self.prev.next() {
+                StreamElement::Item(BinaryElement::Left(item)) => {
+                    self.buffer.push_back(StreamElement::Item(item))
+                }
+                StreamElement::Timestamped(BinaryElement::Left(item), ts) => {
+                    self.buffer.push_back(StreamElement::Timestamped(item, ts))
+                }
+                StreamElement::Item(BinaryElement::Right(sample))
+                | StreamElement::Timestamped(BinaryElement::Right(sample), _) => {
+                    self.sample.merge(sample)
+                }
+                StreamElement::Item(BinaryElement::RightEnd)
+                | StreamElement::Timestamped(BinaryElement::RightEnd, _) => {
+                    self.choose_boundaries()
+                }
+                StreamElement::Item(BinaryElement::LeftEnd)
+                | StreamElement::Timestamped(BinaryElement::LeftEnd, _) => {}
+                // the sort does not preserve the order of the timestamps
+                StreamElement::Watermark(_) => {}
+                StreamElement::FlushAndRestart => {
+                    self.restart = true;
+                    if self.boundaries.is_none() {
+                        self.choose_boundaries();
+                    }
+                }
+                StreamElement::FlushBatch => return StreamElement::FlushBatch,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
+                StreamElement::Terminate => return StreamElement::Terminate,
+            }
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<(u64, I), _>("RangePartition"))
+    }
+}
+
+/// Sort all the items of a replica by their key, emitting them when the stream ends.
+#[derive(Derivative)]
+#[derivative(Clone, Debug)]
+pub(crate) struct Sort<I, K, Keyer, PreviousOperators>
+where
+    I: ExchangeData,
+    K: ExchangeData + Ord,
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<(u64, I)>,
+{
+    prev: PreviousOperators,
+    #[derivative(Debug = "ignore")]
+    keyer: Keyer,
+    #[derivative(Debug = "ignore")]
+    buffer: Vec<(K, StreamElement<I>)>,
+    #[derivative(Debug = "ignore")]
+    sorted: std::vec::IntoIter<(K, StreamElement<I>)>,
+    /// The message that ended the stream, emitted after the sorted items.
+    end: Option<StreamElement<I>>,
+    _in: PhantomData<I>,
+}
+
+impl<I, K, Keyer, PreviousOperators> Display for Sort<I, K, Keyer, PreviousOperators>
+where
+    I: ExchangeData,
+    K: ExchangeData + Ord,
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<(u64, I)>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} -> Sort<{}>", self.prev, std::any::type_name::<K>())
+    }
+}
+
+impl<I, K, Keyer, PreviousOperators> Sort<I, K, Keyer, PreviousOperators>
+where
+    I: ExchangeData,
+    K: ExchangeData + Ord,
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<(u64, I)>,
+{
+    pub(crate) fn new(prev: PreviousOperators, keyer: Keyer) -> Self {
+        Self {
+            prev,
+            keyer,
+            buffer: Default::default(),
+            sorted: Default::default(),
+            end: None,
+            _in: Default::default(),
+        }
+    }
+}
+
+impl<I, K, Keyer, PreviousOperators> Operator<I> for Sort<I, K, Keyer, PreviousOperators>
+where
+    I: ExchangeData,
+    K: ExchangeData + Ord,
+    Keyer: KeyerFn<K, I>,
+    PreviousOperators: Operator<(u64, I)>,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        self.prev.setup(metadata);
+        // the items held back are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("sort is not supported");
+        }
+    }
+
+    fn next(&mut self) -> StreamElement<I> {
+        loop {
+            if let Some((_, element)) = self.sorted.next() {
+                return element;
+            }
+            if let Some(end) = self.end.take() {
+                return end;
+            }
+
+            match self.prev.next() {
+                StreamElement::Item((_, item)) => {
+                    self.buffer
+                        .push(((self.keyer)(&item), StreamElement::Item(item)));
+                }
+                StreamElement::Timestamped((_, item), ts) => {
+                    self.buffer
+                        .push(((self.keyer)(&item), StreamElement::Timestamped(item, ts)));
+                }
+                StreamElement::Watermark(_) => {}
+                StreamElement::FlushBatch => return StreamElement::FlushBatch,
+                StreamElement::Checkpoint(id) => return StreamElement::Checkpoint(id),
+                end @ (StreamElement::FlushAndRestart | StreamElement::Terminate) => {
+                    // stable, the items with the same key keep the order they were received in
+                    glidesort::sort_by(&mut self.buffer, |(a, _), (b, _)| a.cmp(b));
+                    self.sorted = std::mem::take(&mut self.buffer).into_iter();
+                    self.end = Some(end.map(|_| unreachable!()));
+                }
+            }
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        self.prev
+            .structure()
+            .add_operator(OperatorStructure::new::<I, _>("Sort"))
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeData,
+    Op: Operator<I> + 'static,
+{
+    /// Sort the whole stream by the key returned by `keyer`.
+    ///
+    /// The keys are sampled to split them in ranges with about the same number of items, one for
+    /// each replica: the i-th replica receives the i-th range and sorts its items locally. The
+    /// resulting stream is globally ordered following the index of the replicas, use
+    /// [`Stream::collect_vec_ordered`] to collect it in order, or
+    /// [`Stream::write_csv_parts`] to write it as ordered part files. The items with the same
+    /// key end up in the same replica.
+    ///
+    /// The sort has the same parallelism of this stream: if it comes from a single replica
+    /// [`Stream::shuffle`] it first to sort it in parallel.
+    ///
+    /// **Note**: this operator will retain all the messages of the stream and emit the values only
+    /// when the stream ends. Therefore this is not properly _streaming_. The items are held in
+    /// memory twice: before the split in ranges, until the sample of all the keys is complete,
+    /// and by the replicas that sort them. Nothing is spilled to disk, so the whole stream must fit
+    /// in the memory of the hosts. The watermarks are dropped, the items keep their timestamps.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    /// let s = env.stream(IteratorSource::new((0..100).rev()));
+    /// let res = s.shuffle().sort_by_key(|&n| n).collect_vec_ordered();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap(), (0..100).collect::<Vec<_>>());
+    /// ```
+    pub fn sort_by_key<K, Fk>(self, keyer: Fk) -> Stream<I, impl Operator<I>>
+    where
+        Fk: KeyerFn<K, I>,
+        K: ExchangeData + Ord,
+    {
+        let mut splits = self.split(2);
+        let sample_keyer = keyer.clone();
+        let sample = splits.pop().unwrap().fold_assoc(
+            KeySample::default(),
+            move |sample, item| sample.push(sample_keyer(&item)),
+            |sample, other| sample.merge(other),
+        );
+        let data = splits.pop().unwrap();
+
+        let partition_keyer = keyer.clone();
+        let partitioned = data
+            .binary_connection(
+                sample,
+                Start::multiple,
+                NextStrategy::only_one(),
+                NextStrategy::all(),
+            )
+            .add_operator(|prev| RangePartition::new(prev, partition_keyer));
+
+        // one replica for each range
+        let scheduler_requirements = partitioned.block.scheduler_requirements.clone();
+        let next_strategy = NextStrategy::GroupBy(|(range, _): &(u64, I)| *range, PhantomData);
+        let mut sorted = partitioned.split_block(End::new, next_strategy);
+        sorted.block.scheduler_requirements = scheduler_requirements;
+        sorted.add_operator(|prev| Sort::new(prev, keyer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operator::sort::{range_of, KeySample};
+
+    #[test]
+    fn balanced_boundaries() {
+        let mut left = KeySample::default();
+        let mut right = KeySample::default();
+        (0..500u32).for_each(|k| left.push(k));
+        // the right replica saw more keys than it can keep
+        (500..4500u32).for_each(|k| right.push(k));
+
+        let mut sample = KeySample::default();
+        sample.merge(left);
+        sample.merge(right);
+        let boundaries = sample.boundaries(3);
+        assert_eq!(boundaries.len(), 2);
+
+        let mut counts = [0; 3];
+        for k in 0..4500u32 {
+            counts[range_of(&boundaries, &k) as usize] += 1;
+        }
+        for count in counts {
+            assert!(
+                (1200..1800).contains(&count),
+                "unbalanced ranges {counts:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn equal_keys_in_one_range() {
+        let mut sample = KeySample::default();
+        (0..100u32).for_each(|_| sample.push(7));
+        (0..10u32).for_each(|k| sample.push(k));
+        let mut merged = KeySample::default();
+        merged.merge(sample);
+        let boundaries = merged.boundaries(4);
+        let ranges = (0..10u32)
+            .map(|k| range_of(&boundaries, &k))
+            .collect::<Vec<_>>();
+        assert!(ranges.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(boundaries.iter().filter(|&&b| b != 7).count(), 0);
+    }
+}
@@ -3,14 +3,19 @@ use std::fmt::Display;
 use futures::{Stream, StreamExt};
 
 use crate::block::{BlockStructure, OperatorKind, OperatorStructure, Replication};
-use crate::operator::source::Source;
+use crate::concurrency;
+use crate::job::SourceControl;
+use crate::operator::source::{Source, IDLE_HEARTBEAT};
 use crate::operator::{Data, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 
 /// Source that consumes an iterator and emits all its elements into the stream.
 ///
 /// The iterator will be consumed **only from one replica**, therefore this source is not parallel.
-
+///
+/// The items consumed from the stream cannot be read again, so a job with this source fails to
+/// start if the checkpoints are enabled.
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct AsyncStreamSource<Out: Data, S>
@@ -20,6 +25,7 @@ where
     #[derivative(Debug = "ignore")]
     inner: S,
     terminated: bool,
+    control: SourceControl,
 }
 
 impl<Out: Data, S> Display for AsyncStreamSource<Out, S>
@@ -56,6 +62,7 @@ where
         Self {
             inner,
             terminated: false,
+            control: Default::default(),
         }
     }
 }
@@ -73,19 +80,41 @@ impl<Out: Data, S> Operator<Out> for AsyncStreamSource<Out, S>
 where
     S: Stream<Item = Out> + Send + Unpin + 'static,
 {
-    fn setup(&mut self, _metadata: &mut ExecutionMetadata) {}
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        let _operator = OperatorIndex::FIRST.enter();
+        self.control = SourceControl::new(metadata);
+        // the items of the stream cannot be read again
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("async stream sources are not rewindable");
+        }
+    }
 
     fn next(&mut self) -> StreamElement<Out> {
-        if self.terminated {
-            return StreamElement::Terminate;
-        }
-        // TODO: with adaptive batching this does not work since S never emits FlushBatch messages
-        let rt = tokio::runtime::Handle::current();
-        match rt.block_on(self.inner.next()) {
-            Some(t) => StreamElement::Item(t),
-            None => {
+        let _operator = OperatorIndex::FIRST.enter();
+        loop {
+            if self.terminated {
+                return StreamElement::Terminate;
+            }
+            if self.control.is_cancelled() {
                 self.terminated = true;
-                StreamElement::FlushAndRestart
+                return StreamElement::FlushAndRestart;
+            }
+            if let Some(id) = self.control.checkpoint() {
+                return StreamElement::Checkpoint(id);
+            }
+            // wake up periodically to check for the cancellation of the job
+            let rt = tokio::runtime::Handle::current();
+            let next = concurrency::blocking(|| {
+                rt.block_on(tokio::time::timeout(IDLE_HEARTBEAT, self.inner.next()))
+            });
+            match next {
+                Ok(Some(t)) => return StreamElement::Item(t),
+                Ok(None) => {
+                    self.terminated = true;
+                    return StreamElement::FlushAndRestart;
+                }
+                Err(_) if self.control.wake_up() => return StreamElement::FlushBatch,
+                Err(_) => continue,
             }
         }
     }
@@ -1,25 +1,34 @@
 use std::fmt::Display;
 
-use crate::channel::{bounded, Receiver, RecvError, Sender, TryRecvError};
+use crate::channel::{bounded, Receiver, RecvTimeoutError, Sender, TryRecvError};
 
 use crate::block::{BlockStructure, OperatorKind, OperatorStructure, Replication};
-use crate::operator::source::Source;
+use crate::job::SourceControl;
+use crate::operator::source::{Source, IDLE_HEARTBEAT};
 use crate::operator::{Data, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 
 const MAX_RETRY: u8 = 8;
 
 /// Source that consumes an iterator and emits all its elements into the stream.
 ///
 /// The iterator will be consumed **only from one replica**, therefore this source is not parallel.
-
+///
+/// The items read from the channel cannot be read again, so a job with a source created by
+/// [`ChannelSource::new`] fails to start if the checkpoints are enabled. The sources created by
+/// [`ChannelSource::share`] are checkpointed: the channel itself keeps the position of the
+/// following executions of the job.
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct ChannelSource<Out: Data> {
     #[derivative(Debug = "ignore")]
     rx: Receiver<Out>,
     terminated: bool,
+    control: SourceControl,
     retry_count: u8,
+    /// Whether the channel is shared with the next executions of the job.
+    shared: bool,
 }
 
 impl<Out: Data> Display for ChannelSource<Out> {
@@ -52,11 +61,29 @@ impl<Out: Data> ChannelSource<Out> {
         let s = Self {
             rx,
             terminated: false,
+            control: Default::default(),
             retry_count: 0,
+            shared: false,
         };
 
         (tx, s)
     }
+
+    /// Create another source reading from the same channel.
+    ///
+    /// This is useful to build again the job graph of a job started with
+    /// [`StreamEnvironment::execute_rescalable`](crate::StreamEnvironment::execute_rescalable): each
+    /// execution of the job gets its own source, and the items not yet read by an execution are
+    /// read by the next one. The sources should not be part of jobs running at the same time.
+    pub fn share(&self) -> Self {
+        Self {
+            rx: self.rx.clone(),
+            terminated: false,
+            control: Default::default(),
+            retry_count: 0,
+            shared: true,
+        }
+    }
 }
 // TODO: remove Debug requirement
 impl<Out: Data + core::fmt::Debug> Source<Out> for ChannelSource<Out> {
@@ -66,13 +93,30 @@ impl<Out: Data + core::fmt::Debug> Source<Out> for ChannelSource<Out> {
 }
 
 impl<Out: Data + core::fmt::Debug> Operator<Out> for ChannelSource<Out> {
-    fn setup(&mut self, _metadata: &mut ExecutionMetadata) {}
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        let _operator = OperatorIndex::FIRST.enter();
+        self.control = SourceControl::new(metadata);
+        if !self.shared {
+            if let Some(checkpoints) = metadata.job.checkpoints() {
+                checkpoints.disable("channel sources are not rewindable, use ChannelSource::share");
+            }
+        }
+    }
 
     fn next(&mut self) -> StreamElement<Out> {
+        let _operator = OperatorIndex::FIRST.enter();
         loop {
             if self.terminated {
                 return StreamElement::Terminate;
             }
+            self.control.check_stopped();
+            if self.control.is_cancelled() {
+                self.terminated = true;
+                return StreamElement::FlushAndRestart;
+            }
+            if let Some(id) = self.control.checkpoint() {
+                return StreamElement::Checkpoint(id);
+            }
             let result = self.rx.try_recv();
 
             log::debug!("Channel received stuff");
@@ -93,10 +137,16 @@ impl<Out: Data + core::fmt::Debug> Operator<Out> for ChannelSource<Out> {
                 }
                 Err(TryRecvError::Empty) => {
                     log::debug!("flushed and no values ready, blocking");
-                    self.retry_count = 0;
-                    match self.rx.recv() {
-                        Ok(t) => return StreamElement::Item(t),
-                        Err(RecvError::Disconnected) => {
+                    match self.rx.recv_timeout(IDLE_HEARTBEAT) {
+                        Ok(t) => {
+                            self.retry_count = 0;
+                            return StreamElement::Item(t);
+                        }
+                        Err(RecvTimeoutError::Timeout) if self.control.wake_up() => {
+                            return StreamElement::FlushBatch
+                        }
+                        Err(RecvTimeoutError::Timeout) => continue,
+                        Err(RecvTimeoutError::Disconnected) => {
                             self.terminated = true;
                             log::info!("Stream disconnected");
                             return StreamElement::FlushAndRestart;
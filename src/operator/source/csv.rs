@@ -9,10 +9,13 @@ use csv::{Reader, ReaderBuilder, Terminator, Trim};
 use serde::Deserialize;
 
 use crate::block::{BlockStructure, OperatorKind, OperatorStructure, Replication};
+use crate::checkpoint::OperatorState;
 use crate::data_type::{NoirData, NoirDataCsv};
+use crate::job::SourceControl;
 use crate::operator::source::Source;
 use crate::operator::{Data, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 use crate::Stream;
 
 /// Wrapper that limits the bytes that can be read from a type that implements `io::Read`.
@@ -96,6 +99,12 @@ pub struct CsvSource<Out: Data + for<'a> Deserialize<'a>> {
     options: CsvOptions,
     /// Whether the reader has terminated its job.
     terminated: bool,
+    /// Cancellation and checkpoints of the job.
+    control: SourceControl,
+    /// The offset of the first byte read by `csv_reader`, the offset of the next record is saved
+    /// in the checkpoints.
+    start: u64,
+    state: Option<OperatorState>,
     replication: Replication,
     _out: PhantomData<Out>,
 }
@@ -145,6 +154,9 @@ impl<Out: Data + for<'a> Deserialize<'a>> CsvSource<Out> {
             csv_reader: None,
             options: Default::default(),
             terminated: false,
+            control: Default::default(),
+            start: 0,
+            state: None,
             replication: Replication::Unlimited,
             _out: PhantomData,
         }
@@ -274,6 +286,8 @@ impl<Out: Data + for<'a> Deserialize<'a>> Source<Out> for CsvSource<Out> {
 
 impl<Out: Data + for<'a> Deserialize<'a>> Operator<Out> for CsvSource<Out> {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        let _operator = OperatorIndex::FIRST.enter();
+        self.control = SourceControl::new(metadata);
         let global_id = metadata.global_id;
         let instances = metadata.replicas.len();
 
@@ -344,6 +358,13 @@ impl<Out: Data + for<'a> Deserialize<'a>> Operator<Out> for CsvSource<Out> {
                 .expect("Error while reading last line from file") as u64;
         }
 
+        // Resume from the offset saved in the checkpoint
+        self.state = metadata.operator_state();
+        if let Some(offset) = self.state.as_ref().and_then(|s| s.restore()) {
+            start = offset;
+        }
+        self.start = start;
+
         // Rewind BufReader to the start
         buf_reader
             .seek(SeekFrom::Start(start))
@@ -380,9 +401,21 @@ impl<Out: Data + for<'a> Deserialize<'a>> Operator<Out> for CsvSource<Out> {
     }
 
     fn next(&mut self) -> StreamElement<Out> {
+        let _operator = OperatorIndex::FIRST.enter();
         if self.terminated {
             return StreamElement::Terminate;
         }
+        self.control.check_stopped();
+        if self.control.is_cancelled() {
+            self.terminated = true;
+            return StreamElement::FlushAndRestart;
+        }
+        if let Some(id) = self.control.checkpoint() {
+            if let (Some(state), Some(reader)) = (&self.state, &self.csv_reader) {
+                state.snapshot(id, &(self.start + reader.position().byte()));
+            }
+            return StreamElement::Checkpoint(id);
+        }
         let csv_reader = self
             .csv_reader
             .as_mut()
@@ -415,6 +448,9 @@ impl<Out: Data + for<'a> Deserialize<'a>> Clone for CsvSource<Out> {
             csv_reader: None,
             options: self.options.clone(),
             terminated: false,
+            control: Default::default(),
+            start: 0,
+            state: None,
             replication: self.replication,
             _out: PhantomData,
         }
@@ -465,7 +501,7 @@ mod tests {
                 let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
                 let source = CsvSource::<(i32, i32)>::new(file.path()).has_headers(false);
                 let res = env.stream(source).shuffle().collect_vec();
-                env.execute_blocking();
+                env.execute_blocking().unwrap();
 
                 let mut res = res.get().unwrap();
                 res.sort_unstable();
@@ -493,7 +529,7 @@ mod tests {
                 let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
                 let source = CsvSource::<T>::new(file.path());
                 let res = env.stream(source).shuffle().collect_vec();
-                env.execute_blocking();
+                env.execute_blocking().unwrap();
 
                 let res = res
                     .get()
@@ -527,7 +563,7 @@ mod tests {
                 let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
                 let source = CsvSource::<NoirDataCsv>::new(file.path()).has_headers(false);
                 let res = env.stream(source).map(NoirData::from).collect_vec();
-                env.execute_blocking();
+                env.execute_blocking().unwrap();
 
                 let res = res.get().unwrap().into_iter().sorted().collect_vec();
                 assert_eq!(
@@ -557,7 +593,7 @@ mod tests {
                 let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
                 let source = CsvSource::<NoirDataCsv>::new(file.path());
                 let res = env.stream(source).map(NoirData::from).collect_vec();
-                env.execute_blocking();
+                env.execute_blocking().unwrap();
 
                 let res = res.get().unwrap().into_iter().sorted().collect_vec();
                 assert_eq!(
@@ -7,9 +7,11 @@ use csv::{Reader, ReaderBuilder, Terminator, Trim};
 
 use crate::block::{BlockStructure, OperatorKind, OperatorStructure, Replication};
 use crate::data_type::{NoirData, NoirType};
+use crate::job::SourceControl;
 use crate::operator::source::Source;
 use crate::operator::{Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 use crate::Stream;
 
 use super::{CsvOptions, LimitedReader};
@@ -26,6 +28,8 @@ pub struct RowCsvSource {
     options: CsvOptions,
     /// Whether the reader has terminated its job.
     terminated: bool,
+    /// Cancellation and checkpoints of the job.
+    control: SourceControl,
     replication: Replication,
     record: csv::StringRecord,
 }
@@ -75,6 +79,7 @@ impl RowCsvSource {
             csv_reader: None,
             options: Default::default(),
             terminated: false,
+            control: Default::default(),
             replication: Replication::Unlimited,
             record: csv::StringRecord::new(),
         }
@@ -204,6 +209,8 @@ impl Source<NoirData> for RowCsvSource {
 
 impl Operator<NoirData> for RowCsvSource {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        let _operator = OperatorIndex::FIRST.enter();
+        self.control = SourceControl::new(metadata);
         let global_id = metadata.global_id;
         let instances = metadata.replicas.len();
 
@@ -310,9 +317,18 @@ impl Operator<NoirData> for RowCsvSource {
     }
 
     fn next(&mut self) -> StreamElement<NoirData> {
+        let _operator = OperatorIndex::FIRST.enter();
         if self.terminated {
             return StreamElement::Terminate;
         }
+        self.control.check_stopped();
+        if self.control.is_cancelled() {
+            self.terminated = true;
+            return StreamElement::FlushAndRestart;
+        }
+        if let Some(id) = self.control.checkpoint() {
+            return StreamElement::Checkpoint(id);
+        }
         let csv_reader = self
             .csv_reader
             .as_mut()
@@ -374,6 +390,7 @@ impl Clone for RowCsvSource {
             csv_reader: None,
             options: self.options.clone(),
             terminated: false,
+            control: Default::default(),
             replication: self.replication,
             record: csv::StringRecord::new(),
         }
@@ -415,7 +432,7 @@ mod tests {
                 let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
                 let source = RowCsvSource::new(file.path()).has_headers(false);
                 let res = env.stream(source).map(NoirData::from).collect_vec();
-                env.execute_blocking();
+                env.execute_blocking().unwrap();
 
                 let res = res.get().unwrap().into_iter().sorted().collect_vec();
                 assert_eq!(
@@ -445,7 +462,7 @@ mod tests {
                 let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
                 let source = RowCsvSource::new(file.path());
                 let res = env.stream(source).map(NoirData::from).collect_vec();
-                env.execute_blocking();
+                env.execute_blocking().unwrap();
 
                 let res = res.get().unwrap().into_iter().sorted().collect_vec();
                 assert_eq!(
@@ -475,7 +492,7 @@ mod tests {
                 let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
                 let source = RowCsvSource::new(file.path());
                 let res = env.stream(source).map(NoirData::from).collect_vec();
-                env.execute_blocking();
+                env.execute_blocking().unwrap();
 
                 let res = res.get().unwrap().into_iter().sorted().collect_vec();
                 assert_eq!(
@@ -7,10 +7,13 @@ use std::path::PathBuf;
 
 use crate::block::Replication;
 use crate::block::{BlockStructure, OperatorKind, OperatorStructure};
+use crate::checkpoint::OperatorState;
+use crate::job::SourceControl;
 use crate::network::Coord;
 use crate::operator::source::Source;
 use crate::operator::{Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 use crate::Stream;
 
 /// Source that reads a text file line-by-line.
@@ -24,6 +27,9 @@ pub struct FileSource {
     current: usize,
     end: usize,
     terminated: bool,
+    control: SourceControl,
+    /// The offset of the next line, saved in the checkpoints.
+    state: Option<OperatorState>,
     coord: Option<Coord>,
 }
 
@@ -62,6 +68,8 @@ impl FileSource {
             current: 0,
             end: 0,
             terminated: false,
+            control: Default::default(),
+            state: None,
             coord: None,
         }
     }
@@ -75,6 +83,8 @@ impl Source<String> for FileSource {
 
 impl Operator<String> for FileSource {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        let _operator = OperatorIndex::FIRST.enter();
+        self.control = SourceControl::new(metadata);
         let global_id = metadata.global_id;
         let instances = metadata.replicas.len();
 
@@ -108,15 +118,35 @@ impl Operator<String> for FileSource {
                 .read_until(b'\n', &mut v)
                 .expect("Cannot read line from file");
         }
+        // resume from the offset saved in the checkpoint
+        self.state = metadata.operator_state();
+        if let Some(offset) = self.state.as_ref().and_then(|s| s.restore::<usize>()) {
+            self.current = offset;
+            reader
+                .seek(SeekFrom::Start(offset as u64))
+                .expect("seek file");
+        }
         self.coord = Some(metadata.coord);
         self.reader = Some(reader);
     }
 
     fn next(&mut self) -> StreamElement<String> {
+        let _operator = OperatorIndex::FIRST.enter();
         if self.terminated {
             log::trace!("terminate {}", self.coord.unwrap());
             return StreamElement::Terminate;
         }
+        self.control.check_stopped();
+        if self.control.is_cancelled() {
+            self.terminated = true;
+            return StreamElement::FlushAndRestart;
+        }
+        if let Some(id) = self.control.checkpoint() {
+            if let Some(state) = &self.state {
+                state.snapshot(id, &self.current);
+            }
+            return StreamElement::Checkpoint(id);
+        }
         let element = if self.current <= self.end {
             let mut line = String::new();
             match self
@@ -162,6 +192,8 @@ impl Clone for FileSource {
             current: 0,
             end: 0,
             terminated: false,
+            control: Default::default(),
+            state: None,
             coord: None,
         }
     }
@@ -0,0 +1,423 @@
+use std::fmt::Display;
+#[cfg(feature = "timestamp")]
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::block::{BlockStructure, OperatorKind, OperatorStructure, Replication};
+use crate::checkpoint::OperatorState;
+use crate::concurrency;
+use crate::job::SourceControl;
+use crate::network::Coord;
+use crate::operator::source::Source;
+#[cfg(feature = "timestamp")]
+use crate::operator::Timestamp;
+use crate::operator::{Data, Operator, StreamElement};
+use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
+use crate::CoordUInt;
+
+/// If the next event is due in more than this, flush the current batch before waiting.
+const FLUSH_THRESHOLD: Duration = Duration::from_millis(1);
+
+/// Event-time assignment of a [`GeneratorSource`].
+#[cfg(feature = "timestamp")]
+#[derive(Clone)]
+struct EventTime<Out> {
+    timestamp_gen: Arc<dyn Fn(&Out) -> Timestamp + Send + Sync>,
+    /// Emit a watermark every this many events of the replica.
+    watermark_interval: u64,
+    /// Number of events generated since the last watermark.
+    since_watermark: u64,
+    /// Largest timestamp generated so far by this replica.
+    max_timestamp: Option<Timestamp>,
+}
+
+/// Source that generates synthetic events at a controlled rate, useful for benchmarking.
+///
+/// Each event is built by calling the generator function with the _sequence number_ of the event.
+/// The sequence numbers are spread in round robin between the replicas (replica `i` out of `n`
+/// generates the events `i`, `i + n`, `i + 2n`, ...), so the set of generated events does not
+/// depend on the parallelism.
+///
+/// The source can be limited by the total number of events ([`GeneratorSource::count`]) and/or
+/// by the time it runs for ([`GeneratorSource::duration`]); without limits the stream is unbounded.
+///
+/// The checkpoints save the sequence number of the next event of each replica: a job resuming from
+/// a checkpoint with the same number of replicas continues the sequences, while the rate and the
+/// duration are measured again from the start of the resumed job.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct GeneratorSource<Out: Data, F>
+where
+    F: FnMut(u64) -> Out + Clone + Send + 'static,
+{
+    #[derivative(Debug = "ignore")]
+    generator: F,
+    /// Target number of events per second, across all the replicas.
+    rate: Option<f64>,
+    /// Total number of events to generate, across all the replicas.
+    count: Option<u64>,
+    /// For how long the events are generated.
+    duration: Option<Duration>,
+    #[cfg(feature = "timestamp")]
+    #[derivative(Debug = "ignore")]
+    event_time: Option<EventTime<Out>>,
+    replication: Replication,
+
+    /// Sequence number of the next event of this replica.
+    next_seq: u64,
+    /// Distance between two sequence numbers of this replica.
+    step: u64,
+    /// Number of events generated by this replica since the job started.
+    generated: u64,
+    /// The instant the first event was generated at.
+    start: Option<Instant>,
+    /// Whether a `FlushBatch` has been sent since the last item.
+    flushed: bool,
+    #[cfg(feature = "timestamp")]
+    pending_watermark: Option<Timestamp>,
+    terminated: bool,
+    control: SourceControl,
+    /// The sequence number of the next event, saved in the checkpoints.
+    state: Option<OperatorState>,
+    coord: Option<Coord>,
+}
+
+impl<Out: Data, F> Display for GeneratorSource<Out, F>
+where
+    F: FnMut(u64) -> Out + Clone + Send + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GeneratorSource<{}>", std::any::type_name::<Out>())
+    }
+}
+
+impl<Out: Data, F> GeneratorSource<Out, F>
+where
+    F: FnMut(u64) -> Out + Clone + Send + 'static,
+{
+    /// Create a new source that generates the events using the provided function.
+    ///
+    /// The function is cloned in each replica and it is called with the sequence number of the
+    /// event to generate. By default the events are generated as fast as possible, without limits.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::GeneratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    /// // 1000 events, 10000 events per second in total
+    /// let source = GeneratorSource::new(|seq| seq * 2)
+    ///     .rate(10_000.0)
+    ///     .count(1000);
+    /// let res = env.stream(source).collect_count();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap(), 1000);
+    /// ```
+    pub fn new(generator: F) -> Self {
+        Self {
+            generator,
+            rate: None,
+            count: None,
+            duration: None,
+            #[cfg(feature = "timestamp")]
+            event_time: None,
+            replication: Replication::Unlimited,
+            next_seq: 0,
+            step: 1,
+            generated: 0,
+            start: None,
+            flushed: false,
+            #[cfg(feature = "timestamp")]
+            pending_watermark: None,
+            terminated: false,
+            control: Default::default(),
+            state: None,
+            coord: None,
+        }
+    }
+
+    /// Limit the generation to `events_per_sec` events per second, split evenly between the
+    /// replicas.
+    pub fn rate(mut self, events_per_sec: f64) -> Self {
+        assert!(
+            events_per_sec > 0.0,
+            "GeneratorSource rate must be greater than zero"
+        );
+        self.rate = Some(events_per_sec);
+        self
+    }
+
+    /// Generate `count` events in total, across all the replicas.
+    pub fn count(mut self, count: u64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Stop generating events after `duration` from the first event.
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Tag each event with the timestamp returned by `timestamp_gen` and emit a watermark every
+    /// `watermark_interval` events of each replica.
+    ///
+    /// The watermark is the largest timestamp generated by the replica so far, therefore the
+    /// timestamps must be non-decreasing within a replica. This holds if the timestamp is a
+    /// non-decreasing function of the sequence number.
+    #[cfg(feature = "timestamp")]
+    pub fn event_time<G>(mut self, timestamp_gen: G, watermark_interval: u64) -> Self
+    where
+        G: Fn(&Out) -> Timestamp + Send + Sync + 'static,
+    {
+        assert!(
+            watermark_interval > 0,
+            "GeneratorSource watermark interval must be greater than zero"
+        );
+        self.event_time = Some(EventTime {
+            timestamp_gen: Arc::new(timestamp_gen),
+            watermark_interval,
+            since_watermark: 0,
+            max_timestamp: None,
+        });
+        self
+    }
+
+    /// Set the number of replicas of this source.
+    ///
+    /// The default is [`Replication::Unlimited`].
+    pub fn replication(mut self, replication: Replication) -> Self {
+        self.replication = replication;
+        self
+    }
+
+    /// The instant the next event of this replica should be generated at, if rate-limited.
+    fn deadline(&self, start: Instant) -> Option<Instant> {
+        let replica_rate = self.rate? / self.step as f64;
+        Some(start + Duration::from_secs_f64(self.generated as f64 / replica_rate))
+    }
+
+    /// Whether this replica has generated all its events.
+    fn exhausted(&self, start: Instant) -> bool {
+        let count_reached = self.count.is_some_and(|count| self.next_seq >= count);
+        let time_reached = self
+            .duration
+            .is_some_and(|duration| start.elapsed() >= duration);
+        count_reached || time_reached
+    }
+
+    #[cfg(feature = "timestamp")]
+    fn tag(&mut self, item: Out) -> StreamElement<Out> {
+        let event_time = match &mut self.event_time {
+            Some(event_time) => event_time,
+            None => return StreamElement::Item(item),
+        };
+        let ts = (event_time.timestamp_gen)(&item);
+        let max = event_time.max_timestamp.map_or(ts, |max| max.max(ts));
+        event_time.max_timestamp = Some(max);
+        event_time.since_watermark += 1;
+        if event_time.since_watermark == event_time.watermark_interval {
+            event_time.since_watermark = 0;
+            self.pending_watermark = Some(max);
+        }
+        StreamElement::Timestamped(item, ts)
+    }
+
+    #[cfg(not(feature = "timestamp"))]
+    fn tag(&mut self, item: Out) -> StreamElement<Out> {
+        StreamElement::Item(item)
+    }
+}
+
+impl<Out: Data, F> Source<Out> for GeneratorSource<Out, F>
+where
+    F: FnMut(u64) -> Out + Clone + Send + 'static,
+{
+    fn replication(&self) -> Replication {
+        self.replication
+    }
+}
+
+impl<Out: Data, F> Operator<Out> for GeneratorSource<Out, F>
+where
+    F: FnMut(u64) -> Out + Clone + Send + 'static,
+{
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        let _operator = OperatorIndex::FIRST.enter();
+        self.control = SourceControl::new(metadata);
+        let instances: CoordUInt = metadata
+            .replicas
+            .len()
+            .try_into()
+            .expect("Num replicas > max id");
+        self.next_seq = metadata.global_id;
+        self.step = instances;
+        self.state = metadata.operator_state();
+        if let Some(next_seq) = self.state.as_ref().and_then(|s| s.restore::<u64>()) {
+            self.next_seq = next_seq;
+        }
+        self.coord = Some(metadata.coord);
+    }
+
+    fn next(&mut self) -> StreamElement<Out> {
+        let _operator = OperatorIndex::FIRST.enter();
+        #[cfg(feature = "timestamp")]
+        if let Some(ts) = self.pending_watermark.take() {
+            return StreamElement::Watermark(ts);
+        }
+        if self.terminated {
+            log::trace!("terminate {}", self.coord.unwrap());
+            return StreamElement::Terminate;
+        }
+        self.control.check_stopped();
+        if self.control.is_cancelled() {
+            self.terminated = true;
+            return StreamElement::FlushAndRestart;
+        }
+        if let Some(id) = self.control.checkpoint() {
+            if let Some(state) = &self.state {
+                state.snapshot(id, &self.next_seq);
+            }
+            return StreamElement::Checkpoint(id);
+        }
+
+        let start = *self.start.get_or_insert_with(Instant::now);
+        if self.exhausted(start) {
+            self.terminated = true;
+            return StreamElement::FlushAndRestart;
+        }
+        if let Some(deadline) = self.deadline(start) {
+            let now = Instant::now();
+            if deadline > now {
+                // do not keep the generated events in a batch while waiting for the next one
+                if deadline - now > FLUSH_THRESHOLD && !self.flushed {
+                    self.flushed = true;
+                    return StreamElement::FlushBatch;
+                }
+                concurrency::blocking(|| std::thread::sleep(deadline - now));
+                if self.exhausted(start) {
+                    self.terminated = true;
+                    return StreamElement::FlushAndRestart;
+                }
+            }
+        }
+
+        let item = (self.generator)(self.next_seq);
+        self.next_seq += self.step;
+        self.generated += 1;
+        self.flushed = false;
+        self.tag(item)
+    }
+
+    fn structure(&self) -> BlockStructure {
+        let mut operator = OperatorStructure::new::<Out, _>("GeneratorSource");
+        operator.kind = OperatorKind::Source;
+        BlockStructure::default().add_operator(operator)
+    }
+}
+
+impl<Out: Data, F> Clone for GeneratorSource<Out, F>
+where
+    F: FnMut(u64) -> Out + Clone + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        assert!(
+            self.start.is_none(),
+            "GeneratorSource must be cloned before generating events"
+        );
+        Self {
+            generator: self.generator.clone(),
+            rate: self.rate,
+            count: self.count,
+            duration: self.duration,
+            #[cfg(feature = "timestamp")]
+            event_time: self.event_time.clone(),
+            replication: self.replication,
+            next_seq: 0,
+            step: 1,
+            generated: 0,
+            start: None,
+            flushed: false,
+            #[cfg(feature = "timestamp")]
+            pending_watermark: None,
+            terminated: false,
+            control: Default::default(),
+            state: None,
+            coord: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use itertools::Itertools;
+
+    use crate::config::EnvironmentConfig;
+    use crate::environment::StreamEnvironment;
+    use crate::operator::source::GeneratorSource;
+
+    #[test]
+    fn generator_count() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        let source = GeneratorSource::new(|seq| seq * 10).count(1000);
+        let res = env.stream(source).collect_vec();
+        env.execute_blocking().unwrap();
+
+        let res = res.get().unwrap().into_iter().sorted().collect_vec();
+        assert_eq!(res, (0..1000).map(|seq| seq * 10).collect_vec());
+    }
+
+    #[test]
+    fn generator_rate() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(2));
+        let source = GeneratorSource::new(|seq| seq).rate(1000.0).count(200);
+        let res = env.stream(source).collect_count();
+        let start = Instant::now();
+        env.execute_blocking().unwrap();
+
+        assert_eq!(res.get().unwrap(), 200);
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+
+    #[test]
+    fn generator_duration() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(2));
+        let source = GeneratorSource::new(|seq| seq)
+            .rate(1000.0)
+            .duration(Duration::from_millis(100));
+        let res = env.stream(source).collect_count();
+        env.execute_blocking().unwrap();
+
+        let count = res.get().unwrap();
+        assert!(count > 0 && count <= 110, "generated {count} events");
+    }
+
+    #[test]
+    #[cfg(feature = "timestamp")]
+    fn generator_event_time() {
+        use crate::operator::{Operator, StreamElement};
+        use crate::test::FakeNetworkTopology;
+
+        let mut topology = FakeNetworkTopology::<u64>::new(0, 0);
+        let mut source = GeneratorSource::new(|seq| seq)
+            .count(5)
+            .event_time(|&seq| seq as i64 * 10, 2);
+        source.setup(&mut topology.metadata());
+
+        assert_eq!(source.next(), StreamElement::Timestamped(0, 0));
+        assert_eq!(source.next(), StreamElement::Timestamped(1, 10));
+        assert_eq!(source.next(), StreamElement::Watermark(10));
+        assert_eq!(source.next(), StreamElement::Timestamped(2, 20));
+        assert_eq!(source.next(), StreamElement::Timestamped(3, 30));
+        assert_eq!(source.next(), StreamElement::Watermark(30));
+        assert_eq!(source.next(), StreamElement::Timestamped(4, 40));
+        assert_eq!(source.next(), StreamElement::FlushAndRestart);
+        assert_eq!(source.next(), StreamElement::Terminate);
+    }
+}
@@ -1,14 +1,20 @@
 use std::fmt::Display;
 
 use crate::block::{BlockStructure, OperatorKind, OperatorStructure, Replication};
+use crate::checkpoint::OperatorState;
+use crate::job::SourceControl;
 use crate::operator::source::Source;
 use crate::operator::{Data, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 use crate::Stream;
 
 /// Source that consumes an iterator and emits all its elements into the stream.
 ///
 /// The iterator will be consumed **only from one replica**, therefore this source is not parallel.
+///
+/// The checkpoints save the number of items emitted so far: a job resuming from a checkpoint skips
+/// as many items of its iterator, which should yield the same items in the same order.
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct IteratorSource<Out: Data, It>
@@ -18,6 +24,10 @@ where
     #[derivative(Debug = "ignore")]
     inner: It,
     terminated: bool,
+    control: SourceControl,
+    /// The number of items emitted, saved in the checkpoints.
+    emitted: u64,
+    state: Option<OperatorState>,
 }
 
 impl<Out: Data, It> Display for IteratorSource<Out, It>
@@ -53,6 +63,9 @@ where
         Self {
             inner,
             terminated: false,
+            control: Default::default(),
+            emitted: 0,
+            state: None,
         }
     }
 }
@@ -70,15 +83,39 @@ impl<Out: Data, It> Operator<Out> for IteratorSource<Out, It>
 where
     It: Iterator<Item = Out> + Send + 'static,
 {
-    fn setup(&mut self, _metadata: &mut ExecutionMetadata) {}
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        let _operator = OperatorIndex::FIRST.enter();
+        self.control = SourceControl::new(metadata);
+        // skip the items emitted before the checkpoint
+        self.state = metadata.operator_state();
+        if let Some(emitted) = self.state.as_ref().and_then(|s| s.restore::<u64>()) {
+            self.inner.by_ref().take(emitted as usize).for_each(drop);
+            self.emitted = emitted;
+        }
+    }
 
     fn next(&mut self) -> StreamElement<Out> {
+        let _operator = OperatorIndex::FIRST.enter();
         if self.terminated {
             return StreamElement::Terminate;
         }
+        self.control.check_stopped();
+        if self.control.is_cancelled() {
+            self.terminated = true;
+            return StreamElement::FlushAndRestart;
+        }
+        if let Some(id) = self.control.checkpoint() {
+            if let Some(state) = &self.state {
+                state.snapshot(id, &self.emitted);
+            }
+            return StreamElement::Checkpoint(id);
+        }
         // TODO: with adaptive batching this does not work since it never emits FlushBatch messages
         match self.inner.next() {
-            Some(t) => StreamElement::Item(t),
+            Some(t) => {
+                self.emitted += 1;
+                StreamElement::Item(t)
+            }
             None => {
                 self.terminated = true;
                 StreamElement::FlushAndRestart
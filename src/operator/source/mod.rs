@@ -6,9 +6,15 @@ pub use self::csv_fast::*;
 pub use async_stream::*;
 pub use channel::*;
 pub use file::*;
+pub use generator::*;
 pub use iterator::*;
 pub use parallel_iterator::*;
+pub use socket::*;
 
+use std::time::Duration;
+
+#[cfg(feature = "timestamp")]
+use crate::operator::{add_timestamps::AddTimestamp, watermark::WatermarkGenerator, Timestamp};
 use crate::{
     block::Replication,
     operator::{Data, Operator},
@@ -20,11 +26,57 @@ mod channel;
 mod csv;
 pub mod csv_fast;
 mod file;
+mod generator;
 mod iterator;
 mod parallel_iterator;
+mod socket;
+
+/// While waiting for new elements, the sources that can block wake up with this period to notice
+/// the cancellation of the job and the checkpoints. If an operator of the block asked to be woken
+/// up (e.g. a watermark generator that detects idleness), they also emit a `FlushBatch` so that it
+/// can react to the passing time.
+pub(crate) const IDLE_HEARTBEAT: Duration = Duration::from_millis(100);
 
 /// This trait marks all the operators that can be used as sinks.
 pub trait Source<Out: Data>: Operator<Out> {
     /// The maximum parallelism offered by this operator.
     fn replication(&self) -> Replication;
+
+    /// Tag each element produced by this source with a timestamp and insert the watermarks
+    /// decided by `watermark_gen`.
+    ///
+    /// This is the same as calling [`Stream::add_timestamps_with`](crate::Stream::add_timestamps_with)
+    /// right after the source, but it makes clear that the watermarks are generated per source
+    /// replica, which is where idleness should be detected.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// use noir::operator::source::{ChannelSource, Source};
+    /// use noir::operator::watermark::BoundedOutOfOrderness;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    ///
+    /// let (tx, source) = ChannelSource::<i64>::new(64);
+    /// let source = source.with_timestamps(
+    ///     |&n| n,
+    ///     BoundedOutOfOrderness::new(10).idle_timeout(Duration::from_secs(1)),
+    /// );
+    /// let s = env.stream(source);
+    /// # drop(tx);
+    /// ```
+    #[cfg(feature = "timestamp")]
+    fn with_timestamps<F, W>(
+        self,
+        timestamp_gen: F,
+        watermark_gen: W,
+    ) -> AddTimestamp<Out, F, W, Self>
+    where
+        Self: Sized,
+        F: FnMut(&Out) -> Timestamp + Clone + Send + 'static,
+        W: WatermarkGenerator<Out>,
+    {
+        AddTimestamp::with_generator(self, timestamp_gen, watermark_gen)
+    }
 }
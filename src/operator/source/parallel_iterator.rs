@@ -2,9 +2,12 @@ use std::fmt::Display;
 use std::ops::Range;
 
 use crate::block::{BlockStructure, OperatorKind, OperatorStructure, Replication};
+use crate::checkpoint::OperatorState;
+use crate::job::SourceControl;
 use crate::operator::source::Source;
 use crate::operator::{Data, Operator, StreamElement};
 use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
 use crate::{CoordUInt, Stream};
 
 pub trait IntoParallelSource: Clone + Send {
@@ -131,6 +134,10 @@ impl<Source: IntoParallelSource> Clone for IteratorGenerator<Source> {
 ///
 /// Each replica (i.e. each core) will have a different iterator. The iterator are produced by a
 /// generating function passed to the [`ParallelIteratorSource::new`] method.
+///
+/// The checkpoints save the number of items emitted by each replica: a job resuming from a
+/// checkpoint with the same number of replicas skips as many items of each iterator, which should
+/// yield the same items in the same order.
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct ParallelIteratorSource<Source>
@@ -141,6 +148,10 @@ where
     #[derivative(Debug = "ignore")]
     inner: IteratorGenerator<Source>,
     terminated: bool,
+    control: SourceControl,
+    /// The number of items emitted by this replica, saved in the checkpoints.
+    emitted: u64,
+    state: Option<OperatorState>,
 }
 
 impl<Source> Display for ParallelIteratorSource<Source>
@@ -192,6 +203,9 @@ where
         Self {
             inner: IteratorGenerator::Generator(generator),
             terminated: false,
+            control: Default::default(),
+            emitted: 0,
+            state: None,
         }
     }
 }
@@ -214,6 +228,8 @@ where
     Source::Item: Data,
 {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        let _operator = OperatorIndex::FIRST.enter();
+        self.control = SourceControl::new(metadata);
         self.inner.generate(
             metadata.global_id,
             metadata
@@ -222,15 +238,38 @@ where
                 .try_into()
                 .expect("Num replicas > max id"),
         );
+        // skip the items emitted before the checkpoint
+        self.state = metadata.operator_state();
+        if let Some(emitted) = self.state.as_ref().and_then(|s| s.restore::<u64>()) {
+            for _ in 0..emitted {
+                self.inner.next();
+            }
+            self.emitted = emitted;
+        }
     }
 
     fn next(&mut self) -> StreamElement<Source::Item> {
+        let _operator = OperatorIndex::FIRST.enter();
         if self.terminated {
             return StreamElement::Terminate;
         }
+        self.control.check_stopped();
+        if self.control.is_cancelled() {
+            self.terminated = true;
+            return StreamElement::FlushAndRestart;
+        }
+        if let Some(id) = self.control.checkpoint() {
+            if let Some(state) = &self.state {
+                state.snapshot(id, &self.emitted);
+            }
+            return StreamElement::Checkpoint(id);
+        }
         // TODO: with adaptive batching this does not work since it never emits FlushBatch messages
         match self.inner.next() {
-            Some(t) => StreamElement::Item(t),
+            Some(t) => {
+                self.emitted += 1;
+                StreamElement::Item(t)
+            }
             None => {
                 self.terminated = true;
                 StreamElement::FlushAndRestart
@@ -254,6 +293,9 @@ where
         Self {
             inner: self.inner.clone(),
             terminated: false,
+            control: Default::default(),
+            emitted: 0,
+            state: None,
         }
     }
 }
@@ -282,7 +324,7 @@ impl crate::StreamEnvironment {
     ///     })
     ///    .for_each(|q| println!("b: {q}"));
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     /// ```
     pub fn stream_par_iter<Source>(
         &mut self,
@@ -0,0 +1,740 @@
+use std::fmt::Display;
+use std::io;
+use std::io::Read;
+use std::net::{TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::block::{BlockStructure, OperatorKind, OperatorStructure, Replication};
+use crate::concurrency;
+use crate::job::SourceControl;
+use crate::network::Coord;
+use crate::operator::source::{Source, IDLE_HEARTBEAT};
+use crate::operator::{Data, Operator, StreamElement};
+use crate::scheduler::ExecutionMetadata;
+use crate::worker::OperatorIndex;
+
+/// The socket a [`SocketSource`] reads from, and whether the source should wait for the peer to
+/// connect or it should connect to the peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketEndpoint {
+    /// Bind a TCP listener to this address and accept the incoming connections.
+    TcpListen(String),
+    /// Connect to a TCP socket listening at this address.
+    TcpConnect(String),
+    /// Bind a Unix socket at this path and accept the incoming connections.
+    #[cfg(unix)]
+    UnixListen(PathBuf),
+    /// Connect to a Unix socket listening at this path.
+    #[cfg(unix)]
+    UnixConnect(PathBuf),
+}
+
+impl Display for SocketEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SocketEndpoint::TcpListen(addr) => write!(f, "tcp-listen://{addr}"),
+            SocketEndpoint::TcpConnect(addr) => write!(f, "tcp://{addr}"),
+            #[cfg(unix)]
+            SocketEndpoint::UnixListen(path) => write!(f, "unix-listen://{}", path.display()),
+            #[cfg(unix)]
+            SocketEndpoint::UnixConnect(path) => write!(f, "unix://{}", path.display()),
+        }
+    }
+}
+
+/// Strategy used to split the bytes received from a socket into the elements of the stream.
+pub trait Framing: Clone + Send + 'static {
+    /// The type of the decoded frames.
+    type Out: Data;
+
+    /// Decode the frame at the start of `buf`, returning it together with the number of bytes it
+    /// spans.
+    ///
+    /// Returns `Ok(None)` if `buf` does not contain a whole frame yet: the frame is decoded again
+    /// once more bytes are received. When `eof` is set the peer closed the connection and no more
+    /// bytes will follow, so the remaining bytes should either form a last frame or be reported as
+    /// an error.
+    fn decode(&mut self, buf: &[u8], eof: bool) -> io::Result<Option<(Self::Out, usize)>>;
+}
+
+/// Newline-delimited frames, decoded as UTF-8 strings.
+///
+/// The trailing `\n` (or `\r\n`) is not included in the emitted strings. The bytes after the last
+/// newline are emitted as a last line when the peer closes the connection.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineFraming;
+
+impl Framing for LineFraming {
+    type Out = String;
+
+    fn decode(&mut self, buf: &[u8], eof: bool) -> io::Result<Option<(String, usize)>> {
+        let (mut line, len) = match buf.iter().position(|&b| b == b'\n') {
+            Some(pos) => (&buf[..pos], pos + 1),
+            None if eof && !buf.is_empty() => (buf, buf.len()),
+            None => return Ok(None),
+        };
+        if let Some(stripped) = line.strip_suffix(b"\r") {
+            line = stripped;
+        }
+        let line =
+            std::str::from_utf8(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Some((line.to_string(), len)))
+    }
+}
+
+/// Frames prefixed by their length, encoded as a 4 bytes big-endian unsigned integer.
+#[derive(Debug, Clone, Copy)]
+pub struct LengthDelimitedFraming {
+    /// Frames longer than this are rejected to avoid allocating unbounded buffers.
+    max_frame_len: usize,
+}
+
+impl Default for LengthDelimitedFraming {
+    fn default() -> Self {
+        Self {
+            max_frame_len: 16 << 20,
+        }
+    }
+}
+
+impl LengthDelimitedFraming {
+    /// Reject the frames longer than `max_frame_len` bytes (16MiB by default).
+    pub fn max_frame_len(mut self, max_frame_len: usize) -> Self {
+        self.max_frame_len = max_frame_len;
+        self
+    }
+}
+
+impl Framing for LengthDelimitedFraming {
+    type Out = Vec<u8>;
+
+    fn decode(&mut self, buf: &[u8], eof: bool) -> io::Result<Option<(Vec<u8>, usize)>> {
+        let truncated = || {
+            Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("connection closed inside a frame of {} bytes", buf.len()),
+            ))
+        };
+        let Some(header) = buf.get(..4) else {
+            return if eof && !buf.is_empty() {
+                truncated()
+            } else {
+                Ok(None)
+            };
+        };
+        let len = u32::from_be_bytes(header.try_into().unwrap()) as usize;
+        if len > self.max_frame_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "frame of {len} bytes exceeds the limit of {}",
+                    self.max_frame_len
+                ),
+            ));
+        }
+        match buf.get(4..4 + len) {
+            Some(frame) => Ok(Some((frame.to_vec(), 4 + len))),
+            None if eof => truncated(),
+            None => Ok(None),
+        }
+    }
+}
+
+/// How often a replica waiting for an incoming connection checks the listener.
+const ACCEPT_POLL: Duration = Duration::from_millis(10);
+
+/// How many bytes are read from the socket at once.
+const READ_CHUNK: usize = 8 << 10;
+
+/// A bound listener shared by all the replicas of the same host.
+///
+/// The listener is non-blocking: the replicas waiting for a connection poll it, so that they
+/// keep noticing the cancellation of the job. The file of a Unix socket is removed when the
+/// listener is dropped, after all the replicas ended.
+#[derive(Debug)]
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener, PathBuf),
+}
+
+impl Listener {
+    /// Accept a pending connection, if any.
+    fn accept(&self) -> io::Result<Option<Connection>> {
+        let accepted = match self {
+            Listener::Tcp(listener) => listener.accept().map(|(s, _)| Connection::Tcp(s)),
+            #[cfg(unix)]
+            Listener::Unix(listener, _) => listener.accept().map(|(s, _)| Connection::Unix(s)),
+        };
+        match accepted {
+            Ok(connection) => {
+                // on some platforms the accepted socket inherits the flag of the listener
+                connection.set_nonblocking(false)?;
+                Ok(Some(connection))
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Listener {
+    fn drop(&mut self) {
+        if let Listener::Unix(_, path) = self {
+            remove_stale_socket(path);
+        }
+    }
+}
+
+/// The connection a replica reads from.
+#[derive(Debug)]
+enum Connection {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Connection {
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        match self {
+            Connection::Tcp(stream) => stream.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            Connection::Unix(stream) => stream.set_nonblocking(nonblocking),
+        }
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            Connection::Tcp(stream) => stream.set_read_timeout(timeout),
+            #[cfg(unix)]
+            Connection::Unix(stream) => stream.set_read_timeout(timeout),
+        }
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Connection::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Connection::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+/// The bytes received from the connection that have not been decoded yet.
+#[derive(Debug, Default)]
+struct ReadBuffer {
+    bytes: Vec<u8>,
+    /// Offset of the first byte not decoded yet.
+    start: usize,
+    /// Whether the peer closed the connection.
+    eof: bool,
+}
+
+impl ReadBuffer {
+    fn pending(&self) -> &[u8] {
+        &self.bytes[self.start..]
+    }
+
+    fn consume(&mut self, len: usize) {
+        self.start += len;
+    }
+
+    /// Append the next bytes sent by the peer, waiting for at most [`IDLE_HEARTBEAT`] (the read
+    /// timeout of the connection).
+    ///
+    /// Returns `false` if nothing was received in time. The bytes of a partially received frame
+    /// are kept.
+    fn fill(&mut self, connection: &mut Connection) -> io::Result<bool> {
+        self.bytes.drain(..self.start);
+        self.start = 0;
+        let len = self.bytes.len();
+        self.bytes.resize(len + READ_CHUNK, 0);
+        let read = connection.read(&mut self.bytes[len..]);
+        self.bytes.truncate(len + *read.as_ref().unwrap_or(&0));
+        match read {
+            Ok(0) => {
+                self.eof = true;
+                Ok(true)
+            }
+            Ok(_) => Ok(true),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock
+                        | io::ErrorKind::TimedOut
+                        | io::ErrorKind::Interrupted
+                ) =>
+            {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Source that reads the frames sent by a peer over a TCP or Unix socket.
+///
+/// Each replica handles a single connection and emits the frames read from it, the replica ends
+/// when the peer closes the connection. Depending on the [`SocketEndpoint`] the replicas either
+/// connect to the peer (one connection per replica), or accept one of the connections to a
+/// listener that is shared by all the replicas of the same host.
+///
+/// Since the socket is read only when the following operators ask for more elements, a slow
+/// pipeline fills the socket buffers and the backpressure propagates to the peer.
+///
+/// While waiting for a connection or for the next frame, also in the middle of a frame, the
+/// replicas wake up periodically, so the cancellation of the job is noticed even if the peer is
+/// silent. A replica that cannot connect to the peer keeps retrying until the job is cancelled. A
+/// stale Unix socket file left at the path of a listener is removed before binding, and the file
+/// is removed again when the source ends.
+///
+/// If the listener cannot be bound, or the connection fails with an I/O error or with a malformed
+/// frame, the error is logged and the replica ends its stream as if the peer closed the
+/// connection.
+///
+/// The frames read from the socket cannot be read again, so a job with this source fails to start
+/// if the checkpoints are enabled.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct SocketSource<F: Framing> {
+    endpoint: SocketEndpoint,
+    #[derivative(Debug = "ignore")]
+    framing: F,
+    replication: Replication,
+    listener: Arc<Mutex<Option<Listener>>>,
+    // connection is initialized when the connection is established, before it is None
+    connection: Option<Connection>,
+    buffer: ReadBuffer,
+    /// Whether a `FlushBatch` has been sent since the last item.
+    flushed: bool,
+    /// Whether the failure to connect to the peer has already been logged.
+    connect_warned: bool,
+    /// The error of the listener of this host, reported by the first call to `next`.
+    bind_error: Option<io::Error>,
+    terminated: bool,
+    control: SourceControl,
+    coord: Option<Coord>,
+}
+
+impl<F: Framing> Display for SocketSource<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SocketSource<{}>({})",
+            std::any::type_name::<F::Out>(),
+            self.endpoint
+        )
+    }
+}
+
+impl SocketSource<LineFraming> {
+    /// Create a new source that emits the newline-delimited strings read from the socket.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::{SocketEndpoint, SocketSource};
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let source = SocketSource::lines(SocketEndpoint::TcpListen("0.0.0.0:9000".into()));
+    /// let s = env.stream(source);
+    /// ```
+    pub fn lines(endpoint: SocketEndpoint) -> Self {
+        Self::new(endpoint, LineFraming)
+    }
+}
+
+impl SocketSource<LengthDelimitedFraming> {
+    /// Create a new source that emits the length-prefixed frames read from the socket.
+    ///
+    /// See [`LengthDelimitedFraming`] for the format of the frames.
+    ///
+    /// ## Example
+    ///
+    /// ```no_run
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::{SocketEndpoint, SocketSource};
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let source = SocketSource::length_delimited(SocketEndpoint::TcpConnect("127.0.0.1:9000".into()));
+    /// let s = env.stream(source).map(|frame: Vec<u8>| frame.len());
+    /// ```
+    pub fn length_delimited(endpoint: SocketEndpoint) -> Self {
+        Self::new(endpoint, LengthDelimitedFraming::default())
+    }
+}
+
+impl<F: Framing> SocketSource<F> {
+    /// Create a new source that reads from `endpoint`, splitting the bytes into frames with the
+    /// provided [`Framing`].
+    ///
+    /// By default the source is **not parallel**, use [`SocketSource::replication`] to read from
+    /// multiple connections at the same time.
+    pub fn new(endpoint: SocketEndpoint, framing: F) -> Self {
+        Self {
+            endpoint,
+            framing,
+            replication: Replication::One,
+            listener: Default::default(),
+            connection: None,
+            buffer: Default::default(),
+            flushed: false,
+            connect_warned: false,
+            bind_error: None,
+            terminated: false,
+            control: Default::default(),
+            coord: None,
+        }
+    }
+
+    /// Set the number of replicas of this source, each replica reads from its own connection.
+    ///
+    /// The default is [`Replication::One`].
+    pub fn replication(mut self, replication: Replication) -> Self {
+        self.replication = replication;
+        self
+    }
+
+    /// Bind the listener shared with the other replicas of this host, if not already bound.
+    fn bind(&self) -> io::Result<()> {
+        let mut listener = self.listener.lock();
+        if listener.is_some() {
+            return Ok(());
+        }
+        let bound = match &self.endpoint {
+            SocketEndpoint::TcpListen(addr) => TcpListener::bind(addr)
+                .and_then(|l| l.set_nonblocking(true).map(|_| l))
+                .map(Listener::Tcp)?,
+            #[cfg(unix)]
+            SocketEndpoint::UnixListen(path) => {
+                remove_stale_socket(path);
+                UnixListener::bind(path)
+                    .and_then(|l| l.set_nonblocking(true).map(|_| l))
+                    .map(|l| Listener::Unix(l, path.clone()))?
+            }
+            _ => return Ok(()),
+        };
+        *listener = Some(bound);
+        Ok(())
+    }
+
+    /// Open the connection of this replica, or accept it if one is pending.
+    ///
+    /// Returns `Ok(None)` if no peer connected to the listener yet, or if the peer is not
+    /// reachable yet: the failure is logged once and the connection is retried.
+    fn connect(&mut self) -> io::Result<Option<Connection>> {
+        let stream = match &self.endpoint {
+            SocketEndpoint::TcpConnect(addr) => TcpStream::connect(addr).map(Connection::Tcp),
+            #[cfg(unix)]
+            SocketEndpoint::UnixConnect(path) => UnixStream::connect(path).map(Connection::Unix),
+            _ => {
+                return match self.listener.lock().as_ref() {
+                    Some(listener) => listener.accept(),
+                    None => Err(io::Error::new(
+                        io::ErrorKind::NotConnected,
+                        "the listener is not bound",
+                    )),
+                }
+            }
+        };
+        match stream {
+            Ok(stream) => Ok(Some(stream)),
+            Err(e) => {
+                if !self.connect_warned {
+                    self.connect_warned = true;
+                    log::warn!(
+                        "{} cannot connect to {}, retrying: {e}",
+                        self.coord.unwrap(),
+                        self.endpoint
+                    );
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Log the failure of this replica and end its stream.
+    fn fail(&mut self, what: &str, err: io::Error) -> StreamElement<F::Out> {
+        log::error!(
+            "{} {what} {}, ending the stream: {err}",
+            self.coord.unwrap(),
+            self.endpoint
+        );
+        self.connection = None;
+        self.terminated = true;
+        StreamElement::FlushAndRestart
+    }
+}
+
+/// Remove the socket file left at `path` by a previous listener.
+///
+/// Other kinds of files are not removed, binding to them fails.
+#[cfg(unix)]
+fn remove_stale_socket(path: &std::path::Path) {
+    use std::os::unix::fs::FileTypeExt;
+
+    let is_socket = std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket());
+    if is_socket {
+        if let Err(e) = std::fs::remove_file(path) {
+            log::warn!("cannot remove the stale socket {}: {e}", path.display());
+        }
+    }
+}
+
+impl<F: Framing> Source<F::Out> for SocketSource<F> {
+    fn replication(&self) -> Replication {
+        self.replication
+    }
+}
+
+impl<F: Framing> Operator<F::Out> for SocketSource<F> {
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        let _operator = OperatorIndex::FIRST.enter();
+        self.control = SourceControl::new(metadata);
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("socket sources are not rewindable");
+        }
+        self.coord = Some(metadata.coord);
+        // connections are accepted lazily since all the replicas are set up by the same thread
+        self.bind_error = self.bind().err();
+    }
+
+    fn next(&mut self) -> StreamElement<F::Out> {
+        let _operator = OperatorIndex::FIRST.enter();
+        loop {
+            if self.terminated {
+                log::trace!("terminate {}", self.coord.unwrap());
+                return StreamElement::Terminate;
+            }
+            if let Some(e) = self.bind_error.take() {
+                return self.fail("cannot bind", e);
+            }
+            self.control.check_stopped();
+            if self.control.is_cancelled() {
+                self.terminated = true;
+                return StreamElement::FlushAndRestart;
+            }
+            if let Some(id) = self.control.checkpoint() {
+                return StreamElement::Checkpoint(id);
+            }
+            if self.connection.is_none() {
+                let start = Instant::now();
+                let connection = loop {
+                    match self.connect() {
+                        Ok(Some(connection)) => break Ok(Some(connection)),
+                        Ok(None) if start.elapsed() >= IDLE_HEARTBEAT => break Ok(None),
+                        Ok(None) => concurrency::blocking(|| std::thread::sleep(ACCEPT_POLL)),
+                        Err(e) => break Err(e),
+                    }
+                };
+                let connection = match connection {
+                    Ok(Some(connection)) => connection,
+                    Ok(None) if self.control.wake_up() => return StreamElement::FlushBatch,
+                    Ok(None) => continue,
+                    Err(e) => return self.fail("cannot connect to", e),
+                };
+                // the reads wake up periodically, also in the middle of a frame
+                if let Err(e) = connection.set_read_timeout(Some(IDLE_HEARTBEAT)) {
+                    return self.fail("cannot connect to", e);
+                }
+                log::debug!("{} connected to {}", self.coord.unwrap(), self.endpoint);
+                self.connection = Some(connection);
+            }
+            match self.framing.decode(self.buffer.pending(), self.buffer.eof) {
+                Ok(Some((frame, len))) => {
+                    self.buffer.consume(len);
+                    self.flushed = false;
+                    return StreamElement::Item(frame);
+                }
+                Ok(None) if self.buffer.eof => {
+                    log::info!(
+                        "{} disconnected from {}",
+                        self.coord.unwrap(),
+                        self.endpoint
+                    );
+                    self.connection = None;
+                    self.terminated = true;
+                    return StreamElement::FlushAndRestart;
+                }
+                Ok(None) => {}
+                Err(e) => return self.fail("received a malformed frame from", e),
+            }
+            // flush the pending batch before blocking on the socket
+            if !self.flushed {
+                self.flushed = true;
+                return StreamElement::FlushBatch;
+            }
+            // wait for the peer without holding the worker
+            let connection = self.connection.as_mut().unwrap();
+            let buffer = &mut self.buffer;
+            match concurrency::blocking(|| buffer.fill(connection)) {
+                Ok(true) => {}
+                Ok(false) if self.control.wake_up() => return StreamElement::FlushBatch,
+                Ok(false) => {}
+                Err(e) => return self.fail("cannot read from", e),
+            }
+        }
+    }
+
+    fn structure(&self) -> BlockStructure {
+        let mut operator = OperatorStructure::new::<F::Out, _>("SocketSource");
+        operator.kind = OperatorKind::Source;
+        BlockStructure::default().add_operator(operator)
+    }
+}
+
+impl<F: Framing> Clone for SocketSource<F> {
+    fn clone(&self) -> Self {
+        assert!(
+            self.connection.is_none(),
+            "SocketSource must be cloned before calling setup"
+        );
+        Self {
+            endpoint: self.endpoint.clone(),
+            framing: self.framing.clone(),
+            replication: self.replication,
+            listener: self.listener.clone(),
+            connection: None,
+            buffer: Default::default(),
+            flushed: false,
+            connect_warned: false,
+            bind_error: None,
+            terminated: false,
+            control: Default::default(),
+            coord: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    use itertools::Itertools;
+
+    use crate::block::Replication;
+    use crate::config::EnvironmentConfig;
+    use crate::environment::StreamEnvironment;
+    use crate::operator::source::{SocketEndpoint, SocketSource};
+
+    #[test]
+    fn socket_tcp_lines() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let generator = std::thread::spawn(move || {
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                for i in 0..100 {
+                    writeln!(stream, "{i}").unwrap();
+                }
+            }
+        });
+
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        let source = SocketSource::lines(SocketEndpoint::TcpConnect(addr))
+            .replication(Replication::Limited(2));
+        let res = env
+            .stream(source)
+            .map(|line| line.parse::<u32>().unwrap())
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        generator.join().unwrap();
+
+        let res = res.get().unwrap().into_iter().sorted().collect_vec();
+        let expected = (0..100).flat_map(|i| [i, i]).collect_vec();
+        assert_eq!(res, expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_unix_length_delimited() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("source.sock");
+
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        let source = SocketSource::length_delimited(SocketEndpoint::UnixListen(path.clone()));
+        let res = env.stream(source).collect_vec();
+
+        let generator_path = path.clone();
+        let generator = std::thread::spawn(move || {
+            let path = generator_path;
+            // wait for the source to bind the socket
+            let mut stream = loop {
+                match std::os::unix::net::UnixStream::connect(&path) {
+                    Ok(stream) => break stream,
+                    Err(_) => std::thread::sleep(std::time::Duration::from_millis(10)),
+                }
+            };
+            for i in 0..10u8 {
+                stream.write_all(&(i as u32).to_be_bytes()).unwrap();
+                if i == 5 {
+                    // the peer stalls in the middle of a frame for longer than a read timeout
+                    stream.flush().unwrap();
+                    std::thread::sleep(std::time::Duration::from_millis(300));
+                }
+                stream.write_all(&vec![i; i as usize]).unwrap();
+            }
+        });
+        env.execute_blocking().unwrap();
+        generator.join().unwrap();
+
+        let expected = (0..10u8).map(|i| vec![i; i as usize]).collect_vec();
+        assert_eq!(res.get().unwrap(), expected);
+        assert!(!path.exists(), "the socket file is removed at the end");
+    }
+
+    #[test]
+    fn socket_tcp_cancel_mid_frame() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let generator = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            write!(stream, "complete\nparti").unwrap();
+            // keep the connection open with a partial line until the job ends
+            done_rx.recv().unwrap();
+        });
+
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+        let source = SocketSource::lines(SocketEndpoint::TcpConnect(addr));
+        let res = env.stream(source).collect_vec();
+        let job = env.spawn();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        job.cancel();
+        job.wait().unwrap();
+        done_tx.send(()).unwrap();
+        generator.join().unwrap();
+
+        assert_eq!(res.get().unwrap(), vec!["complete".to_string()]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_unix_stale_file_cancel() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("source.sock");
+        // the socket file of a previous listener is left behind
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+        let source = SocketSource::lines(SocketEndpoint::UnixListen(path));
+        let res = env.stream(source).collect_vec();
+        let job = env.spawn();
+        // nobody connects: the source waits for a connection until the job is cancelled
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        job.cancel();
+        job.wait().unwrap();
+
+        assert!(res.get().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::checkpoint::CheckpointId;
+use crate::network::Coord;
+
+/// Align the checkpoint barriers coming from multiple replicas.
+///
+/// When the barrier of a checkpoint is received from a replica, the following batches of that
+/// replica are held back until the same barrier is received from all the other replicas. Then
+/// the barrier is safe to be passed downstream and the held batches are released, in order.
+///
+/// A replica that ended its stream will not send the barrier: it's considered aligned.
+#[derive(Clone, Debug)]
+pub(super) struct BarrierAlignment<B> {
+    num_replicas: usize,
+    /// The last checkpoint passed downstream.
+    last: CheckpointId,
+    /// The checkpoint being aligned.
+    current: Option<CheckpointId>,
+    /// The replicas that sent the barrier of the current checkpoint.
+    aligned: HashSet<Coord>,
+    /// The replicas that ended their stream.
+    ended: HashSet<Coord>,
+    /// The batches received from the aligned replicas.
+    blocked: VecDeque<(Coord, B)>,
+    /// The batches released after the last alignment, to be processed before the new ones.
+    pending: VecDeque<(Coord, B)>,
+}
+
+impl<B> Default for BarrierAlignment<B> {
+    fn default() -> Self {
+        Self {
+            num_replicas: 0,
+            last: 0,
+            current: None,
+            aligned: Default::default(),
+            ended: Default::default(),
+            blocked: Default::default(),
+            pending: Default::default(),
+        }
+    }
+}
+
+impl<B> BarrierAlignment<B> {
+    pub fn new(num_replicas: usize) -> Self {
+        Self {
+            num_replicas,
+            ..Default::default()
+        }
+    }
+
+    /// Whether the batches from this replica should be held back.
+    #[inline]
+    pub fn is_blocked(&self, sender: Coord) -> bool {
+        self.current.is_some() && self.aligned.contains(&sender)
+    }
+
+    /// Hold back a batch of an aligned replica.
+    pub fn block(&mut self, sender: Coord, batch: B) {
+        self.blocked.push_back((sender, batch));
+    }
+
+    /// The next released batch, if any.
+    #[inline]
+    pub fn next_pending(&mut self) -> Option<(Coord, B)> {
+        self.pending.pop_front()
+    }
+
+    /// The barrier of checkpoint `id` has been received from `sender`, followed by `rest`.
+    ///
+    /// Returns the checkpoint if it's now aligned.
+    pub fn receive(&mut self, sender: Coord, id: CheckpointId, rest: B) -> Option<CheckpointId> {
+        // a barrier replayed by a cache
+        if id <= self.last {
+            self.pending.push_front((sender, rest));
+            return None;
+        }
+        debug_assert!(!matches!(self.current, Some(c) if c != id));
+        self.current = Some(id);
+        self.aligned.insert(sender);
+        self.block(sender, rest);
+        self.try_release()
+    }
+
+    /// The replica `sender` ended its stream.
+    ///
+    /// Returns the checkpoint being aligned if it's now aligned.
+    pub fn end(&mut self, sender: Coord) -> Option<CheckpointId> {
+        self.ended.insert(sender);
+        self.try_release()
+    }
+
+    /// Start a new iteration of the stream, in which all the replicas are active.
+    pub fn reset(&mut self) {
+        self.ended.clear();
+    }
+
+    fn try_release(&mut self) -> Option<CheckpointId> {
+        let id = self.current?;
+        let ended = self.ended.difference(&self.aligned).count();
+        if self.aligned.len() + ended < self.num_replicas {
+            return None;
+        }
+        self.current = None;
+        self.last = id;
+        self.aligned.clear();
+        // the blocked batches were received before the pending ones
+        let pending = std::mem::take(&mut self.pending);
+        self.pending = std::mem::take(&mut self.blocked);
+        self.pending.extend(pending);
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::Coord;
+
+    use super::BarrierAlignment;
+
+    #[test]
+    fn alignment() {
+        let (a, b, c) = (
+            Coord::new(0, 0, 0),
+            Coord::new(0, 0, 1),
+            Coord::new(0, 0, 2),
+        );
+        let mut alignment = BarrierAlignment::new(3);
+
+        assert_eq!(alignment.receive(a, 1, "a1"), None);
+        assert!(alignment.is_blocked(a));
+        alignment.block(a, "a2");
+        assert_eq!(alignment.receive(b, 1, "b1"), None);
+        // c ended: the barrier is aligned
+        assert_eq!(alignment.end(c), Some(1));
+        assert!(!alignment.is_blocked(a));
+        assert_eq!(alignment.next_pending(), Some((a, "a1")));
+        assert_eq!(alignment.next_pending(), Some((a, "a2")));
+        assert_eq!(alignment.next_pending(), Some((b, "b1")));
+        assert_eq!(alignment.next_pending(), None);
+
+        // an old barrier is ignored
+        assert_eq!(alignment.receive(a, 1, "a3"), None);
+        assert!(!alignment.is_blocked(a));
+        assert_eq!(alignment.next_pending(), Some((a, "a3")));
+
+        assert_eq!(alignment.receive(a, 2, "a4"), None);
+        assert_eq!(alignment.receive(b, 2, "b2"), Some(2));
+    }
+}
@@ -1,9 +1,11 @@
 use std::fmt::{Debug, Display};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 pub(crate) use binary::*;
 pub(crate) use simple::*;
+pub(crate) use union::*;
 
 #[cfg(feature = "timestamp")]
 use super::Timestamp;
@@ -12,12 +14,15 @@ use crate::channel::RecvTimeoutError;
 use crate::network::{Coord, NetworkDataIterator, NetworkMessage};
 use crate::operator::iteration::IterationStateLock;
 use crate::operator::source::Source;
+use crate::operator::start::barrier_alignment::BarrierAlignment;
 use crate::operator::start::watermark_frontier::WatermarkFrontier;
 use crate::operator::{ExchangeData, Operator, StreamElement};
 use crate::scheduler::{BlockId, ExecutionMetadata};
 
+mod barrier_alignment;
 mod binary;
 mod simple;
+mod union;
 mod watermark_frontier;
 
 /// Trait that abstract the receiving part of the `Start`.
@@ -50,13 +55,16 @@ pub type BinaryStartOperator<OutL, OutR> =
 
 pub type SimpleStartOperator<Out> = Start<Out, SimpleStartReceiver<Out>>;
 
+pub type UnionStartOperator<Out> = Start<Out, UnionStartReceiver<Out>>;
+
 /// Each block should start with a `Start` operator, whose task is to read from the network,
 /// receive from the previous operators and handle the watermark frontier.
 ///
 /// There are different kinds of `Start`, the main difference is in the number of previous
 /// blocks. With a `SimpleStartReceiver` the block is able to receive from the replicas of a
 /// single block of the job graph. If the block needs the data from multiple blocks it should use
-/// `MultipleStartReceiver` which is able to handle 2 previous blocks.
+/// `MultipleStartReceiver` which is able to handle 2 previous blocks, or `UnionStartReceiver` which
+/// is able to handle any number of previous blocks with the same type.
 ///
 /// Following operators will receive the messages in an unspecified order but the watermark property
 /// is followed. Note that the timestamps of the messages are not sorted, it's only guaranteed that
@@ -65,6 +73,9 @@ pub type SimpleStartOperator<Out> = Start<Out, SimpleStartReceiver<Out>>;
 pub struct Start<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> {
     /// Execution metadata of this block.
     max_delay: Option<Duration>,
+    /// How often to emit a `StreamElement::FlushBatch` when no message arrives, for the operators
+    /// that react to the passing of time.
+    wake_up_interval: Option<Duration>,
 
     coord: Option<Coord>,
 
@@ -90,12 +101,17 @@ pub struct Start<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> {
 
     /// The current frontier of the watermarks from the previous replicas.
     watermark_frontier: WatermarkFrontier,
+    /// The alignment of the checkpoint barriers from the previous replicas.
+    barrier_alignment: BarrierAlignment<NetworkDataIterator<StreamElement<Out>>>,
 
     /// Whether the iteration has ended and the current block has to wait for the local iteration
     /// leader to update the iteration state before letting the messages pass.
     wait_for_state: bool,
     state_lock: Option<Arc<IterationStateLock>>,
     state_generation: usize,
+
+    /// The elements emitted by this operator, if the metrics are enabled.
+    items: Option<Arc<AtomicU64>>,
 }
 
 impl<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> Display for Start<Out, Receiver> {
@@ -137,11 +153,22 @@ impl<OutL: ExchangeData, OutR: ExchangeData>
     }
 }
 
+impl<Out: ExchangeData> Start<Out, UnionStartReceiver<Out>> {
+    /// Create a `Start` able to receive data from many previous blocks with the same type.
+    pub(crate) fn union(
+        previous_block_ids: Vec<BlockId>,
+        state_lock: Option<Arc<IterationStateLock>>,
+    ) -> UnionStartOperator<Out> {
+        Start::new(UnionStartReceiver::new(previous_block_ids), state_lock)
+    }
+}
+
 impl<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> Start<Out, Receiver> {
     fn new(receiver: Receiver, state_lock: Option<Arc<IterationStateLock>>) -> Self {
         Self {
             coord: Default::default(),
             max_delay: Default::default(),
+            wake_up_interval: Default::default(),
 
             receiver,
             batch_iter: None,
@@ -153,10 +180,13 @@ impl<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> Start<Out, Receiver
             already_timed_out: Default::default(),
 
             watermark_frontier: Default::default(),
+            barrier_alignment: Default::default(),
 
             wait_for_state: Default::default(),
             state_lock,
             state_generation: Default::default(),
+
+            items: None,
         }
     }
 
@@ -176,6 +206,7 @@ impl<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> Operator<Out>
         self.missing_terminate = self.num_previous_replicas;
         self.missing_flush_and_restart = self.num_previous_replicas;
         self.watermark_frontier = WatermarkFrontier::new(prev_replicas);
+        self.barrier_alignment = BarrierAlignment::new(self.num_previous_replicas);
 
         log::trace!(
             "{} initialized <{}>",
@@ -184,6 +215,13 @@ impl<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> Operator<Out>
         );
         self.coord = Some(metadata.coord);
         self.max_delay = metadata.batch_mode.max_delay();
+        self.wake_up_interval = metadata.wake_up_interval;
+        if let Some(metrics) = metadata.job.metrics().handle(metadata.coord) {
+            let structure = self.receiver.structure();
+            if let Some(operator) = structure.operators.last() {
+                self.items = Some(metrics.operator(0, &operator.title));
+            }
+        }
     }
 
     fn next(&mut self) -> StreamElement<Out> {
@@ -200,6 +238,7 @@ impl<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> Operator<Out>
 
                 self.missing_flush_and_restart = self.num_previous_replicas;
                 self.watermark_frontier.reset();
+                self.barrier_alignment.reset();
                 // this iteration has ended, before starting the next one wait for the state update
                 self.wait_for_state = true;
                 self.state_generation += 2;
@@ -207,7 +246,16 @@ impl<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> Operator<Out>
             }
 
             if let Some((sender, ref mut inner)) = self.batch_iter {
-                let msg = match inner.next() {
+                let item = inner.next();
+                if let Some(StreamElement::Checkpoint(id)) = item {
+                    // hold back the rest of the batch until the barrier is aligned
+                    let (_, rest) = self.batch_iter.take().unwrap();
+                    match self.barrier_alignment.receive(sender, id, rest) {
+                        Some(id) => return StreamElement::Checkpoint(id),
+                        None => continue,
+                    }
+                }
+                let msg = match item {
                     None => {
                         // Current batch is finished
                         self.batch_iter = None;
@@ -229,7 +277,10 @@ impl<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> Operator<Out>
                                     self.watermark_frontier.update(sender, Timestamp::MAX);
                                 }
                                 self.missing_flush_and_restart -= 1;
-                                continue;
+                                match self.barrier_alignment.end(sender) {
+                                    Some(id) => StreamElement::Checkpoint(id),
+                                    None => continue,
+                                }
                             }
                             StreamElement::Terminate => {
                                 self.missing_terminate -= 1;
@@ -253,36 +304,70 @@ impl<Out: ExchangeData, Receiver: StartReceiver<Out> + Send> Operator<Out>
                     }
                     self.wait_for_state = false;
                 }
+                if let Some(items) = &self.items {
+                    if matches!(msg, StreamElement::Item(_) | StreamElement::Timestamped(..)) {
+                        items.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
                 return msg;
             }
 
+            // Process the batches held back during the last alignment
+            if let Some((sender, batch)) = self.barrier_alignment.next_pending() {
+                if self.barrier_alignment.is_blocked(sender) {
+                    self.barrier_alignment.block(sender, batch);
+                } else {
+                    self.batch_iter = Some((sender, batch));
+                }
+                continue;
+            }
+
             // Receive next batch
-            let net_msg = match (self.already_timed_out, self.max_delay) {
-                // check the timeout only if there is one and the last time we didn't timed out
-                (false, Some(max_delay)) => {
-                    match self.receiver.recv_timeout(max_delay) {
-                        Ok(net_msg) => net_msg,
+            // check the timeout only if there is one and the last time we didn't timed out, unless
+            // the block has to be woken up periodically
+            let timeout = match (self.already_timed_out, self.max_delay) {
+                (false, Some(max_delay)) => Some(max_delay),
+                _ => None,
+            };
+            let timeout = match (timeout, self.wake_up_interval) {
+                (Some(timeout), Some(interval)) => Some(timeout.min(interval)),
+                (timeout, interval) => timeout.or(interval),
+            };
+            let net_msg = match timeout {
+                Some(timeout) => {
+                    match self.receiver.recv_timeout(timeout) {
+                        Ok(net_msg) => {
+                            self.already_timed_out = false;
+                            net_msg
+                        }
                         Err(_) => {
                             // timed out: tell the block to flush the current batch
-                            // next time we wait indefinitely without the timeout since the batch is
-                            // currently empty
+                            // next time we wait without the timeout of the batch mode since the
+                            // batch is currently empty
                             self.already_timed_out = true;
                             // this is a fake batch, and its sender is meaningless and will be
                             // forget immediately
-                            NetworkMessage::new_single(
+                            let net_msg = NetworkMessage::new_single(
                                 StreamElement::FlushBatch,
                                 Default::default(),
-                            )
+                            );
+                            self.batch_iter = Some((net_msg.sender(), net_msg.into_iter()));
+                            continue;
                         }
                     }
                 }
-                _ => {
+                None => {
                     self.already_timed_out = false;
                     self.receiver.recv()
                 }
             };
 
-            self.batch_iter = Some((net_msg.sender(), net_msg.into_iter()));
+            let sender = net_msg.sender();
+            if self.barrier_alignment.is_blocked(sender) {
+                self.barrier_alignment.block(sender, net_msg.into_iter());
+            } else {
+                self.batch_iter = Some((sender, net_msg.into_iter()));
+            }
         }
     }
 
@@ -605,4 +690,49 @@ mod tests {
 
         assert_eq!(StreamElement::Terminate, start_block.next());
     }
+
+    #[test]
+    fn test_union() {
+        let mut t = FakeNetworkTopology::new(3, 1);
+        let senders = (0..3)
+            .map(|i| t.senders_mut()[i].pop().unwrap())
+            .collect::<Vec<_>>();
+
+        let prev_block_ids = senders.iter().map(|(from, _)| from.block_id).collect();
+        let mut start_block = Start::<i32, _>::union(prev_block_ids, None);
+        start_block.setup(&mut t.metadata());
+
+        let send_all = |i: usize| {
+            let (from, sender) = &senders[i];
+            sender
+                .send(NetworkMessage::new_batch(
+                    vec![
+                        StreamElement::Item(i as i32),
+                        StreamElement::FlushAndRestart,
+                        StreamElement::Terminate,
+                    ],
+                    *from,
+                ))
+                .unwrap();
+        };
+
+        // the first block terminates before the others send anything
+        send_all(0);
+        assert_eq!(StreamElement::Item(0), start_block.next());
+        send_all(1);
+        send_all(2);
+
+        let mut items = vec![];
+        loop {
+            match start_block.next() {
+                StreamElement::Item(item) => items.push(item),
+                StreamElement::FlushBatch => {}
+                StreamElement::FlushAndRestart => break,
+                other => panic!("unexpected {other:?}"),
+            }
+        }
+        items.sort_unstable();
+        assert_eq!(items, vec![1, 2]);
+        assert_eq!(StreamElement::Terminate, start_block.next());
+    }
 }
@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use crate::block::{BlockStructure, OperatorReceiver, OperatorStructure};
+use crate::channel::RecvTimeoutError;
+use crate::network::{Coord, NetworkMessage, NetworkReceiver};
+use crate::operator::start::{SimpleStartReceiver, StartReceiver};
+use crate::operator::ExchangeData;
+use crate::scheduler::{BlockId, ExecutionMetadata};
+
+/// This receiver is able to receive data from many previous blocks with the same type.
+///
+/// The batches are received from any of the previous blocks as they arrive, without changing
+/// their elements.
+#[derive(Clone, Debug)]
+pub struct UnionStartReceiver<Out: ExchangeData> {
+    receivers: Vec<SimpleStartReceiver<Out>>,
+    /// How many replicas of each previous block have not yet sent `StreamElement::Terminate`.
+    missing_terminate: Vec<usize>,
+}
+
+impl<Out: ExchangeData> UnionStartReceiver<Out> {
+    pub(super) fn new(previous_block_ids: Vec<BlockId>) -> Self {
+        assert!(
+            !previous_block_ids.is_empty(),
+            "A union needs at least one previous block"
+        );
+        Self {
+            missing_terminate: vec![0; previous_block_ids.len()],
+            receivers: previous_block_ids
+                .into_iter()
+                .map(SimpleStartReceiver::new)
+                .collect(),
+        }
+    }
+
+    /// Receive the next batch from the previous blocks that have not terminated yet, or fail with
+    /// a timeout if provided.
+    fn select(
+        &mut self,
+        timeout: Option<Duration>,
+    ) -> Result<NetworkMessage<Out>, RecvTimeoutError> {
+        // a terminated block may have disconnected its channel, don't wait on it
+        let active = (0..self.receivers.len())
+            .filter(|&i| self.missing_terminate[i] > 0)
+            .collect::<Vec<_>>();
+        if active.is_empty() {
+            return Err(RecvTimeoutError::Disconnected);
+        }
+        let receivers = active
+            .iter()
+            .map(|&i| self.receivers[i].receiver.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let (index, message) = match timeout {
+            Some(timeout) => NetworkReceiver::select_any_timeout(&receivers, timeout)?,
+            None => NetworkReceiver::select_any(&receivers),
+        };
+        let message = message.map_err(|_| RecvTimeoutError::Disconnected)?;
+        self.missing_terminate[active[index]] -= message.num_terminates();
+        Ok(message)
+    }
+}
+
+impl<Out: ExchangeData> StartReceiver<Out> for UnionStartReceiver<Out> {
+    fn setup(&mut self, metadata: &mut ExecutionMetadata) {
+        for (receiver, missing) in self.receivers.iter_mut().zip(&mut self.missing_terminate) {
+            receiver.setup(metadata);
+            *missing = receiver.prev_replicas().len();
+        }
+    }
+
+    fn prev_replicas(&self) -> Vec<Coord> {
+        self.receivers
+            .iter()
+            .flat_map(|receiver| receiver.prev_replicas())
+            .collect()
+    }
+
+    fn cached_replicas(&self) -> usize {
+        0
+    }
+
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<NetworkMessage<Out>, RecvTimeoutError> {
+        self.select(Some(timeout))
+    }
+
+    fn recv(&mut self) -> NetworkMessage<Out> {
+        self.select(None).expect("receiver failed")
+    }
+
+    fn structure(&self) -> BlockStructure {
+        let mut operator = OperatorStructure::new::<Out, _>("Start");
+        for receiver in &self.receivers {
+            operator
+                .receivers
+                .push(OperatorReceiver::new::<Out>(receiver.previous_block_id));
+        }
+        BlockStructure::default().add_operator(operator)
+    }
+}
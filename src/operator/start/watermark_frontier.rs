@@ -2,18 +2,29 @@ use indexmap::IndexMap;
 
 use crate::block::CoordHasherBuilder;
 use crate::network::Coord;
+#[cfg(feature = "timestamp")]
+use crate::operator::watermark::IDLE_WATERMARK;
 use crate::operator::Timestamp;
 
 /// Handle watermarks coming from multiple replicas.
 ///
 /// A watermark with timestamp `ts` is safe to be passed downstream if and only if, for every
 /// previous replica, a watermark with timestamp greater or equal to `ts` has already been received.
+///
+/// A replica that sent an [`IDLE_WATERMARK`] is ignored until it sends a new watermark. If all the
+/// replicas are idle the frontier does not advance.
 #[derive(Clone, Debug, Default)]
 pub(super) struct WatermarkFrontier {
-    map: IndexMap<Coord, Option<Timestamp>, CoordHasherBuilder>,
+    map: IndexMap<Coord, ReplicaWatermark, CoordHasherBuilder>,
     front: Option<Timestamp>,
 }
 
+#[derive(Clone, Copy, Debug, Default)]
+struct ReplicaWatermark {
+    ts: Option<Timestamp>,
+    idle: bool,
+}
+
 fn opt_join<T: std::cmp::Ord>(a: Option<T>, b: Option<T>, f: fn(T, T) -> T) -> Option<T> {
     match (a, b) {
         (Some(a), Some(b)) => Some(f(a, b)),
@@ -24,15 +35,22 @@ fn opt_join<T: std::cmp::Ord>(a: Option<T>, b: Option<T>, f: fn(T, T) -> T) -> O
 impl WatermarkFrontier {
     pub fn new(prev_replicas: impl IntoIterator<Item = Coord>) -> Self {
         Self {
-            map: prev_replicas.into_iter().map(|c| (c, None)).collect(),
+            map: prev_replicas
+                .into_iter()
+                .map(|c| (c, Default::default()))
+                .collect(),
             front: None,
         }
     }
 
     fn compute_frontier(&self) -> Option<Timestamp> {
-        let (complete, min) = self.map.values().fold((true, None), |(all, min), x| {
-            (all & x.is_some(), opt_join(min, *x, std::cmp::min))
-        });
+        let (complete, min) = self
+            .map
+            .values()
+            .filter(|r| !r.idle)
+            .fold((true, None), |(all, min), r| {
+                (all & r.ts.is_some(), opt_join(min, r.ts, std::cmp::min))
+            });
 
         if complete {
             min
@@ -43,27 +61,71 @@ impl WatermarkFrontier {
 
     /// Update the frontier, return `Some(ts)` if timestamp `ts` is now safe
     pub fn update(&mut self, coord: Coord, ts: Timestamp) -> Option<Timestamp> {
-        let t0 = &mut self.map[&coord];
-        if matches!(t0, Some(t) if *t >= ts) {
+        let replica = &mut self.map[&coord];
+        #[cfg(feature = "timestamp")]
+        if ts == IDLE_WATERMARK {
+            if replica.idle {
+                return None;
+            }
+            replica.idle = true;
+            return self.advance();
+        }
+
+        let was_idle = std::mem::take(&mut replica.idle);
+        if matches!(replica.ts, Some(t) if t >= ts) && !was_idle {
             // Early break for old watermark
             return None;
         }
-        *t0 = Some(ts);
+        replica.ts = Some(opt_join(replica.ts, Some(ts), std::cmp::max).unwrap());
 
-        // get new frontier
-        let prev_frontier = self.front;
-        self.front = self.compute_frontier();
+        self.advance()
+    }
 
-        match (prev_frontier, self.front) {
-            (None, Some(new)) => Some(new),
-            (Some(old), Some(new)) if old != new => Some(new),
-            _ => None,
+    /// Recompute the frontier, return it if it advanced.
+    ///
+    /// The frontier may move back when an idle replica becomes active again, in that case it is
+    /// not emitted until it passes the previous one.
+    fn advance(&mut self) -> Option<Timestamp> {
+        let new = self.compute_frontier()?;
+        match self.front {
+            Some(old) if old >= new => None,
+            _ => {
+                self.front = Some(new);
+                Some(new)
+            }
         }
     }
 
     /// Reset all the watermarks.
     pub fn reset(&mut self) {
-        self.map.values_mut().for_each(|v| *v = None);
+        self.map.values_mut().for_each(|v| *v = Default::default());
         self.front = None;
     }
 }
+
+#[cfg(all(test, feature = "timestamp"))]
+mod tests {
+    use crate::network::Coord;
+    use crate::operator::watermark::IDLE_WATERMARK;
+
+    use super::WatermarkFrontier;
+
+    #[test]
+    fn idle_replicas() {
+        let (a, b) = (Coord::new(0, 0, 0), Coord::new(0, 0, 1));
+        let mut frontier = WatermarkFrontier::new([a, b]);
+
+        assert_eq!(frontier.update(a, 10), None);
+        assert_eq!(frontier.update(b, 5), Some(5));
+        // b is idle: only a holds the frontier
+        assert_eq!(frontier.update(b, IDLE_WATERMARK), Some(10));
+        assert_eq!(frontier.update(a, 20), Some(20));
+        // all idle: the frontier is held
+        assert_eq!(frontier.update(a, IDLE_WATERMARK), None);
+        // b is back behind the frontier, which must not move back
+        assert_eq!(frontier.update(b, 15), None);
+        assert_eq!(frontier.update(b, 25), Some(25));
+        assert_eq!(frontier.update(a, 30), None);
+        assert_eq!(frontier.update(b, 35), Some(30));
+    }
+}
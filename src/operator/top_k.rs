@@ -0,0 +1,197 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::operator::{Data, DataKey, ExchangeData, Operator};
+use crate::{KeyedStream, Stream};
+
+/// An element ranked by its key, ignoring the element when comparing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Ranked<K, I> {
+    key: K,
+    item: I,
+}
+
+impl<K: Ord, I> PartialEq for Ranked<K, I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord, I> Eq for Ranked<K, I> {}
+
+impl<K: Ord, I> PartialOrd for Ranked<K, I> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, I> Ord for Ranked<K, I> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+/// The `k` elements with the largest key seen so far.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Ord + Serialize, I: Serialize",
+    deserialize = "K: Ord + Deserialize<'de>, I: Deserialize<'de>"
+))]
+pub(crate) struct TopK<K, I> {
+    k: usize,
+    /// A min-heap: the first element to drop is on top.
+    heap: BinaryHeap<Reverse<Ranked<K, I>>>,
+}
+
+impl<K: Ord, I> TopK<K, I> {
+    fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    fn push(&mut self, key: K, item: I) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(Ranked { key, item }));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if key > smallest.0.key {
+                *smallest = Reverse(Ranked { key, item });
+            }
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        for Reverse(Ranked { key, item }) in other.heap {
+            self.push(key, item);
+        }
+    }
+
+    /// The elements from the largest key to the smallest.
+    fn into_sorted(self) -> Vec<I> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(ranked)| ranked.item)
+            .collect()
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeData,
+    Op: Operator<I> + 'static,
+{
+    /// Find the `k` elements of the stream with the largest key, emitted from the largest to the
+    /// smallest.
+    ///
+    /// The key of the elements is obtained with `keyer`. Each replica keeps only its `k` largest
+    /// elements in a heap, then the local results are merged on a single replica: at most `k`
+    /// elements per replica are sent to the network. Elements with the same key are ranked in no
+    /// particular order.
+    ///
+    /// To find the smallest elements reverse the key, for example with [`std::cmp::Reverse`].
+    ///
+    /// **Note**: this operator will retain `k` elements per replica and emit the values only when
+    /// the stream ends. Therefore this is not properly _streaming_.
+    ///
+    /// **Note**: this operator will split the current block.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let words = vec!["a", "bbbb", "cc", "ddd"];
+    /// let s = env.stream(IteratorSource::new(words.into_iter().map(String::from)));
+    /// let res = s.top_k_by(2, |s| s.len()).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap(), vec!["bbbb", "ddd"]);
+    /// ```
+    pub fn top_k_by<K, Fk>(self, k: usize, keyer: Fk) -> Stream<I, impl Operator<I>>
+    where
+        K: ExchangeData + Ord,
+        Fk: Fn(&I) -> K + Send + Clone + 'static,
+    {
+        self.fold_assoc(
+            TopK::new(k),
+            move |top, item| top.push(keyer(&item), item),
+            |top, other| top.merge(other),
+        )
+        .flat_map(TopK::into_sorted)
+    }
+}
+
+impl<Key, I, Op> KeyedStream<Key, I, Op>
+where
+    Key: DataKey,
+    I: Data,
+    Op: Operator<(Key, I)> + 'static,
+{
+    /// Find, for each key of the stream, the `k` elements with the largest rank, emitted from the
+    /// largest to the smallest.
+    ///
+    /// The rank of the elements is obtained with `keyer`. See [`Stream::top_k_by`].
+    ///
+    /// **Note**: this operator will retain `k` elements per key and emit the values only when the
+    /// stream ends. Therefore this is not properly _streaming_.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..10))).group_by(|&n| n % 2);
+    /// let res = s.top_k_by(2, |&n| n).collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 6), (0, 8), (1, 7), (1, 9)]);
+    /// ```
+    pub fn top_k_by<K, Fk>(
+        self,
+        k: usize,
+        keyer: Fk,
+    ) -> KeyedStream<Key, I, impl Operator<(Key, I)>>
+    where
+        K: Data + Ord,
+        Fk: Fn(&I) -> K + Send + Clone + 'static,
+    {
+        self.fold(TopK::new(k), move |top, item| top.push(keyer(&item), item))
+            .flat_map(|(_, top)| top.into_sorted())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TopK;
+
+    #[test]
+    fn top_k_merge() {
+        let mut top = TopK::new(3);
+        let mut other = TopK::new(3);
+        for n in [5, 1, 9, 3] {
+            top.push(n, n.to_string());
+        }
+        for n in [8, 2, 7] {
+            other.push(n, n.to_string());
+        }
+        top.merge(other);
+        assert_eq!(top.into_sorted(), vec!["9", "8", "7"]);
+    }
+
+    #[test]
+    fn top_k_zero() {
+        let mut top = TopK::new(0);
+        top.push(1, ());
+        assert!(top.into_sorted().is_empty());
+    }
+}
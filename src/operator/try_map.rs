@@ -0,0 +1,353 @@
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use super::side_output::OutputTags;
+use super::{Data, ExchangeData, Operator};
+use crate::Stream;
+
+/// What to do when the function of a fallible operator (like [`Stream::try_map`]) returns an
+/// error.
+///
+/// To send the failed elements to a separate stream use the `_with_errors` variants of the
+/// operators (like [`Stream::try_map_with_errors`]).
+#[derive(Clone, Debug, Default)]
+pub enum ErrorPolicy {
+    /// Panic with the error, aborting the job.
+    ///
+    /// The error is reported by
+    /// [`StreamEnvironment::execute_blocking`](crate::StreamEnvironment::execute_blocking).
+    #[default]
+    Abort,
+    /// Drop the element that caused the error, counting it.
+    Drop(ErrorCounter),
+}
+
+impl ErrorPolicy {
+    /// Handle the error of an element, returning `None` if the element should be dropped.
+    fn check<T, E: Debug>(&self, result: Result<T, E>) -> Option<T> {
+        match (result, self) {
+            (Ok(t), _) => Some(t),
+            (Err(e), ErrorPolicy::Abort) => panic!("Fallible operator failed: {e:?}"),
+            (Err(_), ErrorPolicy::Drop(counter)) => {
+                counter.0.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+}
+
+/// Counter of the elements dropped by [`ErrorPolicy::Drop`].
+///
+/// The counter is shared by all the replicas of the operator running in this process.
+#[derive(Clone, Debug, Default)]
+pub struct ErrorCounter(Arc<AtomicU64>);
+
+impl ErrorCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of errors counted so far.
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: Data,
+    Op: Operator<I> + 'static,
+{
+    /// Map the elements of the stream with a fallible function, handling the errors as specified
+    /// by the `policy`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// use noir::operator::{ErrorCounter, ErrorPolicy};
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new(["1", "2", "x", "3"].into_iter()));
+    /// let errors = ErrorCounter::new();
+    /// let res = s
+    ///     .try_map(|s| s.parse::<u32>(), ErrorPolicy::Drop(errors.clone()))
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap(), vec![1, 2, 3]);
+    /// assert_eq!(errors.get(), 1);
+    /// ```
+    pub fn try_map<O, E, F>(self, f: F, policy: ErrorPolicy) -> Stream<O, impl Operator<O>>
+    where
+        F: Fn(I) -> Result<O, E> + Send + Clone + 'static,
+        O: Data,
+        E: Debug,
+    {
+        self.filter_map(move |x| policy.check(f(x)))
+    }
+
+    /// Apply a fallible function to the elements of the stream, flattening the returned
+    /// iterators and handling the errors as specified by the `policy`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// use noir::operator::{ErrorCounter, ErrorPolicy};
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new(["1", "2", "x"].into_iter()));
+    /// let errors = ErrorCounter::new();
+    /// let res = s
+    ///     .try_flat_map(
+    ///         |s| s.parse::<usize>().map(|n| vec![n; n]),
+    ///         ErrorPolicy::Drop(errors.clone()),
+    ///     )
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(res.get().unwrap(), vec![1, 2, 2]);
+    /// assert_eq!(errors.get(), 1);
+    /// ```
+    pub fn try_flat_map<It, O, E, F>(self, f: F, policy: ErrorPolicy) -> Stream<O, impl Operator<O>>
+    where
+        It: IntoIterator<Item = O> + 'static,
+        <It as IntoIterator>::IntoIter: Send + 'static,
+        F: Fn(I) -> Result<It, E> + Send + Clone + 'static,
+        O: Data,
+        E: Debug,
+    {
+        self.flat_map(move |x| {
+            policy
+                .check(f(x))
+                .map(IntoIterator::into_iter)
+                .into_iter()
+                .flatten()
+        })
+    }
+
+    /// Map the elements of the stream with a fallible stateful function, handling the errors as
+    /// specified by the `policy`.
+    ///
+    /// Like [`Stream::rich_map`], each replica has its own copy of the function.
+    pub fn try_rich_map<O, E, F>(self, mut f: F, policy: ErrorPolicy) -> Stream<O, impl Operator<O>>
+    where
+        F: FnMut(I) -> Result<O, E> + Send + Clone + 'static,
+        O: Data,
+        E: Debug,
+    {
+        self.rich_filter_map(move |x| policy.check(f(x)))
+    }
+}
+
+impl<I, Op> Stream<I, Op>
+where
+    I: ExchangeData,
+    Op: Operator<I> + 'static,
+{
+    /// Map the elements of the stream with a fallible function, sending the elements that failed
+    /// to a separate stream together with their error.
+    ///
+    /// The first returned stream contains the results of the successful elements, the second one
+    /// the `(input, error)` pairs of the failed elements. The function takes a reference to the
+    /// element, so that a failed element can be moved to the error stream without cloning it.
+    ///
+    /// **Note**: the error stream starts a new block, see
+    /// [`Stream::flat_map_with_side_outputs`].
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new(["1", "2", "x"].into_iter().map(String::from)));
+    /// let (numbers, errors) = s.try_map_with_errors(|s| s.parse::<u32>().map_err(|e| e.to_string()));
+    /// let numbers = numbers.collect_vec();
+    /// let errors = errors.collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// assert_eq!(numbers.get().unwrap(), vec![1, 2]);
+    /// assert_eq!(errors.get().unwrap()[0].0, "x");
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn try_map_with_errors<O, E, F>(
+        self,
+        f: F,
+    ) -> (
+        Stream<O, impl Operator<O>>,
+        Stream<(I, E), impl Operator<(I, E)>>,
+    )
+    where
+        F: Fn(&I) -> Result<O, E> + Send + Clone + 'static,
+        O: Data,
+        E: ExchangeData,
+    {
+        let mut tags = OutputTags::new();
+        let errors = tags.tag::<(I, E)>();
+        let (stream, mut sides) =
+            self.flat_map_with_side_outputs(tags, move |x: I, out| match f(&x) {
+                Ok(o) => out.emit(o),
+                Err(e) => out.emit_to(&errors, (x, e)),
+            });
+        (stream, sides.take(&errors))
+    }
+
+    /// Apply a fallible function to the elements of the stream, flattening the returned
+    /// iterators and sending the elements that failed to a separate stream together with their
+    /// error.
+    ///
+    /// See [`Stream::try_map_with_errors`].
+    #[allow(clippy::type_complexity)]
+    pub fn try_flat_map_with_errors<It, O, E, F>(
+        self,
+        f: F,
+    ) -> (
+        Stream<O, impl Operator<O>>,
+        Stream<(I, E), impl Operator<(I, E)>>,
+    )
+    where
+        It: IntoIterator<Item = O>,
+        F: Fn(&I) -> Result<It, E> + Send + Clone + 'static,
+        O: Data,
+        E: ExchangeData,
+    {
+        let mut tags = OutputTags::new();
+        let errors = tags.tag::<(I, E)>();
+        let (stream, mut sides) =
+            self.flat_map_with_side_outputs(tags, move |x: I, out| match f(&x) {
+                Ok(it) => it.into_iter().for_each(|o| out.emit(o)),
+                Err(e) => out.emit_to(&errors, (x, e)),
+            });
+        (stream, sides.take(&errors))
+    }
+
+    /// Map the elements of the stream with a fallible stateful function, sending the elements
+    /// that failed to a separate stream together with their error.
+    ///
+    /// Like [`Stream::rich_map`] and [`Stream::try_rich_map`], each replica has its own copy of
+    /// the function.
+    ///
+    /// See [`Stream::try_map_with_errors`].
+    #[allow(clippy::type_complexity)]
+    pub fn try_rich_map_with_errors<O, E, F>(
+        self,
+        mut f: F,
+    ) -> (
+        Stream<O, impl Operator<O>>,
+        Stream<(I, E), impl Operator<(I, E)>>,
+    )
+    where
+        F: FnMut(&I) -> Result<O, E> + Send + Clone + 'static,
+        O: Data,
+        E: ExchangeData,
+    {
+        let mut tags = OutputTags::new();
+        let errors = tags.tag::<(I, E)>();
+        let (stream, mut sides) = self.key_by(|_| ()).drop_key().flat_map_with_side_outputs(
+            tags,
+            move |x: I, out| match f(&x) {
+                Ok(o) => out.emit(o),
+                Err(e) => out.emit_to(&errors, (x, e)),
+            },
+        );
+        (stream, sides.take(&errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::config::EnvironmentConfig;
+    use crate::environment::StreamEnvironment;
+    use crate::operator::source;
+    use crate::operator::{ErrorCounter, ErrorPolicy};
+
+    fn parse(s: &str) -> Result<u32, String> {
+        s.parse().map_err(|_| format!("invalid number: {s}"))
+    }
+
+    fn input() -> impl Iterator<Item = String> + Clone + Send + 'static {
+        ["1", "x", "2", "y", "3"].into_iter().map(String::from)
+    }
+
+    #[test]
+    fn try_map_drop() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        let errors = ErrorCounter::new();
+        let res = env
+            .stream(source::IteratorSource::new(input()))
+            .shuffle()
+            .try_map(|s| parse(&s), ErrorPolicy::Drop(errors.clone()))
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        assert_eq!(
+            res.get().unwrap().into_iter().sorted().collect_vec(),
+            [1, 2, 3]
+        );
+        assert_eq!(errors.get(), 2);
+    }
+
+    #[test]
+    fn try_rich_map_abort() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        env.stream(source::IteratorSource::new(input()))
+            .try_rich_map(|s| parse(&s), ErrorPolicy::Abort)
+            .for_each(|_| {});
+        let error = env.execute_blocking().unwrap_err();
+        assert!(
+            error.message.contains("invalid number: x"),
+            "{}",
+            error.message
+        );
+    }
+
+    #[test]
+    fn try_rich_map_with_errors() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+        let (res, errors) = env
+            .stream(source::IteratorSource::new(input()))
+            .try_rich_map_with_errors({
+                let mut count = 0;
+                move |s| {
+                    count += 1;
+                    parse(s).map(|_| count).map_err(|_| count)
+                }
+            });
+        let res = res.collect_vec();
+        let errors = errors.collect_vec();
+        env.execute_blocking().unwrap();
+        // the state of the function is kept across the elements
+        let mut counts = res.get().unwrap();
+        counts.extend(errors.get().unwrap().into_iter().map(|(_, count)| count));
+        assert_eq!(counts.into_iter().sorted().collect_vec(), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn try_flat_map_with_errors() {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        let (res, errors) = env
+            .stream(source::IteratorSource::new(input()))
+            .shuffle()
+            .try_flat_map_with_errors(|s| parse(s).map(|n| vec![n; n as usize]));
+        let res = res.collect_vec();
+        let errors = errors.collect_vec();
+        env.execute_blocking().unwrap();
+        let res = res.get().unwrap().into_iter().sorted().collect_vec();
+        assert_eq!(res, [1, 2, 2, 3, 3, 3]);
+        let errors = errors.get().unwrap().into_iter().sorted().collect_vec();
+        assert_eq!(
+            errors,
+            [
+                ("x".to_string(), "invalid number: x".to_string()),
+                ("y".to_string(), "invalid number: y".to_string())
+            ]
+        );
+    }
+}
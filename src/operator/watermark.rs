@@ -0,0 +1,328 @@
+//! Strategies for generating watermarks on event-time streams.
+//!
+//! A [`WatermarkGenerator`] is given each element as it gets its timestamp, and it decides when
+//! to insert a watermark into the stream. Generators can be used with
+//! [`Stream::add_timestamps_with`](crate::Stream::add_timestamps_with) or directly on a source
+//! with [`Source::with_timestamps`](crate::operator::source::Source::with_timestamps).
+//!
+//! The provided strategies can be composed:
+//!
+//! ```
+//! # use std::time::Duration;
+//! use noir::operator::watermark::BoundedOutOfOrderness;
+//!
+//! // Tolerate elements up to 100 time units late, emit at most one watermark every 200ms and
+//! // mark the source as idle after 5s without elements.
+//! let strategy = BoundedOutOfOrderness::new(100)
+//!     .periodic(Duration::from_millis(200))
+//!     .idle_timeout(Duration::from_secs(5));
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::operator::Timestamp;
+
+/// Watermark that marks the replica that sent it as idle.
+///
+/// The downstream block ignores an idle replica when computing its watermark frontier, until the
+/// replica sends a new watermark. If all the previous replicas are idle the frontier is held.
+///
+/// The marker returned by a [`WatermarkGenerator`] is not emitted inside the block of the
+/// generator: it is sent only to the next blocks, so the operators never receive it as a
+/// watermark.
+pub const IDLE_WATERMARK: Timestamp = Timestamp::MIN;
+
+/// Idleness of a replica, set by its `AddTimestamp` and read by the operators at the end of its
+/// block that send the [`IDLE_WATERMARK`] to the next blocks.
+///
+/// Each reader keeps its own copy, the marker is sent once every time the replica becomes idle.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct IdleSignal {
+    idle: Arc<AtomicBool>,
+    sent: bool,
+}
+
+impl IdleSignal {
+    pub(crate) fn set_idle(&self, idle: bool) {
+        self.idle.store(idle, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub(crate) fn is_idle(&self) -> bool {
+        self.idle.load(Ordering::Relaxed)
+    }
+
+    /// Whether the [`IDLE_WATERMARK`] should be sent now, because the replica became idle since
+    /// the last call.
+    pub(crate) fn poll(&mut self) -> bool {
+        if !self.is_idle() {
+            self.sent = false;
+            return false;
+        }
+        !std::mem::replace(&mut self.sent, true)
+    }
+}
+
+/// Decides when to insert watermarks into a stream with event time.
+///
+/// Any closure `FnMut(&Out, &Timestamp) -> Option<Timestamp>` is a generator that inserts the
+/// returned watermark after each element.
+pub trait WatermarkGenerator<Out>: Clone + Send + 'static {
+    /// Called for each element with its timestamp, returns a watermark to insert after it.
+    fn on_event(&mut self, item: &Out, ts: Timestamp) -> Option<Timestamp>;
+
+    /// Called when the stream has no element ready, returns a watermark to insert.
+    ///
+    /// The sources signal this condition with a `FlushBatch`. If
+    /// [`needs_heartbeats`](WatermarkGenerator::needs_heartbeats) is true, the sources that wait
+    /// for new elements ([`ChannelSource`](crate::operator::source::ChannelSource),
+    /// `AsyncStreamSource` and [`SocketSource`](crate::operator::source::SocketSource)) keep
+    /// doing so periodically while waiting, the other sources do not.
+    fn on_idle(&mut self) -> Option<Timestamp> {
+        None
+    }
+
+    /// Whether [`on_idle`](WatermarkGenerator::on_idle) has to be called periodically while the
+    /// stream has no element ready.
+    fn needs_heartbeats(&self) -> bool {
+        false
+    }
+}
+
+impl<Out, F> WatermarkGenerator<Out> for F
+where
+    F: FnMut(&Out, &Timestamp) -> Option<Timestamp> + Clone + Send + 'static,
+{
+    #[inline]
+    fn on_event(&mut self, item: &Out, ts: Timestamp) -> Option<Timestamp> {
+        (self)(item, &ts)
+    }
+}
+
+/// Watermarks for streams whose elements arrive at most `max_delay` late.
+///
+/// After an element with timestamp `ts`, no element with a timestamp smaller than
+/// `ts - max_delay` is expected. A watermark is emitted every time this bound advances, consider
+/// wrapping it with [`periodic`](BoundedOutOfOrderness::periodic) to limit the number of watermarks.
+#[derive(Clone, Debug)]
+pub struct BoundedOutOfOrderness {
+    max_delay: Timestamp,
+    max_timestamp: Option<Timestamp>,
+    last_watermark: Option<Timestamp>,
+}
+
+impl BoundedOutOfOrderness {
+    pub fn new(max_delay: Timestamp) -> Self {
+        assert!(max_delay >= 0, "The maximum delay must be non-negative");
+        Self {
+            max_delay,
+            max_timestamp: None,
+            last_watermark: None,
+        }
+    }
+
+    /// Emit the watermarks at most once every `interval` of processing time.
+    ///
+    /// See [`Periodic`].
+    pub fn periodic(self, interval: Duration) -> Periodic<Self> {
+        Periodic::new(self, interval)
+    }
+
+    /// Mark the stream as idle after `timeout` of processing time without elements.
+    ///
+    /// See [`IdleTimeout`].
+    pub fn idle_timeout(self, timeout: Duration) -> IdleTimeout<Self> {
+        IdleTimeout::new(self, timeout)
+    }
+}
+
+impl<Out> WatermarkGenerator<Out> for BoundedOutOfOrderness {
+    fn on_event(&mut self, _item: &Out, ts: Timestamp) -> Option<Timestamp> {
+        let max = self.max_timestamp.map_or(ts, |m| m.max(ts));
+        self.max_timestamp = Some(max);
+        let watermark = max.saturating_sub(self.max_delay).saturating_sub(1);
+        match self.last_watermark {
+            Some(last) if last >= watermark => None,
+            _ => {
+                self.last_watermark = Some(watermark);
+                Some(watermark)
+            }
+        }
+    }
+}
+
+/// Emit the watermarks of the inner generator at most once per interval of processing time.
+///
+/// Only the most recent watermark is emitted, the intermediate ones are dropped.
+#[derive(Clone, Debug)]
+pub struct Periodic<W> {
+    inner: W,
+    interval: Duration,
+    last_emit: Option<Instant>,
+    pending: Option<Timestamp>,
+}
+
+impl<W> Periodic<W> {
+    pub fn new(inner: W, interval: Duration) -> Self {
+        Self {
+            inner,
+            interval,
+            last_emit: None,
+            pending: None,
+        }
+    }
+
+    /// Mark the stream as idle after `timeout` of processing time without elements.
+    ///
+    /// See [`IdleTimeout`].
+    pub fn idle_timeout(self, timeout: Duration) -> IdleTimeout<Self> {
+        IdleTimeout::new(self, timeout)
+    }
+
+    fn merge(&mut self, watermark: Option<Timestamp>) {
+        if let Some(w) = watermark {
+            self.pending = Some(self.pending.map_or(w, |p| p.max(w)));
+        }
+    }
+
+    fn poll(&mut self) -> Option<Timestamp> {
+        self.pending?;
+        let now = Instant::now();
+        if self
+            .last_emit
+            .is_some_and(|last| now.duration_since(last) < self.interval)
+        {
+            return None;
+        }
+        self.last_emit = Some(now);
+        self.pending.take()
+    }
+}
+
+impl<Out, W: WatermarkGenerator<Out>> WatermarkGenerator<Out> for Periodic<W> {
+    fn on_event(&mut self, item: &Out, ts: Timestamp) -> Option<Timestamp> {
+        let watermark = self.inner.on_event(item, ts);
+        self.merge(watermark);
+        self.poll()
+    }
+
+    fn on_idle(&mut self) -> Option<Timestamp> {
+        let watermark = self.inner.on_idle();
+        self.merge(watermark);
+        self.poll()
+    }
+
+    fn needs_heartbeats(&self) -> bool {
+        // the pending watermark is emitted when the interval expires
+        true
+    }
+}
+
+/// Mark the stream as idle after a timeout of processing time without elements.
+///
+/// An idle stream sends an [`IDLE_WATERMARK`] to the downstream block so that it does not hold back
+/// its watermark, and it becomes active again with its next element. Since the idleness marker
+/// must reach the next block unchanged, this should be the outermost strategy.
+///
+/// Idleness is detected only with the sources that keep signaling it while waiting for new
+/// elements: [`ChannelSource`](crate::operator::source::ChannelSource), `AsyncStreamSource` and
+/// [`SocketSource`](crate::operator::source::SocketSource).
+#[derive(Clone, Debug)]
+pub struct IdleTimeout<W> {
+    inner: W,
+    timeout: Duration,
+    last_active: Option<Instant>,
+    received: bool,
+    idle: bool,
+}
+
+impl<W> IdleTimeout<W> {
+    pub fn new(inner: W, timeout: Duration) -> Self {
+        Self {
+            inner,
+            timeout,
+            last_active: None,
+            received: false,
+            idle: false,
+        }
+    }
+}
+
+impl<Out, W: WatermarkGenerator<Out>> WatermarkGenerator<Out> for IdleTimeout<W> {
+    fn on_event(&mut self, item: &Out, ts: Timestamp) -> Option<Timestamp> {
+        // reading the clock for every element is too expensive, the time of the last element is
+        // approximated when the stream becomes empty
+        self.received = true;
+        self.idle = false;
+        self.inner.on_event(item, ts)
+    }
+
+    fn on_idle(&mut self) -> Option<Timestamp> {
+        let now = Instant::now();
+        if std::mem::take(&mut self.received) || self.last_active.is_none() {
+            self.last_active = Some(now);
+        }
+        if self.idle {
+            return None;
+        }
+        if let Some(watermark) = self.inner.on_idle() {
+            return Some(watermark);
+        }
+        if now.duration_since(self.last_active.unwrap()) >= self.timeout {
+            self.idle = true;
+            return Some(IDLE_WATERMARK);
+        }
+        None
+    }
+
+    fn needs_heartbeats(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn bounded_out_of_orderness() {
+        let mut gen = BoundedOutOfOrderness::new(10);
+        assert_eq!(gen.on_event(&(), 100), Some(89));
+        assert_eq!(gen.on_event(&(), 95), None);
+        assert_eq!(gen.on_event(&(), 100), None);
+        assert_eq!(gen.on_event(&(), 105), Some(94));
+    }
+
+    #[test]
+    fn periodic_emits_latest() {
+        let mut gen = BoundedOutOfOrderness::new(0).periodic(Duration::from_millis(50));
+        assert_eq!(gen.on_event(&(), 10), Some(9));
+        assert_eq!(gen.on_event(&(), 20), None);
+        assert_eq!(gen.on_event(&(), 30), None);
+        assert_eq!(WatermarkGenerator::<()>::on_idle(&mut gen), None);
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(WatermarkGenerator::<()>::on_idle(&mut gen), Some(29));
+        assert_eq!(WatermarkGenerator::<()>::on_idle(&mut gen), None);
+    }
+
+    #[test]
+    fn idle_timeout() {
+        let mut gen = BoundedOutOfOrderness::new(0).idle_timeout(Duration::from_millis(50));
+        assert_eq!(gen.on_event(&(), 10), Some(9));
+        assert_eq!(WatermarkGenerator::<()>::on_idle(&mut gen), None);
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(
+            WatermarkGenerator::<()>::on_idle(&mut gen),
+            Some(IDLE_WATERMARK)
+        );
+        // idleness is signaled only once
+        assert_eq!(WatermarkGenerator::<()>::on_idle(&mut gen), None);
+        // an element makes the stream active again
+        assert_eq!(gen.on_event(&(), 20), Some(19));
+        assert_eq!(WatermarkGenerator::<()>::on_idle(&mut gen), None);
+    }
+}
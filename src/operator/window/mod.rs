@@ -9,7 +9,10 @@ pub use descr::*;
 // pub use description::*;
 
 use crate::block::{GroupHasherBuilder, OperatorStructure, Replication};
-use crate::operator::{Data, DataKey, ExchangeData, Operator, StreamElement, Timestamp};
+use crate::operator::retention::KeyRetention;
+use crate::operator::{
+    Data, DataKey, ExchangeData, Operator, StateRetention, StreamElement, Timestamp,
+};
 use crate::stream::{KeyedStream, Stream, WindowedStream};
 
 mod aggr;
@@ -47,6 +50,8 @@ pub trait WindowAccumulator: Clone + Send + 'static {
 pub(crate) struct KeyedWindowManager<Key, In, Out, W: WindowManager> {
     windows: HashMap<Key, W, GroupHasherBuilder>,
     init: W,
+    /// The keys whose windows have to be dropped before the end of the stream, if any.
+    retention: Option<KeyRetention<Key>>,
     _in: PhantomData<In>,
     _out: PhantomData<Out>,
 }
@@ -165,7 +170,15 @@ where
     Out: Data,
 {
     fn setup(&mut self, metadata: &mut crate::ExecutionMetadata) {
+        let retention = self.manager.retention.as_ref();
+        if let Some(interval) = retention.and_then(|r| r.wake_up_interval()) {
+            metadata.wake_up_every(interval);
+        }
         self.prev.setup(metadata);
+        // the open windows are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("windows are not supported");
+        }
     }
 
     fn next(&mut self) -> StreamElement<(Key, Out)> {
@@ -175,6 +188,7 @@ where
             }
 
             let el = self.prev.next();
+            self.evict(&el);
             match el {
                 el @ (StreamElement::Item(_) | StreamElement::Timestamped(_, _)) => {
                     let (key, el) = el.take_key();
@@ -192,7 +206,9 @@ where
                             .map(|e| StreamElement::from(e).add_key(key.clone())),
                     );
                 }
-                StreamElement::FlushBatch => return StreamElement::FlushBatch,
+                StreamElement::FlushBatch => {
+                    self.output_buffer.push_back(StreamElement::FlushBatch);
+                }
                 el => {
                     let (_, el) = el.take_key();
 
@@ -202,8 +218,18 @@ where
                             ret.into_iter()
                                 .map(|e| StreamElement::from(e).add_key(key.clone())),
                         );
-                        !mgr.recycle()
+                        let recycle = mgr.recycle();
+                        if let (true, Some(retention)) = (recycle, &mut self.manager.retention) {
+                            retention.remove(key);
+                        }
+                        !recycle
                     });
+                    // at the end of the stream all the windows are closed
+                    if !matches!(el, StreamElement::Watermark(_)) {
+                        if let Some(retention) = &mut self.manager.retention {
+                            retention.clear();
+                        }
+                    }
 
                     // Forward system messages and watermarks
                     let msg = match el {
@@ -227,7 +253,10 @@ where
 
 impl<Key, In, Out, Prev, W> WindowOperator<Key, In, Out, Prev, W>
 where
-    W: WindowManager,
+    W: WindowManager<In = In, Out = Out>,
+    Key: DataKey,
+    In: Data,
+    Out: Data,
 {
     pub(crate) fn new(
         prev: Prev,
@@ -241,6 +270,26 @@ where
             output_buffer: Default::default(),
         }
     }
+
+    /// Drop the windows of the keys to forget after the element, emitting their results if asked.
+    fn evict(&mut self, element: &StreamElement<(Key, In)>) {
+        let Some(retention) = &mut self.manager.retention else {
+            return;
+        };
+        for key in retention.update(element) {
+            let Some(mut mgr) = self.manager.windows.remove(&key) else {
+                continue;
+            };
+            if retention.emit_evicted() {
+                // close the windows like at the end of the stream
+                let ret = mgr.process(StreamElement::FlushAndRestart);
+                self.output_buffer.extend(
+                    ret.into_iter()
+                        .map(|e| StreamElement::from(e).add_key(key.clone())),
+                );
+            }
+        }
+    }
 }
 
 impl<Key, Out, WindowDescr, OperatorChain> WindowedStream<Key, Out, OperatorChain, Out, WindowDescr>
@@ -269,6 +318,7 @@ where
             KeyedWindowManager {
                 windows: HashMap::default(),
                 init,
+                retention: self.retention.map(KeyRetention::new),
                 _in: PhantomData,
                 _out: PhantomData,
             };
@@ -278,6 +328,49 @@ where
     }
 }
 
+impl<Key, Out, WinOut, WindowDescr, OperatorChain>
+    WindowedStream<Key, Out, OperatorChain, WinOut, WindowDescr>
+where
+    WindowDescr: WindowDescription<Out>,
+    OperatorChain: Operator<(Key, Out)> + 'static,
+    Key: DataKey,
+    Out: Data,
+    WinOut: Data,
+{
+    /// Drop the windows of the keys according to `retention`, instead of keeping them until the
+    /// end of the stream.
+    ///
+    /// The open windows of a key are dropped when the key expires or exceeds the maximum number of
+    /// keys (see [`StateRetention`]), and closed like at the end of the stream if
+    /// [`StateRetention::emit_evicted`] is set.
+    ///
+    /// ## Example
+    /// ```
+    /// # use noir::{StreamEnvironment, EnvironmentConfig};
+    /// # use noir::operator::source::IteratorSource;
+    /// # use noir::operator::StateRetention;
+    /// # use noir::operator::window::CountWindow;
+    /// # let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    /// let s = env.stream(IteratorSource::new((0..5)));
+    /// let res = s
+    ///     .group_by(|&n| n % 2)
+    ///     .window(CountWindow::new(2, 2, false))
+    ///     .with_retention(StateRetention::new().max_keys(1).emit_evicted())
+    ///     .sum()
+    ///     .collect_vec();
+    ///
+    /// env.execute_blocking().unwrap();
+    ///
+    /// let mut res = res.get().unwrap();
+    /// res.sort_unstable();
+    /// assert_eq!(res, vec![(0, 0), (0, 2), (0, 4), (1, 1), (1, 3)]);
+    /// ```
+    pub fn with_retention(mut self, retention: StateRetention) -> Self {
+        self.retention = Some(retention);
+        self
+    }
+}
+
 impl<Key: DataKey, Out: Data, OperatorChain> KeyedStream<Key, Out, OperatorChain>
 where
     OperatorChain: Operator<(Key, Out)> + 'static,
@@ -300,7 +393,7 @@ where
     ///     .sum()
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// res.sort_unstable();
@@ -313,6 +406,7 @@ where
         WindowedStream {
             inner: self,
             descr,
+            retention: None,
             _win_out: PhantomData,
         }
     }
@@ -343,7 +437,7 @@ where
     ///     .drop_key()
     ///     .collect_vec();
     ///
-    /// env.execute_blocking();
+    /// env.execute_blocking().unwrap();
     ///
     /// let mut res = res.get().unwrap();
     /// assert_eq!(res, vec![0 + 1, 2 + 3]);
@@ -57,6 +57,10 @@ impl<Out1: ExchangeData, Out2: ExchangeData> Zip<Out1, Out2> {
 impl<Out1: ExchangeData, Out2: ExchangeData> Operator<(Out1, Out2)> for Zip<Out1, Out2> {
     fn setup(&mut self, metadata: &mut ExecutionMetadata) {
         self.prev.setup(metadata);
+        // the stashed items are not part of the checkpoints
+        if let Some(checkpoints) = metadata.job.checkpoints() {
+            checkpoints.disable("zip is not supported");
+        }
     }
 
     #[inline]
@@ -93,9 +97,9 @@ impl<Out1: ExchangeData, Out2: ExchangeData> Operator<(Out1, Out2)> for Zip<Out1
                     return item.map(|_| unreachable!());
                 }
 
-                StreamElement::FlushBatch | StreamElement::Terminate => {
-                    return item.map(|_| unreachable!())
-                }
+                StreamElement::FlushBatch
+                | StreamElement::Terminate
+                | StreamElement::Checkpoint(_) => return item.map(|_| unreachable!()),
             }
         }
         let item1 = self.stash1.pop_front().unwrap();
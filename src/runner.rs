@@ -15,6 +15,7 @@ use sha2::Digest;
 #[cfg(feature = "ssh")]
 use ssh2::Session;
 
+use crate::cluster::forward_output;
 use crate::config::CONFIG_ENV_VAR;
 use crate::config::HOST_ID_ENV_VAR;
 use crate::config::{RemoteHostConfig, RemoteRuntimeConfig};
@@ -102,13 +103,7 @@ pub(crate) fn spawn_remote_workers(config: RemoteRuntimeConfig) {
         }
     }
     if let Some(path) = config.tracing_dir {
-        std::fs::create_dir_all(&path).expect("Cannot create tracing directory");
-        let now = chrono::Local::now();
-        let file_name = format!("{}.json", now.format("%Y-%m-%d-%H%M%S"));
-        let target = path.join(file_name);
-        let mut target = std::fs::File::create(target).expect("Cannot create tracing json file");
-        serde_json::to_writer(&mut target, &tracing_data)
-            .expect("Failed to write tracing json file");
+        tracing_data.save(&path);
     }
 
     log::info!("total time: {:?}", start.elapsed());
@@ -229,35 +224,7 @@ fn remote_worker(
     let stderr_reader = BufReader::new(channel.stderr());
     let stdout_reader = BufReader::new(&mut channel);
 
-    let mut tracing_data = None;
-
-    std::thread::scope(|s| {
-        s.spawn(|| {
-            for l in stdout_reader.lines() {
-                println!(
-                    "{}|{}",
-                    host_id,
-                    l.unwrap_or_else(|e| format!("ERROR: {e}"))
-                );
-            }
-        });
-        s.spawn(|| {
-            // copy to stderr the output of the remote process
-            for line in stderr_reader.lines().flatten() {
-                if let Some(pos) = line.find("__noir2_TRACING_DATA__") {
-                    let json_data = &line[(pos + "__noir2_TRACING_DATA__ ".len())..];
-                    match serde_json::from_str(json_data) {
-                        Ok(data) => tracing_data = Some(data),
-                        Err(err) => {
-                            error!("Corrupted tracing data from host {}: {:?}", host_id, err);
-                        }
-                    }
-                } else {
-                    eprintln!("{host_id}|{line}");
-                }
-            }
-        });
-    });
+    let tracing_data = forward_output(host_id, stdout_reader, stderr_reader);
 
     channel.wait_close().unwrap();
     let exit_code = channel.exit_status().unwrap();
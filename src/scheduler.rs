@@ -1,13 +1,21 @@
 use std::any::TypeId;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
+use std::sync::Arc;
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use itertools::Itertools;
 
 use crate::block::{BatchMode, Block, BlockStructure, JobGraphGenerator, Replication};
-use crate::config::{EnvironmentConfig, ExecutionRuntime, LocalRuntimeConfig, RemoteRuntimeConfig};
+use crate::checkpoint::OperatorState;
+use crate::config::{
+    EnvironmentConfig, ExecutionRuntime, LocalRuntimeConfig, RemoteHostConfig, RemoteRuntimeConfig,
+};
+use crate::job::{JobError, JobErrorKind, JobReport, JobState, StopFlag};
 use crate::network::{Coord, NetworkTopology};
+#[cfg(feature = "timestamp")]
+use crate::operator::watermark::IdleSignal;
 use crate::operator::{Data, Operator};
 use crate::profiler::{wait_profiler, ProfilerResult};
 use crate::worker::spawn_worker;
@@ -39,6 +47,43 @@ pub struct ExecutionMetadata<'a> {
     pub(crate) network: &'a mut NetworkTopology,
     /// The batching mode to use inside this block.
     pub batch_mode: BatchMode,
+    /// The state of the job the block belongs to.
+    pub(crate) job: Arc<JobState>,
+    /// The number of operators of this replica that registered their state.
+    pub(crate) operator_states: usize,
+    /// How often the block has to be woken up when no message arrives, asked by the operators
+    /// that react to the passing of time.
+    pub(crate) wake_up_interval: Option<Duration>,
+    /// The idleness of the replica detected by its watermark generator, sent to the next blocks
+    /// by the operators at the end of the block.
+    #[cfg(feature = "timestamp")]
+    pub(crate) idle_signal: Option<IdleSignal>,
+}
+
+impl ExecutionMetadata<'_> {
+    /// Ask to wake up the block at least every `interval` when no message arrives.
+    ///
+    /// This should be called during `setup`, before the setup of the previous operators.
+    pub(crate) fn wake_up_every(&mut self, interval: Duration) {
+        let interval = self.wake_up_interval.map_or(interval, |i| i.min(interval));
+        self.wake_up_interval = Some(interval);
+    }
+
+    /// Register an operator whose state is saved in the checkpoints of the job.
+    ///
+    /// This should be called during `setup`, the operators are identified by the order in which
+    /// they register. Returns `None` if the checkpoints are disabled.
+    pub fn operator_state(&mut self) -> Option<OperatorState> {
+        self.job.checkpoints()?;
+        let state = OperatorState::new(
+            self.job.clone(),
+            self.coord.block_id,
+            self.coord.replica_id,
+            self.operator_states,
+        );
+        self.operator_states += 1;
+        Some(state)
+    }
 }
 
 /// Information about a block in the job graph.
@@ -71,20 +116,37 @@ pub(crate) struct Scheduler {
     block_init: Vec<(Coord, BlockInitFn)>,
     /// The network topology that keeps track of all the connections inside the execution graph.
     network: NetworkTopology,
+    /// The state of the job shared with the workers.
+    job: Arc<JobState>,
+    /// The first block that cannot be placed on the hosts, the job fails without starting.
+    placement_error: Option<JobError>,
+    /// The blocks ending with a limit, with the flag set when the limit is reached.
+    limits: Vec<(BlockId, Arc<StopFlag>)>,
 }
 
 impl Scheduler {
     pub fn new(config: EnvironmentConfig) -> Self {
+        let job = Arc::new(JobState::new(&config));
+        let mut network = NetworkTopology::new(config.clone());
+        network.set_metrics(job.metrics().clone());
         Self {
             next_blocks: Default::default(),
             prev_blocks: Default::default(),
             block_info: Default::default(),
             block_init: Default::default(),
-            network: NetworkTopology::new(config.clone()),
+            network,
+            job,
+            placement_error: None,
+            limits: Vec::new(),
             config,
         }
     }
 
+    /// The state of the job that will be started by this scheduler.
+    pub(crate) fn job(&self) -> Arc<JobState> {
+        self.job.clone()
+    }
+
     /// Register a new block inside the scheduler.
     ///
     /// This spawns a worker for each replica of the block in the execution graph and saves its
@@ -97,7 +159,13 @@ impl Scheduler {
         OperatorChain: Operator<Out> + 'static,
     {
         let block_id = block.id;
-        let info = self.block_info(&block);
+        let info = match self.block_info(&block) {
+            Ok(info) => info,
+            Err(error) => {
+                self.placement_error.get_or_insert(error);
+                return;
+            }
+        };
         debug!(
             "schedule block (b{:02}): {}",
             block_id,
@@ -124,9 +192,10 @@ impl Scheduler {
 
         for (coord, block) in blocks {
             // spawn the actual worker
+            let job = self.job.clone();
             self.block_init.push((
                 coord,
-                Box::new(move |metadata| spawn_worker(block, metadata)),
+                Box::new(move |metadata| spawn_worker(block, metadata, job)),
             ));
         }
     }
@@ -153,8 +222,13 @@ impl Scheduler {
 
     fn build_all(&mut self) -> (Vec<JoinHandle<()>>, Vec<(Coord, BlockStructure)>) {
         self.build_execution_graph();
+        self.stop_limited_sources();
         self.network.build();
         self.network.log();
+        if let Some(host_id) = self.config.host_id {
+            let addresses = self.network.control_addresses().to_vec();
+            self.job.start_control(host_id, addresses);
+        }
 
         let mut join = vec![];
         let mut block_structures = vec![];
@@ -171,6 +245,11 @@ impl Scheduler {
                 prev: self.network.prev(coord),
                 network: &mut self.network,
                 batch_mode: block_info.batch_mode,
+                job: self.job.clone(),
+                operator_states: 0,
+                wake_up_interval: None,
+                #[cfg(feature = "timestamp")]
+                idle_signal: None,
             };
             let (handle, structure) = init_fn(&mut metadata);
             join.push(handle);
@@ -182,14 +261,18 @@ impl Scheduler {
         log::debug!("job graph:\n{}", job_graph);
 
         self.network.finalize();
+        self.job.start(self.config.host_id.unwrap());
 
         (join, block_structures)
     }
 
     #[cfg(feature = "async-tokio")]
-    /// Start the computation returning the list of handles used to join the workers.
-    pub(crate) async fn start(mut self, block_count: CoordUInt) {
+    /// Start the computation and wait for all the workers to complete.
+    pub(crate) async fn start(mut self, block_count: CoordUInt) -> Result<JobReport, JobError> {
         debug!("start scheduler: {:?}", self.config);
+        if let Some(error) = self.placement_error.take() {
+            return Err(error);
+        }
         self.log_topology();
 
         assert_eq!(
@@ -200,13 +283,17 @@ impl Scheduler {
             self.block_info.len(),
         );
 
+        let start = Instant::now();
         let (join, block_structures) = self.build_all();
+        let workers = join.len();
+        let _checkpoint_timer = self.job.checkpoints().map(|c| c.start_timer());
+        let _metrics_reporter = self.job.metrics().start_reporter();
 
         let (_, join_result) = tokio::join!(
             self.network.stop_and_wait(),
             tokio::task::spawn_blocking(move || {
                 for handle in join {
-                    handle.join().unwrap();
+                    handle.join().expect("Could not join worker thread");
                 }
             })
         );
@@ -214,14 +301,27 @@ impl Scheduler {
         join_result.expect("Could not join worker threads");
 
         Self::log_tracing_data(block_structures, wait_profiler());
+        self.job.stop_control();
+        self.job.result(JobReport {
+            duration: start.elapsed(),
+            blocks: block_count as usize,
+            workers,
+            cancelled: self.job.is_cancelled(),
+        })
     }
 
-    /// Start the computation returning the list of handles used to join the workers.
+    /// Start the computation and wait for all the workers to complete.
+    ///
+    /// If a worker fails all the others are stopped, and the error of the first failure is
+    /// returned. If a block cannot be placed on the hosts no worker is started.
     ///
     /// NOTE: If running with the `async-tokio` feature enable, this will create a new
     /// tokio runtime.
-    pub(crate) fn start_blocking(mut self, num_blocks: CoordUInt) {
+    pub(crate) fn start_blocking(mut self, num_blocks: CoordUInt) -> Result<JobReport, JobError> {
         debug!("start scheduler: {:?}", self.config);
+        if let Some(error) = self.placement_error.take() {
+            return Err(error);
+        }
         self.log_topology();
 
         assert_eq!(
@@ -232,40 +332,57 @@ impl Scheduler {
             self.block_info.len(),
         );
 
+        let start = Instant::now();
         #[cfg(feature = "async-tokio")]
-        {
+        let workers = {
             tokio::runtime::Builder::new_multi_thread()
                 .enable_io()
                 .enable_time()
                 .build()
                 .unwrap()
-                .block_on(async move {
+                .block_on(async {
                     let (join, block_structures) = self.build_all();
+                    let workers = join.len();
+                    let _checkpoint_timer = self.job.checkpoints().map(|c| c.start_timer());
+                    let _metrics_reporter = self.job.metrics().start_reporter();
 
                     let (_, join_result) = tokio::join!(
                         self.network.stop_and_wait(),
                         tokio::task::spawn_blocking(move || {
                             for handle in join {
-                                handle.join().unwrap();
+                                handle.join().expect("Could not join worker thread");
                             }
                         })
                     );
                     join_result.expect("Could not join worker threads");
                     Self::log_tracing_data(block_structures, wait_profiler());
-                });
-        }
+                    workers
+                })
+        };
         #[cfg(not(feature = "async-tokio"))]
-        {
+        let workers = {
             let (join, block_structures) = self.build_all();
+            let workers = join.len();
+            let _checkpoint_timer = self.job.checkpoints().map(|c| c.start_timer());
+            let _metrics_reporter = self.job.metrics().start_reporter();
 
             for handle in join {
-                handle.join().unwrap();
+                handle.join().expect("Could not join worker thread");
             }
 
             self.network.stop_and_wait();
             let profiler_results = wait_profiler();
             Self::log_tracing_data(block_structures, profiler_results);
-        }
+            workers
+        };
+
+        self.job.stop_control();
+        self.job.result(JobReport {
+            duration: start.elapsed(),
+            blocks: num_blocks as usize,
+            workers,
+            cancelled: self.job.is_cancelled(),
+        })
     }
 
     /// Get the ids of the previous blocks of a given block in the job graph
@@ -273,6 +390,54 @@ impl Scheduler {
         self.prev_blocks.get(&block_id).cloned()
     }
 
+    /// Register a limit at the end of the block, `flag` is set when the limit is reached.
+    pub(crate) fn register_limit(&mut self, block_id: BlockId, flag: Arc<StopFlag>) {
+        self.limits.push((block_id, flag));
+    }
+
+    /// Let the sources of each block end their stream once the limits are reached, if all the
+    /// outputs of the block go through a limit.
+    fn stop_limited_sources(&self) {
+        if self.limits.is_empty() {
+            return;
+        }
+        for &block_id in self.block_info.keys() {
+            let mut flags = Vec::new();
+            if self.limited_outputs(block_id, &mut HashSet::new(), &mut flags) {
+                self.job.stop_sources_on(block_id, flags);
+            }
+        }
+    }
+
+    /// Whether all the outputs of the block go through a limit, collecting the flags of the
+    /// limits.
+    fn limited_outputs(
+        &self,
+        block_id: BlockId,
+        path: &mut HashSet<BlockId>,
+        flags: &mut Vec<Arc<StopFlag>>,
+    ) -> bool {
+        let limited = self.limits.iter().filter(|(b, _)| *b == block_id);
+        let len = flags.len();
+        flags.extend(limited.map(|(_, flag)| flag.clone()));
+        if flags.len() > len {
+            return true;
+        }
+        // the feedback of an iteration leads back to the same block
+        if !path.insert(block_id) {
+            return false;
+        }
+        let limited = match self.next_blocks.get(&block_id) {
+            Some(next) if !next.is_empty() => next
+                .iter()
+                .all(|&(next, _, _)| self.limited_outputs(next, path, flags)),
+            // the sinks are not limited
+            _ => false,
+        };
+        path.remove(&block_id);
+        limited
+    }
+
     /// Build the execution graph for the network topology, multiplying each block of the job graph
     /// into all its replicas.
     fn build_execution_graph(&mut self) {
@@ -332,12 +497,12 @@ impl Scheduler {
     fn block_info<Out: Data, OperatorChain>(
         &self,
         block: &Block<Out, OperatorChain>,
-    ) -> SchedulerBlockInfo
+    ) -> Result<SchedulerBlockInfo, JobError>
     where
         OperatorChain: Operator<Out>,
     {
         match &self.config.runtime {
-            ExecutionRuntime::Local(local) => self.local_block_info(block, local),
+            ExecutionRuntime::Local(local) => Ok(self.local_block_info(block, local)),
             ExecutionRuntime::Remote(remote) => self.remote_block_info(block, remote),
         }
     }
@@ -348,6 +513,9 @@ impl Scheduler {
     ///
     ///  - the number of logical cores.
     ///  - the `replication` of the block.
+    ///
+    /// With [`Replication::PerCore`] the number of replicas is relative to the number of cores.
+    /// The labels requested by the block are ignored, since there is only one host.
     fn local_block_info<Out: Data, OperatorChain>(
         &self,
         block: &Block<Out, OperatorChain>,
@@ -379,17 +547,49 @@ impl Scheduler {
 
     /// Extract the `SchedulerBlockInfo` of a block that runs remotely.
     ///
-    /// The block can be replicated at most `replication` times (if specified). Assign the
-    /// replicas starting from the first host giving as much replicas as possible..
+    /// The block can be replicated at most `replication` times (if specified). The limited
+    /// replicas are split between the hosts proportionally to their weight, without giving a
+    /// host more replicas than its cores.
+    ///
+    /// With [`Replication::PerCore`] each host gets a number of replicas relative to its cores.
+    ///
+    /// Only the hosts with all the labels requested by the block are considered, it's an error if
+    /// there are none.
     fn remote_block_info<Out: Data, OperatorChain>(
         &self,
         block: &Block<Out, OperatorChain>,
         remote: &RemoteRuntimeConfig,
-    ) -> SchedulerBlockInfo
+    ) -> Result<SchedulerBlockInfo, JobError>
     where
         OperatorChain: Operator<Out>,
     {
         let replication = block.scheduler_requirements.replication;
+        // only the hosts with all the labels requested by the block
+        let labels = &block.scheduler_requirements.host_labels;
+        let hosts = remote
+            .hosts
+            .iter()
+            .enumerate()
+            .filter(|(_, host_info)| host_info.has_labels(labels))
+            .map(|(host_id, host_info)| (HostId::try_from(host_id).unwrap(), host_info))
+            .collect_vec();
+        if hosts.is_empty() {
+            let structure = block.operators.structure();
+            return Err(JobError {
+                kind: JobErrorKind::Placement,
+                block_id: block.id,
+                host_id: self.config.host_id.unwrap(),
+                replica_id: 0,
+                operator: structure
+                    .operators
+                    .last()
+                    .map(|op| op.title.clone())
+                    .unwrap_or_default(),
+                message: format!("no host has the labels {labels:?} required by the block"),
+                location: None,
+                failed_workers: 0,
+            });
+        }
         // number of replicas we can assign at most
         let mut global_counter = 0;
         let mut replicas: HashMap<_, Vec<_>, crate::block::CoordHasherBuilder> = HashMap::default();
@@ -415,37 +615,97 @@ impl Scheduler {
             }};
         }
 
+        // without weights the replicas fill the hosts in order
+        let weighted = hosts
+            .iter()
+            .any(|(_, host_info)| host_info.weight.is_some());
         match replication {
             Replication::Unlimited => {
-                for (host_id, host_info) in remote.hosts.iter().enumerate() {
-                    add_replicas!(host_id.try_into().unwrap(), host_info, host_info.num_cores);
+                for &(host_id, host_info) in &hosts {
+                    add_replicas!(host_id, host_info, host_info.num_cores);
+                }
+            }
+            Replication::Limited(n) if weighted => {
+                let counts = weighted_split(n, &hosts);
+                for (&(host_id, host_info), n) in hosts.iter().zip(counts) {
+                    add_replicas!(host_id, host_info, n);
                 }
             }
             Replication::Limited(mut remaining) => {
-                for (host_id, host_info) in remote.hosts.iter().enumerate() {
+                for &(host_id, host_info) in &hosts {
                     let n = remaining.min(host_info.num_cores);
-                    add_replicas!(host_id.try_into().unwrap(), host_info, n);
+                    add_replicas!(host_id, host_info, n);
                     remaining -= n;
                 }
             }
+            Replication::PerCore { .. } => {
+                for &(host_id, host_info) in &hosts {
+                    let n = replication.clamp(host_info.num_cores);
+                    add_replicas!(host_id, host_info, n);
+                }
+            }
             Replication::Host => {
-                for (host_id, host_info) in remote.hosts.iter().enumerate() {
-                    add_replicas!(host_id.try_into().unwrap(), host_info, 1);
+                for &(host_id, host_info) in &hosts {
+                    add_replicas!(host_id, host_info, 1);
                 }
             }
+            Replication::One if weighted => {
+                // the first of the hosts with the largest weight
+                let (host_id, host_info) = *hosts
+                    .iter()
+                    .rev()
+                    .max_by_key(|(_, host_info)| host_info.weight.unwrap_or(1))
+                    .unwrap();
+                add_replicas!(host_id, host_info, 1);
+            }
             Replication::One => {
-                add_replicas!(0, remote.hosts[0], 1);
+                let (host_id, host_info) = hosts[0];
+                add_replicas!(host_id, host_info, 1);
             }
         }
 
-        SchedulerBlockInfo {
+        Ok(SchedulerBlockInfo {
             repr: block.to_string(),
             replicas,
             global_ids,
             batch_mode: block.batch_mode,
             is_only_one_strategy: block.is_only_one_strategy,
+        })
+    }
+}
+
+/// Split `n` replicas between the hosts proportionally to their weights (1 for the hosts without
+/// one), without giving a host more replicas than its cores.
+///
+/// Each replica goes to the host with the largest weight per assigned replica (the D'Hondt
+/// method), preferring the first hosts on ties.
+fn weighted_split(n: CoordUInt, hosts: &[(HostId, &RemoteHostConfig)]) -> Vec<CoordUInt> {
+    let mut counts = vec![0; hosts.len()];
+    for _ in 0..n {
+        let next = hosts
+            .iter()
+            .zip(&counts)
+            .enumerate()
+            .filter(|(_, ((_, host), &count))| count < host.num_cores)
+            // compare weight_a / (count_a + 1) with weight_b / (count_b + 1)
+            .reduce(|best, candidate| {
+                let ((_, best_host), &best_count) = best.1;
+                let ((_, host), &count) = candidate.1;
+                let (weight, best_weight) =
+                    (host.weight.unwrap_or(1), best_host.weight.unwrap_or(1));
+                if weight * (best_count + 1) > best_weight * (count + 1) {
+                    candidate
+                } else {
+                    best
+                }
+            });
+        match next {
+            Some((i, _)) => counts[i] += 1,
+            // all the cores are taken
+            None => break,
         }
     }
+    counts
 }
 
 impl SchedulerBlockInfo {
@@ -458,9 +718,45 @@ impl SchedulerBlockInfo {
 #[cfg(not(feature = "async-tokio"))]
 #[cfg(test)]
 mod tests {
-    use crate::config::EnvironmentConfig;
+    use crate::block::{BatchMode, Block, Replication};
+    use crate::config::{
+        EnvironmentConfig, ExecutionRuntime, RemoteHostConfig, RemoteRuntimeConfig,
+    };
     use crate::environment::StreamEnvironment;
+    use crate::job::JobErrorKind;
     use crate::operator::source::IteratorSource;
+    use crate::test::FakeOperator;
+    use crate::CoordUInt;
+
+    use super::Scheduler;
+
+    fn labeled_hosts(labels: &[&[&str]]) -> EnvironmentConfig {
+        weighted_hosts(labels, &vec![None; labels.len()])
+    }
+
+    fn weighted_hosts(labels: &[&[&str]], weights: &[Option<CoordUInt>]) -> EnvironmentConfig {
+        let hosts = labels
+            .iter()
+            .zip(weights)
+            .map(|(labels, &weight)| RemoteHostConfig {
+                address: "127.0.0.1".into(),
+                base_port: 9500,
+                num_cores: 2,
+                ssh: Default::default(),
+                perf_path: None,
+                labels: labels.iter().map(|l| l.to_string()).collect(),
+                weight,
+            })
+            .collect();
+        let mut config = EnvironmentConfig::local(1);
+        config.runtime = ExecutionRuntime::Remote(RemoteRuntimeConfig {
+            hosts,
+            tracing_dir: None,
+            cleanup_executable: false,
+            launcher: Default::default(),
+        });
+        config
+    }
 
     #[test]
     #[should_panic(expected = "Some streams do not have a sink attached")]
@@ -468,7 +764,7 @@ mod tests {
         let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
         let source = IteratorSource::new(vec![1, 2, 3].into_iter());
         let _stream = env.stream(source);
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
     }
 
     #[test]
@@ -477,6 +773,107 @@ mod tests {
         let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
         let source = IteratorSource::new(vec![1, 2, 3].into_iter());
         let _stream = env.stream(source).shuffle();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
+    }
+
+    #[test]
+    fn test_scheduler_host_labels() {
+        let config = labeled_hosts(&[&["ingest"], &["bigmem"], &["ingest", "bigmem"]]);
+        let scheduler = Scheduler::new(config);
+        let block_info = |labels: &[&str], replication: Replication| {
+            let mut block =
+                Block::new(0, FakeOperator::<u8>::empty(), BatchMode::default(), vec![]);
+            for label in labels {
+                block.scheduler_requirements.host_label(label.to_string());
+            }
+            block.scheduler_requirements.replication(replication);
+            let info = scheduler.block_info(&block).unwrap();
+            (0..3)
+                .map(|host| info.replicas(host).len())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(block_info(&[], Replication::Unlimited), vec![2, 2, 2]);
+        assert_eq!(
+            block_info(&["ingest"], Replication::Unlimited),
+            vec![2, 0, 2]
+        );
+        assert_eq!(block_info(&["bigmem"], Replication::Host), vec![0, 1, 1]);
+        assert_eq!(
+            block_info(&["ingest", "bigmem"], Replication::One),
+            vec![0, 0, 1]
+        );
+        assert_eq!(
+            block_info(&["ingest"], Replication::Limited(3)),
+            vec![2, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_scheduler_weighted_hosts() {
+        let config = weighted_hosts(&[&[], &[], &["ingest"]], &[None, Some(3), Some(2)]);
+        let scheduler = Scheduler::new(config);
+        let block_info = |labels: &[&str], replication: Replication| {
+            let mut block =
+                Block::new(0, FakeOperator::<u8>::empty(), BatchMode::default(), vec![]);
+            for label in labels {
+                block.scheduler_requirements.host_label(label.to_string());
+            }
+            block.scheduler_requirements.replication(replication);
+            let info = scheduler.block_info(&block).unwrap();
+            (0..3)
+                .map(|host| info.replicas(host).len())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(block_info(&[], Replication::Limited(3)), vec![0, 2, 1]);
+        // the cores of a host are not exceeded
+        assert_eq!(block_info(&[], Replication::Limited(5)), vec![1, 2, 2]);
+        assert_eq!(block_info(&[], Replication::Limited(10)), vec![2, 2, 2]);
+        assert_eq!(block_info(&[], Replication::One), vec![0, 1, 0]);
+        assert_eq!(block_info(&["ingest"], Replication::One), vec![0, 0, 1]);
+        // the weights do not change the unlimited replication
+        assert_eq!(block_info(&[], Replication::Unlimited), vec![2, 2, 2]);
+    }
+
+    #[test]
+    fn test_scheduler_unweighted_hosts() {
+        // without labels and weights the replicas fill the hosts in order
+        let mut config = labeled_hosts(&[&[], &[]]);
+        let ExecutionRuntime::Remote(remote) = &mut config.runtime else {
+            unreachable!()
+        };
+        for host in &mut remote.hosts {
+            host.num_cores = 4;
+        }
+        let scheduler = Scheduler::new(config);
+        let block_info = |replication: Replication| {
+            let mut block =
+                Block::new(0, FakeOperator::<u8>::empty(), BatchMode::default(), vec![]);
+            block.scheduler_requirements.replication(replication);
+            let info = scheduler.block_info(&block).unwrap();
+            (0..2)
+                .map(|host| info.replicas(host).len())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(block_info(Replication::Limited(1)), vec![1, 0]);
+        assert_eq!(block_info(Replication::Limited(3)), vec![3, 0]);
+        assert_eq!(block_info(Replication::Limited(6)), vec![4, 2]);
+        assert_eq!(block_info(Replication::Limited(10)), vec![4, 4]);
+        assert_eq!(block_info(Replication::One), vec![1, 0]);
+        assert_eq!(block_info(Replication::Host), vec![1, 1]);
+        assert_eq!(block_info(Replication::Unlimited), vec![4, 4]);
+    }
+
+    #[test]
+    fn test_scheduler_missing_host_label() {
+        let scheduler = Scheduler::new(labeled_hosts(&[&["ingest"]]));
+        let mut block = Block::new(3, FakeOperator::<u8>::empty(), BatchMode::default(), vec![]);
+        block.scheduler_requirements.host_label("gpu".into());
+        let error = scheduler.block_info(&block).unwrap_err();
+        assert_eq!(error.kind, JobErrorKind::Placement);
+        assert_eq!(error.block_id, 3);
+        assert!(error.message.contains("gpu"), "{}", error.message);
     }
 }
@@ -10,8 +10,8 @@ use crate::operator::iteration::IterationStateLock;
 use crate::operator::source::Source;
 use crate::operator::window::WindowDescription;
 use crate::operator::Start;
-use crate::operator::{Data, ExchangeData, KeyerFn, Operator};
-use crate::operator::{DataKey, SimpleStartOperator};
+use crate::operator::{Data, ExchangeData, KeyerFn, Operator, StateRetention};
+use crate::operator::{DataKey, SimpleStartOperator, UnionStartOperator};
 use crate::scheduler::BlockId;
 
 /// A Stream represents a chain of operators that work on a flow of data. The type of the elements
@@ -73,6 +73,7 @@ where
 {
     pub(crate) inner: KeyedStream<K, I, Op>,
     pub(crate) descr: WinDescr,
+    pub(crate) retention: Option<StateRetention>,
     pub(crate) _win_out: PhantomData<O>,
 }
 
@@ -249,6 +250,70 @@ where
         }
     }
 
+    /// Start a new block that receives the elements of this stream and of all the `others`,
+    /// closing the current block of each of them.
+    ///
+    /// All the streams must have the same parallelism and must be inside the same iteration, since
+    /// the elements are sent with the `OnlyOne` strategy and no side is cached.
+    pub(crate) fn union_connection<Op2>(
+        self,
+        others: Vec<Stream<I, Op2>>,
+    ) -> Stream<I, UnionStartOperator<I>>
+    where
+        I: ExchangeData,
+        Op2: Operator<I> + 'static,
+    {
+        let Stream { block, env } = self;
+
+        let batch_mode = block.batch_mode;
+        let sched = block.scheduler_requirements.clone();
+        let iteration_ctx = block.iteration_ctx.clone();
+        for other in &others {
+            let other_sched = &other.block.scheduler_requirements;
+            if other_sched.replication != sched.replication {
+                panic!(
+                    "The parallelism of all the blocks coming inside a union must be equal. \
+                    The first ({}) is {:?}, another ({}) is {:?}",
+                    block, sched.replication, other.block, other_sched.replication
+                );
+            }
+            if other.block.iteration_ctx() != block.iteration_ctx() {
+                panic!("All the streams of a union must be inside the same iteration");
+            }
+        }
+
+        let mut env_lock = env.lock();
+        let mut prev_ids = Vec::with_capacity(others.len() + 1);
+
+        // close previous blocks
+        let mut block =
+            block.add_operator(|prev| End::new(prev, NextStrategy::only_one(), batch_mode));
+        block.is_only_one_strategy = true;
+        prev_ids.push(env_lock.close_block(block));
+        for other in others {
+            let mut block = other
+                .block
+                .add_operator(|prev| End::new(prev, NextStrategy::only_one(), batch_mode));
+            block.is_only_one_strategy = true;
+            prev_ids.push(env_lock.close_block(block));
+        }
+
+        let source = Start::union(prev_ids.clone(), iteration_ctx.last().cloned());
+        let mut new_block = env_lock.new_block(source, batch_mode, iteration_ctx);
+        for &id in &prev_ids {
+            env_lock.connect_blocks::<I>(id, new_block.id);
+        }
+        drop(env_lock);
+
+        // make sure the new block has the same parallelism of the previous ones
+        new_block.scheduler_requirements = sched;
+
+        Stream {
+            block: new_block,
+            env,
+        }
+    }
+
     /// Clone the given block, taking care of connecting the new block to the same previous blocks
     /// of the original one.
     pub(crate) fn clone(&mut self) -> Self {
@@ -113,6 +113,11 @@ impl<T: ExchangeData> FakeNetworkTopology<T> {
             prev: self.prev.clone(),
             network: &mut self.topology,
             batch_mode: BatchMode::adaptive(100, Duration::from_millis(100)),
+            job: Default::default(),
+            operator_states: 0,
+            wake_up_interval: None,
+            #[cfg(feature = "timestamp")]
+            idle_signal: None,
         }
     }
 
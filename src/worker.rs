@@ -1,89 +1,249 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Once};
 use std::thread::JoinHandle;
 
+use crate::affinity;
 use crate::block::{Block, BlockStructure};
+use crate::concurrency::{self, TIME_SLICE};
+use crate::job::JobState;
+use crate::metrics;
 use crate::network::Coord;
 use crate::operator::{Data, Operator, StreamElement};
-use crate::scheduler::ExecutionMetadata;
+use crate::scheduler::{BlockId, ExecutionMetadata};
 
 thread_local! {
     /// Coordinates of the replica the current worker thread is working on.
     ///
     /// Access to this by calling `replica_coord()`.
     static COORD: RefCell<Option<Coord>> = RefCell::new(None);
+    /// Location of the last panic of the current thread.
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// The block and the index inside it of the operator the current worker thread is running.
+    ///
+    /// The worker sets it to the last operator of the block around `setup` and `next`, the
+    /// operators set it to themselves while running their own code with [`OperatorIndex::enter`].
+    /// When the worker panics it is left to the operator that panicked.
+    static OPERATOR: Cell<Option<(BlockId, usize)>> = const { Cell::new(None) };
+    /// The job of the replica the current worker thread is working on.
+    static JOB: RefCell<Option<Arc<JobState>>> = const { RefCell::new(None) };
 }
 
-/// Get the coord of the replica the current thread is working on.
+static PANIC_HOOK: Once = Once::new();
+
+/// Extend the panic hook to remember the location of the last panic of each worker thread.
 ///
-/// This will return `Some(coord)` only when called from a worker thread of a replica, otherwise
-/// `None` is returned.
-pub fn replica_coord() -> Option<Coord> {
-    COORD.with(|x| *x.borrow())
+/// The panics of the workers of a job torn down by [`JobState::stop`] are not reported.
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let prev = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let location = info.location().map(|l| l.to_string());
+            PANIC_LOCATION.with(|x| *x.borrow_mut() = location);
+            let stopped = JOB.with(|x| x.borrow().as_ref().is_some_and(|job| job.is_stopped()));
+            if !stopped {
+                prev(info);
+            }
+        }));
+    });
 }
 
-/// Call a function if this struct goes out of scope without calling `defuse`, including during a
-/// panic stack-unwinding.
-struct CatchPanic<F: FnOnce()> {
-    /// True if the function should be called.
-    primed: bool,
-    /// Function to call.
-    ///
-    /// The `Drop` implementation will move out the function.
-    handler: Option<F>,
-}
+/// The position of an operator inside the structure of its block.
+///
+/// The sources and the operators that run the functions of the user keep their index, and mark the
+/// code they run with [`OperatorIndex::enter`]: if it panics the error of the job names them. A
+/// panic elsewhere is reported as a panic of the last operator of the block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct OperatorIndex(usize);
 
-impl<F: FnOnce()> CatchPanic<F> {
-    fn new(handler: F) -> Self {
-        Self {
-            primed: true,
-            handler: Some(handler),
-        }
+impl OperatorIndex {
+    /// The first operator of a block: its source, or the `Start` receiving from the previous
+    /// blocks.
+    pub(crate) const FIRST: Self = Self(0);
+
+    /// The index of the operator following the ones of `prev` inside the block.
+    pub(crate) fn after(prev: BlockStructure) -> Self {
+        Self(prev.operators.len())
     }
 
-    /// Avoid calling the function on drop.
-    fn defuse(&mut self) {
-        self.primed = false;
+    /// Mark the current worker as running this operator until the returned guard is dropped.
+    #[inline]
+    pub(crate) fn enter(self) -> OperatorGuard {
+        let outer = OPERATOR.with(|op| op.get());
+        OPERATOR.with(|op| op.set(outer.map(|(block_id, _)| (block_id, self.0))));
+        OperatorGuard { outer }
     }
 }
 
-impl<F: FnOnce()> Drop for CatchPanic<F> {
+/// Restores the operator the worker was running before [`OperatorIndex::enter`].
+#[must_use]
+pub(crate) struct OperatorGuard {
+    outer: Option<(BlockId, usize)>,
+}
+
+impl Drop for OperatorGuard {
+    #[inline]
     fn drop(&mut self) {
-        if self.primed {
-            (self.handler.take().unwrap())();
+        // while unwinding the operator that panicked is left for the worker
+        if !std::thread::panicking() {
+            OPERATOR.with(|op| op.set(self.outer));
         }
     }
 }
 
+/// Run `f` as the worker of the block, catching its panics.
+///
+/// Until an operator marks its own code, the last operator of the block is the one running. After
+/// a panic, the operator that panicked is found with [`panic_operator`].
+fn run_block<R>(coord: Coord, f: impl FnOnce() -> R) -> std::thread::Result<R> {
+    OPERATOR.with(|op| op.set(Some((coord.block_id, usize::MAX))));
+    let res = catch_unwind(AssertUnwindSafe(f));
+    if res.is_ok() {
+        OPERATOR.with(|op| op.set(None));
+    }
+    res
+}
+
+/// The title of the operator that was running when the worker of the block panicked.
+fn panic_operator(coord: Coord, structure: &BlockStructure) -> String {
+    let running = match OPERATOR.with(|op| op.take()) {
+        Some((block_id, index)) if block_id == coord.block_id => structure.operators.get(index),
+        _ => None,
+    };
+    running
+        .or_else(|| structure.operators.last())
+        .map(|op| op.title.clone())
+        .unwrap_or_default()
+}
+
+/// Get the coord of the replica the current thread is working on.
+///
+/// This will return `Some(coord)` only when called from a worker thread of a replica, otherwise
+/// `None` is returned.
+pub fn replica_coord() -> Option<Coord> {
+    COORD.with(|x| *x.borrow())
+}
+
 pub(crate) fn spawn_worker<Out: Data, OperatorChain: Operator<Out> + 'static>(
     mut block: Block<Out, OperatorChain>,
     metadata: &mut ExecutionMetadata,
+    job: Arc<JobState>,
 ) -> (JoinHandle<()>, BlockStructure) {
     let coord = metadata.coord;
+    install_panic_hook();
+    if let Some(checkpoints) = job.checkpoints() {
+        checkpoints.register_worker(coord);
+    }
 
     debug!("starting worker {}: {}", coord, block.to_string(),);
 
-    block.operators.setup(metadata);
-    let structure = block.operators.structure();
+    let setup = run_block(coord, || {
+        block.operators.setup(metadata);
+        block.operators.structure()
+    });
+    let structure = match setup {
+        Ok(structure) => structure,
+        Err(payload) => {
+            // the worker is still spawned, it will exit immediately since the job is aborted
+            error!("worker {} setup failed!", coord);
+            let location = PANIC_LOCATION.with(|x| x.borrow_mut().take());
+            let structure =
+                catch_unwind(AssertUnwindSafe(|| block.operators.structure())).unwrap_or_default();
+            job.fail(coord, panic_operator(coord, &structure), payload, location);
+            BlockStructure::default()
+        }
+    };
+
+    let operators = structure
+        .operators
+        .iter()
+        .map(|op| op.title.as_str())
+        .collect::<Vec<_>>()
+        .join(" -> ");
+    // the elements emitted by the `Start` of the block are counted by the `Start` itself
+    let items = match (job.metrics().handle(coord), structure.operators.last()) {
+        (Some(metrics), Some(last)) => {
+            Some(metrics.operator(structure.operators.len() - 1, &last.title))
+        }
+        _ => None,
+    };
+    let core = job.affinity().and_then(|a| a.core_for(coord));
+    if let Some(core) = core {
+        debug!("worker {} pinned to core {}", coord, core);
+    }
+    let worker_structure = structure.clone();
 
     let join_handle = std::thread::Builder::new()
         .name(format!("block-{}", block.id))
         .spawn(move || {
             // remember in the thread-local the coordinate of this block
             COORD.with(|x| *x.borrow_mut() = Some(coord));
-            do_work(block, coord)
+            JOB.with(|x| *x.borrow_mut() = Some(job.clone()));
+            if job.metrics().is_enabled() {
+                job.metrics().enter(coord, operators);
+            }
+            if let Some(core) = core {
+                affinity::pin_current_thread(core);
+            }
+            job.wait_started();
+            if let Some(limit) = job.concurrency_limit() {
+                concurrency::enter(limit.clone());
+            }
+            do_work(block, coord, job, &worker_structure, items);
+            concurrency::exit();
         })
         .unwrap();
 
     (join_handle, structure)
 }
 
-fn do_work<Out: Data, Op: Operator<Out> + 'static>(mut block: Block<Out, Op>, coord: Coord) {
-    let mut catch_panic = CatchPanic::new(|| {
-        error!("worker {} crashed!", coord);
+fn do_work<Out: Data, Op: Operator<Out> + 'static>(
+    mut block: Block<Out, Op>,
+    coord: Coord,
+    job: Arc<JobState>,
+    structure: &BlockStructure,
+    items: Option<Arc<AtomicU64>>,
+) {
+    let result = run_block(coord, || {
+        let mut slice = 0;
+        while !job.is_aborted() {
+            slice += 1;
+            if slice == TIME_SLICE {
+                slice = 0;
+                concurrency::yield_now();
+            }
+            match block.operators.next() {
+                StreamElement::Terminate => return true,
+                StreamElement::Item(_) | StreamElement::Timestamped(..) => {
+                    if let Some(items) = &items {
+                        items.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                StreamElement::Watermark(ts) => metrics::watermark(ts),
+                // the barrier reached the end of the block
+                StreamElement::Checkpoint(id) => {
+                    if let Some(checkpoints) = job.checkpoints() {
+                        checkpoints.ack(coord, id);
+                    }
+                }
+                _ => {}
+            }
+        }
+        false
     });
-    while !matches!(block.operators.next(), StreamElement::Terminate) {
-        // nothing to do
+    if let Some(checkpoints) = job.checkpoints() {
+        checkpoints.finish(coord);
+    }
+    match result {
+        Ok(true) => info!("worker {} completed", coord),
+        Ok(false) => info!("worker {} aborted", coord),
+        // the channels are closed while the stopped job is torn down
+        Err(_) if job.is_stopped() => info!("worker {} stopped", coord),
+        Err(payload) => {
+            error!("worker {} crashed!", coord);
+            let location = PANIC_LOCATION.with(|x| x.borrow_mut().take());
+            job.fail(coord, panic_operator(coord, structure), payload, location);
+        }
     }
-    catch_panic.defuse();
-    info!("worker {} completed", coord);
 }
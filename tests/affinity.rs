@@ -0,0 +1,56 @@
+use itertools::Itertools;
+use noir::config::CoreAffinity;
+use noir::operator::source::{GeneratorSource, IteratorSource};
+use noir::{EnvironmentConfig, Replication, StreamEnvironment};
+
+fn run(affinity: CoreAffinity) {
+    let mut config = EnvironmentConfig::local(4);
+    config.affinity = affinity;
+    let mut env = StreamEnvironment::new(config);
+    let res = env
+        .stream(IteratorSource::new(0..1000u64))
+        .shuffle()
+        .map(|x| x * 2)
+        .group_by_sum(|x| x % 5, |x| x)
+        .unkey()
+        .collect_vec();
+    env.execute_blocking().unwrap();
+
+    let res = res.get().unwrap().into_iter().sorted().collect_vec();
+    let expected = (0..1000u64)
+        .map(|x| x * 2)
+        .into_group_map_by(|x| x % 5)
+        .into_iter()
+        .map(|(k, v)| (k, v.into_iter().sum::<u64>()))
+        .sorted()
+        .collect_vec();
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn pinned_replicas() {
+    run(CoreAffinity::Replica);
+}
+
+#[test]
+fn pinned_round_robin() {
+    run(CoreAffinity::RoundRobin);
+}
+
+#[test]
+fn replicas_per_core() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    let source = GeneratorSource::new(|seq| seq)
+        .count(1000)
+        .replication(Replication::new_per_core(2, 1));
+    let res = env
+        .stream(source)
+        .map(|x| (format!("{:?}", std::thread::current().id()), x))
+        .collect_vec();
+    env.execute_blocking().unwrap();
+
+    let res = res.get().unwrap();
+    let values = res.iter().map(|(_, x)| *x).sorted().collect_vec();
+    assert_eq!(values, (0..1000).collect_vec());
+    assert_eq!(res.iter().map(|(t, _)| t).unique().count(), 8);
+}
@@ -14,7 +14,7 @@ fn group_by_min_element() {
             .stream(source)
             .group_by_min_element(|&x| x % 2, |&x| x)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(res, &[(0, 0), (1, 1)]);
@@ -30,7 +30,7 @@ fn group_by_max_element() {
             .stream(source)
             .group_by_max_element(|&x| x % 2, |&x| x)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(res, &[(0, 8), (1, 9)]);
@@ -47,7 +47,7 @@ fn group_by_sum() {
             .stream(source)
             .group_by_sum(|&x| x % 2, |x| x)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(res, &[(0, 0 + 2 + 4 + 6 + 8), (1, 1 + 3 + 5 + 7 + 9)]);
@@ -64,7 +64,7 @@ fn group_by_avg() {
             .stream(source)
             .group_by_avg(|&x| x % 2, |&x| x as f64)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_by_key(|(m, _)| *m);
             assert_eq!(
@@ -83,7 +83,7 @@ fn group_by_count() {
     TestHelper::local_remote_env(|mut env| {
         let source = IteratorSource::new(0..10u8);
         let res = env.stream(source).group_by_count(|&x| x % 2).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_by_key(|(m, _)| *m);
             assert_eq!(res, &[(0, 5), (1, 5)]);
@@ -96,7 +96,7 @@ fn max() {
     TestHelper::local_remote_env(|mut env| {
         let source = IteratorSource::new(vec![1, 2, 0, 5, 9, 3, 7, 6, 4, 8].into_iter());
         let res = env.stream(source).max(|&n| n).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [9u8]);
@@ -109,7 +109,7 @@ fn max_assoc() {
     TestHelper::local_remote_env(|mut env| {
         let source = IteratorSource::new(vec![1, 2, 0, 5, 9, 3, 7, 6, 4, 8].into_iter());
         let res = env.stream(source).max_assoc(|&n| n).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [9u8]);
@@ -130,7 +130,7 @@ fn max_noir_data() {
             .into_iter(),
         );
         let res = env.stream(source).max_noir_data(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::Int32(5))]);
@@ -143,7 +143,7 @@ fn min() {
     TestHelper::local_remote_env(|mut env| {
         let source = IteratorSource::new(vec![1, 2, 0, 5, 9, 3, 7, 6, 4, 8].into_iter());
         let res = env.stream(source).min(|&n| n).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [0u8]);
@@ -156,7 +156,7 @@ fn min_assoc() {
     TestHelper::local_remote_env(|mut env| {
         let source = IteratorSource::new(vec![1, 2, 0, 5, 9, 3, 7, 6, 4, 8].into_iter());
         let res = env.stream(source).min_assoc(|&n| n).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [0u8]);
@@ -169,7 +169,7 @@ fn mean() {
     TestHelper::local_remote_env(|mut env| {
         let source = IteratorSource::new(0..10);
         let res = env.stream(source).mean(|&n| n as f64).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [4.5]);
@@ -220,7 +220,7 @@ fn mean_noir_data() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).mean_noir_data(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -261,7 +261,7 @@ fn mean_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).mean_noir_data(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -288,7 +288,7 @@ fn mean_noir_type() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).mean_noir_data(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(4.0))]);
@@ -307,7 +307,7 @@ fn mean_noir_type_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).mean_noir_data(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(f32::NAN))]);
@@ -326,7 +326,7 @@ fn mean_noir_type_none() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).mean_noir_data(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(None::<f32>))]);
@@ -0,0 +1,55 @@
+use itertools::Itertools;
+
+use noir::operator::source::IteratorSource;
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn broadcast_variable_map() {
+    TestHelper::local_remote_env(|mut env| {
+        let table = env
+            .stream(IteratorSource::new((0..10u32).map(|n| (n, n * n))))
+            .broadcast_variable();
+        let res = env
+            .stream(IteratorSource::new(0..1000u32))
+            .shuffle()
+            .map(move |n| {
+                let (_, square) = table.get().iter().find(|(k, _)| *k == n % 10).unwrap();
+                *square
+            })
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let expected = (0..1000)
+                .map(|n| (n % 10) * (n % 10))
+                .sorted()
+                .collect_vec();
+            assert_eq!(res.into_iter().sorted().collect_vec(), expected);
+        }
+    });
+}
+
+#[test]
+fn broadcast_variable_rich_map() {
+    TestHelper::local_remote_env(|mut env| {
+        let total = env
+            .stream(IteratorSource::new(0..100u64))
+            .shuffle()
+            .fold_assoc(0, |acc, n| *acc += n, |acc, n| *acc += n)
+            .broadcast_variable();
+        let res = env
+            .stream(IteratorSource::new(0..10u64))
+            .shuffle()
+            .rich_map({
+                let mut offset = None;
+                move |n| n + *offset.get_or_insert_with(|| total.get()[0])
+            })
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let expected = (0..10).map(|n| 4950 + n).collect_vec();
+            assert_eq!(res.into_iter().sorted().collect_vec(), expected);
+        }
+    });
+}
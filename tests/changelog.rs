@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use itertools::Itertools;
+
+use noir::operator::source::IteratorSource;
+use noir::operator::ChangelogMode;
+use noir::BatchMode;
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn fold_changelog_every_change() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..10u32);
+        let res = env
+            .stream(source)
+            .group_by(|n| n % 2)
+            .fold_changelog(0, |acc, n| *acc += n, ChangelogMode::EveryChange)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let even = res.iter().filter(|(k, _)| *k == 0).map(|(_, s)| *s);
+            assert_eq!(even.collect_vec(), vec![0, 2, 6, 12, 20]);
+            let odd = res.iter().filter(|(k, _)| *k == 1).map(|(_, s)| *s);
+            assert_eq!(odd.collect_vec(), vec![1, 4, 9, 16, 25]);
+        }
+    });
+}
+
+#[test]
+fn group_by_count_changelog_on_watermark() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..20i64);
+        let res = env
+            .stream(source)
+            .add_timestamps(|&n| n, |&n, _| (n % 5 == 4).then_some(n))
+            .group_by_count_changelog(|n| n % 2, ChangelogMode::OnWatermark)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            // one update per key for each group of 5 elements between the watermarks
+            let res = res.into_iter().sorted().collect_vec();
+            let expected = vec![
+                (0, 3),
+                (0, 5),
+                (0, 8),
+                (0, 10),
+                (1, 2),
+                (1, 5),
+                (1, 7),
+                (1, 10),
+            ];
+            assert_eq!(res, expected);
+        }
+    });
+}
+
+#[test]
+fn reduce_changelog_interval() {
+    TestHelper::local_remote_env(|mut env| {
+        // the last element arrives after the interval of the first ones
+        let source = IteratorSource::new(vec![1, 2, 3].into_iter().chain(
+            std::iter::once(4).inspect(|_| {
+                std::thread::sleep(Duration::from_millis(300));
+            }),
+        ));
+        let res = env
+            .stream(source)
+            .batch_mode(BatchMode::single())
+            .group_by(|_| 0)
+            .reduce_changelog(
+                |acc, n| *acc += n,
+                ChangelogMode::Interval(Duration::from_millis(100)),
+            )
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res, vec![(0, 6), (0, 10)]);
+        }
+    });
+}
+
+#[test]
+fn group_by_sum_changelog() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..10u32);
+        let res = env
+            .stream(source)
+            .group_by_sum_changelog(|n| n % 2, |n| n, ChangelogMode::default())
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res.len(), 10);
+            let max = res.into_iter().into_grouping_map().max();
+            assert_eq!(
+                max.into_iter().sorted().collect_vec(),
+                vec![(0, 20), (1, 25)]
+            );
+        }
+    });
+}
@@ -0,0 +1,95 @@
+use std::path::Path;
+use std::time::Duration;
+
+use itertools::Itertools;
+use noir::config::CheckpointConfig;
+use noir::operator::source::{ChannelSource, IteratorSource};
+use noir::{EnvironmentConfig, JobErrorKind, StreamEnvironment};
+
+fn config(dir: &Path) -> EnvironmentConfig {
+    let mut config = EnvironmentConfig::local(4);
+    config.checkpoint = Some(CheckpointConfig::new(dir, Duration::from_millis(20)));
+    config
+}
+
+/// The numbers from 0 to 19, slowly enough for some checkpoints to be taken.
+fn slow_numbers() -> IteratorSource<u64, impl Iterator<Item = u64> + Send> {
+    IteratorSource::new((0..20u64).inspect(|_| std::thread::sleep(Duration::from_millis(10))))
+}
+
+fn is_empty(dir: &Path) -> bool {
+    std::fs::read_dir(dir).unwrap().next().is_none()
+}
+
+#[test]
+fn resume_keyed_fold() {
+    let dir = tempfile::tempdir().unwrap();
+
+    // first execution: the state is checkpointed, then the job is cancelled
+    let mut env = StreamEnvironment::new(config(dir.path()));
+    env.stream(slow_numbers())
+        .group_by_sum(|x: &u64| x % 3, |x| x)
+        .for_each(|_| {});
+    let job = env.spawn();
+    std::thread::sleep(Duration::from_millis(100));
+    job.cancel();
+    assert!(job.wait().unwrap().cancelled);
+    assert!(!is_empty(dir.path()));
+
+    // second execution: the source skips the numbers already summed in the checkpoint
+    let mut env = StreamEnvironment::new(config(dir.path()));
+    let res = env
+        .stream(slow_numbers())
+        .group_by_sum(|x: &u64| x % 3, |x| x)
+        .collect_vec();
+    env.execute_blocking().unwrap();
+
+    let res = res.get().unwrap().into_iter().sorted().collect_vec();
+    let expected = (0..20u64)
+        .into_group_map_by(|x| x % 3)
+        .into_iter()
+        .map(|(k, v)| (k, v.into_iter().sum::<u64>()))
+        .sorted()
+        .collect_vec();
+    assert_eq!(res, expected);
+    // the job completed, it won't be resumed
+    assert!(is_empty(dir.path()));
+}
+
+#[test]
+fn channel_source_fails_with_checkpoints() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut env = StreamEnvironment::new(config(dir.path()));
+    let (tx, source) = ChannelSource::new(16);
+    let res = env.stream(source).shuffle().collect_vec();
+    let job = env.spawn();
+    // the job fails before reading from the channel
+    let _ = tx.send(1);
+    let error = job.wait().unwrap_err();
+
+    assert_eq!(error.kind, JobErrorKind::Checkpoint);
+    assert!(error.message.contains("channel"), "{}", error.message);
+    assert!(res.get().unwrap_or_default().is_empty());
+    assert!(is_empty(dir.path()));
+}
+
+#[test]
+fn iterations_fail_with_checkpoints() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut env = StreamEnvironment::new(config(dir.path()));
+    let (state, res) = env.stream(IteratorSource::new(0..10u64)).shuffle().iterate(
+        3,
+        0u64,
+        |s, _| s.map(|x| x + 1),
+        |delta: &mut u64, x| *delta += x,
+        |state, delta| *state += delta,
+        |_| true,
+    );
+    state.for_each(|_| {});
+    res.for_each(|_| {});
+    let error = env.execute_blocking().unwrap_err();
+
+    assert_eq!(error.kind, JobErrorKind::Checkpoint);
+    assert!(error.message.contains("iterations"), "{}", error.message);
+    assert!(is_empty(dir.path()));
+}
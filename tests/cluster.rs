@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use noir::operator::source::IteratorSource;
+use noir::StreamEnvironment;
+use utils::TestHelper;
+
+mod utils;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+struct Record {
+    name: String,
+    values: Vec<u32>,
+}
+
+#[test]
+fn cluster_shuffle_structs() {
+    let body = |mut env: StreamEnvironment| {
+        let res = env
+            .stream(IteratorSource::new(0..200u32))
+            .map(|i| Record {
+                name: format!("r{}", i % 13),
+                values: (0..i % 5).collect(),
+            })
+            .shuffle()
+            .group_by(|r| r.name.clone())
+            .reduce(|a, b| a.values.extend(b.values))
+            .map(|(_, r)| (r.name, r.values.len()))
+            .drop_key()
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let expected = (0..200u32)
+                .map(|i| (format!("r{}", i % 13), (i % 5) as usize))
+                .into_grouping_map()
+                .sum()
+                .into_iter()
+                .sorted()
+                .collect_vec();
+            assert_eq!(res.into_iter().sorted().collect_vec(), expected);
+        }
+    };
+    TestHelper::cluster_env(Arc::new(body), 3, 2);
+}
@@ -0,0 +1,61 @@
+use itertools::Itertools;
+use noir::config::Concurrency;
+use noir::operator::source::IteratorSource;
+use noir::{EnvironmentConfig, StreamEnvironment};
+
+fn config(max_running: usize) -> EnvironmentConfig {
+    let mut config = EnvironmentConfig::local(8);
+    config.concurrency = Concurrency::Limited { max_running };
+    config
+}
+
+#[test]
+fn more_blocks_than_running_replicas() {
+    let mut env = StreamEnvironment::new(config(1));
+    let res = env
+        .stream(IteratorSource::new(0..10_000u64))
+        .shuffle()
+        .map(|x| x * 2)
+        .shuffle()
+        .filter(|x| x % 3 == 0)
+        .group_by_sum(|x| x % 7, |x| x)
+        .unkey()
+        .collect_vec();
+    env.execute_blocking().unwrap();
+
+    let res = res.get().unwrap().into_iter().sorted().collect_vec();
+    let expected = (0..10_000u64)
+        .map(|x| x * 2)
+        .filter(|x| x % 3 == 0)
+        .into_group_map_by(|x| x % 7)
+        .into_iter()
+        .map(|(k, v)| (k, v.into_iter().sum::<u64>()))
+        .sorted()
+        .collect_vec();
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn iteration_with_few_running_replicas() {
+    let mut env = StreamEnvironment::new(config(2));
+    let (state, res) = env
+        .stream(IteratorSource::new(0..100u64))
+        .shuffle()
+        .iterate(
+            5,
+            0u64,
+            |s, _| s.map(|x| x + 1),
+            |delta: &mut u64, x| *delta += x,
+            |state, delta| *state += delta,
+            |_| true,
+        );
+    let state = state.collect_vec();
+    let res = res.collect_vec();
+    env.execute_blocking().unwrap();
+
+    let expected: u64 = (1..=5u64)
+        .map(|i| (0..100).map(|x| x + i).sum::<u64>())
+        .sum();
+    assert_eq!(state.get().unwrap(), vec![expected]);
+    assert_eq!(res.get().unwrap().len(), 100);
+}
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use noir::operator::source::IteratorSource;
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn connect_co_map() {
+    TestHelper::local_remote_env(|mut env| {
+        let numbers = env.stream(IteratorSource::new(0..100u32)).shuffle();
+        let words = env
+            .stream(IteratorSource::new((0..50u32).map(|n| n.to_string())))
+            .shuffle();
+        let res = numbers
+            .connect(words)
+            .co_map(|_, n| n, |_, w| w.parse::<u32>().unwrap() + 1000)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let expected = (0..100).chain(1000..1050).collect_vec();
+            assert_eq!(res.into_iter().sorted().collect_vec(), expected);
+        }
+    });
+}
+
+#[test]
+fn connect_shared_state() {
+    TestHelper::local_remote_env(|mut env| {
+        let numbers = env.stream(IteratorSource::new(0..100u32));
+        let words = env.stream(IteratorSource::new((50..150u32).map(|n| n.to_string())));
+        // a single replica sees both the streams: keep the first occurrence of each number
+        let res = numbers
+            .connect(words)
+            .with_state(HashSet::new())
+            .co_flat_map(
+                |seen, n| seen.insert(n).then_some(n),
+                |seen, w| {
+                    let n = w.parse().unwrap();
+                    seen.insert(n).then_some(n)
+                },
+            )
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(
+                res.into_iter().sorted().collect_vec(),
+                (0..150).collect_vec()
+            );
+        }
+    });
+}
@@ -49,7 +49,7 @@ fn covariance_noir_data() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).covariance([1, 2]).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::Float32(-2.0))]);
@@ -82,7 +82,7 @@ fn covariance_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).covariance([2, 4]).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::NaN())]);
@@ -0,0 +1,33 @@
+use itertools::Itertools;
+
+use noir::operator::source::IteratorSource;
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn cross() {
+    TestHelper::local_remote_env(|mut env| {
+        let s1 = env.stream(IteratorSource::new(0..100u32)).shuffle();
+        let s2 = env.stream(IteratorSource::new(0..5u8));
+        let res = s1.cross(s2).collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let expected = (0..100).cartesian_product(0..5).collect_vec();
+            assert_eq!(res.into_iter().sorted().collect_vec(), expected);
+        }
+    });
+}
+
+#[test]
+fn cross_empty_side() {
+    TestHelper::local_remote_env(|mut env| {
+        let s1 = env.stream(IteratorSource::new(0..100u32)).shuffle();
+        let s2 = env.stream(IteratorSource::new(0..0u8));
+        let res = s1.cross(s2).collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert!(res.is_empty());
+        }
+    });
+}
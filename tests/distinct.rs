@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use itertools::Itertools;
+
+use noir::operator::source::IteratorSource;
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn distinct_stream() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new((0..1000u32).map(|n| n % 37));
+        let res = env.stream(source).shuffle().distinct().collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(
+                res.into_iter().sorted().collect_vec(),
+                (0..37).collect_vec()
+            );
+        }
+    });
+}
+
+#[test]
+fn distinct_by_keeps_one_element_per_key() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..1000u32);
+        let res = env
+            .stream(source)
+            .shuffle()
+            .distinct_by(|n| n % 10)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res.len(), 10);
+            assert_eq!(
+                res.iter().map(|n| n % 10).sorted().collect_vec(),
+                (0..10).collect_vec()
+            );
+        }
+    });
+}
+
+#[test]
+fn distinct_by_within() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new((0..1000u32).map(|n| n % 5));
+        let res = env
+            .stream(source)
+            .shuffle()
+            .distinct_by_within(|&n| n, Duration::from_secs(3600))
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res.into_iter().sorted().collect_vec(), (0..5).collect_vec());
+        }
+    });
+}
+
+#[test]
+fn distinct_keyed() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..1000u32);
+        let res = env
+            .stream(source)
+            .group_by(|n| n % 3)
+            .map(|(_, n)| n % 7)
+            .distinct()
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let expected = (0..3).cartesian_product(0..7).collect_vec();
+            assert_eq!(res.into_iter().sorted().collect_vec(), expected);
+        }
+    });
+}
+
+#[test]
+fn approx_count_distinct() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new((0..200_000u64).map(|n| n % 50_000));
+        let res = env
+            .stream(source)
+            .shuffle()
+            .approx_count_distinct()
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res.len(), 1);
+            let error = (res[0] as f64 - 50_000.0).abs() / 50_000.0;
+            assert!(error < 0.03, "estimate {} is too far", res[0]);
+        }
+    });
+}
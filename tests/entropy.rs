@@ -51,7 +51,7 @@ fn entropy_noir_data() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).entropy(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -92,7 +92,7 @@ fn entropy_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).entropy(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -119,7 +119,7 @@ fn entropy_noir_type() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).entropy(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res[0], NoirData::NoirType(NoirType::Float32(0.9182958)));
@@ -138,7 +138,7 @@ fn entropy_noir_type_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).entropy(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res[0], NoirData::NoirType(NoirType::from(f32::NAN)));
@@ -157,7 +157,7 @@ fn entropy_noir_type_none() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).entropy(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res[0], NoirData::NoirType(NoirType::from(None::<f32>)));
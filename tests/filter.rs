@@ -10,7 +10,7 @@ fn filter_stream() {
     TestHelper::local_remote_env(|mut env| {
         let source = IteratorSource::new(0..10u8);
         let res = env.stream(source).filter(|x| x % 2 == 1).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             assert_eq!(res, &[1, 3, 5, 7, 9]);
         }
@@ -27,7 +27,7 @@ fn filter_keyed_stream() {
             .filter(|(_key, x)| *x < 6)
             .drop_key()
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(res, (0..6u8).collect_vec());
@@ -13,7 +13,7 @@ fn filter_map_stream() {
             .stream(source)
             .filter_map(|x| if x % 2 == 1 { Some(x * 2 + 1) } else { None })
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             assert_eq!(res, &[3, 7, 11, 15, 19]);
         }
@@ -30,7 +30,7 @@ fn filter_map_keyed_stream() {
             .filter_map(|(_key, x)| if x < 6 { Some(x * 2 + 1) } else { None })
             .drop_key()
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(res, (0..6u8).map(|x| x * 2 + 1).collect_vec());
@@ -13,7 +13,7 @@ fn fold_stream() {
             .stream(source)
             .fold("".to_string(), |s, n| *s += &n.to_string())
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             assert_eq!(res.len(), 1);
             assert_eq!(res[0], "0123456789");
@@ -33,7 +33,7 @@ fn fold_assoc_stream() {
                 |s1, s2| *s1 += &s2,
             )
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             assert_eq!(res.len(), 1);
             assert_eq!(res[0], "0123456789");
@@ -50,7 +50,7 @@ fn fold_shuffled_stream() {
             .shuffle()
             .fold(Vec::new(), |v, n| v.push(n))
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             assert_eq!(res.len(), 1);
             res[0].sort_unstable();
@@ -72,7 +72,7 @@ fn fold_assoc_shuffled_stream() {
                 |v1, mut v2| v1.append(&mut v2),
             )
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             assert_eq!(res.len(), 1);
             res[0].sort_unstable();
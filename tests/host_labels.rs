@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+
+use noir::operator::source::{GeneratorSource, IteratorSource};
+use noir::JobErrorKind;
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn labeled_blocks() {
+    let labels = vec![
+        vec!["ingest".to_string()],
+        vec!["bigmem".to_string()],
+        vec![],
+    ];
+    let body = |mut env: noir::StreamEnvironment| {
+        let source = GeneratorSource::new(|seq| seq).count(1000);
+        let res = env
+            .stream(source)
+            .on_hosts("ingest")
+            .map(|x| x * 2)
+            .group_by_sum(|x| x % 7, |x| x)
+            .on_hosts("bigmem")
+            .unkey()
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let res = res.into_iter().sorted().collect_vec();
+            let expected = (0..1000u64)
+                .map(|x| x * 2)
+                .into_group_map_by(|x| x % 7)
+                .into_iter()
+                .map(|(k, v)| (k, v.into_iter().sum::<u64>()))
+                .sorted()
+                .collect_vec();
+            assert_eq!(res, expected);
+        }
+    };
+    TestHelper::labeled_remote_env(Arc::new(body), labels, 2);
+}
+
+#[test]
+fn missing_label() {
+    let labels = vec![vec!["ingest".to_string()], vec![]];
+    let body = |mut env: noir::StreamEnvironment| {
+        env.stream(IteratorSource::new(0..10u64))
+            .shuffle()
+            .map(|x| x + 1)
+            .on_hosts("gpu")
+            .for_each(|_| {});
+        let error = env.execute_blocking().unwrap_err();
+        assert_eq!(error.kind, JobErrorKind::Placement);
+        assert!(error.message.contains("gpu"), "{error}");
+    };
+    TestHelper::labeled_remote_env(Arc::new(body), labels, 2);
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use noir::operator::source::{ChannelSource, FileSource, IteratorSource, ParallelIteratorSource};
+use noir::{EnvironmentConfig, Replication, StreamEnvironment};
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn job_report() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    let res = env
+        .stream(IteratorSource::new(0..100u32))
+        .shuffle()
+        .map(|x| x * 2)
+        .collect_vec();
+    let report = env.execute_blocking().unwrap();
+    assert_eq!(report.blocks, 3);
+    assert_eq!(report.workers, 1 + 4 + 1);
+    assert_eq!(res.get().unwrap().len(), 100);
+}
+
+#[test]
+fn operator_panic() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    let source = ParallelIteratorSource::new(|id, _| 0..(1000 * (id + 1)));
+    env.stream(source)
+        .shuffle()
+        .map(|x| {
+            if x == 42 {
+                panic!("found the answer");
+            }
+            x
+        })
+        .for_each(|_| {});
+    let error = env.execute_blocking().unwrap_err();
+    assert_eq!(error.block_id, 1);
+    assert_eq!(error.message, "found the answer");
+    assert_eq!(error.operator, "Map");
+    assert!(error.location.unwrap().contains("job_error.rs"));
+    assert!(error.failed_workers >= 1);
+}
+
+#[test]
+fn panic_with_blocked_source() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(2));
+    let (tx, source) = ChannelSource::new(4);
+    env.stream(source)
+        .shuffle()
+        .map(|x: u32| {
+            if x == 3 {
+                panic!("bad item");
+            }
+            x
+        })
+        .for_each(|_| {});
+
+    // the source is still waiting for new elements when the job fails
+    tx.send(1).unwrap();
+    tx.send(3).unwrap();
+    let error = env.execute_blocking().unwrap_err();
+    assert_eq!(error.message, "bad item");
+    std::thread::sleep(Duration::from_millis(10));
+    drop(tx);
+}
+
+#[test]
+fn setup_panic() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(2));
+    env.stream(FileSource::new("/this/file/does/not/exist"))
+        .for_each(|_| {});
+    let error = env.execute_blocking().unwrap_err();
+    assert_eq!(error.block_id, 0);
+    assert_eq!(error.operator, "FileSource");
+    assert!(error.message.contains("FileSource"), "{}", error.message);
+}
+
+#[test]
+fn source_panic() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(2));
+    let source = IteratorSource::new((0..100).inspect(|&x| {
+        if x == 42 {
+            panic!("found the answer");
+        }
+    }));
+    env.stream(source).map(|x| x + 1).for_each(|_| {});
+    let error = env.execute_blocking().unwrap_err();
+    assert_eq!(error.operator, "IteratorSource");
+}
+
+#[test]
+fn remote_failure() {
+    // the panic happens on the host with the single replica, after it received an element from
+    // the other host, whose sink is still waiting for the end of the stream
+    let body = |mut env: StreamEnvironment| {
+        env.stream(ParallelIteratorSource::new(|id, _| id..id + 1))
+            .replication(Replication::One)
+            .map(|x| {
+                if x == 1 {
+                    panic!("bad item");
+                }
+                x
+            })
+            .shuffle()
+            .for_each(|_| {});
+        let error = env.execute_blocking().unwrap_err();
+        assert_eq!(error.message, "bad item");
+        assert_eq!(error.operator, "Map");
+    };
+    TestHelper::remote_env(Arc::new(body), 2, 1);
+}
+
+#[test]
+fn panic_inside_the_block() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(2));
+    env.stream(IteratorSource::new(0..100))
+        .shuffle()
+        .filter(|&x| {
+            if x == 42 {
+                panic!("found the answer");
+            }
+            true
+        })
+        .map(|x| x + 1)
+        .for_each(|_| {});
+    let error = env.execute_blocking().unwrap_err();
+    assert_eq!(error.block_id, 1);
+    assert_eq!(error.operator, "Filter");
+    assert_eq!(error.message, "found the answer");
+}
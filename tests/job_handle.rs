@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use noir::operator::source::{ChannelSource, GeneratorSource, IteratorSource};
+use noir::{EnvironmentConfig, StreamEnvironment};
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn wait_completed_job() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    let res = env
+        .stream(IteratorSource::new(0..100u32))
+        .shuffle()
+        .map(|x| x * 2)
+        .collect_vec();
+    let job = env.spawn();
+    let report = job.wait().unwrap();
+    assert!(!report.cancelled);
+    assert_eq!(res.get().unwrap().len(), 100);
+}
+
+#[test]
+fn cancel_channel_source() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    let (tx, source) = ChannelSource::new(16);
+    let res = env
+        .stream(source)
+        .shuffle()
+        .group_by_count(|x: &u32| x % 3)
+        .collect_vec();
+    let job = env.spawn();
+    for i in 0..9 {
+        tx.send(i).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(!job.is_finished());
+
+    job.cancel();
+    let report = job.wait().unwrap();
+    assert!(report.cancelled);
+    let mut res = res.get().unwrap();
+    res.sort_unstable();
+    // the elements sent before the cancellation are processed
+    assert_eq!(res, vec![(0, 3), (1, 3), (2, 3)]);
+    // the sender is still alive: the job stopped because of the cancellation
+    drop(tx);
+}
+
+#[test]
+fn cancel_parallel_source() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    let source = GeneratorSource::new(|_| 1u64);
+    let res = env
+        .stream(source)
+        .shuffle()
+        .reduce(|a, b| a + b)
+        .collect_vec();
+    let job = env.spawn();
+    std::thread::sleep(Duration::from_millis(100));
+    job.cancel();
+    let report = job.wait().unwrap();
+    assert!(report.cancelled);
+    assert!(res.get().unwrap()[0] > 0);
+}
+
+#[test]
+fn cancel_failed_job() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    let (tx, source) = ChannelSource::<u32>::new(16);
+    env.stream(source)
+        .shuffle()
+        .map(|_| -> u32 { panic!("failure") })
+        .for_each(|_| {});
+    let job = env.spawn();
+    tx.send(1).unwrap();
+    while !job.is_finished() {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    // cancelling a job that already completed has no effect
+    job.cancel();
+    assert_eq!(job.wait().unwrap_err().message, "failure");
+}
+
+#[test]
+fn cancel_remote_job() {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let body = move |mut env: StreamEnvironment| {
+        env.stream(GeneratorSource::new(|_| 1u64))
+            .shuffle()
+            .reduce(|a, b| a + b)
+            .for_each(|_| {});
+        let job = env.spawn();
+        // only one of the hosts cancels the job
+        if !cancelled.swap(true, Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(200));
+            job.cancel();
+        }
+        let report = job.wait().unwrap();
+        assert!(report.cancelled);
+    };
+    TestHelper::remote_env(Arc::new(body), 2, 1);
+}
@@ -0,0 +1,127 @@
+use itertools::Itertools;
+
+use noir::operator::source::IteratorSource;
+use noir::{EnvironmentConfig, StreamEnvironment};
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn limit_bounded() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..1000u32);
+        let res = env.stream(source).shuffle().limit(10).collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res.len(), 10);
+            assert!(res.iter().all_unique());
+            assert!(res.iter().all(|&n| n < 1000));
+        }
+    });
+}
+
+#[test]
+fn limit_larger_than_stream() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..10u32);
+        let res = env.stream(source).shuffle().limit(100).collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(
+                res.into_iter().sorted().collect_vec(),
+                (0..10).collect_vec()
+            );
+        }
+    });
+}
+
+#[test]
+fn limit_unbounded() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0u64..);
+        let res = env
+            .stream(source)
+            .shuffle()
+            .map(|n| n * 2)
+            .limit(20)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res.len(), 20);
+            assert!(res.iter().all(|n| n % 2 == 0));
+        }
+    });
+}
+
+#[test]
+fn limit_one_of_many_streams() {
+    TestHelper::local_remote_env(|mut env| {
+        let mut splits = env.stream(IteratorSource::new(0..1000u32)).split(2);
+        let limited = splits.pop().unwrap().shuffle().limit(5).collect_vec();
+        let all = splits.pop().unwrap().collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(limited) = limited.get() {
+            assert_eq!(limited.len(), 5);
+        }
+        // the source is not stopped by the limit of the other stream
+        if let Some(all) = all.get() {
+            assert_eq!(
+                all.into_iter().sorted().collect_vec(),
+                (0..1000).collect_vec()
+            );
+        }
+    });
+}
+
+#[test]
+fn limit_all_streams() {
+    TestHelper::local_remote_env(|mut env| {
+        let mut splits = env.stream(IteratorSource::new(0u64..)).split(2);
+        let first = splits.pop().unwrap().shuffle().limit(5).collect_vec();
+        let second = splits.pop().unwrap().limit(10).collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(first) = first.get() {
+            assert_eq!(first.len(), 5);
+        }
+        if let Some(second) = second.get() {
+            assert_eq!(second.len(), 10);
+        }
+    });
+}
+
+#[test]
+fn limit_inside_iteration() {
+    TestHelper::local_remote_env(|mut env| {
+        let (state, res) = env
+            .stream(IteratorSource::new(0..1000u64))
+            .shuffle()
+            .iterate(
+                3,
+                0usize,
+                // the body ends with a block on all the replicas
+                |s, _| s.map(|n| n + 1).limit(5).shuffle(),
+                |count: &mut usize, _| *count += 1,
+                |state, count| *state += count,
+                |_| true,
+            );
+        let state = state.collect_vec();
+        let res = res.collect_vec();
+        env.execute_blocking().unwrap();
+        // each iteration emits 5 elements
+        if let Some(state) = state.get() {
+            assert_eq!(state, vec![15]);
+        }
+        if let Some(res) = res.get() {
+            assert_eq!(res.len(), 5);
+        }
+    });
+}
+
+#[test]
+fn limit_zero() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    let source = IteratorSource::new(0u64..);
+    let res = env.stream(source).shuffle().limit(0).collect_vec();
+    env.execute_blocking().unwrap();
+    assert!(res.get().unwrap().is_empty());
+}
@@ -13,7 +13,7 @@ fn map_memo_stream() {
             .map(|n| n % 5)
             .map_memo(|v| v * v, 1000)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             let mut expected = (0..100u32).map(|n| (n % 5) * (n % 5)).collect_vec();
             res.sort();
@@ -39,7 +39,7 @@ fn map_memo_by_stream() {
                 1000,
             )
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             let mut expected = (0..1000i64)
                 .map(|v| v.rem_euclid(30))
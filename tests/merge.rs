@@ -3,8 +3,9 @@ use std::sync::Arc;
 
 use itertools::Itertools;
 
+use noir::box_op::BoxedOperator;
 use noir::operator::source::IteratorSource;
-use noir::Replication;
+use noir::{Replication, Stream};
 use utils::{TestHelper, WatermarkChecker};
 
 mod utils;
@@ -19,7 +20,7 @@ fn merge_stream() {
         let stream2 = env.stream(source2);
 
         let res = stream1.merge(stream2).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             let res_sorted = res.into_iter().sorted().collect_vec();
             let expected = (0..20000u16).collect_vec();
@@ -38,7 +39,7 @@ fn merge_stream_with_empty() {
         let stream2 = env.stream(source2);
 
         let res = stream1.merge(stream2).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             let res_sorted = res.into_iter().sorted().collect_vec();
             let expected = (0..10000u16).collect_vec();
@@ -57,7 +58,7 @@ fn merge_stream_with_empty_other_way() {
         let stream2 = env.stream(source2);
 
         let res = stream1.merge(stream2).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             let res_sorted = res.into_iter().sorted().collect_vec();
             let expected = (0..10000u16).collect_vec();
@@ -76,7 +77,7 @@ fn merge_empty_with_empty() {
         let stream2 = env.stream(source2);
 
         let res = stream1.merge(stream2).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             assert!(res.is_empty());
         }
@@ -112,7 +113,7 @@ fn merge_with_timestamps() {
             .add_operator(|prev| WatermarkChecker::new(prev, num_watermarks.clone()));
         let res = stream.collect_vec();
 
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             assert_eq!(res.len(), 20);
             assert_eq!(num_watermarks.load(Ordering::Acquire), 5);
@@ -130,7 +131,7 @@ fn merge_keyed_stream() {
         let stream2 = env.stream(source2).group_by(|x| x % 3);
 
         let res = stream1.merge(stream2).reduce(|x, y| *x += y).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(mut res) = res.get() {
             res.sort_unstable();
@@ -142,3 +143,86 @@ fn merge_keyed_stream() {
         }
     });
 }
+
+#[test]
+fn union_all_streams() {
+    TestHelper::local_remote_env(|mut env| {
+        let stream = env.stream(IteratorSource::new(0..1000u16));
+        let others = (1..4)
+            .map(|i| env.stream(IteratorSource::new(i * 1000..(i + 1) * 1000u16)))
+            .collect_vec();
+
+        let res = stream.union_all(others).collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let res_sorted = res.into_iter().sorted().collect_vec();
+            let expected = (0..4000u16).collect_vec();
+            assert_eq!(res_sorted, expected);
+        }
+    });
+}
+
+#[test]
+fn union_all_shuffled_with_empty() {
+    TestHelper::local_remote_env(|mut env| {
+        let stream = env.stream(IteratorSource::new(0..0u16)).shuffle();
+        let others = (1..4)
+            .map(|i| {
+                env.stream(IteratorSource::new(i * 1000..(i + 1) * 1000u16))
+                    .shuffle()
+            })
+            .collect_vec();
+
+        let res = stream.union_all(others).collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let res_sorted = res.into_iter().sorted().collect_vec();
+            let expected = (1000..4000u16).collect_vec();
+            assert_eq!(res_sorted, expected);
+        }
+    });
+}
+
+#[test]
+fn union_all_no_others() {
+    TestHelper::local_remote_env(|mut env| {
+        let stream = env.stream(IteratorSource::new(0..100u16));
+        let others: Vec<Stream<u16, BoxedOperator<u16>>> = vec![];
+
+        let res = stream.union_all(others).collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res, (0..100u16).collect_vec());
+        }
+    });
+}
+
+#[test]
+fn union_all_with_timestamps() {
+    TestHelper::local_remote_env(|mut env| {
+        let mut timestamped = |range: std::ops::Range<u64>| {
+            env.stream(IteratorSource::new(range))
+                .add_timestamps(
+                    |&x| x as i64 % 10,
+                    |&x, &ts| if x % 2 == 1 { Some(ts) } else { None },
+                )
+                .shuffle()
+        };
+        let stream = timestamped(0..10);
+        let others = vec![timestamped(100..110), timestamped(200..210)];
+
+        let num_watermarks = Arc::new(AtomicUsize::new(0));
+        let stream = stream
+            .union_all(others)
+            .shuffle()
+            .replication(Replication::One)
+            .add_operator(|prev| WatermarkChecker::new(prev, num_watermarks.clone()));
+        let res = stream.collect_vec();
+
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res.len(), 30);
+            assert_eq!(num_watermarks.load(Ordering::Acquire), 5);
+        }
+    });
+}
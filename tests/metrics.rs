@@ -0,0 +1,115 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use noir::config::MetricsConfig;
+use noir::metrics::{MetricsReporter, MetricsSnapshot, PrometheusReporter};
+use noir::operator::source::{ChannelSource, IteratorSource};
+use noir::{EnvironmentConfig, StreamEnvironment};
+
+fn config() -> EnvironmentConfig {
+    let mut config = EnvironmentConfig::local(4);
+    config.metrics = Some(MetricsConfig::new(Duration::from_millis(10)));
+    config
+}
+
+#[derive(Clone, Default)]
+struct LastSnapshot(Arc<Mutex<Option<MetricsSnapshot>>>);
+
+impl MetricsReporter for LastSnapshot {
+    fn report(&mut self, metrics: &MetricsSnapshot) {
+        *self.0.lock().unwrap() = Some(metrics.clone());
+    }
+}
+
+#[test]
+fn final_report() {
+    let mut env = StreamEnvironment::new(config());
+    let last = LastSnapshot::default();
+    env.add_metrics_reporter(last.clone());
+    let source = IteratorSource::new(0..1000u64);
+    let res = env
+        .stream(source)
+        .shuffle()
+        .filter(|x| x % 2 == 0)
+        .collect_vec();
+    env.execute_blocking().unwrap();
+    assert_eq!(res.get().unwrap().len(), 500);
+
+    let snapshot = last.0.lock().unwrap().take().unwrap();
+    let sum = |block, f: fn(&noir::metrics::ReplicaSnapshot) -> u64| -> u64 {
+        snapshot
+            .replicas
+            .iter()
+            .filter(|r| r.coord.block_id == block)
+            .map(f)
+            .sum()
+    };
+    // source -> shuffle -> filter -> collect
+    assert_eq!(sum(0, |r| r.items_out), 1000);
+    assert_eq!(sum(1, |r| r.items_in), 1000);
+    assert_eq!(sum(1, |r| r.items_out), 500);
+    assert_eq!(sum(2, |r| r.items_in), 500);
+    assert_eq!(sum(1, |r| r.backlog), 0);
+    // the filter between the start and the end of the block drops half of the elements
+    let operator = |name: &str| -> u64 {
+        snapshot
+            .replicas
+            .iter()
+            .filter(|r| r.coord.block_id == 1)
+            .flat_map(|r| &r.operator_items)
+            .filter(|o| o.name == name)
+            .map(|o| o.items_out)
+            .sum()
+    };
+    assert_eq!(operator("Start"), 1000);
+    assert_eq!(operator("End"), 500);
+    assert!(sum(0, |r| r.batches) > 0);
+    assert_eq!(
+        snapshot
+            .replicas
+            .iter()
+            .filter(|r| r.coord.block_id == 1)
+            .count(),
+        4
+    );
+}
+
+fn scrape(addr: SocketAddr) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn prometheus_endpoint() {
+    let mut env = StreamEnvironment::new(config());
+    let reporter = PrometheusReporter::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = reporter.local_addr();
+    env.add_metrics_reporter(reporter);
+    let (tx, source) = ChannelSource::new(16);
+    env.stream(source).shuffle().for_each(|_: u64| {});
+    let job = env.spawn();
+    (0..100).for_each(|x| tx.send(x).unwrap());
+
+    // the endpoint is served while the job runs
+    let mut response = scrape(addr);
+    for _ in 0..100 {
+        if response.contains("noir_items_in_total{block=\"1\"") {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+        response = scrape(addr);
+    }
+    drop(tx);
+    job.wait().unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("# TYPE noir_items_in_total counter"));
+    assert!(response.contains("noir_items_in_total{block=\"1\""));
+}
@@ -49,7 +49,7 @@ fn drop_row() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).drop_none().collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -122,7 +122,7 @@ fn drop_columns() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).drop_columns(vec![1, 3]).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -184,7 +184,7 @@ fn fill_constant() {
             .stream(source)
             .fill_constant(NoirType::Float32(3.0))
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -275,7 +275,7 @@ fn fill_back() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).fill_backward().collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -366,7 +366,7 @@ fn fill_max() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).fill_max().collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert!(res.iter().all(|v| !v.contains_none()));
@@ -417,7 +417,7 @@ fn fill_forward() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).fill_forward().collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert!(res.iter().all(|v| !v.contains_none()));
@@ -49,7 +49,7 @@ fn mode_noir_data() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).mode(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -90,7 +90,7 @@ fn mode_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).mode(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -117,7 +117,7 @@ fn mode_noir_type() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).mode(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res[0], [NoirData::Row(vec![NoirType::Int32(4)])]);
@@ -136,7 +136,7 @@ fn mode_noir_type_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).mode(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res[0], [NoirData::NoirType(NoirType::from(f32::NAN))]);
@@ -155,7 +155,7 @@ fn mode_noir_type_none() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).mode(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res[0], [NoirData::NoirType(NoirType::from(None::<f32>))]);
@@ -54,7 +54,7 @@ fn pearson_noir_data() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).pearson([1, 2]).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             let mut corr: NoirType = res[0].clone().to_type();
@@ -94,7 +94,7 @@ fn pearson_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).pearson([2, 4]).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::NaN())]);
@@ -111,7 +111,7 @@ fn pearson_noir_type_none() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).pearson([1, 2]).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::None())]);
@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use itertools::Itertools;
+
+use noir::operator::source::IteratorSource;
+use noir::operator::{ListState, MapState, Timer, ValueState};
+use noir::BatchMode;
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn process_list_state() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..20u32);
+        let res = env
+            .stream(source)
+            .group_by(|n| n % 2)
+            .process(
+                ListState::default(),
+                |ctx, n| {
+                    ctx.state().add(n);
+                    if ctx.state().len() == 3 {
+                        let list = ctx.state().take();
+                        ctx.emit(list);
+                        ctx.clear_state();
+                    }
+                },
+                |_, _| {},
+            )
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let res = res.into_iter().sorted().collect_vec();
+            let expected = vec![
+                (0, vec![0, 2, 4]),
+                (0, vec![6, 8, 10]),
+                (0, vec![12, 14, 16]),
+                (1, vec![1, 3, 5]),
+                (1, vec![7, 9, 11]),
+                (1, vec![13, 15, 17]),
+            ];
+            assert_eq!(res, expected);
+        }
+    });
+}
+
+#[test]
+fn process_value_and_map_state() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..30u32);
+        let res = env
+            .stream(source)
+            .group_by(|n| n % 3)
+            .process(
+                (ValueState::default(), MapState::default()),
+                |ctx, n| {
+                    let (max, counts) = ctx.state();
+                    if max.get().is_none_or(|&max| n > max) {
+                        max.set(n);
+                    }
+                    *counts.get_or_insert(n % 2, 0) += 1;
+                    if counts.len() == 1 && counts.get(&(n % 2)) == Some(&1) {
+                        // emit the summary of the key at the end of the stream
+                        ctx.register_processing_time_timer(Duration::from_secs(3600));
+                    }
+                },
+                |ctx, _| {
+                    let (max, counts) = ctx.state();
+                    let summary = (
+                        max.take().unwrap(),
+                        counts.remove(&0).unwrap_or(0),
+                        counts.remove(&1).unwrap_or(0),
+                    );
+                    ctx.emit(summary);
+                    ctx.clear_state();
+                },
+            )
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let res = res.into_iter().sorted().collect_vec();
+            let expected = vec![(0, (27, 5, 5)), (1, (28, 5, 5)), (2, (29, 5, 5))];
+            assert_eq!(res, expected);
+        }
+    });
+}
+
+#[test]
+fn process_event_time_timers() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..10i64);
+        let res = env
+            .stream(source)
+            .add_timestamps(|&n| n, |_, &ts| Some(ts))
+            .group_by(|n| n % 2)
+            .process(
+                None,
+                |ctx, n| {
+                    if let Some(sum) = ctx.state() {
+                        *sum += n;
+                    } else {
+                        *ctx.state() = Some(n);
+                        let ts = ctx.timestamp().unwrap();
+                        ctx.register_event_time_timer(ts + 3);
+                    }
+                },
+                |ctx, timer| {
+                    assert_eq!(timer, Timer::EventTime(ctx.timestamp().unwrap()));
+                    let sum = ctx.state().take().unwrap();
+                    ctx.emit(sum);
+                    ctx.clear_state();
+                },
+            )
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let res = res.into_iter().sorted().collect_vec();
+            let expected = vec![(0, 2), (0, 8), (0, 10), (1, 4), (1, 9), (1, 12)];
+            assert_eq!(res, expected);
+        }
+    });
+}
+
+#[test]
+fn process_processing_time_timeout() {
+    TestHelper::local_remote_env(|mut env| {
+        // the second key arrives after the timeout of the first one
+        let source = IteratorSource::new(
+            vec![(0, 'a')]
+                .into_iter()
+                .chain(std::iter::once((1, 'b')).inspect(|_| {
+                    std::thread::sleep(Duration::from_millis(300));
+                }))
+                .chain(std::iter::once((0, 'c'))),
+        );
+        let res = env
+            .stream(source)
+            .batch_mode(BatchMode::single())
+            .group_by(|(k, _)| *k)
+            .process(
+                None,
+                |ctx, (_, c)| {
+                    if let Some(timer) = ctx.state().take() {
+                        ctx.delete_processing_time_timer(timer);
+                    }
+                    let timer = ctx.register_processing_time_timer(Duration::from_millis(100));
+                    *ctx.state() = Some(timer);
+                    ctx.emit(c);
+                },
+                |ctx, timer| {
+                    assert!(matches!(timer, Timer::ProcessingTime(_)));
+                    ctx.clear_state();
+                    ctx.emit('!');
+                },
+            )
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            // the timers still pending at the end of the stream fire early
+            let first = res.iter().filter(|(k, _)| *k == 0).map(|(_, c)| *c);
+            assert_eq!(first.collect_vec(), vec!['a', '!', 'c', '!']);
+            let second = res.iter().filter(|(k, _)| *k == 1).map(|(_, c)| *c);
+            assert_eq!(second.collect_vec(), vec!['b', '!']);
+        }
+    });
+}
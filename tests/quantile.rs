@@ -20,7 +20,7 @@ fn quantiles_noir_type() {
             .stream(source)
             .quantile_parallel(0.7, true)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(700.5))]);
@@ -74,7 +74,7 @@ fn quantile_noir_data() {
             .stream(source)
             .quantile_parallel(0.5, true)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -118,7 +118,7 @@ fn quantile_noir_data_nan() {
             .stream(source)
             .quantile_parallel(0.5, false)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -148,7 +148,7 @@ fn quantile_noir_type() {
             .stream(source)
             .quantile_parallel(0.5, true)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(4.0))]);
@@ -170,7 +170,7 @@ fn quantile_noir_type_nan() {
             .stream(source)
             .quantile_parallel(0.5, false)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(f32::NAN))]);
@@ -192,10 +192,61 @@ fn quantile_noir_type_none() {
             .stream(source)
             .quantile_parallel(0.5, true)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(None::<f32>))]);
         }
     });
 }
+
+#[test]
+fn quantiles_exact_distributed() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new((0..100_000u64).map(|n| (n * 7919) % 100_000));
+        let res = env
+            .stream(source)
+            .shuffle()
+            .quantiles_exact(vec![0.5, 0.9, 0.99, 0.0, 1.0], |n| n)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+
+        if let Some(res) = res.get() {
+            assert_eq!(res, vec![vec![49_999, 89_999, 98_999, 0, 99_999]]);
+        }
+    });
+}
+
+#[test]
+fn quantiles_exact_noir_type() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new((1..=1000).rev().map(NoirType::Int32));
+        let res = env
+            .stream(source)
+            .shuffle()
+            .quantiles_exact(vec![0.25, 0.7], |v| v)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+
+        if let Some(res) = res.get() {
+            assert_eq!(res, vec![vec![NoirType::Int32(250), NoirType::Int32(700)]]);
+        }
+    });
+}
+
+#[test]
+fn quantiles_exact_empty() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(std::iter::empty::<i32>());
+        let res = env
+            .stream(source)
+            .shuffle()
+            .quantiles_exact(vec![0.5], |v| v)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+
+        if let Some(res) = res.get() {
+            assert_eq!(res, vec![Vec::<i32>::new()]);
+        }
+    });
+}
@@ -17,7 +17,7 @@ fn quantiles_noir_type() {
 
         let source = IteratorSource::new(data.into_iter());
         let res = env.stream(source).ckms(0.5, 0.001, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(500))]);
@@ -68,7 +68,7 @@ fn quantile_noir_data() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).ckms(0.5, 0.001, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -109,7 +109,7 @@ fn quantile_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).ckms(0.5, 0.001, false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -136,7 +136,7 @@ fn quantile_noir_type() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).ckms(0.5, 0.001, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(4.0))]);
@@ -155,7 +155,7 @@ fn quantile_noir_type_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).ckms(0.5, 0.001, false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(f32::NAN))]);
@@ -174,7 +174,7 @@ fn quantile_noir_type_none() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).ckms(0.5, 0.001, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(None::<f32>))]);
@@ -193,7 +193,7 @@ fn gk_noir_type() {
 
         let source = IteratorSource::new(data.into_iter());
         let res = env.stream(source).gk(0.5, 0.001, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(501))]);
@@ -244,7 +244,7 @@ fn gks_noir_data() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).gk(0.5, 0.001, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -285,7 +285,7 @@ fn gk_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).gk(0.5, 0.001, false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -312,7 +312,7 @@ fn gks_noir_type() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).gk(0.5, 0.001, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(0.0))]);
@@ -331,7 +331,7 @@ fn gk_noir_type_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).gk(0.5, 0.001, false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(f32::NAN))]);
@@ -350,7 +350,7 @@ fn gk_noir_type_none() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).gk(0.5, 0.001, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(None::<f32>))]);
@@ -369,7 +369,7 @@ fn p2_noir_types() {
 
         let source = IteratorSource::new(data.into_iter());
         let res = env.stream(source).p2(0.5, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(500.0))]);
@@ -420,7 +420,7 @@ fn p2_noir_data() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).p2(0.5, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -461,7 +461,7 @@ fn p2_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).p2(0.5, false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -488,7 +488,7 @@ fn p2_noir_type() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).p2(0.5, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(8.0))]);
@@ -507,7 +507,7 @@ fn p2_noir_type_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).p2(0.5, false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(f32::NAN))]);
@@ -526,7 +526,7 @@ fn p2_noir_type_none() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).p2(0.5, true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(None::<f32>))]);
@@ -8,7 +8,7 @@ fn reduce_stream() {
     TestHelper::local_remote_env(|mut env| {
         let source = IteratorSource::new(0..10u8);
         let res = env.stream(source).reduce(|a, b| a + b).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             assert_eq!(res.len(), 1);
             assert_eq!(res[0], 45);
@@ -21,7 +21,7 @@ fn reduce_assoc_stream() {
     TestHelper::local_remote_env(|mut env| {
         let source = IteratorSource::new(0..10u8);
         let res = env.stream(source).reduce_assoc(|a, b| a + b).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             assert_eq!(res.len(), 1);
             assert_eq!(res[0], 45);
@@ -38,7 +38,7 @@ fn reduce_shuffled_stream() {
             .shuffle()
             .reduce(|a, b| a + b)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             assert_eq!(res.len(), 1);
             assert_eq!(res[0], 45);
@@ -55,7 +55,7 @@ fn reduce_assoc_shuffled_stream() {
             .shuffle()
             .reduce_assoc(|a, b| a + b)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             assert_eq!(res.len(), 1);
             assert_eq!(res[0], 45);
@@ -0,0 +1,166 @@
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use itertools::Itertools;
+use noir::config::CheckpointConfig;
+use noir::operator::source::{ChannelSource, IteratorSource};
+use noir::{EnvironmentConfig, JobErrorKind, RescaleError, StreamEnvironment};
+
+fn config(dir: &Path, num_cores: u64) -> EnvironmentConfig {
+    let mut config = EnvironmentConfig::local(num_cores);
+    config.checkpoint = Some(CheckpointConfig::new(dir, Duration::from_secs(60)));
+    config
+}
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wait until the job processed `n` more items.
+fn wait_processed(processed: &Receiver<()>, n: usize) {
+    for _ in 0..n {
+        processed.recv_timeout(TIMEOUT).unwrap();
+    }
+}
+
+#[test]
+fn rescale_keyed_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let (tx, source) = ChannelSource::new(64);
+    let (processed_tx, processed) = channel();
+    let sums = Arc::new(Mutex::new(Vec::new()));
+    let out = sums.clone();
+    let mut job = StreamEnvironment::execute_rescalable(config(dir.path(), 2), move |env| {
+        let out = out.clone();
+        let processed = processed_tx.clone();
+        env.stream(source.share())
+            .shuffle()
+            .inspect(move |_| processed.send(()).unwrap())
+            .group_by_sum(|x: &u64| x % 10, |x| x)
+            .for_each(move |kv| out.lock().unwrap().push(kv));
+    });
+
+    let mut items = 0..3000u64;
+    items.by_ref().take(1000).for_each(|x| tx.send(x).unwrap());
+    // stop the job while it holds the state of the items
+    wait_processed(&processed, 1000);
+    job.rescale(4, TIMEOUT).unwrap();
+    items.by_ref().take(1000).for_each(|x| tx.send(x).unwrap());
+    wait_processed(&processed, 1000);
+    job.rescale(3, TIMEOUT).unwrap();
+    items.for_each(|x| tx.send(x).unwrap());
+    drop(tx);
+    job.wait().unwrap();
+
+    let res = sums.lock().unwrap().iter().copied().sorted().collect_vec();
+    let expected = (0..3000u64)
+        .into_group_map_by(|x| x % 10)
+        .into_iter()
+        .map(|(k, v)| (k, v.into_iter().sum::<u64>()))
+        .sorted()
+        .collect_vec();
+    assert_eq!(res, expected);
+}
+
+#[test]
+fn rescale_global_aggregation() {
+    let dir = tempfile::tempdir().unwrap();
+    let (tx, source) = ChannelSource::new(64);
+    let (processed_tx, processed) = channel();
+    let sums = Arc::new(Mutex::new(Vec::new()));
+    let out = sums.clone();
+    let mut job = StreamEnvironment::execute_rescalable(config(dir.path(), 4), move |env| {
+        let out = out.clone();
+        let processed = processed_tx.clone();
+        env.stream(source.share())
+            .shuffle()
+            .inspect(move |_| processed.send(()).unwrap())
+            .reduce_assoc(|a: u64, b| a + b)
+            .for_each(move |x| out.lock().unwrap().push(x));
+    });
+
+    (0..500u64).for_each(|x| tx.send(x).unwrap());
+    wait_processed(&processed, 500);
+    job.rescale(2, TIMEOUT).unwrap();
+    (500..1000u64).for_each(|x| tx.send(x).unwrap());
+    wait_processed(&processed, 500);
+    job.rescale(3, TIMEOUT).unwrap();
+    drop(tx);
+    job.wait().unwrap();
+
+    assert_eq!(*sums.lock().unwrap(), vec![(0..1000u64).sum::<u64>()]);
+}
+
+#[test]
+fn not_rescalable() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(2));
+    env.stream(IteratorSource::new(0..10u64)).for_each(|_| {});
+    let mut job = env.spawn();
+    assert!(matches!(
+        job.rescale(4, TIMEOUT),
+        Err(RescaleError::NotRescalable)
+    ));
+    job.wait().unwrap();
+}
+
+#[test]
+fn unsupported_operator() {
+    let dir = tempfile::tempdir().unwrap();
+    let (tx, source) = ChannelSource::new(64);
+    let mut job = StreamEnvironment::execute_rescalable(config(dir.path(), 2), move |env| {
+        env.stream(source.share())
+            .group_by(|x: &u64| x % 2)
+            .rich_map({
+                let mut count = 0;
+                move |_| {
+                    count += 1;
+                    count
+                }
+            })
+            .for_each(|_| {});
+    });
+    let res = job.rescale(4, TIMEOUT);
+    assert!(
+        matches!(&res, Err(RescaleError::Unsupported(reason)) if reason.contains("rich_map")),
+        "{res:?}"
+    );
+    drop(tx);
+    assert_eq!(job.wait().unwrap_err().kind, JobErrorKind::Checkpoint);
+}
+
+#[test]
+fn rescale_timeout() {
+    let dir = tempfile::tempdir().unwrap();
+    let (tx, source) = ChannelSource::new(64);
+    let (processed_tx, processed) = channel();
+    let gate = Arc::new(Mutex::new(()));
+    let sums = Arc::new(Mutex::new(Vec::new()));
+    let out = sums.clone();
+    let blocked = gate.clone();
+    let mut job = StreamEnvironment::execute_rescalable(config(dir.path(), 2), move |env| {
+        let out = out.clone();
+        let blocked = blocked.clone();
+        let processed = processed_tx.clone();
+        env.stream(source.share())
+            .inspect(move |_| {
+                processed.send(()).unwrap();
+                drop(blocked.lock().unwrap());
+            })
+            .reduce(|a: u64, b| a + b)
+            .for_each(move |x| out.lock().unwrap().push(x));
+    });
+
+    // the source is blocked by an item, so the job cannot be paused
+    let guard = gate.lock().unwrap();
+    tx.send(1).unwrap();
+    wait_processed(&processed, 1);
+    let res = job.rescale(4, Duration::from_millis(100));
+    assert!(matches!(res, Err(RescaleError::Timeout)), "{res:?}");
+    drop(guard);
+
+    // the job keeps running after the timeout
+    (2..=10u64).for_each(|x| tx.send(x).unwrap());
+    drop(tx);
+    job.wait().unwrap();
+    assert_eq!(*sums.lock().unwrap(), vec![55]);
+}
@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use itertools::Itertools;
+
+use noir::operator::source::IteratorSource;
+use noir::operator::window::CountWindow;
+use noir::operator::StateRetention;
+use noir::{BatchMode, EnvironmentConfig, Replication, StreamEnvironment};
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn fold_event_time_ttl() {
+    TestHelper::local_remote_env(|mut env| {
+        let retention = StateRetention::new().event_time_ttl(2).emit_evicted();
+        let source = IteratorSource::new(0..20i64);
+        let res = env
+            .stream(source)
+            .add_timestamps(|&n| n, |_, &ts| Some(ts))
+            .group_by(|n| n / 5)
+            .fold_with_retention(0, |acc, n| *acc += n, retention)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let res = res.into_iter().sorted().collect_vec();
+            assert_eq!(res, vec![(0, 10), (1, 35), (2, 60), (3, 85)]);
+        }
+    });
+}
+
+#[test]
+fn fold_event_time_ttl_dropped() {
+    TestHelper::local_remote_env(|mut env| {
+        let retention = StateRetention::new().event_time_ttl(2);
+        let source = IteratorSource::new(0..20i64);
+        let res = env
+            .stream(source)
+            .add_timestamps(|&n| n, |_, &ts| Some(ts))
+            .group_by(|n| n / 5)
+            .fold_with_retention(0, |acc, n| *acc += n, retention)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            // only the last key has not expired before the end of the stream
+            assert_eq!(res, vec![(3, 85)]);
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "cannot emit the evicted keys")]
+fn rich_map_emit_evicted() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    env.stream(IteratorSource::new(0..10))
+        .group_by(|&n| n)
+        .rich_map_with_retention(|(_, n)| n, StateRetention::new().emit_evicted())
+        .for_each(|_| {});
+}
+
+#[test]
+fn rich_map_processing_time_ttl() {
+    TestHelper::local_remote_env(|mut env| {
+        let retention = StateRetention::new().processing_time_ttl(Duration::from_millis(50));
+        let source =
+            IteratorSource::new(vec![0, 0].into_iter().chain(
+                std::iter::once(0).inspect(|_| std::thread::sleep(Duration::from_millis(300))),
+            ));
+        let res = env
+            .stream(source)
+            .batch_mode(BatchMode::single())
+            .group_by(|&n| n)
+            .rich_map_with_retention(
+                {
+                    let mut count = 0;
+                    move |_| {
+                        count += 1;
+                        count
+                    }
+                },
+                retention,
+            )
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res, vec![(0, 1), (0, 2), (0, 1)]);
+        }
+    });
+}
+
+#[test]
+fn window_max_keys() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..5);
+        let res = env
+            .stream(source)
+            // the keys are bounded for each replica
+            .replication(Replication::new_one())
+            .key_by(|&n| n % 2)
+            .window(CountWindow::new(2, 2, false))
+            .with_retention(StateRetention::new().max_keys(1))
+            .sum()
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            // the windows of the key evicted by the next element are dropped
+            assert_eq!(res, vec![(0, 4)]);
+        }
+    });
+}
@@ -0,0 +1,112 @@
+use itertools::Itertools;
+
+use noir::operator::source::IteratorSource;
+use noir::{EnvironmentConfig, StreamEnvironment};
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn sample_fraction() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..10_000u32);
+        let res = env
+            .stream(source)
+            .shuffle()
+            .sample_fraction(0.2, 42)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert!(res.len() > 1_700 && res.len() < 2_300, "{}", res.len());
+            assert!(res.iter().all_unique());
+        }
+    });
+}
+
+#[test]
+fn sample_fraction_is_reproducible() {
+    let run = |seed| {
+        let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+        let source = IteratorSource::new(0..10_000u32);
+        let res = env.stream(source).sample_fraction(0.1, seed).collect_vec();
+        env.execute_blocking().unwrap();
+        res.get().unwrap().into_iter().sorted().collect_vec()
+    };
+    assert_eq!(run(1), run(1));
+    assert_ne!(run(1), run(2));
+}
+
+#[test]
+fn sample_reservoir() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..10_000u32);
+        let res = env
+            .stream(source)
+            .shuffle()
+            .sample_reservoir(100, 42)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res.len(), 100);
+            assert!(res.iter().all_unique());
+            assert!(res.iter().all(|&n| n < 10_000));
+        }
+    });
+}
+
+#[test]
+fn sample_reservoir_small_stream() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..10u32);
+        let res = env
+            .stream(source)
+            .shuffle()
+            .sample_reservoir(100, 42)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(
+                res.into_iter().sorted().collect_vec(),
+                (0..10).collect_vec()
+            );
+        }
+    });
+}
+
+#[test]
+fn sample_stratified_by() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new((0..10_000u32).map(|n| (n % 4, n)));
+        let res = env
+            .stream(source)
+            .shuffle()
+            .sample_stratified_by(|&(k, _)| k, 10, 42)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let counts = res.iter().counts_by(|(k, _)| *k);
+            assert_eq!(counts.len(), 4);
+            assert!(counts.values().all(|&c| c == 10));
+            assert!(res.iter().all(|(k, (k2, _))| k == k2));
+        }
+    });
+}
+
+#[test]
+fn sample_reservoir_keyed() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..10_000u32);
+        let res = env
+            .stream(source)
+            .group_by(|n| n % 3)
+            .sample_reservoir(5, 42)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let counts = res.iter().counts_by(|(k, _)| *k);
+            assert_eq!(counts.len(), 3);
+            assert!(counts.values().all(|&c| c == 5));
+            assert!(res.iter().all(|(k, n)| n % 3 == *k));
+        }
+    });
+}
@@ -54,7 +54,7 @@ fn skew() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).skewness(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             let mut row = res[0].clone().to_row();
@@ -105,7 +105,7 @@ fn skew_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).skewness(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -132,7 +132,7 @@ fn skew_noir_type() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).skewness(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(0.0))]);
@@ -151,7 +151,7 @@ fn skew_noir_type_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).skewness(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(f32::NAN))]);
@@ -170,7 +170,7 @@ fn skew_noir_type_none() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).skewness(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(None::<f32>))]);
@@ -221,7 +221,7 @@ fn kurt() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).kurtosis(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             let mut row = res[0].clone().to_row();
@@ -272,7 +272,7 @@ fn kurt_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).kurtosis(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -299,7 +299,7 @@ fn kurt_noir_type() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).kurtosis(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             let mut v = res[0].clone().to_type();
@@ -326,7 +326,7 @@ fn kurt_noir_type_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).kurtosis(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(f32::NAN))]);
@@ -345,7 +345,7 @@ fn kurt_noir_type_none() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).kurtosis(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(None::<f32>))]);
@@ -405,7 +405,7 @@ fn kurtosis_unbiased() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).kurtosis_unbiased(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             let mut row = res[0].clone().to_row();
@@ -474,7 +474,7 @@ fn kurtosis_unbiased_noir_data_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).kurtosis_unbiased(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(
@@ -503,7 +503,7 @@ fn kurtosis_unbiased_noir_type() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).kurtosis_unbiased(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             let mut v = res[0].clone().to_type();
@@ -530,7 +530,7 @@ fn kurtosis_unbiased_noir_type_nan() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).kurtosis_unbiased(false).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(f32::NAN))]);
@@ -549,7 +549,7 @@ fn kurtosis_unbiased_noir_type_none() {
         ];
         let source = IteratorSource::new(rows.into_iter());
         let res = env.stream(source).kurtosis_unbiased(true).collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             assert_eq!(res, [NoirData::NoirType(NoirType::from(None::<f32>))]);
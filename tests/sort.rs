@@ -0,0 +1,118 @@
+use itertools::Itertools;
+
+use noir::operator::source::{CsvSource, IteratorSource};
+use noir::{EnvironmentConfig, StreamEnvironment};
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn sort_by_key() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new((0..2000u64).map(|n| (n * 7919) % 2000));
+        let res = env
+            .stream(source)
+            .shuffle()
+            .sort_by_key(|&n| n)
+            .collect_vec_ordered();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res, (0..2000u64).collect_vec());
+        }
+    });
+}
+
+#[test]
+fn sort_duplicate_keys() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new((0..1000u32).map(|n| (n % 5, n)));
+        let res = env
+            .stream(source)
+            .shuffle()
+            .sort_by_key(|&(k, _)| k)
+            .collect_vec_ordered();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res.len(), 1000);
+            assert!(res.windows(2).all(|w| w[0].0 <= w[1].0));
+            let expected = (0..1000u32).map(|n| (n % 5, n)).sorted().collect_vec();
+            assert_eq!(res.into_iter().sorted().collect_vec(), expected);
+        }
+    });
+}
+
+#[test]
+fn sort_empty_stream() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(std::iter::empty::<i32>());
+        let res = env
+            .stream(source)
+            .shuffle()
+            .sort_by_key(|&n| n)
+            .collect_vec_ordered();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert!(res.is_empty());
+        }
+    });
+}
+
+#[test]
+fn sort_inside_iteration() {
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    let source = IteratorSource::new((0..100i64).rev());
+    let (state, res) = env.stream(source).shuffle().iterate(
+        2,
+        0,
+        |s, _| s.sort_by_key(|&n| -n).map(|n| n + 1),
+        |changed: &mut i64, _| *changed += 1,
+        |state, changed| *state += changed,
+        |_| true,
+    );
+    let state = state.collect_vec();
+    let res = res.collect_vec();
+    env.execute_blocking().unwrap();
+    assert_eq!(state.get().unwrap(), vec![200]);
+    let mut res = res.get().unwrap();
+    res.sort();
+    assert_eq!(res, (2..102).collect_vec());
+}
+
+#[test]
+fn write_csv_parts() {
+    let dir = tempfile::tempdir().unwrap();
+    // left by a previous run with more replicas
+    std::fs::write(dir.path().join("part-00007.csv"), "9999,0\n").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "kept").unwrap();
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(4));
+    let source = IteratorSource::new((0..1000u32).rev().map(|n| (n, n * 2)));
+    env.stream(source)
+        .shuffle()
+        .sort_by_key(|&(n, _)| n)
+        .write_csv_parts(dir.path());
+    env.execute_blocking().unwrap();
+
+    let parts = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().unwrap() == "csv")
+        .sorted()
+        .collect_vec();
+    assert_eq!(parts.len(), 4);
+    assert!(dir.path().join("notes.txt").exists());
+
+    let mut env = StreamEnvironment::new(EnvironmentConfig::local(1));
+    let mut streams = parts
+        .into_iter()
+        .map(|part| {
+            env.stream(CsvSource::<(u32, u32)>::new(part).has_headers(false))
+                .collect_vec()
+        })
+        .collect_vec();
+    env.execute_blocking().unwrap();
+    let res = streams
+        .drain(..)
+        .flat_map(|part| part.get().unwrap())
+        .collect_vec();
+    assert_eq!(res, (0..1000u32).map(|n| (n, n * 2)).collect_vec());
+}
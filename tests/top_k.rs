@@ -0,0 +1,60 @@
+use std::cmp::Reverse;
+
+use itertools::Itertools;
+
+use noir::operator::source::IteratorSource;
+use utils::TestHelper;
+
+mod utils;
+
+#[test]
+fn top_k_by() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new((0..1000u32).map(|n| (n * 7919) % 1000));
+        let res = env
+            .stream(source)
+            .shuffle()
+            .top_k_by(5, |&n| n)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res, vec![999, 998, 997, 996, 995]);
+        }
+    });
+}
+
+#[test]
+fn top_k_by_smallest() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new((0..1000u32).rev());
+        let res = env
+            .stream(source)
+            .shuffle()
+            .top_k_by(3, |&n| Reverse(n))
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            assert_eq!(res, vec![0, 1, 2]);
+        }
+    });
+}
+
+#[test]
+fn top_k_by_keyed() {
+    TestHelper::local_remote_env(|mut env| {
+        let source = IteratorSource::new(0..1000u32);
+        let res = env
+            .stream(source)
+            .group_by(|n| n % 4)
+            .top_k_by(2, |&n| n)
+            .collect_vec();
+        env.execute_blocking().unwrap();
+        if let Some(res) = res.get() {
+            let expected = (0..4)
+                .flat_map(|k| [(k, 996 + k), (k, 992 + k)])
+                .sorted()
+                .collect_vec();
+            assert_eq!(res.into_iter().sorted().collect_vec(), expected);
+        }
+    });
+}
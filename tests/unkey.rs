@@ -15,7 +15,7 @@ fn unkey_keyed_stream() {
             .key_by(|&n| n.to_string())
             .unkey()
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             let res = res.into_iter().sorted().collect_vec();
             let expected = (0..10u8).map(|n| (n.to_string(), n)).collect_vec();
@@ -34,7 +34,7 @@ fn drop_key_keyed_stream() {
             .key_by(|&n| n % 2)
             .drop_key()
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(res) = res.get() {
             let res = res.into_iter().sorted().collect_vec();
             assert_eq!(res, (0..10u8).collect_vec());
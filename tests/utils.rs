@@ -10,7 +10,10 @@ use std::time::Duration;
 
 use itertools::{process_results, Itertools};
 
-use noir::config::{ExecutionRuntime, RemoteHostConfig, RemoteRuntimeConfig};
+use noir::cluster::LocalCluster;
+use noir::config::{
+    Concurrency, ExecutionRuntime, Launcher, RemoteHostConfig, RemoteRuntimeConfig, HOST_ID_ENV_VAR,
+};
 use noir::operator::{Data, Operator, StreamElement, Timestamp};
 use noir::structure::BlockStructure;
 use noir::CoordUInt;
@@ -142,31 +145,45 @@ impl TestHelper {
         Self::env_with_config(config, body)
     }
 
+    /// Run the test body under a local environment where at most `max_running` replicas run at
+    /// the same time.
+    pub fn limited_env(
+        body: Arc<dyn Fn(StreamEnvironment) + Send + Sync>,
+        num_cores: CoordUInt,
+        max_running: usize,
+    ) {
+        Self::setup();
+        let mut config = EnvironmentConfig::local(num_cores);
+        config.concurrency = Concurrency::Limited { max_running };
+        log::debug!("Running test with env: {:?}", config);
+        Self::env_with_config(config, body)
+    }
+
     /// Run the test body under a simulated remote environment.
     pub fn remote_env(
         body: Arc<dyn Fn(StreamEnvironment) + Send + Sync>,
         num_hosts: CoordUInt,
         cores_per_host: CoordUInt,
+    ) {
+        let labels = vec![vec![]; num_hosts as usize];
+        Self::labeled_remote_env(body, labels, cores_per_host);
+    }
+
+    /// Run the test body under a simulated remote environment, with a host for each set of
+    /// labels.
+    pub fn labeled_remote_env(
+        body: Arc<dyn Fn(StreamEnvironment) + Send + Sync>,
+        labels: Vec<Vec<String>>,
+        cores_per_host: CoordUInt,
     ) {
         Self::setup();
-        let mut hosts = vec![];
-        for host_id in 0..num_hosts {
-            let test_id = TEST_INDEX.fetch_add(1, Ordering::SeqCst) + 1;
-            let high_part = (test_id & 0xff00) >> 8;
-            let low_part = test_id & 0xff;
-            let address = format!("127.{high_part}.{low_part}.{host_id}");
-            hosts.push(RemoteHostConfig {
-                address,
-                base_port: TEST_BASE_PORT,
-                num_cores: cores_per_host,
-                ssh: Default::default(),
-                perf_path: None,
-            });
-        }
+        let num_hosts = labels.len() as CoordUInt;
+        let hosts = Self::hosts(labels, cores_per_host);
         let runtime = ExecutionRuntime::Remote(RemoteRuntimeConfig {
             hosts,
             tracing_dir: None,
             cleanup_executable: true,
+            launcher: Default::default(),
         });
         log::debug!("Running with remote configuration: {:?}", runtime);
 
@@ -176,6 +193,10 @@ impl TestHelper {
                 runtime: runtime.clone(),
                 host_id: Some(host_id),
                 skip_single_remote_check: true,
+                checkpoint: None,
+                concurrency: Default::default(),
+                affinity: Default::default(),
+                metrics: None,
             };
             let body = body.clone();
             join_handles.push(
@@ -192,17 +213,102 @@ impl TestHelper {
         }
     }
 
-    /// Run the test body under a local environment and later under a simulated remote environment.
+    /// Run the test body under a cluster of processes of this machine, communicating through the
+    /// loopback interface.
+    ///
+    /// Each process runs the current test again, executing only the body with its host of the
+    /// cluster. The test is skipped if its name is not known (e.g. with `--test-threads=1`).
+    pub fn cluster_env(
+        body: Arc<dyn Fn(StreamEnvironment) + Send + Sync>,
+        num_hosts: CoordUInt,
+        cores_per_host: CoordUInt,
+    ) {
+        Self::setup();
+        if Self::is_cluster_worker() {
+            // the configuration is read from the environment
+            let mut config = EnvironmentConfig::remote("").unwrap();
+            config.skip_single_remote_check = true;
+            return Self::env_with_config(config, body);
+        }
+        let test_name = match std::thread::current().name() {
+            Some(name) if name != "main" => name.to_string(),
+            _ => {
+                log::warn!("Skipping the cluster test: its name is not known");
+                return;
+            }
+        };
+
+        let config = RemoteRuntimeConfig {
+            hosts: Self::hosts(vec![vec![]; num_hosts as usize], cores_per_host),
+            tracing_dir: None,
+            cleanup_executable: false,
+            launcher: Launcher::Cluster,
+        };
+        log::debug!("Running with cluster configuration: {:?}", config);
+        let args = [
+            test_name.as_str(),
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ];
+        let cluster = LocalCluster::spawn(&config, args).expect("Cannot spawn the cluster");
+        let exit_code = cluster.wait();
+        assert_eq!(
+            exit_code, 0,
+            "A process of the cluster failed running {test_name}"
+        );
+    }
+
+    /// Whether this process is a host of a cluster spawned by [`TestHelper::cluster_env`].
+    fn is_cluster_worker() -> bool {
+        std::env::var_os(HOST_ID_ENV_VAR).is_some()
+    }
+
+    /// The configuration of the hosts of a simulated remote environment, with a host for each set
+    /// of labels.
+    fn hosts(labels: Vec<Vec<String>>, cores_per_host: CoordUInt) -> Vec<RemoteHostConfig> {
+        let mut hosts = vec![];
+        for (host_id, labels) in labels.into_iter().enumerate() {
+            let test_id = TEST_INDEX.fetch_add(1, Ordering::SeqCst) + 1;
+            let high_part = (test_id & 0xff00) >> 8;
+            let low_part = test_id & 0xff;
+            let address = format!("127.{high_part}.{low_part}.{host_id}");
+            hosts.push(RemoteHostConfig {
+                address,
+                base_port: TEST_BASE_PORT,
+                num_cores: cores_per_host,
+                ssh: Default::default(),
+                perf_path: None,
+                labels,
+                weight: None,
+            });
+        }
+        hosts
+    }
+
+    /// Run the test body under a local environment and a simulated remote environment.
+    ///
+    /// The body also runs under a local environment with a concurrency limit for each number of
+    /// running replicas listed in `RSTREAM_TEST_MAX_RUNNING`, and under a cluster of processes for each number of hosts
+    /// listed in `RSTREAM_TEST_CLUSTER_HOSTS`, none by default.
     pub fn local_remote_env<F>(body: F)
     where
         F: Fn(StreamEnvironment) + Send + Sync + 'static,
     {
         let body = Arc::new(body);
+        if Self::is_cluster_worker() {
+            // only the body of the host is executed
+            return Self::cluster_env(body, 0, 0);
+        }
 
         let local_cores =
             Self::parse_list_from_env("RSTREAM_TEST_LOCAL_CORES").unwrap_or_else(|| vec![4]);
+        let max_running = Self::parse_list_from_env("RSTREAM_TEST_MAX_RUNNING").unwrap_or_default();
         for num_cores in local_cores {
             Self::local_env(body.clone(), num_cores);
+            for &max_running in &max_running {
+                Self::limited_env(body.clone(), num_cores, max_running as usize);
+            }
         }
 
         let remote_hosts =
@@ -214,6 +320,14 @@ impl TestHelper {
                 Self::remote_env(body.clone(), num_hosts, num_cores);
             }
         }
+
+        let cluster_hosts =
+            Self::parse_list_from_env("RSTREAM_TEST_CLUSTER_HOSTS").unwrap_or_default();
+        for num_hosts in cluster_hosts {
+            for &num_cores in &remote_cores {
+                Self::cluster_env(body.clone(), num_hosts, num_cores);
+            }
+        }
     }
 
     /// Parse a list of arguments from an environment variable.
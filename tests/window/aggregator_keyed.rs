@@ -15,7 +15,7 @@ fn test_first_window_keyed() {
             .window(CountWindow::sliding(3, 2))
             .first()
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(
@@ -44,7 +44,7 @@ fn test_fold_window_keyed() {
             .window(CountWindow::sliding(3, 2))
             .fold(0, |acc, x| *acc += x)
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(
@@ -76,7 +76,7 @@ fn test_sum_window_keyed() {
             .window(CountWindow::sliding(3, 2))
             .sum()
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(
@@ -107,7 +107,7 @@ fn test_min_window_keyed() {
             .window(CountWindow::sliding(3, 2))
             .min()
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(
@@ -135,7 +135,7 @@ fn test_max_window_keyed() {
             .window(CountWindow::sliding(3, 2))
             .max()
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(
@@ -169,7 +169,7 @@ fn test_map_window_keyed() {
                 res
             })
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
         if let Some(mut res) = res.get() {
             res.sort_unstable();
             assert_eq!(
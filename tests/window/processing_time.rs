@@ -20,7 +20,7 @@ fn tumbling_processing_time() {
             .fold(0, |acc, x| *acc += x)
             .drop_key()
             .collect_vec();
-        env.execute_blocking();
+        env.execute_blocking().unwrap();
 
         if let Some(res) = res.get() {
             eprintln!("{res:?}");